@@ -0,0 +1,453 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Administrative HTTP handlers for Scedge Core.
+//!
+//! These endpoints are protected by the operator token configured through
+//! `SCEDGE_ADMIN_TOKEN` and are disabled entirely when no token is set:
+//!
+//! - `POST /admin/tenants/:id/export` - Stream all cached artifacts of a tenant
+//! - `DELETE /admin/tenants/:id/data` - Verified erasure of a tenant's artifacts
+//! - `POST /admin/tenants/:id/keys` - Register a new encryption key version
+//! - `POST /admin/tenants/:id/dictionary` - Train a compression dictionary
+//! - `GET|PUT|DELETE /admin/tenants/:id/overrides` - Persisted runtime overrides
+//! - `GET /admin/sync` - Stream entries modified since a time to a new sibling
+//! - `GET|PUT /admin/loglevel` - Inspect or replace the log filter at runtime
+//!
+//! The operator console endpoints live in [`crate::console`].
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use futures_util::{stream, StreamExt};
+use sha2::{Digest, Sha256};
+
+use crate::api::AppState;
+use crate::cache::tenant_pattern;
+use crate::crypto::TenantKey;
+use crate::error::AppError;
+use crate::logging::LogLevel;
+use crate::model::{
+    DictionaryTrainingRequest, DictionaryTrainingResponse, ErasureRecord, KeyRotationResponse,
+    LogFilter, SyncQuery,
+};
+use crate::overrides::{self, TenantOverrides};
+use crate::policy::extract_bearer_token;
+
+/// Ensure the request carries the configured admin token
+pub fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), AppError> {
+    let expected = state
+        .admin_token
+        .as_deref()
+        .ok_or_else(|| AppError::forbidden("admin API disabled"))?;
+
+    let provided = extract_bearer_token(
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok()),
+    )
+    .ok_or_else(|| AppError::unauthorized("admin token required"))?;
+
+    if !tokens_match(&provided, expected) {
+        return Err(AppError::unauthorized("invalid admin token"));
+    }
+
+    Ok(())
+}
+
+/// Compare tokens in time independent of where they differ
+///
+/// Both sides are hashed first, so neither their contents nor their lengths
+/// can be probed through response timing.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    provided
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Stream every cached artifact of a tenant as newline-delimited JSON
+pub async fn handle_tenant_export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<String>,
+) -> Result<Response, AppError> {
+    require_admin(&state, &headers)?;
+
+    tracing::info!(
+        target: "scedge::audit",
+        tenant = %tenant_id,
+        "Tenant export started"
+    );
+
+    let filename = format!("attachment; filename=\"{}-export.ndjson\"", tenant_id);
+
+    let body = state
+        .cache
+        .scan_entries(tenant_pattern(&tenant_id))
+        .filter_map(move |entry| {
+            let line = match entry {
+                Ok(record) if record.artifact.policy.tenant == tenant_id => {
+                    match serde_json::to_vec(&record) {
+                        Ok(mut line) => {
+                            line.push(b'\n');
+                            Some(Ok(Bytes::from(line)))
+                        }
+                        Err(e) => Some(Err(AppError::Internal(anyhow::anyhow!(
+                            "Failed to serialize artifact: {}",
+                            e
+                        )))),
+                    }
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            };
+            async move { line }
+        });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (header::CONTENT_DISPOSITION, filename),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
+
+/// Keys fetched per backend round trip while streaming a sync
+const SYNC_BATCH: usize = 100;
+
+/// Stream every entry written since `since` as newline-delimited JSON
+///
+/// Entries come oldest first from the backend's modified-at index, so a new
+/// sibling can copy the working set instead of filling it through misses.
+pub async fn handle_sync(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SyncQuery>,
+) -> Result<Response, AppError> {
+    require_admin(&state, &headers)?;
+
+    let since = query.since.unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let keys = state.cache.modified_since(since).await?;
+    tracing::info!(
+        target: "scedge::audit",
+        since = %since,
+        keys = keys.len(),
+        "Cache sync started"
+    );
+
+    let cache = state.cache.clone();
+    let body = stream::iter(keys)
+        .chunks(SYNC_BATCH)
+        .then(move |keys| {
+            let cache = cache.clone();
+            async move { cache.get_many(&keys).await }
+        })
+        .flat_map(|batch| match batch {
+            Ok(records) => stream::iter(records.into_iter().map(Ok)).left_stream(),
+            Err(err) => stream::once(async move { Err(err) }).right_stream(),
+        })
+        .filter_map(move |entry| {
+            let line = match entry {
+                Ok(Some(record)) if record.stored_at >= since => {
+                    match serde_json::to_vec(&record) {
+                        Ok(mut line) => {
+                            line.push(b'\n');
+                            Some(Ok(Bytes::from(line)))
+                        }
+                        Err(e) => Some(Err(AppError::Internal(anyhow::anyhow!(
+                            "Failed to serialize artifact: {}",
+                            e
+                        )))),
+                    }
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            };
+            async move { line }
+        });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
+
+/// Erase all cached artifacts of a tenant and verify nothing remains
+pub async fn handle_tenant_erasure(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<ErasureRecord>, AppError> {
+    require_admin(&state, &headers)?;
+
+    let requested_at = Utc::now();
+    let pattern = tenant_pattern(&tenant_id);
+
+    let keys = state.cache.scan_by_pattern(&pattern).await?;
+    let purged = state.cache.delete_many(&keys).await?;
+    state.metrics.record_cache_purge(purged);
+
+    // Re-scan to verify the erasure actually removed everything
+    let remaining = state.cache.scan_by_pattern(&pattern).await?.len();
+
+    let record = ErasureRecord {
+        tenant: tenant_id,
+        requested_at,
+        completed_at: Utc::now(),
+        purged,
+        remaining,
+        verified: remaining == 0,
+    };
+
+    tracing::info!(
+        target: "scedge::audit",
+        tenant = %record.tenant,
+        purged = record.purged,
+        remaining = record.remaining,
+        verified = record.verified,
+        requested_at = %record.requested_at,
+        completed_at = %record.completed_at,
+        "Tenant data erased"
+    );
+
+    Ok(Json(record))
+}
+
+/// Register a new encryption key for a tenant and re-encrypt its entries
+///
+/// The new key becomes active immediately; older versions stay available for
+/// decryption. Entries sealed under older keys are rewritten by a background
+/// job whose outcome is written to the `scedge::audit` log target. Keys are
+/// persisted in the backend's control namespace before they are used, so
+/// restarts and sibling nodes can decrypt the rewritten entries.
+pub async fn handle_register_tenant_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<String>,
+    Json(key): Json<TenantKey>,
+) -> Result<(StatusCode, Json<KeyRotationResponse>), AppError> {
+    require_admin(&state, &headers)?;
+
+    if state.policy.get_tenant(&tenant_id).await.is_none() {
+        return Err(AppError::not_found("Unknown tenant"));
+    }
+
+    state.keyring.check_key(&tenant_id, &key)?;
+    state.cache.persist_tenant_key(&tenant_id, &key).await?;
+    state.keyring.add_key(&tenant_id, &key)?;
+
+    tracing::info!(
+        target: "scedge::audit",
+        tenant = %tenant_id,
+        key_id = %key.key_id,
+        "Tenant encryption key registered"
+    );
+
+    let cache = state.cache.clone();
+    let job_tenant = tenant_id.clone();
+    let job_key_id = key.key_id.clone();
+    tokio::spawn(async move {
+        match cache.reencrypt_tenant(&job_tenant).await {
+            Ok(rewritten) => tracing::info!(
+                target: "scedge::audit",
+                tenant = %job_tenant,
+                key_id = %job_key_id,
+                rewritten,
+                "Tenant re-encryption completed"
+            ),
+            Err(err) => tracing::error!(
+                target: "scedge::audit",
+                tenant = %job_tenant,
+                key_id = %job_key_id,
+                error = %err,
+                "Tenant re-encryption failed"
+            ),
+        }
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(KeyRotationResponse {
+            tenant: tenant_id,
+            active_key_id: key.key_id,
+        }),
+    ))
+}
+
+/// Train a zstd dictionary from a sample of the tenant's cached answers
+///
+/// The dictionary is stored in the backend under a new version and used for
+/// the tenant's subsequent stores. Entries written earlier keep decoding with
+/// the version they were compressed with.
+pub async fn handle_train_dictionary(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<String>,
+    request: Option<Json<DictionaryTrainingRequest>>,
+) -> Result<Json<DictionaryTrainingResponse>, AppError> {
+    require_admin(&state, &headers)?;
+
+    if state.policy.get_tenant(&tenant_id).await.is_none() {
+        return Err(AppError::not_found("Unknown tenant"));
+    }
+
+    let Json(request) = request.unwrap_or_default();
+    if request.samples == 0 || request.max_size == 0 {
+        return Err(AppError::bad_request(
+            "samples and max_size must be positive",
+        ));
+    }
+
+    let trained = state
+        .cache
+        .train_dictionary(&tenant_id, request.samples, request.max_size)
+        .await?;
+
+    tracing::info!(
+        target: "scedge::audit",
+        tenant = %tenant_id,
+        version = trained.version,
+        size_bytes = trained.size_bytes,
+        samples = trained.samples,
+        "Compression dictionary trained"
+    );
+
+    Ok(Json(DictionaryTrainingResponse {
+        tenant: tenant_id,
+        version: trained.version,
+        size_bytes: trained.size_bytes,
+        samples: trained.samples,
+    }))
+}
+
+/// Show the runtime overrides persisted for a tenant
+pub async fn handle_get_tenant_overrides(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<TenantOverrides>, AppError> {
+    require_admin(&state, &headers)?;
+
+    if state.policy.get_tenant(&tenant_id).await.is_none() {
+        return Err(AppError::not_found("Unknown tenant"));
+    }
+
+    let overrides = overrides::load_tenant(&state.cache, &tenant_id).await?;
+    Ok(Json(overrides.unwrap_or_default()))
+}
+
+/// Persist runtime overrides for a tenant and apply them immediately
+pub async fn handle_put_tenant_overrides(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<String>,
+    Json(overrides): Json<TenantOverrides>,
+) -> Result<Json<TenantOverrides>, AppError> {
+    require_admin(&state, &headers)?;
+
+    let Some(mut tenant) = state.policy.get_tenant(&tenant_id).await else {
+        return Err(AppError::not_found("Unknown tenant"));
+    };
+
+    if overrides.max_concurrency == Some(0) {
+        return Err(AppError::bad_request("max_concurrency must be positive"));
+    }
+
+    overrides::save_tenant(&state.cache, &tenant_id, &overrides).await?;
+    overrides.apply(&mut tenant);
+    state.policy.add_tenant(tenant).await;
+
+    tracing::info!(
+        target: "scedge::audit",
+        tenant = %tenant_id,
+        ?overrides,
+        "Tenant overrides applied"
+    );
+
+    Ok(Json(overrides))
+}
+
+/// Forget a tenant's persisted overrides
+///
+/// Values already applied stay in effect until the tenant is installed again
+/// from the tenants file, on restart or `reload_config`.
+pub async fn handle_delete_tenant_overrides(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    require_admin(&state, &headers)?;
+
+    overrides::clear_tenant(&state.cache, &tenant_id).await?;
+
+    tracing::info!(
+        target: "scedge::audit",
+        tenant = %tenant_id,
+        "Tenant overrides cleared"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Show the active log filter
+pub async fn handle_get_log_level(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<LogFilter>, AppError> {
+    require_admin(&state, &headers)?;
+
+    let filter = adjustable_log_level(&state)?.current()?;
+    Ok(Json(LogFilter { filter }))
+}
+
+/// Replace the log filter without restarting the node
+pub async fn handle_put_log_level(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<LogFilter>,
+) -> Result<Json<LogFilter>, AppError> {
+    require_admin(&state, &headers)?;
+
+    let log_level = adjustable_log_level(&state)?;
+    log_level.set(&request.filter)?;
+
+    tracing::info!(
+        target: "scedge::audit",
+        filter = %request.filter,
+        "Log filter replaced"
+    );
+
+    Ok(Json(LogFilter {
+        filter: log_level.current()?,
+    }))
+}
+
+fn adjustable_log_level(state: &AppState) -> Result<&LogLevel, AppError> {
+    state
+        .log_level
+        .as_ref()
+        .ok_or_else(|| AppError::bad_request("Log level is not adjustable"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_identical_tokens_match() {
+        assert!(tokens_match("operator-secret", "operator-secret"));
+        assert!(!tokens_match("operator-secreT", "operator-secret"));
+        assert!(!tokens_match("operator-secret-", "operator-secret"));
+        assert!(!tokens_match("", "operator-secret"));
+    }
+}
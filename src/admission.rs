@@ -0,0 +1,154 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission control based on predicted reuse.
+//!
+//! Many prompt artifacts are looked up once and never again. On
+//! memory-constrained edge nodes, keeping them in the L1 evicts entries that
+//! would have been reused. For tenants with `admission_control` enabled, the
+//! L1 only takes a key once it has been looked up
+//! `SCEDGE_ADMISSION_MIN_ACCESSES` times (2 by default). The backend behind
+//! the L1 is authoritative and still receives every store and hydration.
+//!
+//! Lookups are counted in a count-min sketch, a fixed-size table of small
+//! counters that may overestimate a key's count but never underestimates it.
+//! Counters are halved every `10 * width` lookups so that keys popular a long
+//! time ago lose their head start. The sketch is local to the node.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::keys::key_tenant;
+use crate::metrics::Metrics;
+
+/// Independent hash rows of the sketch
+const DEPTH: usize = 4;
+
+/// Lookups between agings, as a multiple of the sketch width
+const AGING_FACTOR: usize = 10;
+
+struct Sketch {
+    counters: Vec<u8>,
+    width: usize,
+    additions: usize,
+}
+
+impl Sketch {
+    fn slots(&self, key: &str) -> [usize; DEPTH] {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let h1 = hasher.finish();
+        0x5bd1_e995_u32.hash(&mut hasher);
+        let h2 = hasher.finish() | 1;
+
+        let width = self.width as u64;
+        std::array::from_fn(|row| {
+            let column = h1.wrapping_add((row as u64).wrapping_mul(h2)) % width;
+            row * self.width + column as usize
+        })
+    }
+
+    fn increment(&mut self, key: &str) {
+        for slot in self.slots(key) {
+            self.counters[slot] = self.counters[slot].saturating_add(1);
+        }
+
+        self.additions += 1;
+        if self.additions >= self.width * AGING_FACTOR {
+            self.counters.iter_mut().for_each(|counter| *counter /= 2);
+            self.additions = 0;
+        }
+    }
+
+    fn estimate(&self, key: &str) -> u8 {
+        self.slots(key)
+            .into_iter()
+            .map(|slot| self.counters[slot])
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// Lookup frequency sketch deciding which keys are worth caching
+#[derive(Clone)]
+pub struct Admission {
+    sketch: Arc<Mutex<Sketch>>,
+    min_accesses: u8,
+    /// Tenants with `admission_control` enabled
+    tenants: Arc<RwLock<HashSet<String>>>,
+    metrics: Option<Metrics>,
+}
+
+impl Admission {
+    /// Sketch with `width` counters per row, admitting keys looked up at
+    /// least `min_accesses` times
+    pub fn new(width: usize, min_accesses: u8) -> Self {
+        let width = width.max(1);
+        Self {
+            sketch: Arc::new(Mutex::new(Sketch {
+                counters: vec![0; width * DEPTH],
+                width,
+                additions: 0,
+            })),
+            min_accesses,
+            tenants: Arc::new(RwLock::new(HashSet::new())),
+            metrics: None,
+        }
+    }
+
+    /// Count keys kept out of the L1 in `scedge_admission_rejections_total`
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Turn admission control on or off for a tenant
+    pub fn set_tenant(&self, tenant: &str, enabled: bool) {
+        let mut tenants = self.tenants.write().unwrap_or_else(|e| e.into_inner());
+        if enabled {
+            tenants.insert(tenant.to_string());
+        } else {
+            tenants.remove(tenant);
+        }
+    }
+
+    /// Count a lookup of `key`
+    pub fn record_access(&self, key: &str) {
+        let mut sketch = self.sketch.lock().unwrap_or_else(|e| e.into_inner());
+        sketch.increment(key);
+    }
+
+    /// Estimated recent lookups of `key`, never an underestimate
+    pub fn accesses(&self, key: &str) -> u8 {
+        let sketch = self.sketch.lock().unwrap_or_else(|e| e.into_inner());
+        sketch.estimate(key)
+    }
+
+    /// Whether `key` has been looked up often enough to be cached
+    pub fn admits(&self, key: &str) -> bool {
+        let sketch = self.sketch.lock().unwrap_or_else(|e| e.into_inner());
+        sketch.estimate(key) >= self.min_accesses
+    }
+
+    /// Whether the L1 may hold `key`
+    ///
+    /// Keys of tenants without `admission_control` are always admitted.
+    /// Rejections are counted.
+    pub fn admits_into_l1(&self, key: &str) -> bool {
+        let tenant = key_tenant(key);
+        let enabled = self
+            .tenants
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(tenant);
+        if !enabled || self.admits(key) {
+            return true;
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.record_admission_rejection(tenant);
+        }
+        false
+    }
+}
@@ -17,28 +17,74 @@
 //! This module implements all REST API endpoints for the caching service:
 //!
 //! - `GET /healthz` - Service health check
+//! - `GET /readyz` - Per-component readiness
 //! - `GET /metrics` - Prometheus metrics export
 //! - `GET /lookup` - Retrieve cached artifacts
+//! - `POST /lookup/batch` - Retrieve many artifacts for one tenant
+//! - `GET /lookup/by-hash` - Find a tenant's artifacts by content hash
+//! - `GET /ttl` - Inspect a key's expiry without its payload
+//! - `POST /contains` - Check presence and hashes of many keys
 //! - `POST /store` - Store new artifacts
+//! - `POST /store/batch` - Store many artifacts, reporting failures per item
+//! - `POST /hash` - Compute the canonical hash of an answer
 //! - `POST /purge` - Remove cached artifacts
+//! - `POST /purge/schedules` - Register recurring purge rules
+//! - `POST /invalidate` - Publish a graph event onto the internal event bus
 //!
 //! All handlers enforce tenant isolation, policy validation, and observability.
 
-use axum::extract::{Query, State};
-use axum::http::HeaderMap;
+use axum::body::Bytes;
+use axum::extract::{FromRequestParts, MatchedPath, Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
 use axum::Json;
-use chrono::{Duration, Utc};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::broadcast;
 use tokio::time::Instant;
 
-use crate::cache::Cache;
+use crate::admin::require_admin;
+use crate::admission::Admission;
+use crate::cache::{escape_glob, Cache, SetOutcome, StoredEntry};
+use crate::config::TtlPrecedence;
+use crate::console::RecentInvalidations;
+use crate::crypto::Keyring;
+use crate::drain::Drain;
 use crate::error::AppError;
+use crate::events::{EventEnvelope, EventLagMonitor, EventPublisher, EventResync};
+use crate::hashing;
+use crate::keygen::KeyGenerator;
+use crate::keys::{key_namespace, key_tenant, strip_namespace, validate_key, validate_keys};
+use crate::logging::LogLevel;
 use crate::metrics::Metrics;
+use crate::mirror::Mirror;
 use crate::model::{
-    LookupQuery, LookupResponse, PurgeRequest, PurgeResponse, StoreRequest, StoreResponse,
-    StoreStatus,
+    ApiKeyRotationResponse, BatchItemError, BatchLookupRequest, BatchLookupResponse,
+    BatchStoreResult, CachedArtifact, ComponentHealth, ComponentStatus, Consistency,
+    ContainsRequest, ContainsResponse, DiffQuery, DiffResponse, HashLookupQuery,
+    HashLookupResponse, HashRequest, HashResponse, KeyPresence, LookupQuery, LookupResponse,
+    LookupTimings, PolicyEvaluateRequest, PolicyEvaluateResponse, ProvenanceDiff, PurgeRequest,
+    PurgeResponse, PurgeScheduleRequest, PurgeScheduleResponse, ReadinessResponse, ReadinessStatus,
+    RuleOutcome, StoreBatchRequest, StoreBatchResponse, StoreMode, StoreQuery, StoreRequest,
+    StoreResponse, StoreStatus, TouchBatchRequest, TouchBatchResponse, TouchRequest, TouchResponse,
+    TouchResult, TtlQuery, TtlResponse,
 };
-use crate::policy::PolicyEngine;
-use crate::upstream::UpstreamClient;
+use crate::outbox::{self, ArtifactEvent, OutboxMessage};
+use crate::peers::{PeerClient, PEER_HOP_HEADER};
+use crate::plugins::{PluginHook, PolicyPlugins};
+use crate::policy::{ExpiredReads, LookupStage, PolicyDenial, PolicyEngine, PolicyRule};
+use crate::rendered::RenderedLookup;
+use crate::scheduler::parse_schedule;
+use crate::singleflight::Singleflight;
+use crate::tenant::TenantContext;
+use crate::ttl_tuner::TtlTuner;
+use crate::upstream::{HttpFreshness, UpstreamClient, UpstreamRecord};
+use crate::wal;
+use crate::warmup::Warmup;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -47,6 +93,162 @@ pub struct AppState {
     pub policy: PolicyEngine,
     pub default_ttl_seconds: u64,
     pub upstream: Option<UpstreamClient>,
+    pub peers: Option<PeerClient>,
+    /// Shared client for proxy-mode origin requests
+    pub http: reqwest::Client,
+    pub admin_token: Option<String>,
+    pub xfetch_beta: f64,
+    pub event_bus: Option<async_nats::Client>,
+    /// Publishes graph events onto the event bus subject
+    pub event_publisher: Option<EventPublisher>,
+    pub graph_events: broadcast::Sender<EventEnvelope>,
+    /// Invalidation backlog sampler, when a lag threshold is configured
+    pub event_lag: Option<EventLagMonitor>,
+    /// Flush of node-local caches after dropped graph events
+    pub event_resync: EventResync,
+    /// Graph events shown in the operator console
+    pub recent_invalidations: RecentInvalidations,
+    /// Per-tenant encryption keys for answers at rest
+    pub keyring: Keyring,
+    pub ready_when_degraded: bool,
+    /// Reject stores whose algorithm-prefixed hash does not match the answer
+    pub verify_hashes: bool,
+    /// Report lookup phase timings to callers that ask for them
+    pub debug_timings: bool,
+    /// How long a rotated API key stays valid
+    pub api_key_grace: std::time::Duration,
+    /// Most keys a single batch lookup may ask for
+    pub max_batch_lookup_keys: usize,
+    /// Operator and tenant WASM policy hooks
+    pub plugins: PolicyPlugins,
+    /// Lookup frequencies for tenants with admission control
+    pub admission: Admission,
+    /// Reject writes, toggled by the control plane
+    pub read_only: Arc<AtomicBool>,
+    /// Runtime-adjustable log filter, when this process installed the subscriber
+    pub log_level: Option<LogLevel>,
+    /// Per-tenant default TTLs, when autotuning is enabled
+    pub ttl_tuner: Option<TtlTuner>,
+    /// Shadow instance receiving a sample of lookups
+    pub mirror: Option<Mirror>,
+    /// Upstream hydrations in flight, shared by concurrent misses of a key
+    pub hydrations: Singleflight<SharedHydration>,
+    /// In-flight request tracking and the shutdown drain deadline
+    pub drain: Drain,
+    /// Derives keys for stores that omit one
+    pub key_generator: Arc<dyn KeyGenerator>,
+    /// Startup warm-up holding back readiness
+    pub warmup: Warmup,
+}
+
+impl AppState {
+    /// TTL for a tenant's artifacts that carry no `ttl_seconds`
+    pub fn default_ttl_for(&self, tenant: &str) -> u64 {
+        match &self.ttl_tuner {
+            Some(ttl_tuner) => ttl_tuner.default_ttl_seconds(tenant),
+            None => self.default_ttl_seconds,
+        }
+    }
+}
+
+/// Header set on every response while the invalidation backlog is over threshold
+pub const DEGRADED_HEADER: &str = "x-scedge-degraded";
+
+/// Request header asking for diagnostics; `timings` adds [`LookupTimings`]
+pub const DEBUG_HEADER: &str = "x-scedge-debug";
+
+/// Whether the request's debug header lists `option`
+fn debug_requested(headers: &HeaderMap, option: &str) -> bool {
+    headers
+        .get_all(DEBUG_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|value| value.trim().eq_ignore_ascii_case(option))
+}
+
+/// Middleware flagging responses served while invalidations are lagging
+pub async fn mark_event_lag(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    if state
+        .event_lag
+        .as_ref()
+        .is_some_and(|monitor| monitor.is_lagging())
+    {
+        response
+            .headers_mut()
+            .insert(DEGRADED_HEADER, HeaderValue::from_static("event-lag"));
+    }
+    response
+}
+
+/// Routes that mutate the cache or tenant state
+const WRITE_ROUTES: &[&str] = &[
+    "/store",
+    "/store/batch",
+    "/touch",
+    "/touch/batch",
+    "/purge",
+    "/purge/schedules",
+    "/tenant/keys/rotate",
+    "/invalidate",
+    "/admin/tenants/:id/data",
+    "/admin/tenants/:id/keys",
+    "/admin/tenants/:id/dictionary",
+];
+
+/// Middleware rejecting writes with `503` while the node is read-only
+pub async fn enforce_read_only(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let is_write = request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|path| WRITE_ROUTES.contains(&path.as_str()));
+    if is_write && state.read_only.load(Ordering::Acquire) {
+        return Err(AppError::unavailable("node is read-only"));
+    }
+    Ok(next.run(request).await)
+}
+
+/// Middleware attributing the request's cache mutations in the write-ahead log
+pub async fn record_actor(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let actor = match TenantContext::from_request_parts(&mut parts, &state).await {
+        Ok(ctx) => match ctx.caller() {
+            Some(tenant) => format!("tenant:{}", tenant),
+            None => "anonymous".to_string(),
+        },
+        Err(_) => "anonymous".to_string(),
+    };
+    wal::with_actor(actor, next.run(Request::from_parts(parts, body))).await
+}
+
+/// Middleware publishing a `POLICY_DENIED` event for every policy rejection
+pub async fn track_policy_denials(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let endpoint = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let response = next.run(request).await;
+
+    if let Some(denial) = response.extensions().get::<PolicyDenial>() {
+        state.policy.publish_denial(denial.clone(), &endpoint);
+    }
+
+    response
 }
 
 /// Health check endpoint
@@ -58,33 +260,296 @@ pub async fn health() -> Result<Json<serde_json::Value>, AppError> {
     })))
 }
 
+/// Readiness endpoint reporting per-component status and latency
+///
+/// Redis is required; an unavailable optional subsystem (event bus, upstream)
+/// reports `degraded`, which still counts as ready unless
+/// `SCEDGE_READY_WHEN_DEGRADED=false`.
+pub async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let redis = async {
+        let start = Instant::now();
+        component_health(state.cache.ping().await, start)
+    };
+
+    let event_bus = async {
+        match &state.event_bus {
+            Some(client) => {
+                let start = Instant::now();
+                let result =
+                    if client.connection_state() == async_nats::connection::State::Connected {
+                        client.flush().await.map_err(|e| {
+                            AppError::Internal(anyhow::anyhow!("NATS flush failed: {}", e))
+                        })
+                    } else {
+                        Err(AppError::Internal(anyhow::anyhow!(
+                            "NATS connection is {}",
+                            client.connection_state()
+                        )))
+                    };
+                component_health(result, start)
+            }
+            None => disabled_component(),
+        }
+    };
+
+    let upstream = async {
+        match &state.upstream {
+            Some(upstream) => {
+                let start = Instant::now();
+                component_health(upstream.health().await, start)
+            }
+            None => disabled_component(),
+        }
+    };
+
+    let (redis, event_bus, upstream) = tokio::join!(redis, event_bus, upstream);
+
+    let event_lag = match &state.event_lag {
+        Some(monitor) if monitor.is_lagging() => ComponentHealth {
+            status: ComponentStatus::Down,
+            latency_ms: None,
+            error: Some(format!(
+                "{} pending invalidation events exceed threshold {}",
+                monitor.pending(),
+                monitor.threshold()
+            )),
+        },
+        Some(_) => ComponentHealth {
+            status: ComponentStatus::Up,
+            latency_ms: None,
+            error: None,
+        },
+        None => disabled_component(),
+    };
+    let event_resync = if state.event_resync.is_resyncing() {
+        ComponentHealth {
+            status: ComponentStatus::Down,
+            latency_ms: None,
+            error: Some("Flushing node-local caches after dropped graph events".to_string()),
+        }
+    } else {
+        ComponentHealth {
+            status: ComponentStatus::Up,
+            latency_ms: None,
+            error: None,
+        }
+    };
+    let lag_fails_readiness = state
+        .event_lag
+        .as_ref()
+        .is_some_and(|monitor| monitor.fail_readiness() && monitor.is_lagging());
+
+    let optional_down = [&event_bus, &upstream, &event_lag]
+        .iter()
+        .any(|component| component.status == ComponentStatus::Down);
+
+    let warmup = if state.warmup.is_warming() {
+        ComponentHealth {
+            status: ComponentStatus::Down,
+            latency_ms: None,
+            error: Some(format!("{} keys left to warm up", state.warmup.remaining())),
+        }
+    } else if state.warmup.is_enabled() {
+        ComponentHealth {
+            status: ComponentStatus::Up,
+            latency_ms: None,
+            error: None,
+        }
+    } else {
+        disabled_component()
+    };
+
+    let status = if redis.status == ComponentStatus::Down
+        || lag_fails_readiness
+        || state.drain.is_draining()
+        || state.warmup.is_warming()
+        || state.event_resync.is_resyncing()
+    {
+        ReadinessStatus::NotReady
+    } else if optional_down {
+        if state.ready_when_degraded {
+            ReadinessStatus::Degraded
+        } else {
+            ReadinessStatus::NotReady
+        }
+    } else {
+        ReadinessStatus::Ready
+    };
+
+    let mut components = BTreeMap::new();
+    components.insert("redis".to_string(), redis);
+    components.insert("event_bus".to_string(), event_bus);
+    components.insert("event_lag".to_string(), event_lag);
+    components.insert("event_resync".to_string(), event_resync);
+    components.insert("upstream".to_string(), upstream);
+    components.insert("warmup".to_string(), warmup);
+    // No vector index is wired into this build yet
+    components.insert("vector_index".to_string(), disabled_component());
+
+    let code = match status {
+        ReadinessStatus::NotReady => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+
+    (code, Json(ReadinessResponse { status, components }))
+}
+
+fn component_health(result: Result<(), AppError>, start: Instant) -> ComponentHealth {
+    let latency_ms = Some(start.elapsed().as_secs_f64() * 1000.0);
+    match result {
+        Ok(()) => ComponentHealth {
+            status: ComponentStatus::Up,
+            latency_ms,
+            error: None,
+        },
+        Err(err) => ComponentHealth {
+            status: ComponentStatus::Down,
+            latency_ms,
+            error: Some(match err {
+                AppError::Internal(inner) => inner.to_string(),
+                other => other.to_string(),
+            }),
+        },
+    }
+}
+
+fn disabled_component() -> ComponentHealth {
+    ComponentHealth {
+        status: ComponentStatus::Disabled,
+        latency_ms: None,
+        error: None,
+    }
+}
+
 /// Metrics endpoint
 pub async fn metrics(State(state): State<AppState>) -> Result<String, AppError> {
     state.metrics.export()
 }
 
+/// Compute the canonical hash producers should send with an answer
+pub async fn handle_hash(Json(request): Json<HashRequest>) -> Json<HashResponse> {
+    Json(HashResponse {
+        algorithm: request.algorithm,
+        hash: hashing::artifact_hash(&request.answer, request.algorithm),
+    })
+}
+
 /// Store an artifact in the cache
 pub async fn handle_store(
     State(state): State<AppState>,
+    ctx: TenantContext,
+    Query(mut query): Query<StoreQuery>,
     headers: HeaderMap,
     Json(request): Json<StoreRequest>,
 ) -> Result<Json<StoreResponse>, AppError> {
+    if let Some(if_match) = headers.get(header::IF_MATCH) {
+        if query.mode != StoreMode::Always {
+            return Err(AppError::bad_request(
+                "If-Match cannot be combined with a store mode",
+            ));
+        }
+        let version = if_match
+            .to_str()
+            .map_err(|_| AppError::bad_request("invalid If-Match header"))?;
+        query.if_match = Some(version.parse()?);
+    }
+
+    store_artifact(&state, &ctx, &query, request)
+        .await
+        .map(Json)
+}
+
+/// Store many artifacts, each validated and stored like a `POST /store`
+///
+/// A failing item does not fail the batch: it is reported in `errors` with
+/// its index, key, and error code, and the response is `207 Multi-Status`
+/// unless every item was stored. Policy denials of items are still published
+/// as `POLICY_DENIED` events.
+pub async fn handle_store_batch(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Query(query): Query<StoreQuery>,
+    Json(request): Json<StoreBatchRequest>,
+) -> Result<(StatusCode, Json<StoreBatchResponse>), AppError> {
+    if request.items.is_empty() {
+        return Err(AppError::bad_request("items must not be empty"));
+    }
+
+    let mut stored = Vec::new();
+    let mut errors = Vec::new();
+    for (index, item) in request.items.into_iter().enumerate() {
+        let key = item
+            .get("key")
+            .and_then(|key| key.as_str())
+            .filter(|key| !key.is_empty())
+            .map(str::to_string);
+        let result = match serde_json::from_value::<StoreRequest>(item) {
+            Ok(item) => store_artifact(&state, &ctx, &query, item).await,
+            Err(e) => Err(AppError::bad_request(format!("invalid item: {}", e))),
+        };
+        match result {
+            Ok(response) => stored.push(BatchStoreResult { index, response }),
+            Err(err) => {
+                if let AppError::PolicyDenied { tenant, rule, .. } = &err {
+                    let denial = PolicyDenial {
+                        tenant: tenant.clone(),
+                        rule: *rule,
+                    };
+                    state.policy.publish_denial(denial, "/store/batch");
+                }
+                errors.push(BatchItemError::new(index, key, &err));
+            }
+        }
+    }
+
+    Ok((
+        batch_status(&errors),
+        Json(StoreBatchResponse { stored, errors }),
+    ))
+}
+
+/// `200 OK` for a fully successful batch, `207 Multi-Status` otherwise
+fn batch_status(errors: &[BatchItemError]) -> StatusCode {
+    if errors.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::MULTI_STATUS
+    }
+}
+
+async fn store_artifact(
+    state: &AppState,
+    ctx: &TenantContext,
+    query: &StoreQuery,
+    mut request: StoreRequest,
+) -> Result<StoreResponse, AppError> {
     // Validate inputs
-    if request.key.trim().is_empty() {
-        return Err(AppError::bad_request("key is required"));
+    if request.key.is_empty() {
+        request.key = state.key_generator.generate(&request.artifact);
     }
+    validate_key(&request.key)?;
+    request.key = ctx.scope_key(&request.key)?;
 
     if request.artifact.hash.trim().is_empty() {
         return Err(AppError::bad_request("artifact hash is required"));
     }
 
-    let tenant_id = &request.artifact.policy.tenant;
+    if state.verify_hashes
+        && hashing::verify(&request.artifact.hash, &request.artifact.answer) == Some(false)
+    {
+        return Err(AppError::bad_request("artifact hash does not match answer"));
+    }
 
-    // Validate API key if provided
-    if let Some(api_key) = headers.get("x-api-key").and_then(|h| h.to_str().ok()) {
-        state.policy.validate_api_key(tenant_id, api_key).await?;
+    if let Some(content_type) = request.artifact.answer_content_type {
+        content_type.encode(&request.artifact.answer)?;
     }
 
+    let tenant_id = request.artifact.policy.tenant.clone();
+    let tenant_id = &tenant_id;
+
+    // Validate API key if provided
+    ctx.authorize(&state.policy, tenant_id).await?;
+
     // Validate TTL against tenant limits
     state
         .policy
@@ -107,11 +572,28 @@ pub async fn handle_store(
         )
         .await?;
 
+    if state.policy.read_only(tenant_id).await {
+        return Err(AppError::unavailable("tenant is read-only"));
+    }
+
+    // Enforce provenance and metadata limits
+    state
+        .policy
+        .enforce_entry_limits(tenant_id, &mut request.artifact)
+        .await?;
+
+    state
+        .plugins
+        .check(PluginHook::Store, &request.key, &request.artifact)
+        .await?;
+
+    let _permit = state.policy.acquire_bulkhead(tenant_id).await?;
+
     // Calculate expiration
     let ttl_seconds = request
         .artifact
         .ttl_seconds
-        .unwrap_or(state.default_ttl_seconds);
+        .unwrap_or_else(|| state.default_ttl_for(tenant_id));
     let expires_at = if ttl_seconds > 0 {
         Some(Utc::now() + Duration::seconds(ttl_seconds as i64))
     } else {
@@ -119,218 +601,1688 @@ pub async fn handle_store(
     };
 
     // Store in cache
-    let cached = state
+    let mode = query.store_mode();
+    let (cached, status) = match state
         .cache
-        .set(request.key.clone(), request.artifact, expires_at)
-        .await?;
+        .set_conditional(request.key.clone(), request.artifact, expires_at, mode)
+        .await?
+    {
+        SetOutcome::Created(cached) => (cached, StoreStatus::Created),
+        SetOutcome::Updated(cached) => (cached, StoreStatus::Updated),
+        SetOutcome::Kept(existing) if matches!(mode, StoreMode::IfMatch(_)) => {
+            return Err(AppError::precondition_failed(format!(
+                "version mismatch: entry is at version {}",
+                existing.version()
+            )));
+        }
+        SetOutcome::Kept(existing) => {
+            return Ok(StoreResponse {
+                key: ctx.unscope_key(&existing.key),
+                status: StoreStatus::Unchanged,
+                version: Some(existing.version().to_string()),
+                hash: existing.artifact.hash,
+                expires_at: existing.expires_at,
+            });
+        }
+    };
 
     // Record metrics
     state.metrics.record_cache_store();
 
-    let response = StoreResponse {
-        key: cached.key,
-        status: StoreStatus::Created,
+    if state
+        .policy
+        .serve_stale_on_error(&cached.artifact.policy.tenant)
+        .await
+    {
+        state.cache.remember_stale(&cached);
+    }
+
+    if query.notify {
+        let webhooks = state
+            .policy
+            .get_tenant(&cached.artifact.policy.tenant)
+            .await
+            .map(|tenant| tenant.webhooks)
+            .unwrap_or_default();
+
+        let message = OutboxMessage {
+            event: ArtifactEvent::ArtifactStored {
+                tenant: cached.artifact.policy.tenant.clone(),
+                key: cached.key.clone(),
+                hash: cached.artifact.hash.clone(),
+                stored_at: cached.stored_at,
+                expires_at: cached.expires_at,
+            },
+            webhooks,
+        };
+        outbox::enqueue(&state.cache, &message).await?;
+    }
+
+    Ok(StoreResponse {
+        key: ctx.unscope_key(&cached.key),
+        status,
         hash: cached.artifact.hash.clone(),
+        version: Some(cached.version().to_string()),
         expires_at: cached.expires_at,
-    };
-
-    Ok(Json(response))
+    })
 }
 
 /// Lookup an artifact from the cache
+///
+/// Misses are resolved through the tenant's `lookup_pipeline`, in order. A
+/// failing stage falls through to the next one; the error is returned only
+/// when no later stage finds the key.
+///
+/// With `raw=true` the body is the bare answer, served with its
+/// `answer_content_type`, instead of the JSON envelope.
 pub async fn handle_lookup(
     State(state): State<AppState>,
     headers: HeaderMap,
-    Query(query): Query<LookupQuery>,
-) -> Result<Json<LookupResponse>, AppError> {
+    ctx: TenantContext,
+    Query(mut query): Query<LookupQuery>,
+) -> Result<Response, AppError> {
     if query.key.trim().is_empty() {
         return Err(AppError::bad_request("key query parameter is required"));
     }
+    validate_key(&query.key)?;
+    query.key = ctx.scope_key(&query.key)?;
 
-    // Attempt to get from cache
-    match state.cache.get(&query.key).await? {
-        Some(record) => {
-            let tenant_id = &record.artifact.policy.tenant;
+    state.admission.record_access(&query.key);
 
-            // If tenant is specified in query, validate it matches
-            if let Some(requested_tenant) = &query.tenant {
-                if requested_tenant != tenant_id {
-                    state.metrics.record_cache_miss();
-                    return Err(AppError::not_found("cache miss"));
-                }
+    let report_timings = state.debug_timings && debug_requested(&headers, "timings");
+    let mut timings = LookupTimings::default();
+    let policy_start = Instant::now();
+
+    let bulkhead_tenant = query
+        .tenant
+        .as_deref()
+        .unwrap_or_else(|| key_tenant(&query.key));
+    ctx.authorize(&state.policy, bulkhead_tenant).await?;
+    let _permit = state.policy.acquire_bulkhead(bulkhead_tenant).await?;
+
+    let pipeline = state.policy.lookup_pipeline(bulkhead_tenant).await;
+    timings.policy_ms += elapsed_ms(policy_start);
+
+    if pipeline.first() == Some(&LookupStage::Cache)
+        && query.consistency == Consistency::Eventual
+        && !query.raw
+        && query.template.is_none()
+        && !report_timings
+    {
+        if let Some(response) = rendered_lookup(&state, &ctx, &query).await? {
+            return Ok(response);
+        }
+    }
+
+    // Requests from sibling nodes only consult the local cache
+    let peer_hop = headers.contains_key(PEER_HOP_HEADER);
+
+    let mut last_error = None;
+    let mut expired = None;
+    for stage in pipeline {
+        let stage_start = Instant::now();
+        let result = match stage {
+            LookupStage::Cache => {
+                let result =
+                    lookup_cache_stage(&state, &ctx, &query, bulkhead_tenant, &mut expired).await;
+                timings.backend_ms += elapsed_ms(stage_start);
+                result
+            }
+            _ if peer_hop => continue,
+            // Siblings are asked for plain keys, which would cross namespaces
+            LookupStage::Peers if ctx.namespace().is_some() => continue,
+            LookupStage::Peers => {
+                let result = lookup_peers_stage(&state, &ctx, &query).await;
+                timings.upstream_ms += elapsed_ms(stage_start);
+                result
+            }
+            LookupStage::Upstream => {
+                let result = lookup_upstream_stage(&state, &ctx, &query).await;
+                timings.upstream_ms += elapsed_ms(stage_start);
+                result
             }
+        };
 
-            // Validate API key if provided
-            if let Some(api_key) = headers.get("x-api-key").and_then(|h| h.to_str().ok()) {
-                state.policy.validate_api_key(tenant_id, api_key).await?;
+        match result {
+            Ok(Some((headers, Json(response)))) => {
+                if expired.is_some() {
+                    state.metrics.record_expired_read("refreshed");
+                }
+                let timings = report_timings.then_some(timings);
+                return respond_to_lookup(&state, &ctx, &query, stage, headers, response, timings)
+                    .await;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                tracing::warn!(key = %query.key, stage = ?stage, error = %err, "Lookup stage failed");
+                last_error = Some(err);
             }
+        }
+    }
+
+    // Nothing newer than the expired entry kept for `expired_reads: refresh`
+    if let Some((headers, Json(response))) = expired {
+        if let Some(err) = &last_error {
+            tracing::warn!(key = %query.key, error = %err, "Refresh of expired entry failed, serving it stale");
+        }
+        state.metrics.record_expired_read("stale");
+        let timings = report_timings.then_some(timings);
+        return respond_to_lookup(
+            &state,
+            &ctx,
+            &query,
+            LookupStage::Cache,
+            headers,
+            response,
+            timings,
+        )
+        .await;
+    }
+
+    Err(last_error.unwrap_or_else(|| AppError::not_found("cache miss")))
+}
+
+/// Check and shape the answer `stage` found into the lookup response
+///
+/// `timings`, when requested, are reported with the time spent on plugins added.
+async fn respond_to_lookup(
+    state: &AppState,
+    ctx: &TenantContext,
+    query: &LookupQuery,
+    stage: LookupStage,
+    headers: HeaderMap,
+    mut response: LookupResponse,
+    mut timings: Option<LookupTimings>,
+) -> Result<Response, AppError> {
+    ctx.authorize_read(&response.artifact.policy)?;
+    response.key = ctx.unscope_key(&response.key);
+
+    let plugin_start = Instant::now();
+    state
+        .plugins
+        .check(PluginHook::Lookup, &query.key, &response.artifact)
+        .await?;
+    if let Some(timings) = &mut timings {
+        timings.policy_ms += elapsed_ms(plugin_start);
+    }
+
+    if let Some(name) = &query.template {
+        let template = state
+            .policy
+            .answer_template(&response.artifact.policy.tenant, name)
+            .await
+            .ok_or_else(|| AppError::bad_request(format!("unknown answer template {}", name)))?;
+        response.artifact.answer = template.render(&response.artifact.answer);
+        response.artifact.answer_content_type = None;
+    }
+
+    if query.raw {
+        return raw_answer(headers, &response);
+    }
+    if stage == LookupStage::Cache
+        && timings.is_none()
+        && query.template.is_none()
+        && !headers.contains_key(STALE_ERROR_HEADER)
+    {
+        if let Some(rendered) = render_hot_lookup(state, &query.key, headers.clone(), &response)? {
+            return Ok(rendered);
+        }
+    }
+    response.timings = timings;
+    Ok((headers, Json(response)).into_response())
+}
+
+/// Answer a lookup from the key's rendered response, if it has one
+///
+/// The same tenant, age, authorization, and read ACL checks apply as on a
+/// cache hit; only serialization is skipped.
+async fn rendered_lookup(
+    state: &AppState,
+    ctx: &TenantContext,
+    query: &LookupQuery,
+) -> Result<Option<Response>, AppError> {
+    let Some(rendered) = state.cache.rendered() else {
+        return Ok(None);
+    };
+    let now = Utc::now();
+    let Some(lookup) = rendered.get(&query.key, now) else {
+        return Ok(None);
+    };
+
+    let tenant_id = &lookup.policy.tenant;
+    if query
+        .tenant
+        .as_ref()
+        .is_some_and(|requested| requested != tenant_id)
+        || query
+            .max_age
+            .is_some_and(|max_age| (now - lookup.stored_at).num_seconds() > max_age as i64)
+        || state.plugins.applies_to(tenant_id)
+    {
+        return Ok(None);
+    }
+
+    ctx.authorize(&state.policy, tenant_id).await?;
+    ctx.authorize_read(&lookup.policy)?;
+
+    state.metrics.record_cache_hit();
+    state.metrics.record_tenant_lookup(tenant_id, true);
+    state.metrics.record_compute_cost_saved(lookup.compute_cost);
+
+    if should_refresh_early(state, lookup.expires_at, now) {
+        spawn_early_refresh(state.clone(), query.key.clone(), tenant_id.clone());
+    }
+
+    Ok(Some(
+        (
+            lookup.headers.clone(),
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            )],
+            lookup.body.clone(),
+        )
+            .into_response(),
+    ))
+}
 
-            state.metrics.record_cache_hit();
+/// Serialize a cache hit of a hot key once, keeping the bytes for later lookups
+///
+/// `key` is the stored key, which differs from `response.key` in namespaces.
+/// Returns `None` when rendering is disabled, the key is not hot yet, or
+/// plugins check the tenant's artifacts on every lookup.
+fn render_hot_lookup(
+    state: &AppState,
+    key: &str,
+    headers: HeaderMap,
+    response: &LookupResponse,
+) -> Result<Option<Response>, AppError> {
+    let Some(rendered) = state.cache.rendered() else {
+        return Ok(None);
+    };
+    let policy = &response.artifact.policy;
+    if state.admission.accesses(key) < rendered.min_hits()
+        || state.plugins.applies_to(&policy.tenant)
+    {
+        return Ok(None);
+    }
+    let Some(stored_at) = response.stored_at else {
+        return Ok(None);
+    };
+
+    let body =
+        Bytes::from(serde_json::to_vec(response).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to serialize lookup: {}", e))
+        })?);
+    rendered.insert(
+        key.to_string(),
+        RenderedLookup {
+            policy: policy.clone(),
+            stored_at,
+            expires_at: response.expires_at,
+            compute_cost: response.artifact.compute_cost(),
+            ttl_remaining_seconds: response.ttl_remaining_seconds,
+            headers: headers.clone(),
+            body: body.clone(),
+        },
+    );
+
+    Ok(Some(
+        (
+            headers,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            )],
+            body,
+        )
+            .into_response(),
+    ))
+}
+
+/// Bare answer of a lookup, keeping the freshness and cache status headers
+fn raw_answer(mut headers: HeaderMap, response: &LookupResponse) -> Result<Response, AppError> {
+    let content_type = response.artifact.answer_content_type.unwrap_or_default();
+    let body = content_type
+        .encode(&response.artifact.answer)
+        .map_err(|e| {
+            AppError::Internal(anyhow::anyhow!(
+                "Cached answer of {} does not match its content type: {}",
+                response.key,
+                e
+            ))
+        })?;
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(content_type.header_value()),
+    );
+    Ok((headers, body).into_response())
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Report a key's expiry without transferring the artifact
+pub async fn handle_ttl(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Query(query): Query<TtlQuery>,
+) -> Result<Json<TtlResponse>, AppError> {
+    validate_key(&query.key)?;
+    let key = ctx.scope_key(&query.key)?;
+
+    let metadata = state
+        .cache
+        .metadata(&key)
+        .await?
+        .filter(|metadata| match &query.tenant {
+            Some(requested) => *requested == metadata.tenant,
+            None => true,
+        })
+        .ok_or_else(|| AppError::not_found("key not cached"))?;
+
+    ctx.authorize(&state.policy, &metadata.tenant).await?;
+
+    let now = Utc::now();
+    Ok(Json(TtlResponse {
+        key: query.key,
+        ttl_remaining_seconds: metadata
+            .expires_at
+            .map(|exp| (exp - now).num_seconds().max(0) as u64),
+        age_seconds: (now - metadata.stored_at).num_seconds().max(0) as u64,
+        tenant: metadata.tenant,
+        stored_at: metadata.stored_at,
+        expires_at: metadata.expires_at,
+        ttl_seconds: metadata.ttl_seconds,
+    }))
+}
+
+/// Compare a cached artifact's hash and provenance against an expected hash
+///
+/// Answers "why is the edge serving the old version": the cached hash, whether
+/// the cached answer hashes to `against_hash`, and which provenance entries
+/// carry a different hash.
+pub async fn handle_diff(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Query(query): Query<DiffQuery>,
+) -> Result<Json<DiffResponse>, AppError> {
+    validate_key(&query.key)?;
+    if query.against_hash.trim().is_empty() {
+        return Err(AppError::bad_request(
+            "against_hash query parameter is required",
+        ));
+    }
+    let key = ctx.scope_key(&query.key)?;
+
+    let cached = state
+        .cache
+        .get(&key)
+        .await?
+        .filter(|record| match &query.tenant {
+            Some(requested) => *requested == record.artifact.policy.tenant,
+            None => true,
+        })
+        .ok_or_else(|| AppError::not_found("key not cached"))?;
+
+    ctx.authorize(&state.policy, &cached.artifact.policy.tenant)
+        .await?;
+    ctx.authorize_read(&cached.artifact.policy)?;
+
+    let against_hash = query.against_hash;
+    let differs_from = |hash: &str| !hash.eq_ignore_ascii_case(&against_hash);
+    let CachedArtifact {
+        artifact,
+        stored_at,
+        expires_at,
+        ..
+    } = cached;
+
+    Ok(Json(DiffResponse {
+        key: query.key,
+        tenant: artifact.policy.tenant,
+        differs: differs_from(&artifact.hash),
+        answer_matches: hashing::verify(&against_hash, &artifact.answer),
+        hash: artifact.hash,
+        stored_at,
+        expires_at,
+        provenance: artifact
+            .provenance
+            .into_iter()
+            .map(|entry| ProvenanceDiff {
+                differs: entry.hash.as_deref().map(differs_from),
+                entry,
+            })
+            .collect(),
+        against_hash,
+    }))
+}
+
+type LookupResult = Result<Option<(HeaderMap, Json<LookupResponse>)>, AppError>;
+
+/// Serve the key from the local cache
+async fn lookup_cache_stage(
+    state: &AppState,
+    ctx: &TenantContext,
+    query: &LookupQuery,
+    bulkhead_tenant: &str,
+    expired: &mut Option<(HeaderMap, Json<LookupResponse>)>,
+) -> LookupResult {
+    let expired_reads = state.policy.expired_reads(bulkhead_tenant).await;
+    let (cached, is_expired) = match state.cache.read(&query.key, query.consistency).await {
+        Ok(Some(StoredEntry::Live(record))) => (Some(record), false),
+        Ok(Some(StoredEntry::Expired(record))) if expired_reads == ExpiredReads::Miss => {
+            state.metrics.record_expired_read("miss");
+            // Best effort; the native TTL or a sweep removes it otherwise
+            let _ = state.cache.discard_expired(&record).await;
+            (None, false)
+        }
+        Ok(Some(StoredEntry::Expired(record))) => (Some(record), true),
+        Ok(None) => (None, false),
+        Err(err) => {
+            record_backend_failure(state, bulkhead_tenant, &err);
+            return serve_stale_on_error(state, ctx, query, bulkhead_tenant, err).await;
+        }
+    };
+
+    // Entries older than max_age count as misses
+    let cached = cached
+        .filter(|record| match query.max_age {
+            Some(max_age) => record.is_within_age(max_age, Utc::now()),
+            None => true,
+        })
+        .filter(|record| match &query.tenant {
+            Some(requested) => *requested == record.artifact.policy.tenant,
+            None => true,
+        });
+
+    let Some(record) = cached else {
+        state.metrics.record_cache_miss();
+        state.metrics.record_tenant_lookup(bulkhead_tenant, false);
+        return Ok(None);
+    };
+
+    let tenant_id = &record.artifact.policy.tenant;
+
+    // Validate API key if provided
+    ctx.authorize(&state.policy, tenant_id).await?;
+
+    if is_expired {
+        let response = record.into_lookup_response(Utc::now());
+        let mut headers = freshness_headers(&response);
+        headers.insert(STALE_ERROR_HEADER, HeaderValue::from_static("STALE"));
+        if expired_reads == ExpiredReads::Refresh {
+            // Later stages look for a fresh answer before this one is served
+            *expired = Some((headers, Json(response)));
+            return Ok(None);
+        }
+        state.metrics.record_expired_read("stale");
+        return Ok(Some((headers, Json(response))));
+    }
+
+    state.metrics.record_cache_hit();
+    state.metrics.record_tenant_lookup(tenant_id, true);
+    state
+        .metrics
+        .record_compute_cost_saved(record.artifact.compute_cost());
+
+    if should_refresh_early(state, record.expires_at, Utc::now()) {
+        spawn_early_refresh(state.clone(), record.key.clone(), tenant_id.clone());
+    }
+
+    if state.policy.serve_stale_on_error(tenant_id).await {
+        state.cache.remember_stale(&record);
+    }
+
+    let response = record.into_lookup_response(Utc::now());
+    Ok(Some((freshness_headers(&response), Json(response))))
+}
+
+/// Count a failed cache read against the backend rather than as a miss
+fn record_backend_failure(state: &AppState, tenant: &str, err: &AppError) {
+    if matches!(err, AppError::BackendUnavailable(_)) {
+        state.metrics.record_backend_unavailable(tenant);
+    }
+}
+
+/// Header marking a lookup answered from a stale copy after a backend failure
+/// (`STALE-ERROR`) or from an expired entry (`STALE`)
+pub const STALE_ERROR_HEADER: &str = "x-cache";
+
+/// Answer a failed backend read from the key's last-known-good copy
+///
+/// Only for tenants with `serve_stale_on_error`; otherwise, or without a copy,
+/// the backend error is returned.
+async fn serve_stale_on_error(
+    state: &AppState,
+    ctx: &TenantContext,
+    query: &LookupQuery,
+    bulkhead_tenant: &str,
+    err: AppError,
+) -> LookupResult {
+    if !state.policy.serve_stale_on_error(bulkhead_tenant).await {
+        return Err(err);
+    }
+    let Some(record) = state.cache.stale_copy(&query.key).filter(|record| {
+        query
+            .tenant
+            .as_ref()
+            .is_none_or(|requested| *requested == record.artifact.policy.tenant)
+    }) else {
+        return Err(err);
+    };
+
+    ctx.authorize(&state.policy, &record.artifact.policy.tenant)
+        .await?;
+    tracing::warn!(key = %query.key, error = %err, "Backend read failed, serving stale copy");
+    state.metrics.record_stale_on_error();
+
+    let response = record.into_lookup_response(Utc::now());
+    let mut headers = freshness_headers(&response);
+    headers.insert(STALE_ERROR_HEADER, HeaderValue::from_static("STALE-ERROR"));
+    Ok(Some((headers, Json(response))))
+}
+
+/// Fetch the key from sibling nodes and cache it locally
+async fn lookup_peers_stage(
+    state: &AppState,
+    ctx: &TenantContext,
+    query: &LookupQuery,
+) -> LookupResult {
+    let Some(peers) = &state.peers else {
+        return Ok(None);
+    };
+
+    let peer_record = peers
+        .lookup(&query.key, query.tenant.as_deref())
+        .await
+        .filter(|record| match &query.tenant {
+            Some(requested) => *requested == record.artifact.policy.tenant,
+            None => true,
+        });
+    state.metrics.record_peer_lookup(peer_record.is_some());
+
+    let Some(peer_record) = peer_record else {
+        return Ok(None);
+    };
+
+    let tenant_id = &peer_record.artifact.policy.tenant;
+    ctx.authorize(&state.policy, tenant_id).await?;
+
+    let expires_at = upstream_expiry(
+        &peer_record,
+        None,
+        TtlPrecedence::Artifact,
+        state.default_ttl_for(tenant_id),
+    );
+    let (cached, stored) = state
+        .cache
+        .get_or_set(query.key.clone(), peer_record.artifact, expires_at)
+        .await?;
+
+    if stored {
+        state.metrics.record_cache_store();
+        tracing::debug!(key = %cached.key, "cached artifact from peer");
+    }
+
+    let response = cached.into_lookup_response(Utc::now());
+    Ok(Some((freshness_headers(&response), Json(response))))
+}
+
+/// Hydrate the key from the upstream graph and cache it
+async fn lookup_upstream_stage(
+    state: &AppState,
+    ctx: &TenantContext,
+    query: &LookupQuery,
+) -> LookupResult {
+    let Some(upstream) = &state.upstream else {
+        return Ok(None);
+    };
+
+    let tenant_id = query
+        .tenant
+        .as_deref()
+        .unwrap_or_else(|| key_tenant(&query.key));
+    let result = hydrate(
+        state,
+        upstream,
+        tenant_id,
+        &query.key,
+        &ctx.unscope_key(&query.key),
+        query.tenant.as_deref(),
+        false,
+    )
+    .await;
+
+    let UpstreamRecord {
+        record: upstream_record,
+        freshness,
+    } = match result {
+        Ok(Some(record)) => record,
+        Ok(None) => return Ok(None),
+        Err(err) => {
+            state.metrics.record_upstream_failure();
+            return Err(err);
+        }
+    };
 
-            let now = Utc::now();
-            let ttl_remaining = record.ttl_remaining_seconds(now);
+    let tenant_id = &upstream_record.artifact.policy.tenant;
 
-            let response = LookupResponse {
-                key: record.key,
-                artifact: record.artifact,
-                expires_at: record.expires_at,
-                ttl_remaining_seconds: ttl_remaining,
+    if let Some(requested_tenant) = &query.tenant {
+        if requested_tenant != tenant_id {
+            tracing::warn!(
+                requested = %requested_tenant,
+                upstream = %tenant_id,
+                key = %query.key,
+                "Tenant mismatch between request and upstream response",
+            );
+            state.metrics.record_upstream_failure();
+            return Ok(None);
+        }
+    }
+
+    ctx.authorize(&state.policy, tenant_id).await?;
+
+    let expires_at = upstream_expiry(
+        &upstream_record,
+        freshness.ttl_seconds,
+        upstream.ttl_precedence(),
+        state.default_ttl_for(tenant_id),
+    );
+
+    // Serve responses the upstream marked uncacheable without storing them
+    if !is_cacheable(&freshness, expires_at) {
+        tracing::debug!(key = %query.key, "upstream response not cacheable");
+        return Ok(Some((
+            freshness_headers(&upstream_record),
+            Json(upstream_record),
+        )));
+    }
+
+    // Low-confidence answers pass through rather than being re-served from cache
+    if !state
+        .policy
+        .meets_cache_score(&upstream_record.artifact)
+        .await
+    {
+        tracing::debug!(key = %query.key, "upstream artifact below cache score threshold");
+        state.metrics.record_low_score_bypass();
+        return Ok(Some((
+            freshness_headers(&upstream_record),
+            Json(upstream_record),
+        )));
+    }
+
+    if state.read_only.load(Ordering::Acquire)
+        || state
+            .policy
+            .read_only(&upstream_record.artifact.policy.tenant)
+            .await
+    {
+        tracing::debug!(key = %query.key, "read-only node or tenant, upstream artifact not cached");
+        return Ok(Some((
+            freshness_headers(&upstream_record),
+            Json(upstream_record),
+        )));
+    }
+
+    // A concurrent miss may have stored the key first; serve its entry
+    let (cached, stored) = state
+        .cache
+        .get_or_set(query.key.clone(), upstream_record.artifact, expires_at)
+        .await?;
+
+    if stored {
+        state.metrics.record_cache_store();
+        tracing::debug!(key = %cached.key, "cached artifact from upstream");
+    }
+
+    let response = cached.into_lookup_response(Utc::now());
+    Ok(Some((freshness_headers(&response), Json(response))))
+}
+
+/// Outcome of an upstream hydration, shared with coalesced callers
+///
+/// Errors are carried as their message, since [`AppError`] cannot be cloned.
+pub type SharedHydration = Result<Option<UpstreamRecord>, String>;
+
+/// Fetch `key` of `tenant_id` from upstream as `upstream_key`, passing on the
+/// tenant the caller asked for
+///
+/// Concurrent hydrations of the same key are coalesced into one upstream
+/// request, which takes one of the tenant's hydration slots. Callers that
+/// joined another's request get its result; its failures become internal
+/// errors.
+async fn hydrate(
+    state: &AppState,
+    upstream: &UpstreamClient,
+    tenant_id: &str,
+    key: &str,
+    upstream_key: &str,
+    requested_tenant: Option<&str>,
+    refresh: bool,
+) -> Result<Option<UpstreamRecord>, AppError> {
+    let mut failure = None;
+    let (result, shared) = state
+        .hydrations
+        .run(tenant_id, key, || async {
+            let hydration = match state
+                .policy
+                .acquire_hydration(tenant_id, key, refresh)
+                .await
+            {
+                Ok(permit) => permit,
+                Err(err) => {
+                    let message = err.to_string();
+                    failure = Some(err);
+                    return Err(message);
+                }
             };
 
-            Ok(Json(response))
+            state.metrics.record_upstream_request();
+            let start = Instant::now();
+            let result = upstream.lookup(upstream_key, requested_tenant).await;
+            state
+                .metrics
+                .record_upstream_latency(start.elapsed().as_secs_f64());
+            drop(hydration);
+
+            result.map_err(|err| {
+                let message = match &err {
+                    AppError::Internal(source) => source.to_string(),
+                    other => other.to_string(),
+                };
+                failure = Some(err);
+                message
+            })
+        })
+        .await;
+
+    if shared {
+        state.metrics.record_coalesced_hydration();
+    }
+    match (result, failure) {
+        (Ok(record), _) => Ok(record),
+        (Err(_), Some(err)) => Err(err),
+        (Err(message), None) => Err(AppError::Internal(anyhow::anyhow!(message))),
+    }
+}
+
+/// Resolve the expiry of an upstream record
+///
+/// The record's own lifetime is its explicit deadline, then TTL remaining,
+/// then artifact TTL. `precedence` decides whether it or the response header
+/// lifetime is tried first; the configured default applies when neither is set.
+fn upstream_expiry(
+    record: &LookupResponse,
+    header_ttl_seconds: Option<u64>,
+    precedence: TtlPrecedence,
+    default_ttl_seconds: u64,
+) -> Option<DateTime<Utc>> {
+    let header_expiry = header_ttl_seconds.map(|ttl| Utc::now() + Duration::seconds(ttl as i64));
+
+    let expires_at = match precedence {
+        TtlPrecedence::Headers => header_expiry.or_else(|| record_expiry(record)),
+        TtlPrecedence::Artifact => record_expiry(record).or(header_expiry),
+    };
+
+    if expires_at.is_none() && default_ttl_seconds > 0 {
+        return Some(Utc::now() + Duration::seconds(default_ttl_seconds as i64));
+    }
+
+    expires_at
+}
+
+/// Whether a hydrated record may be stored given its response freshness
+fn is_cacheable(freshness: &HttpFreshness, expires_at: Option<DateTime<Utc>>) -> bool {
+    if freshness.no_store {
+        return false;
+    }
+    match expires_at {
+        Some(exp) => exp > Utc::now(),
+        None => true,
+    }
+}
+
+/// Lifetime declared by the record itself
+fn record_expiry(record: &LookupResponse) -> Option<DateTime<Utc>> {
+    let mut expires_at = record.expires_at;
+
+    if expires_at.is_none() {
+        if let Some(ttl_remaining) = record.ttl_remaining_seconds {
+            if ttl_remaining > 0 {
+                expires_at = Some(Utc::now() + Duration::seconds(ttl_remaining as i64));
+            }
         }
-        None => {
-            state.metrics.record_cache_miss();
-            if let Some(upstream) = &state.upstream {
-                state.metrics.record_upstream_request();
-                let start = Instant::now();
+    }
 
-                match upstream.lookup(&query.key, query.tenant.as_deref()).await {
-                    Ok(Some(upstream_record)) => {
-                        state
-                            .metrics
-                            .record_upstream_latency(start.elapsed().as_secs_f64());
-
-                        let tenant_id = &upstream_record.artifact.policy.tenant;
-
-                        if let Some(requested_tenant) = &query.tenant {
-                            if requested_tenant != tenant_id {
-                                tracing::warn!(
-                                    requested = %requested_tenant,
-                                    upstream = %tenant_id,
-                                    key = %query.key,
-                                    "Tenant mismatch between request and upstream response",
-                                );
-                                state.metrics.record_upstream_failure();
-                                return Err(AppError::not_found("cache miss"));
-                            }
-                        }
-
-                        if let Some(api_key) =
-                            headers.get("x-api-key").and_then(|h| h.to_str().ok())
-                        {
-                            state.policy.validate_api_key(tenant_id, api_key).await?;
-                        }
-
-                        let mut expires_at = upstream_record.expires_at;
-
-                        if expires_at.is_none() {
-                            if let Some(ttl_remaining) = upstream_record.ttl_remaining_seconds {
-                                if ttl_remaining > 0 {
-                                    expires_at =
-                                        Some(Utc::now() + Duration::seconds(ttl_remaining as i64));
-                                }
-                            }
-                        }
-
-                        if expires_at.is_none() {
-                            if let Some(ttl) = upstream_record.artifact.ttl_seconds {
-                                if ttl > 0 {
-                                    expires_at = Some(Utc::now() + Duration::seconds(ttl as i64));
-                                }
-                            }
-                        }
-
-                        if expires_at.is_none() && state.default_ttl_seconds > 0 {
-                            expires_at = Some(
-                                Utc::now() + Duration::seconds(state.default_ttl_seconds as i64),
-                            );
-                        }
-
-                        let cached = state
-                            .cache
-                            .set(query.key.clone(), upstream_record.artifact, expires_at)
-                            .await?;
+    if expires_at.is_none() {
+        if let Some(ttl) = record.artifact.ttl_seconds {
+            if ttl > 0 {
+                expires_at = Some(Utc::now() + Duration::seconds(ttl as i64));
+            }
+        }
+    }
 
-                        state.metrics.record_cache_store();
-                        tracing::debug!(key = %cached.key, "cached artifact from upstream");
+    expires_at
+}
 
-                        let now = Utc::now();
-                        let ttl_remaining = cached.ttl_remaining_seconds(now);
+/// Probabilistic early expiration ("x-fetch")
+///
+/// A hit triggers a refresh when `now - delta * beta * ln(rand) >= expires_at`,
+/// where `delta` is the mean upstream hydration latency. The probability rises
+/// as the entry approaches expiry, spreading refreshes of hot keys over time.
+fn should_refresh_early(
+    state: &AppState,
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    if state.upstream.is_none() || state.xfetch_beta <= 0.0 {
+        return false;
+    }
+
+    let Some(expires_at) = expires_at else {
+        return false;
+    };
 
-                        let response = LookupResponse {
-                            key: cached.key,
-                            artifact: cached.artifact,
-                            expires_at: cached.expires_at,
-                            ttl_remaining_seconds: ttl_remaining,
-                        };
+    let delta = state.metrics.upstream_mean_latency().unwrap_or(0.1);
+    // 1 - random() lies in (0, 1], keeping ln() finite
+    let sample = 1.0 - rand::random::<f64>();
+    let gap = -delta * state.xfetch_beta * sample.ln();
 
-                        return Ok(Json(response));
-                    }
-                    Ok(None) => {
-                        state
-                            .metrics
-                            .record_upstream_latency(start.elapsed().as_secs_f64());
+    now + Duration::milliseconds((gap * 1000.0) as i64) >= expires_at
+}
+
+/// Hydrate `key` of `tenant_id` from upstream unless it is already cached,
+/// returning whether it was stored
+///
+/// Used by the startup warm-up. The answer is stored under the same rules as
+/// an upstream lookup.
+pub async fn warm_key(state: &AppState, tenant_id: &str, key: &str) -> Result<bool, AppError> {
+    let Some(upstream) = &state.upstream else {
+        return Ok(false);
+    };
+    if state.cache.get(key).await?.is_some() {
+        return Ok(false);
+    }
+
+    let _permit = state.policy.acquire_bulkhead(tenant_id).await?;
+    let result = hydrate(state, upstream, tenant_id, key, key, Some(tenant_id), false)
+        .await
+        .inspect_err(|_| state.metrics.record_upstream_failure())?;
+    let Some(UpstreamRecord { record, freshness }) = result else {
+        return Ok(false);
+    };
+    if record.artifact.policy.tenant != tenant_id {
+        state.metrics.record_upstream_failure();
+        return Err(AppError::Internal(anyhow::anyhow!(
+            "upstream returned an artifact of tenant {}",
+            record.artifact.policy.tenant
+        )));
+    }
+
+    let expires_at = upstream_expiry(
+        &record,
+        freshness.ttl_seconds,
+        upstream.ttl_precedence(),
+        state.default_ttl_for(tenant_id),
+    );
+    if !is_cacheable(&freshness, expires_at)
+        || !state.policy.meets_cache_score(&record.artifact).await
+        || state.read_only.load(Ordering::Acquire)
+        || state.policy.read_only(tenant_id).await
+    {
+        return Ok(false);
+    }
+
+    let (_, stored) = state
+        .cache
+        .get_or_set(key.to_string(), record.artifact, expires_at)
+        .await?;
+    if stored {
+        state.metrics.record_cache_store();
+    }
+    Ok(stored)
+}
+
+/// Refresh a hot entry from upstream in the background
+fn spawn_early_refresh(state: AppState, key: String, tenant_id: String) {
+    let Some(upstream) = state.upstream.clone() else {
+        return;
+    };
+
+    state.metrics.record_early_refresh();
+
+    tokio::spawn(async move {
+        let _permit = match state.policy.acquire_bulkhead(&tenant_id).await {
+            Ok(permit) => permit,
+            Err(err) => {
+                tracing::warn!(key = %key, error = %err, "Early refresh skipped");
+                return;
+            }
+        };
+        let result = hydrate(
+            &state,
+            &upstream,
+            &tenant_id,
+            &key,
+            &strip_namespace(&key),
+            Some(&tenant_id),
+            true,
+        )
+        .await;
+
+        match result {
+            Ok(Some(UpstreamRecord { record, freshness }))
+                if record.artifact.policy.tenant == tenant_id =>
+            {
+                let expires_at = upstream_expiry(
+                    &record,
+                    freshness.ttl_seconds,
+                    upstream.ttl_precedence(),
+                    state.default_ttl_for(&tenant_id),
+                );
+                if !is_cacheable(&freshness, expires_at) {
+                    tracing::debug!(key = %key, "Early refresh response not cacheable");
+                    return;
+                }
+                if !state.policy.meets_cache_score(&record.artifact).await {
+                    tracing::debug!(key = %key, "Early refresh artifact below cache score threshold");
+                    state.metrics.record_low_score_bypass();
+                    return;
+                }
+                match state
+                    .cache
+                    .set(key.clone(), record.artifact, expires_at)
+                    .await
+                {
+                    Ok(_) => {
+                        state.metrics.record_cache_store();
+                        tracing::debug!(key = %key, "refreshed artifact ahead of expiry");
                     }
                     Err(err) => {
-                        state.metrics.record_upstream_failure();
-                        state
-                            .metrics
-                            .record_upstream_latency(start.elapsed().as_secs_f64());
-                        return Err(err);
+                        tracing::warn!(key = %key, error = %err, "Early refresh store failed")
                     }
                 }
             }
+            Ok(_) => {
+                tracing::debug!(key = %key, "Early refresh found no matching upstream artifact")
+            }
+            Err(err) => {
+                state.metrics.record_upstream_failure();
+                tracing::warn!(key = %key, error = %err, "Early refresh failed");
+            }
+        }
+    });
+}
+
+/// Freshness headers mirroring the lookup body for header-only consumers
+fn freshness_headers(response: &LookupResponse) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    if let Some(stored_at) = response.stored_at {
+        if let Ok(value) = HeaderValue::from_str(&stored_at.to_rfc3339()) {
+            headers.insert("x-scedge-stored-at", value);
+        }
+    }
 
-            Err(AppError::not_found("cache miss"))
+    if let Some(expires_at) = response.expires_at {
+        if let Ok(value) = HeaderValue::from_str(&expires_at.to_rfc3339()) {
+            headers.insert("x-scedge-expires-at", value);
         }
     }
+
+    if let Some(ttl_remaining) = response.ttl_remaining_seconds {
+        headers.insert("x-scedge-ttl-remaining", HeaderValue::from(ttl_remaining));
+    }
+
+    if let Some(version) = &response.version {
+        if let Ok(value) = HeaderValue::from_str(version) {
+            headers.insert("x-scedge-version", value);
+        }
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&format!("\"{}\"", response.artifact.hash)) {
+        headers.insert(header::ETAG, value);
+    }
+
+    headers
+}
+
+/// Resolve keys under the `{tenant}:` prefix, keeping already-prefixed keys
+pub fn tenant_scoped_keys(tenant_id: &str, keys: &[String]) -> Vec<String> {
+    let prefix = format!("{}:", tenant_id);
+    keys.iter()
+        .map(|key| {
+            if key.starts_with(&prefix) {
+                key.clone()
+            } else {
+                format!("{}{}", prefix, key)
+            }
+        })
+        .collect()
+}
+
+/// Lookup many artifacts for a single tenant
+///
+/// This is the fast path for bulk reads: the API key is checked once for the
+/// whole batch and an invalid key rejects the request before any backend work.
+/// Keys are resolved under the `{tenant}:` prefix, and entries owned by another
+/// tenant are reported as misses. Misses are not hydrated from upstream.
+/// Invalid keys are reported in `errors` without failing the batch, in which
+/// case the response is `207 Multi-Status`.
+pub async fn handle_batch_lookup(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Json(request): Json<BatchLookupRequest>,
+) -> Result<(StatusCode, Json<BatchLookupResponse>), AppError> {
+    let start = Instant::now();
+
+    if request.tenant.trim().is_empty() {
+        return Err(AppError::bad_request("tenant is required"));
+    }
+
+    if request.keys.is_empty() {
+        return Err(AppError::bad_request("keys must not be empty"));
+    }
+
+    if request.keys.len() > state.max_batch_lookup_keys {
+        return Err(AppError::bad_request(format!(
+            "keys exceeds the maximum of {} per batch",
+            state.max_batch_lookup_keys
+        )));
+    }
+
+    let tenant_id = &request.tenant;
+
+    // Authenticate once for the whole batch
+    ctx.authorize(&state.policy, tenant_id).await?;
+
+    let mut keys = Vec::new();
+    let mut errors = Vec::new();
+    let scoped = tenant_scoped_keys(tenant_id, &request.keys);
+    for (index, (requested, key)) in request.keys.iter().zip(scoped).enumerate() {
+        let key = if requested.trim().is_empty() {
+            Err(AppError::bad_request("key must not be empty"))
+        } else {
+            validate_key(&key).and_then(|_| ctx.scope_key(&key))
+        };
+        match key {
+            Ok(key) => keys.push(key),
+            Err(err) => errors.push(BatchItemError::new(index, Some(requested.clone()), &err)),
+        }
+    }
+
+    let _permit = state.policy.acquire_bulkhead(tenant_id).await?;
+
+    let records = state
+        .cache
+        .get_many(&keys)
+        .await
+        .inspect_err(|err| record_backend_failure(&state, tenant_id, err))?;
+
+    let now = Utc::now();
+    let mut hits = Vec::new();
+    let mut misses = Vec::new();
+
+    for (key, record) in keys.into_iter().zip(records) {
+        match record {
+            Some(record)
+                if record.artifact.policy.tenant == *tenant_id
+                    && ctx.can_read(&record.artifact.policy) =>
+            {
+                state.metrics.record_cache_hit();
+                state.metrics.record_tenant_lookup(tenant_id, true);
+                state
+                    .metrics
+                    .record_compute_cost_saved(record.artifact.compute_cost());
+                let mut hit = record.into_lookup_response(now);
+                hit.key = ctx.unscope_key(&hit.key);
+                hits.push(hit);
+            }
+            _ => {
+                state.metrics.record_cache_miss();
+                state.metrics.record_tenant_lookup(tenant_id, false);
+                misses.push(ctx.unscope_key(&key));
+            }
+        }
+    }
+
+    state
+        .metrics
+        .record_batch_lookup_latency(start.elapsed().as_secs_f64());
+
+    Ok((
+        batch_status(&errors),
+        Json(BatchLookupResponse {
+            tenant: request.tenant,
+            hits,
+            misses,
+            errors,
+        }),
+    ))
+}
+
+/// Find a tenant's cached artifacts by content hash, without knowing their keys
+pub async fn handle_lookup_by_hash(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Query(query): Query<HashLookupQuery>,
+) -> Result<Json<HashLookupResponse>, AppError> {
+    if query.tenant.trim().is_empty() {
+        return Err(AppError::bad_request("tenant is required"));
+    }
+    if query.hash.trim().is_empty() {
+        return Err(AppError::bad_request("hash query parameter is required"));
+    }
+
+    let tenant_id = &query.tenant;
+    ctx.authorize(&state.policy, tenant_id).await?;
+
+    let _permit = state.policy.acquire_bulkhead(tenant_id).await?;
+
+    let records: Vec<_> = state
+        .cache
+        .get_by_hash(tenant_id, &query.hash)
+        .await
+        .inspect_err(|err| record_backend_failure(&state, tenant_id, err))?
+        .into_iter()
+        .filter(|record| {
+            key_namespace(&record.key) == ctx.namespace() && ctx.can_read(&record.artifact.policy)
+        })
+        .collect();
+    if records.is_empty() {
+        state.metrics.record_cache_miss();
+        state.metrics.record_tenant_lookup(tenant_id, false);
+        return Err(AppError::not_found("no cached artifact with this hash"));
+    }
+
+    state.metrics.record_cache_hit();
+    state.metrics.record_tenant_lookup(tenant_id, true);
+
+    let now = Utc::now();
+    Ok(Json(HashLookupResponse {
+        tenant: query.tenant,
+        hash: query.hash,
+        artifacts: records
+            .into_iter()
+            .map(|record| {
+                let mut response = record.into_lookup_response(now);
+                response.key = ctx.unscope_key(&response.key);
+                response
+            })
+            .collect(),
+    }))
+}
+
+/// Check which keys of a tenant are cached, without transferring artifact bodies
+pub async fn handle_contains(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Json(request): Json<ContainsRequest>,
+) -> Result<Json<ContainsResponse>, AppError> {
+    if request.tenant.trim().is_empty() {
+        return Err(AppError::bad_request("tenant is required"));
+    }
+
+    if request.keys.is_empty() {
+        return Err(AppError::bad_request("keys must not be empty"));
+    }
+
+    let tenant_id = &request.tenant;
+
+    ctx.authorize(&state.policy, tenant_id).await?;
+
+    let keys = tenant_scoped_keys(tenant_id, &request.keys);
+    validate_keys(&keys)?;
+    let keys = ctx.scope_keys(&keys)?;
+
+    let _permit = state.policy.acquire_bulkhead(tenant_id).await?;
+    let present = state.cache.exists_many(&keys).await?;
+
+    let mut hashes = vec![None; keys.len()];
+    if request.include_hashes {
+        let present_keys: Vec<String> = keys
+            .iter()
+            .zip(&present)
+            .filter(|(_, present)| **present)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let mut found = state.cache.hashes_many(&present_keys).await?.into_iter();
+        for (slot, present) in hashes.iter_mut().zip(&present) {
+            if *present {
+                // Read-restricted entries only show their hash to allowed readers
+                *slot = found
+                    .next()
+                    .flatten()
+                    .filter(|entry| ctx.can_read(&entry.policy))
+                    .map(|entry| entry.hash);
+            }
+        }
+    }
+
+    let results = keys
+        .into_iter()
+        .zip(present.into_iter().zip(hashes))
+        .map(|(key, (present, hash))| (ctx.unscope_key(&key), KeyPresence { present, hash }))
+        .collect();
+
+    Ok(Json(ContainsResponse {
+        tenant: request.tenant,
+        results,
+    }))
+}
+
+/// Extend the TTL of keys given in full, which may span tenants
+///
+/// Each key belongs to the tenant named by its first segment, whose
+/// credential, `max_ttl_seconds`, and read-only flag apply. Every tenant is
+/// checked before any key is touched, so a rejected request changes nothing.
+pub async fn handle_touch(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Json(request): Json<TouchRequest>,
+) -> Result<Json<TouchResponse>, AppError> {
+    if request.keys.is_empty() {
+        return Err(AppError::bad_request("keys are required"));
+    }
+    if request.ttl_seconds == 0 {
+        return Err(AppError::bad_request("ttl_seconds must be positive"));
+    }
+    validate_keys(&request.keys)?;
+    let keys = ctx.scope_keys(&request.keys)?;
+
+    // Positions of each tenant's keys in the request
+    let mut by_tenant: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (position, key) in request.keys.iter().enumerate() {
+        by_tenant.entry(key_tenant(key)).or_default().push(position);
+    }
+
+    for &tenant_id in by_tenant.keys() {
+        ctx.authorize(&state.policy, tenant_id).await?;
+        state
+            .policy
+            .validate_ttl(tenant_id, Some(request.ttl_seconds))
+            .await?;
+        if state.policy.read_only(tenant_id).await {
+            return Err(AppError::unavailable("tenant is read-only"));
+        }
+    }
+
+    let mut expiries = vec![None; keys.len()];
+    for (tenant_id, positions) in by_tenant {
+        let _permit = state.policy.acquire_bulkhead(tenant_id).await?;
+        let tenant_keys: Vec<String> = positions
+            .iter()
+            .map(|&position| keys[position].clone())
+            .collect();
+        let extended = state
+            .cache
+            .touch_many(tenant_id, &tenant_keys, request.ttl_seconds)
+            .await?;
+        for (position, expires_at) in positions.into_iter().zip(extended) {
+            expiries[position] = expires_at;
+        }
+    }
+
+    let results = request
+        .keys
+        .into_iter()
+        .zip(expiries)
+        .map(|(key, expires_at)| TouchResult {
+            key,
+            touched: expires_at.is_some(),
+            expires_at,
+        })
+        .collect();
+
+    Ok(Json(TouchResponse { results }))
+}
+
+/// Extend the TTL of many keys of one tenant in a single pipelined operation
+///
+/// Keys are listed explicitly (resolved under `{tenant}:` like batch lookups)
+/// or selected by `prefix`. Prefix touches scan within the configured limits
+/// and return a `cursor` when partial. The new TTL must not exceed the
+/// tenant's `max_ttl_seconds`.
+pub async fn handle_touch_batch(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Json(request): Json<TouchBatchRequest>,
+) -> Result<Json<TouchBatchResponse>, AppError> {
+    let tenant_id = &request.tenant;
+    if tenant_id.trim().is_empty() {
+        return Err(AppError::bad_request("tenant is required"));
+    }
+    if request.ttl_seconds == 0 {
+        return Err(AppError::bad_request("ttl_seconds must be positive"));
+    }
+
+    ctx.authorize(&state.policy, tenant_id).await?;
+    state
+        .policy
+        .validate_ttl(tenant_id, Some(request.ttl_seconds))
+        .await?;
+    if state.policy.read_only(tenant_id).await {
+        return Err(AppError::unavailable("tenant is read-only"));
+    }
+
+    let _permit = state.policy.acquire_bulkhead(tenant_id).await?;
+
+    let (keys, cursor) = match (&request.prefix, request.keys.is_empty()) {
+        (Some(prefix), true) => {
+            let prefix = ctx.scope_key(&format!("{}:{}", tenant_id, prefix))?;
+            let pattern = format!("{}*", escape_glob(&prefix));
+            let page = state
+                .cache
+                .scan_bounded(&pattern, request.cursor.as_deref())
+                .await?;
+            (page.keys, page.cursor)
+        }
+        (None, false) => {
+            let keys = tenant_scoped_keys(tenant_id, &request.keys);
+            validate_keys(&keys)?;
+            (ctx.scope_keys(&keys)?, None)
+        }
+        _ => return Err(AppError::bad_request("must specify either keys or prefix")),
+    };
+
+    let extended = state
+        .cache
+        .touch_many(tenant_id, &keys, request.ttl_seconds)
+        .await?;
+
+    let results = keys
+        .into_iter()
+        .zip(extended)
+        .map(|(key, expires_at)| TouchResult {
+            key: ctx.unscope_key(&key),
+            touched: expires_at.is_some(),
+            expires_at,
+        })
+        .collect();
+
+    Ok(Json(TouchBatchResponse {
+        tenant: request.tenant,
+        results,
+        partial: cursor.is_some(),
+        cursor,
+    }))
+}
+
+/// Scope a JWT must grant to purge
+const PURGE_SCOPE: &str = "cache:purge";
+
+/// Resolve the tenant a purge caller is authenticated as, if any
+///
+/// Every purge needs a credential: a JWT with the `cache:purge` scope or a
+/// valid API key. An explicit `tenant` in the request must match the
+/// credential. Without one, the credential's own tenant applies, and an
+/// unrecognized API key is rejected.
+async fn authenticate_purge(
+    state: &AppState,
+    ctx: &TenantContext,
+    requested_tenant: Option<&str>,
+) -> Result<String, AppError> {
+    if !ctx.is_authenticated() {
+        return Err(AppError::unauthorized("Purges require a JWT or API key"));
+    }
+    ctx.require_scope(PURGE_SCOPE)?;
+
+    if let Some(tenant_id) = requested_tenant {
+        ctx.authorize(&state.policy, tenant_id).await?;
+        return Ok(tenant_id.to_string());
+    }
+
+    ctx.caller()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::unauthorized("Invalid API key"))
 }
 
 /// Purge artifacts from the cache
+///
+/// Callers may only purge their own tenant: listed keys must all carry the
+/// caller's `{tenant}:` prefix, and provenance purges only remove the
+/// caller's keys.
+///
+/// Tenant and provenance purges resolve their keys through secondary indexes,
+/// so every purge completes in one request.
 pub async fn handle_purge(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    ctx: TenantContext,
     Json(request): Json<PurgeRequest>,
 ) -> Result<Json<PurgeResponse>, AppError> {
     let purged;
 
-    // Validate API key for tenant if specified
-    if let Some(tenant_id) = &request.tenant {
-        if let Some(api_key) = headers.get("x-api-key").and_then(|h| h.to_str().ok()) {
-            state.policy.validate_api_key(tenant_id, api_key).await?;
-        }
-    }
+    let caller = authenticate_purge(&state, &ctx, request.tenant.as_deref()).await?;
+
+    let _permit = state.policy.acquire_bulkhead(&caller).await?;
 
     // Purge by explicit keys
     if !request.keys.is_empty() {
-        purged = state.cache.delete_many(&request.keys).await?;
+        validate_keys(&request.keys)?;
+        if let Some(foreign) = request.keys.iter().find(|key| key_tenant(key) != caller) {
+            return Err(AppError::policy_denied(
+                &caller,
+                PolicyRule::CrossTenant,
+                format!("key {} belongs to another tenant", foreign),
+            ));
+        }
+        purged = state
+            .cache
+            .delete_many(&ctx.scope_keys(&request.keys)?)
+            .await?;
+    }
+    // The other purges resolve keys through indexes spanning every namespace
+    else if ctx.namespace().is_some() {
+        return Err(AppError::bad_request(
+            "namespaced purges must list their keys",
+        ));
+    }
+    // Purge by family
+    else if let Some(family) = &request.family {
+        purged = state.cache.purge_family(&caller, family).await?;
     }
     // Purge by tenant
-    else if let Some(tenant_id) = &request.tenant {
-        let pattern = format!("{}:*", tenant_id);
-        let keys = state.cache.scan_by_pattern(&pattern).await?;
-        purged = state.cache.delete_many(&keys).await?;
+    else if request.tenant.is_some() {
+        purged = state.cache.purge_tenant(&caller).await?;
     }
     // Purge by provenance hash
     else if let Some(prov_hash) = &request.provenance_hash {
-        // Scan all keys and check provenance
-        let keys = state.cache.scan_by_pattern("*").await?;
-        let mut to_purge = Vec::new();
-
-        for key in keys {
-            if let Ok(Some(artifact)) = state.cache.get(&key).await {
-                let has_hash = artifact
-                    .artifact
-                    .provenance
-                    .iter()
-                    .any(|p| p.hash.as_deref() == Some(prov_hash.as_str()))
-                    || artifact.artifact.hash == *prov_hash;
-
-                if has_hash {
-                    to_purge.push(key);
-                }
-            }
-        }
-
-        purged = state.cache.delete_many(&to_purge).await?;
+        purged = state
+            .cache
+            .purge_referencing_hash(Some(&caller), prov_hash)
+            .await?
+            + state
+                .cache
+                .purge_dependents(&caller, vec![prov_hash.clone()])
+                .await?;
     } else {
         return Err(AppError::bad_request(
-            "must specify keys, tenant, or provenance_hash",
+            "must specify keys, family, tenant, or provenance_hash",
         ));
     }
 
     state.metrics.record_cache_purge(purged);
 
-    Ok(Json(PurgeResponse { purged }))
+    Ok(Json(PurgeResponse {
+        purged,
+        partial: false,
+    }))
+}
+
+/// Outcome of a rule check, named after the rule a denial reports
+fn rule_outcome(rule: &'static str, result: Result<(), AppError>) -> RuleOutcome {
+    match result {
+        Ok(()) => RuleOutcome {
+            rule,
+            passed: true,
+            reason: None,
+        },
+        Err(err) => RuleOutcome {
+            rule: match &err {
+                AppError::PolicyDenied { rule, .. } => rule.as_str(),
+                _ => rule,
+            },
+            passed: false,
+            reason: Some(err.to_string()),
+        },
+    }
+}
+
+/// Check a hypothetical artifact policy and the caller's credentials against
+/// the tenant's rules without storing anything
+///
+/// Every rule is evaluated even after one fails. Failures are reported in the
+/// response rather than returned as errors, so no `POLICY_DENIED` event is
+/// published.
+pub async fn handle_policy_evaluate(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Json(request): Json<PolicyEvaluateRequest>,
+) -> Result<Json<PolicyEvaluateResponse>, AppError> {
+    let policy = &request.policy;
+    let tenant_id = &policy.tenant;
+    if tenant_id.trim().is_empty() {
+        return Err(AppError::bad_request("policy.tenant is required"));
+    }
+
+    let known = state.policy.get_tenant(tenant_id).await.is_some();
+    let mut rules = vec![
+        rule_outcome(
+            PolicyRule::UnknownTenant.as_str(),
+            if known {
+                Ok(())
+            } else {
+                Err(AppError::bad_request("Tenant is not configured"))
+            },
+        ),
+        rule_outcome("credentials", ctx.authorize(&state.policy, tenant_id).await),
+        rule_outcome(
+            PolicyRule::Ttl.as_str(),
+            state
+                .policy
+                .validate_ttl(tenant_id, request.ttl_seconds)
+                .await,
+        ),
+        rule_outcome(
+            PolicyRule::Region.as_str(),
+            state
+                .policy
+                .validate_region(tenant_id, policy.region.as_deref())
+                .await,
+        ),
+        rule_outcome(
+            PolicyRule::Compliance.as_str(),
+            state
+                .policy
+                .validate_compliance(tenant_id, policy.phi, policy.pii)
+                .await,
+        ),
+    ];
+    for scope in &request.scopes {
+        rules.push(rule_outcome(
+            PolicyRule::Scope.as_str(),
+            ctx.require_scope(scope),
+        ));
+    }
+    if policy.is_read_restricted() {
+        rules.push(rule_outcome(
+            PolicyRule::ReadAcl.as_str(),
+            ctx.authorize_read(policy),
+        ));
+    }
+
+    Ok(Json(PolicyEvaluateResponse {
+        tenant: tenant_id.clone(),
+        allowed: rules.iter().all(|outcome| outcome.passed),
+        rules,
+    }))
+}
+
+/// Register a recurring purge rule for a tenant
+///
+/// Schedules purge on the tenant's behalf, so registering one takes the same
+/// credential as a purge: a JWT with the `cache:purge` scope or the tenant's
+/// API key.
+pub async fn handle_register_purge_schedule(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+    Json(request): Json<PurgeScheduleRequest>,
+) -> Result<Json<PurgeScheduleResponse>, AppError> {
+    let tenant_id = &request.tenant;
+    authenticate_purge(&state, &ctx, Some(tenant_id)).await?;
+
+    parse_schedule(&request.schedule.cron)?;
+
+    if request.schedule.prefix.is_none() && request.schedule.tag.is_none() {
+        return Err(AppError::bad_request("schedule must specify prefix or tag"));
+    }
+
+    let schedules = state
+        .policy
+        .add_purge_schedule(tenant_id, request.schedule, &state.cache)
+        .await?;
+
+    tracing::info!(tenant = %tenant_id, count = schedules.len(), "Registered purge schedule");
+
+    Ok(Json(PurgeScheduleResponse {
+        tenant: request.tenant,
+        schedules,
+    }))
+}
+
+/// Scope a JWT must grant to rotate its tenant's API key
+const KEY_ROTATION_SCOPE: &str = "tenant:keys";
+
+/// Mint a new API key for the caller's tenant
+///
+/// The caller must authenticate with its current API key or a JWT. The old
+/// key stays valid for the configured grace window so clients can roll over.
+pub async fn handle_rotate_api_key(
+    State(state): State<AppState>,
+    ctx: TenantContext,
+) -> Result<Json<ApiKeyRotationResponse>, AppError> {
+    ctx.require_scope(KEY_ROTATION_SCOPE)?;
+    let tenant_id = ctx
+        .caller()
+        .ok_or_else(|| AppError::unauthorized("A valid API key or JWT is required"))?
+        .to_string();
+
+    let (api_key, previous_valid_until) = state
+        .policy
+        .rotate_api_key(&tenant_id, state.api_key_grace, &state.cache)
+        .await?;
+
+    Ok(Json(ApiKeyRotationResponse {
+        tenant: tenant_id,
+        api_key,
+        previous_valid_until,
+    }))
+}
+
+/// Publish a graph event over HTTP, the same way the NATS transport would
+pub async fn handle_invalidate(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(mut event): Json<EventEnvelope>,
+) -> Result<StatusCode, AppError> {
+    require_admin(&state, &headers)?;
+
+    // Fall back to the request's own trace context
+    if event.traceparent.is_none() {
+        event.traceparent = headers
+            .get("traceparent")
+            .and_then(|h| h.to_str().ok())
+            .map(str::to_string);
+    }
+
+    state
+        .graph_events
+        .send(event)
+        .map_err(|_| AppError::Internal(anyhow::anyhow!("Invalidation engine is not running")))?;
+
+    Ok(StatusCode::ACCEPTED)
 }
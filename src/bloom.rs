@@ -0,0 +1,184 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-tenant bloom filters of cached keys.
+//!
+//! At high QPS most misses are for keys that were never cached. When a
+//! [`KeyFilter`] is attached to the [`Cache`](crate::cache::Cache), lookups
+//! for keys the tenant's filter has never seen return a miss without a backend
+//! round trip.
+//!
+//! Keys are added on store. Bloom filters cannot forget keys, so deletes and
+//! expiries leave false positives behind (which only cost a normal backend
+//! read) until the periodic rebuild from a full key scan clears them. Keys
+//! written by other nodes sharing the backend are only picked up by that
+//! rebuild, so the filter suits deployments where each node is the primary
+//! writer for its backend. Until the first rebuild completes the filter lets
+//! every lookup through.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use crate::cache::Cache;
+use crate::error::AppError;
+use crate::keys::key_tenant;
+
+/// Fixed-size bloom filter using double hashing
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Size a filter for `capacity` items at the given false-positive rate
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let rate = false_positive_rate.clamp(1e-9, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = (-(capacity * rate.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / capacity) * ln2).round().max(1.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    pub fn insert(&mut self, item: &str) {
+        for bit in self.bit_positions(item) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    pub fn might_contain(&self, item: &str) -> bool {
+        self.bit_positions(item)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    fn bit_positions(&self, item: &str) -> impl Iterator<Item = u64> {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let h1 = hasher.finish();
+        0xa5a5_a5a5_u32.hash(&mut hasher);
+        let h2 = hasher.finish() | 1;
+
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+/// Bloom filters of cached keys, one per tenant
+pub struct KeyFilter {
+    capacity: usize,
+    false_positive_rate: f64,
+    filters: RwLock<HashMap<String, BloomFilter>>,
+    /// Keys stored while a rebuild scan is running, replayed into the new filters
+    pending: Mutex<Option<Vec<String>>>,
+    ready: AtomicBool,
+}
+
+impl KeyFilter {
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        Self {
+            capacity,
+            false_positive_rate,
+            filters: RwLock::new(HashMap::new()),
+            pending: Mutex::new(None),
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    /// Record a stored key
+    pub fn insert(&self, key: &str) {
+        if let Some(pending) = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_mut()
+        {
+            pending.push(key.to_string());
+        }
+
+        let mut filters = self.filters.write().unwrap_or_else(|e| e.into_inner());
+        self.insert_into(&mut filters, key);
+    }
+
+    /// Whether the key may be cached; `false` means it definitely is not
+    pub fn might_contain(&self, key: &str) -> bool {
+        if !self.ready.load(Ordering::Acquire) {
+            return true;
+        }
+
+        let filters = self.filters.read().unwrap_or_else(|e| e.into_inner());
+        filters
+            .get(key_tenant(key))
+            .is_some_and(|filter| filter.might_contain(key))
+    }
+
+    /// Rebuild every tenant filter from a full key scan
+    pub async fn rebuild(&self, cache: &Cache) -> Result<usize, AppError> {
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = Some(Vec::new());
+
+        let keys = match cache.scan_by_pattern("*").await {
+            Ok(keys) => keys,
+            Err(err) => {
+                *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                return Err(err);
+            }
+        };
+
+        let mut rebuilt = HashMap::new();
+        for key in &keys {
+            self.insert_into(&mut rebuilt, key);
+        }
+
+        // Swap under the filters lock so no concurrent insert falls in between
+        let mut filters = self.filters.write().unwrap_or_else(|e| e.into_inner());
+        let pending = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .unwrap_or_default();
+        for key in &pending {
+            self.insert_into(&mut rebuilt, key);
+        }
+        *filters = rebuilt;
+        self.ready.store(true, Ordering::Release);
+
+        Ok(keys.len())
+    }
+
+    /// Rebuild immediately, then every `interval`
+    pub fn spawn_rebuild(
+        self: std::sync::Arc<Self>,
+        cache: Cache,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.rebuild(&cache).await {
+                    Ok(keys) => tracing::debug!(keys, "Rebuilt key bloom filters"),
+                    Err(err) => tracing::warn!(error = %err, "Failed to rebuild key bloom filters"),
+                }
+            }
+        })
+    }
+
+    fn insert_into(&self, filters: &mut HashMap<String, BloomFilter>, key: &str) {
+        filters
+            .entry(key_tenant(key).to_string())
+            .or_insert_with(|| BloomFilter::new(self.capacity, self.false_positive_rate))
+            .insert(key);
+    }
+}
@@ -22,6 +22,10 @@
 //!
 //! - `CacheBackend` trait: Common interface for all cache implementations
 //! - `RedisCache`: Production-ready Redis backend with connection pooling
+//! - `MemoryCache`: In-process backend for single-node use and tests
+//! - `TieredCache`: Bounded in-memory L1 in front of another backend
+//! - `SqliteCache`: File-backed backend for single-node deployments without Redis
+//! - `RocksDbCache`: Disk-backed backend for caches larger than RAM (`rocksdb` feature)
 //! - `Cache`: Wrapper providing a unified API
 //!
 //! # Example
@@ -31,103 +35,1138 @@
 //!
 //! let redis = RedisCache::new("redis://localhost:6379")?;
 //! let cache = Cache::new(redis);
+//! # Ok::<(), scedge::error::AppError>(())
 //! ```
 
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use redis::aio::ConnectionManager;
 use redis::AsyncCommands;
+use rusqlite::OptionalExtension;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use tokio::sync::RwLock;
 
+use crate::admission::Admission;
+use crate::bloom::KeyFilter;
+use crate::clock::Clock;
+use crate::compression::{self, Dictionaries, TrainedDictionary};
+use crate::crypto::{Keyring, TenantKey};
+use crate::entry_format::{self, EntryFormat};
 use crate::error::AppError;
-use crate::model::{ArtifactPayload, CachedArtifact};
+use crate::keys::key_tenant;
+use crate::metrics::Metrics;
+use crate::model::{
+    ArtifactPayload, CachedArtifact, Consistency, EntryMetadata, EntryVersion, PolicyContext,
+    StoreMode,
+};
+use crate::rendered::RenderedResponses;
+use crate::stale::StaleCopies;
+use crate::ttl_tuner::TtlTuner;
+use crate::wal::{WalConfig, WalEntry, WalOp};
+
+/// Result of a conditional store
+#[derive(Debug, Clone)]
+pub enum SetOutcome {
+    /// Stored where no live entry was
+    Created(CachedArtifact),
+    /// Replaced a live entry
+    Updated(CachedArtifact),
+    /// The live entry, left in place
+    Kept(CachedArtifact),
+}
+
+impl SetOutcome {
+    /// Entry now at the key
+    pub fn record(&self) -> &CachedArtifact {
+        match self {
+            SetOutcome::Created(record)
+            | SetOutcome::Updated(record)
+            | SetOutcome::Kept(record) => record,
+        }
+    }
+
+    pub fn into_record(self) -> CachedArtifact {
+        match self {
+            SetOutcome::Created(record)
+            | SetOutcome::Updated(record)
+            | SetOutcome::Kept(record) => record,
+        }
+    }
+}
+
+/// Hash of an entry, with the policy deciding who may see it
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntryHash {
+    pub hash: String,
+    pub policy: PolicyContext,
+}
+
+/// Entry read by [`CacheBackend::get_or_expired`]
+#[derive(Debug, Clone)]
+pub enum StoredEntry {
+    /// Within its expiry
+    Live(CachedArtifact),
+    /// Past its `expires_at` but still held by the backend
+    Expired(CachedArtifact),
+}
 
 /// Trait for cache backends
 #[async_trait]
-pub trait CacheBackend: Send + Sync {
+pub trait CacheBackend: Send + Sync + 'static {
     async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError>;
+
+    /// Fetch `key`, returning an entry past its expiry instead of dropping it
+    ///
+    /// Lets [`Cache::read`] decide what a lookup does with such entries. The
+    /// default implementation only sees live entries.
+    async fn get_or_expired(&self, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        Ok(self.get(key).await?.map(StoredEntry::Live))
+    }
+
+    /// Fetch `key` like [`CacheBackend::get_or_expired`], from the
+    /// authoritative copy
+    ///
+    /// Strong reads must observe every completed store, so copies kept in
+    /// front of the store are skipped. The default implementation is
+    /// [`CacheBackend::get_or_expired`], for backends holding a single copy.
+    async fn get_or_expired_strong(&self, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        self.get_or_expired(key).await
+    }
+
+    /// Fetch many keys at once, in order, with `None` for absent keys
+    ///
+    /// The default implementation reads keys one by one; backends should
+    /// override it to fetch them in a single round trip.
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<CachedArtifact>>, AppError> {
+        let mut records = Vec::with_capacity(keys.len());
+        for key in keys {
+            records.push(self.get(key).await?);
+        }
+        Ok(records)
+    }
+
     async fn set(
         &self,
         key: String,
         artifact: ArtifactPayload,
         expires_at: Option<DateTime<Utc>>,
     ) -> Result<CachedArtifact, AppError>;
+
+    /// Store `artifact` at `key` unless `mode` keeps the live entry there
+    ///
+    /// The default implementation reads and writes in separate steps;
+    /// backends should override it so concurrent writers cannot interleave
+    /// between the check and the store.
+    async fn set_conditional(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+        mode: StoreMode,
+    ) -> Result<SetOutcome, AppError> {
+        let existing = self.get(&key).await?;
+        if existing.is_none() && matches!(mode, StoreMode::IfMatch(_)) {
+            return Err(no_entry_to_match());
+        }
+        if let Some(existing) = existing
+            .clone()
+            .filter(|existing| mode.keeps(existing, &artifact))
+        {
+            return Ok(SetOutcome::Kept(existing));
+        }
+        let cached = self.set(key, artifact, expires_at).await?;
+        Ok(match existing {
+            Some(_) => SetOutcome::Updated(cached),
+            None => SetOutcome::Created(cached),
+        })
+    }
+
     async fn delete(&self, key: &str) -> Result<bool, AppError>;
     async fn delete_many(&self, keys: &[String]) -> Result<usize, AppError>;
+
+    /// Delete `key` only while it still holds revision `version`
+    ///
+    /// Dropping an entry read past its expiry must not remove a store that
+    /// replaced it in the meantime. The default implementation checks and
+    /// deletes in separate steps; backends should override it to do both at
+    /// once.
+    async fn delete_if_match(&self, key: &str, version: EntryVersion) -> Result<bool, AppError> {
+        match self.get_or_expired_strong(key).await? {
+            Some(StoredEntry::Live(record) | StoredEntry::Expired(record))
+                if record.version() == version =>
+            {
+                self.delete(key).await
+            }
+            _ => Ok(false),
+        }
+    }
+
     async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError>;
+
+    /// Stream every live artifact whose key matches `pattern`
+    ///
+    /// Lets large-tenant operations process entries as they arrive instead of
+    /// materializing every key first. The default implementation scans keys
+    /// and fetches them one by one; backends should override it with batched
+    /// reads.
+    fn scan_entries(
+        self: Arc<Self>,
+        pattern: String,
+    ) -> BoxStream<'static, Result<CachedArtifact, AppError>> {
+        let backend = self.clone();
+        stream::once(async move { self.scan_by_pattern(&pattern).await })
+            .flat_map(move |keys| match keys {
+                Ok(keys) => {
+                    let backend = backend.clone();
+                    stream::iter(keys)
+                        .then(move |key| {
+                            let backend = backend.clone();
+                            async move { backend.get(&key).await }
+                        })
+                        .filter_map(|result| async move { result.transpose() })
+                        .boxed()
+                }
+                Err(err) => stream::once(async move { Err(err) }).boxed(),
+            })
+            .boxed()
+    }
+
+    /// Return one page of keys matching `pattern`, starting at `cursor`
+    ///
+    /// Cursors are opaque backend tokens; `None` starts a new scan, and a
+    /// returned `None` means the scan is complete. `count` is a hint for the
+    /// page size. The default implementation pages through a sorted full scan,
+    /// resuming after the last key returned so deleting scanned keys between
+    /// pages skips nothing.
+    async fn scan_page(
+        &self,
+        pattern: &str,
+        cursor: Option<&str>,
+        count: usize,
+    ) -> Result<(Option<String>, Vec<String>), AppError> {
+        let mut keys = self.scan_by_pattern(pattern).await?;
+        keys.sort_unstable();
+
+        let start = match cursor {
+            Some(after) => keys.partition_point(|key| key.as_str() <= after),
+            None => 0,
+        };
+        let end = start.saturating_add(count.max(1)).min(keys.len());
+        let page = keys[start..end].to_vec();
+        let next = if end < keys.len() {
+            page.last().cloned()
+        } else {
+            None
+        };
+        Ok((next, page))
+    }
+
+    /// Add members to a named secondary index set
+    async fn index_add(&self, index: &str, members: &[String]) -> Result<(), AppError>;
+
+    /// Remove members from a named secondary index set
+    async fn index_remove(&self, index: &str, members: &[String]) -> Result<(), AppError>;
+
+    /// List the members of a named secondary index set
+    async fn index_members(&self, index: &str) -> Result<Vec<String>, AppError>;
+
+    /// Drop a named secondary index set
+    async fn index_clear(&self, index: &str) -> Result<(), AppError>;
+
+    /// Remove index members whose artifact no longer exists, returning how
+    /// many were removed
+    ///
+    /// Expired entries, whether dropped natively or by a sweep, otherwise stay
+    /// listed in their tenant, family, reference, capsule, dependents, and
+    /// modified-at indexes until a purge names them. A store racing the prune
+    /// must keep its membership. The default implementation removes nothing.
+    async fn prune_indexes(&self) -> Result<u64, AppError> {
+        Ok(0)
+    }
+
+    /// Record when `key` was last written, for [`CacheBackend::modified_since`]
+    ///
+    /// Backends answering `modified_since` by scanning keep no index.
+    async fn index_modified(&self, _key: &str, _at: DateTime<Utc>) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Drop deleted keys from the modified-at index
+    async fn unindex_modified(&self, _keys: &[String]) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Keys last written at or after `since`, oldest first
+    ///
+    /// The default implementation scans every key and reads its metadata.
+    async fn modified_since(&self, since: DateTime<Utc>) -> Result<Vec<String>, AppError> {
+        let mut modified = Vec::new();
+        for key in self.scan_by_pattern("*").await? {
+            if let Some(metadata) = self.metadata(&key).await? {
+                if metadata.stored_at >= since {
+                    modified.push((metadata.stored_at, key));
+                }
+            }
+        }
+        modified.sort_unstable();
+        Ok(modified.into_iter().map(|(_, key)| key).collect())
+    }
+
+    /// Read a key's tenant and expiry without returning the artifact body
+    async fn metadata(&self, key: &str) -> Result<Option<EntryMetadata>, AppError> {
+        Ok(self.get(key).await?.map(|record| EntryMetadata {
+            tenant: record.artifact.policy.tenant,
+            stored_at: record.stored_at,
+            expires_at: record.expires_at,
+            ttl_seconds: record.artifact.ttl_seconds,
+        }))
+    }
+
+    /// Check presence of many keys without loading artifact bodies
+    async fn exists_many(&self, keys: &[String]) -> Result<Vec<bool>, AppError> {
+        let mut present = Vec::with_capacity(keys.len());
+        for key in keys {
+            present.push(self.get(key).await?.is_some());
+        }
+        Ok(present)
+    }
+
+    /// Fetch the artifact hash and policy of many keys without returning
+    /// artifact bodies
+    async fn hashes_many(&self, keys: &[String]) -> Result<Vec<Option<EntryHash>>, AppError> {
+        let mut hashes = Vec::with_capacity(keys.len());
+        for key in keys {
+            hashes.push(self.get(key).await?.map(|record| EntryHash {
+                hash: record.artifact.hash,
+                policy: record.artifact.policy,
+            }));
+        }
+        Ok(hashes)
+    }
+
+    /// Delete those of `keys` whose hash or any provenance hash is `hash`,
+    /// returning the deleted keys
+    ///
+    /// The default implementation reads and deletes in separate steps.
+    /// Backends should override it to check and delete atomically.
+    async fn purge_referencing_hash(
+        &self,
+        keys: &[String],
+        hash: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let matching: Vec<String> = self
+            .get_many(keys)
+            .await?
+            .into_iter()
+            .flatten()
+            .filter(|record| record.artifact.references_hash(hash))
+            .map(|record| record.key)
+            .collect();
+        self.delete_many(&matching).await?;
+        Ok(matching)
+    }
+
+    /// Number of keys held by the backend
+    async fn key_count(&self) -> Result<u64, AppError> {
+        Ok(self.scan_by_pattern("*").await?.len() as u64)
+    }
+
+    /// Delete expired artifacts and event ledger entries, returning how many
+    /// artifacts were removed
+    ///
+    /// Backends whose entries expire natively, like Redis, have nothing to
+    /// sweep; the default implementation does nothing.
+    async fn sweep_expired(&self) -> Result<u64, AppError> {
+        Ok(0)
+    }
+
+    /// Drop copies of entries this node keeps in front of the shared store
+    ///
+    /// Only tiered backends keep such copies; the default implementation does
+    /// nothing.
+    async fn flush_local(&self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Extend the expiry of a tenant's keys to `ttl_seconds` from now
+    ///
+    /// Returns the new expiry per key, `None` for keys that are absent or owned
+    /// by another tenant. Only the expiry changes, so the entry keeps its
+    /// [`EntryVersion`]. The default implementation reads and rewrites each
+    /// entry in separate steps, which also resets its `stored_at`; backends
+    /// should override it to move the expiry alone.
+    async fn touch_many(
+        &self,
+        tenant: &str,
+        keys: &[String],
+        ttl_seconds: u64,
+    ) -> Result<Vec<Option<DateTime<Utc>>>, AppError> {
+        let expires_at = Utc::now() + Duration::seconds(ttl_seconds as i64);
+        let mut touched = Vec::with_capacity(keys.len());
+        for key in keys {
+            let extended = match self.get(key).await? {
+                Some(record) if record.artifact.policy.tenant == tenant => {
+                    self.set(key.clone(), record.artifact, Some(expires_at))
+                        .await?
+                        .expires_at
+                }
+                _ => None,
+            };
+            touched.push(extended);
+        }
+        Ok(touched)
+    }
+
+    /// Record an event id in the processed-event ledger for `retention`
+    ///
+    /// Returns `false` when the id was already recorded, i.e. the event is a
+    /// redelivery or replay. Backends without a ledger accept every event.
+    async fn mark_event_processed(
+        &self,
+        _event_id: &str,
+        _retention: std::time::Duration,
+    ) -> Result<bool, AppError> {
+        Ok(true)
+    }
+
+    /// Remove an event id from the ledger so a redelivery is applied again
+    async fn unmark_event_processed(&self, _event_id: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Append a serialized notification to the outbox queue
+    async fn outbox_push(&self, _entry: String) -> Result<(), AppError> {
+        Err(AppError::Internal(anyhow::anyhow!(
+            "Backend does not support notifications"
+        )))
+    }
+
+    /// Move the oldest notification from the outbox queue to the in-flight
+    /// list and return it
+    ///
+    /// It stays in flight until acknowledged or dead-lettered, so a node that
+    /// stops mid-delivery leaves it for [`CacheBackend::outbox_recover`].
+    async fn outbox_claim(&self) -> Result<Option<String>, AppError> {
+        Ok(None)
+    }
+
+    /// Remove a delivered notification from the in-flight list
+    async fn outbox_ack(&self, _entry: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Move an undeliverable notification from the in-flight list to the
+    /// dead-letter list
+    async fn outbox_dead_letter(&self, _entry: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Return every in-flight notification to the front of the queue,
+    /// returning how many were returned
+    async fn outbox_recover(&self) -> Result<usize, AppError> {
+        Ok(0)
+    }
+
+    /// Read a record from the control namespace
+    async fn control_get(&self, _name: &str) -> Result<Option<String>, AppError> {
+        Ok(None)
+    }
+
+    /// Write a record to the control namespace, replacing any previous value
+    async fn control_set(&self, _name: &str, _value: &str) -> Result<(), AppError> {
+        Err(AppError::Internal(anyhow::anyhow!(
+            "Backend does not support control records"
+        )))
+    }
+
+    /// Remove a record from the control namespace
+    async fn control_delete(&self, _name: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Persist a trained compression dictionary, returning its version
+    async fn dictionary_store(&self, _tenant: &str, _dictionary: &[u8]) -> Result<u32, AppError> {
+        Err(AppError::Internal(anyhow::anyhow!(
+            "Backend does not support compression dictionaries"
+        )))
+    }
+
+    /// Load one version of a tenant's compression dictionary
+    async fn dictionary_load(
+        &self,
+        _tenant: &str,
+        _version: u32,
+    ) -> Result<Option<Vec<u8>>, AppError> {
+        Ok(None)
+    }
+
+    /// Newest compression dictionary of a tenant with its version
+    async fn dictionary_latest(&self, _tenant: &str) -> Result<Option<(u32, Vec<u8>)>, AppError> {
+        Ok(None)
+    }
+
+    /// Append mutations to the write-ahead log stream
+    ///
+    /// Backends without streams drop the entries.
+    async fn wal_append(&self, _config: &WalConfig, _entries: &[WalEntry]) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Check that the backend is reachable
+    async fn ping(&self) -> Result<(), AppError> {
+        Ok(())
+    }
 }
 
-/// Redis-based cache backend
-#[derive(Clone)]
-pub struct RedisCache {
+/// Lua function decoding a stored entry in either format (see [`entry_format`])
+///
+/// Returns `pcall`'s status and the decoded table. MessagePack bodies follow
+/// the version byte and the expiry header, so their tables have no
+/// `expires_at`; bodies of compressed entries also have no answer.
+macro_rules! lua_decode_entry {
+    () => {
+        r#"
+local function decode_entry(raw)
+    local format = string.byte(raw, 1)
+    if format == 1 then
+        return pcall(cmsgpack.unpack, string.sub(raw, 15))
+    end
+    if format == 2 then
+        local ok, len = pcall(struct.unpack, '>I4', raw, 19)
+        if not ok then
+            return false, len
+        end
+        return pcall(cmsgpack.unpack, string.sub(raw, 23, 22 + len))
+    end
+    return pcall(cjson.decode, raw)
+end
+"#
+    };
+}
+
+/// Extracts expiry fields server-side so the artifact body never crosses the wire
+///
+/// Returns the fields as JSON together with the expiry header of MessagePack
+/// and compressed entries (empty for JSON entries), or `false` for a missing key.
+const METADATA_SCRIPT: &str = concat!(
+    lua_decode_entry!(),
+    r#"
+local raw = redis.call('GET', KEYS[1])
+if not raw then
+    return false
+end
+local ok, decoded = decode_entry(raw)
+if not ok or type(decoded.artifact) ~= 'table' then
+    return false
+end
+local metadata = {
+    tenant = decoded.artifact.policy and decoded.artifact.policy.tenant,
+    stored_at = decoded.stored_at,
+    expires_at = decoded.expires_at,
+    ttl_seconds = decoded.artifact.ttl_seconds,
+}
+local expiry = ''
+if string.byte(raw, 1) == 1 or string.byte(raw, 1) == 2 then
+    expiry = string.sub(raw, 2, 14)
+end
+return {cjson.encode(metadata), expiry}
+"#
+);
+
+/// Extracts `artifact.hash` and the read policy server-side so artifact
+/// bodies never cross the wire
+///
+/// Returns each entry's [`EntryHash`] as JSON, or `false`. Empty lists are
+/// left out, since `cjson` would encode them as objects.
+const HASHES_SCRIPT: &str = concat!(
+    lua_decode_entry!(),
+    r#"
+local function non_empty(list)
+    if type(list) == 'table' and #list > 0 then
+        return list
+    end
+    return nil
+end
+local hashes = {}
+for i, key in ipairs(KEYS) do
+    local raw = redis.call('GET', key)
+    hashes[i] = false
+    if raw then
+        local ok, decoded = decode_entry(raw)
+        if ok and type(decoded.artifact) == 'table' and type(decoded.artifact.hash) == 'string'
+            and type(decoded.artifact.policy) == 'table' then
+            local policy = decoded.artifact.policy
+            hashes[i] = cjson.encode({
+                hash = decoded.artifact.hash,
+                policy = {
+                    tenant = policy.tenant,
+                    allowed_subjects = non_empty(policy.allowed_subjects),
+                    allowed_scopes = non_empty(policy.allowed_scopes),
+                },
+            })
+        end
+    end
+end
+return hashes
+"#
+);
+
+/// Moves each key's expiry to `ARGV[2]` and resets its TTL to `ARGV[1]` seconds
+///
+/// Only entries owned by tenant `ARGV[3]` are touched. `expires_at` is the last
+/// field of a JSON entry, so it is rewritten in place rather than re-encoding
+/// the body. MessagePack and compressed entries get `ARGV[4]` as their expiry
+/// header (see [`entry_format::encode_expiry`]). Returns 1 per touched key and
+/// 0 otherwise.
+const TOUCH_SCRIPT: &str = concat!(
+    lua_decode_entry!(),
+    r#"
+local touched = {}
+for i, key in ipairs(KEYS) do
+    touched[i] = 0
+    local raw = redis.call('GET', key)
+    if raw then
+        local ok, decoded = decode_entry(raw)
+        if ok and type(decoded.artifact) == 'table' and decoded.artifact.policy
+            and decoded.artifact.policy.tenant == ARGV[3] then
+            local rewritten, count
+            if string.byte(raw, 1) == 1 or string.byte(raw, 1) == 2 then
+                rewritten, count = string.sub(raw, 1, 1) .. ARGV[4] .. string.sub(raw, 15), 1
+            else
+                rewritten, count = string.gsub(
+                    raw, '"expires_at":[^,}]*}$', '"expires_at":' .. ARGV[2] .. '}')
+            end
+            if count == 1 then
+                redis.call('SET', key, rewritten, 'EX', ARGV[1])
+                touched[i] = 1
+            end
+        end
+    end
+end
+return touched
+"#
+);
+
+/// Stores `ARGV[1]` at `KEYS[1]` unless mode `ARGV[3]` keeps the entry there
+///
+/// `ARGV[2]` is the TTL in seconds, 0 for none, `ARGV[4]` the new artifact's
+/// hash, and `ARGV[5]` the `stored_at` an `if_match` store expects. Returns
+/// `{0, entry}` when the existing entry was kept, `{3, ''}` when an
+/// `if_match` store found no entry, otherwise `{1, ''}` after creating or
+/// `{2, ''}` after replacing it. Checking and storing in one script keeps
+/// concurrent writers from interleaving.
+const SET_CONDITIONAL_SCRIPT: &str = concat!(
+    lua_decode_entry!(),
+    r#"
+local existing = redis.call('GET', KEYS[1])
+if not existing and ARGV[3] == 'if_match' then
+    return {3, ''}
+end
+if existing then
+    local keep = ARGV[3] == 'if_absent'
+    if ARGV[3] == 'if_hash_differs' then
+        local ok, decoded = decode_entry(existing)
+        keep = ok and type(decoded.artifact) == 'table' and decoded.artifact.hash == ARGV[4]
+    elseif ARGV[3] == 'if_match' then
+        local ok, decoded = decode_entry(existing)
+        keep = not (ok and decoded.stored_at == ARGV[5])
+    end
+    if keep then
+        return {0, existing}
+    end
+end
+if tonumber(ARGV[2]) > 0 then
+    redis.call('SET', KEYS[1], ARGV[1], 'EX', ARGV[2])
+else
+    redis.call('SET', KEYS[1], ARGV[1])
+end
+if existing then
+    return {2, ''}
+end
+return {1, ''}
+"#
+);
+
+/// Deletes `KEYS[1]` if its `stored_at` is still `ARGV[1]`, returning 1 if it did
+const DELETE_IF_MATCH_SCRIPT: &str = concat!(
+    lua_decode_entry!(),
+    r#"
+local raw = redis.call('GET', KEYS[1])
+if not raw then
+    return 0
+end
+local ok, decoded = decode_entry(raw)
+if ok and decoded.stored_at == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#
+);
+
+/// Deletes every artifact matching the key pattern `ARGV[1]` whose hash or any
+/// provenance hash is `ARGV[2]`, returning the deleted keys
+///
+/// Running the whole scan inside one script keeps concurrent stores from
+/// slipping a superseded artifact in between matching and deletion.
+const SUPERSEDE_SCRIPT: &str = concat!(
+    lua_decode_entry!(),
+    r#"
+local purged = {}
+for _, key in ipairs(KEYS) do
+    local raw = redis.call('GET', key)
+    if raw then
+        local ok, decoded = decode_entry(raw)
+        if ok and type(decoded.artifact) == 'table' then
+            local artifact = decoded.artifact
+            local matches = artifact.hash == ARGV[1]
+            if not matches and type(artifact.provenance) == 'table' then
+                for _, source in ipairs(artifact.provenance) do
+                    if source.hash == ARGV[1] then
+                        matches = true
+                        break
+                    end
+                end
+            end
+            if matches then
+                redis.call('UNLINK', key)
+                table.insert(purged, key)
+            end
+        end
+    end
+end
+return purged
+"#
+);
+
+/// Removes members of the index `KEYS[1]` whose artifact key, `KEYS[i]` for
+/// member `ARGV[i]`, no longer exists; `ARGV[1]` is `zset` for the sorted
+/// modified-at index. Returns how many were removed
+///
+/// Checking and removing inside one script keeps a concurrent store, which
+/// writes its artifact before indexing it, from losing its membership.
+const PRUNE_INDEX_SCRIPT: &str = r#"
+local pruned = 0
+for i = 2, #KEYS do
+    if redis.call('EXISTS', KEYS[i]) == 0 then
+        if ARGV[1] == 'zset' then
+            pruned = pruned + redis.call('ZREM', KEYS[1], ARGV[i])
+        else
+            pruned = pruned + redis.call('SREM', KEYS[1], ARGV[i])
+        end
+    end
+end
+return pruned
+"#;
+
+/// Redis workloads served from separate connection pools
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedisWorkload {
+    /// Latency-critical lookups, which may go to a replica
+    Read,
+    /// Stores, deletes, index updates, and scripts
+    Write,
+    /// Key scans behind pattern purges, exports, and syncs
+    Scan,
+}
+
+/// Fixed set of auto-reconnecting connections serving one workload
+///
+/// Connections are opened on first use and handed out round-robin; each is
+/// multiplexed, so concurrent commands share it rather than waiting.
+struct RedisPool {
     client: redis::Client,
+    connections: Vec<tokio::sync::OnceCell<ConnectionManager>>,
+    next: AtomicUsize,
 }
 
-impl RedisCache {
-    pub fn new(redis_url: &str) -> Result<Self, AppError> {
+impl RedisPool {
+    fn open(redis_url: &str, size: usize) -> Result<Self, AppError> {
         let client = redis::Client::open(redis_url).map_err(|e| {
             AppError::Internal(anyhow::anyhow!("Failed to create Redis client: {}", e))
         })?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            connections: (0..size.max(1))
+                .map(|_| tokio::sync::OnceCell::new())
+                .collect(),
+            next: AtomicUsize::new(0),
+        })
     }
 
-    /// Test the Redis connection
-    pub async fn ping(&self) -> Result<(), AppError> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
+    async fn get(&self) -> Result<ConnectionManager, AppError> {
+        let slot = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        self.connections[slot]
+            .get_or_try_init(|| {
+                // No connect retries: an unreachable Redis must surface as
+                // `BackendUnavailable` right away
+                ConnectionManager::new_with_backoff(self.client.clone(), 2, 100, 0)
+            })
             .await
-            .map_err(|e| {
-                AppError::Internal(anyhow::anyhow!("Failed to connect to Redis: {}", e))
-            })?;
+            .cloned()
+            .map_err(|e| redis_error("Redis connection failed", e))
+    }
+}
+
+/// Connections per workload when not configured
+pub const DEFAULT_REDIS_POOL_SIZE: usize = 2;
+
+/// Redis-based cache backend
+///
+/// Reads, writes, and scans use separate pools, optionally against separate
+/// endpoints, so bulk purges and scans cannot starve lookups. Lua scripts and
+/// strong reads run on the write pool, since replicas may reject the former
+/// and lag behind the latter.
+#[derive(Clone)]
+pub struct RedisCache {
+    reads: Arc<RedisPool>,
+    writes: Arc<RedisPool>,
+    scans: Arc<RedisPool>,
+    clock_skew_tolerance: Duration,
+    format: EntryFormat,
+    dictionaries: Dictionaries,
+}
+
+impl RedisCache {
+    pub fn new(redis_url: &str) -> Result<Self, AppError> {
+        Ok(Self {
+            reads: Arc::new(RedisPool::open(redis_url, DEFAULT_REDIS_POOL_SIZE)?),
+            writes: Arc::new(RedisPool::open(redis_url, DEFAULT_REDIS_POOL_SIZE)?),
+            scans: Arc::new(RedisPool::open(redis_url, DEFAULT_REDIS_POOL_SIZE)?),
+            clock_skew_tolerance: Duration::zero(),
+            format: EntryFormat::Json,
+            dictionaries: Dictionaries::new(),
+        })
+    }
+
+    /// Serve `workload` from `size` connections to `redis_url`
+    ///
+    /// Reads pointed at a replica see writes only once they have replicated.
+    pub fn with_pool(
+        mut self,
+        workload: RedisWorkload,
+        redis_url: &str,
+        size: usize,
+    ) -> Result<Self, AppError> {
+        let pool = Arc::new(RedisPool::open(redis_url, size)?);
+        match workload {
+            RedisWorkload::Read => self.reads = pool,
+            RedisWorkload::Write => self.writes = pool,
+            RedisWorkload::Scan => self.scans = pool,
+        }
+        Ok(self)
+    }
+
+    /// Remove the dead members of one index, scanning it over `conn` and
+    /// checking each page on the primary
+    async fn prune_index(
+        &self,
+        conn: &mut ConnectionManager,
+        index: &str,
+        sorted: bool,
+    ) -> Result<u64, AppError> {
+        let script = redis::Script::new(PRUNE_INDEX_SCRIPT);
+        let mut pruned = 0;
+        let mut cursor = 0;
+        loop {
+            let (next, members): (u64, Vec<String>) = if sorted {
+                let (next, scored): (u64, Vec<String>) = redis::cmd("ZSCAN")
+                    .arg(index)
+                    .arg(cursor)
+                    .arg("COUNT")
+                    .arg(SCAN_PAGE_SIZE)
+                    .query_async(conn)
+                    .await
+                    .map_err(|e| redis_error("Redis ZSCAN failed", e))?;
+                // Replies alternate member and score
+                (next, scored.into_iter().step_by(2).collect())
+            } else {
+                redis::cmd("SSCAN")
+                    .arg(index)
+                    .arg(cursor)
+                    .arg("COUNT")
+                    .arg(SCAN_PAGE_SIZE)
+                    .query_async(conn)
+                    .await
+                    .map_err(|e| redis_error("Redis SSCAN failed", e))?
+            };
+
+            if !members.is_empty() {
+                let mut invocation = script.prepare_invoke();
+                invocation.key(index);
+                invocation.arg(if sorted { "zset" } else { "set" });
+                for member in &members {
+                    invocation.key(self.build_redis_key(member));
+                    invocation.arg(member);
+                }
+                let mut writes = self.writes.get().await?;
+                let removed: u64 = invocation
+                    .invoke_async(&mut writes)
+                    .await
+                    .map_err(|e| redis_error("Redis index prune failed", e))?;
+                pruned += removed;
+            }
 
-        redis::cmd("PING")
-            .query_async::<_, String>(&mut conn)
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// `GET` of `key` over a connection from `pool`
+    async fn get_from(&self, pool: &RedisPool, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        let mut conn = pool.get().await?;
+
+        let data: Option<Vec<u8>> = conn
+            .get(self.build_redis_key(key))
             .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis PING failed: {}", e)))?;
+            .map_err(|e| redis_error("Redis GET failed", e))?;
 
-        Ok(())
+        match data {
+            Some(raw) => self.decode_stored(&raw).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Allow `expires_at` to lag local time by up to `tolerance` before an entry
+    /// is treated as expired
+    pub fn with_clock_skew_tolerance(mut self, tolerance: std::time::Duration) -> Self {
+        self.clock_skew_tolerance = Duration::from_std(tolerance).unwrap_or(Duration::zero());
+        self
+    }
+
+    /// Write new entries in `format`; entries in either format are read
+    pub fn with_entry_format(mut self, format: EntryFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Compress answers of new entries with `dictionaries`
+    ///
+    /// Share them with [`Cache::with_dictionaries`], which trains and loads
+    /// the dictionaries.
+    pub fn with_dictionaries(mut self, dictionaries: Dictionaries) -> Self {
+        self.dictionaries = dictionaries;
+        self
     }
 
     fn build_redis_key(&self, key: &str) -> String {
         format!("scedge:artifact:{}", key)
     }
+
+    fn build_index_key(&self, index: &str) -> String {
+        format!("scedge:index:{}", index)
+    }
+
+    fn build_control_key(&self, name: &str) -> String {
+        format!("scedge:control:{}", name)
+    }
+
+    fn build_dictionary_key(&self, tenant: &str, field: &str) -> String {
+        format!("scedge:zdict:{}:{}", tenant, field)
+    }
+
+    fn build_event_key(&self, event_id: &str) -> String {
+        format!("scedge:event:{}", event_id)
+    }
+
+    /// Deserialize a stored entry in any format
+    ///
+    /// A dictionary another node trained is loaded on first use.
+    async fn decode_artifact(&self, raw: &[u8]) -> Result<CachedArtifact, AppError> {
+        if let Some((tenant, version)) = entry_format::missing_dictionary(raw, &self.dictionaries) {
+            if let Some(dictionary) = self.dictionary_load(&tenant, version).await? {
+                self.dictionaries.add(&tenant, version, dictionary);
+            }
+        }
+        entry_format::decode(raw, &self.dictionaries)
+    }
+
+    /// Deserialize a stored entry, or `None` when it has expired
+    async fn decode_entry(&self, raw: &[u8]) -> Result<Option<CachedArtifact>, AppError> {
+        Ok(match self.decode_stored(raw).await? {
+            StoredEntry::Live(artifact) => Some(artifact),
+            StoredEntry::Expired(_) => None,
+        })
+    }
+
+    /// Deserialize a stored entry, classifying it by its expiry
+    async fn decode_stored(&self, raw: &[u8]) -> Result<StoredEntry, AppError> {
+        let artifact = self.decode_artifact(raw).await?;
+        Ok(if self.is_expired(artifact.expires_at, Utc::now()) {
+            StoredEntry::Expired(artifact)
+        } else {
+            StoredEntry::Live(artifact)
+        })
+    }
+
+    /// Whether an entry is past `expires_at`, allowing for clock skew
+    ///
+    /// Every read applies this one rule. The Redis-native TTL, rounded down to
+    /// whole seconds at store time, normally removes an entry first; an entry
+    /// still present past its deadline was stored without one, or by a node
+    /// whose clock is behind this one's by more than the tolerance.
+    fn is_expired(&self, expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        expires_at.is_some_and(|exp| exp + self.clock_skew_tolerance <= now)
+    }
+}
+
+/// Error of an `If-Match` store that found no entry to compare
+fn no_entry_to_match() -> AppError {
+    AppError::precondition_failed("no entry to match If-Match")
+}
+
+/// A version's `stored_at` as serialized in an entry, for comparing in Lua
+fn serialized_stored_at(version: EntryVersion) -> String {
+    serde_json::to_value(version.stored_at())
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Classify a Redis error, failing with context
+///
+/// Connection, I/O, and timeout failures mean Redis is unreachable and become
+/// [`AppError::BackendUnavailable`]; anything else is an internal error.
+fn redis_error(context: &str, err: redis::RedisError) -> AppError {
+    if err.is_io_error()
+        || err.is_connection_refusal()
+        || err.is_connection_dropped()
+        || err.is_timeout()
+    {
+        AppError::backend_unavailable(anyhow::anyhow!("{}: {}", context, err))
+    } else {
+        AppError::Internal(anyhow::anyhow!("{}: {}", context, err))
+    }
+}
+
+/// Redis list holding undelivered notifications
+const OUTBOX_KEY: &str = "scedge:outbox";
+const OUTBOX_IN_FLIGHT_KEY: &str = "scedge:outbox:in_flight";
+const OUTBOX_DEAD_KEY: &str = "scedge:outbox:dead";
+
+/// Cursor state of a streaming `SCAN` + `MGET` traversal
+struct RedisScan {
+    cache: Arc<RedisCache>,
+    conn: Option<ConnectionManager>,
+    pattern: String,
+    cursor: u64,
+    done: bool,
+}
+
+impl RedisScan {
+    async fn next_page(&mut self) -> Result<Vec<CachedArtifact>, AppError> {
+        if self.conn.is_none() {
+            self.conn = Some(self.cache.scans.get().await?);
+        }
+        let conn = self.conn.as_mut().expect("connection initialized above");
+
+        let (cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(self.cursor)
+            .arg("MATCH")
+            .arg(&self.pattern)
+            .arg("COUNT")
+            .arg(100)
+            .query_async(conn)
+            .await
+            .map_err(|e| redis_error("Redis SCAN failed", e))?;
+
+        self.cursor = cursor;
+        self.done = cursor == 0;
+
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET")
+            .arg(&keys)
+            .query_async(conn)
+            .await
+            .map_err(|e| redis_error("Redis MGET failed", e))?;
+
+        let now = Utc::now();
+        let mut entries = Vec::with_capacity(values.len());
+        for (key, raw) in keys.iter().zip(values) {
+            let Some(raw) = raw else {
+                continue;
+            };
+            // One corrupt entry must not end a tenant-wide traversal
+            let artifact = match self.cache.decode_artifact(&raw).await {
+                Ok(artifact) => artifact,
+                Err(err) => {
+                    tracing::warn!(key = %key, error = %err, "Skipping undecodable entry in scan");
+                    continue;
+                }
+            };
+            if !self.cache.is_expired(artifact.expires_at, now) {
+                entries.push(artifact);
+            }
+        }
+
+        Ok(entries)
+    }
 }
 
 #[async_trait]
 impl CacheBackend for RedisCache {
     async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis connection failed: {}", e)))?;
+        Ok(match self.get_or_expired(key).await? {
+            Some(StoredEntry::Live(artifact)) => Some(artifact),
+            _ => None,
+        })
+    }
 
-        let redis_key = self.build_redis_key(key);
-        let data: Option<String> = conn
-            .get(&redis_key)
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis GET failed: {}", e)))?;
+    async fn get_or_expired(&self, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        self.get_from(&self.reads, key).await
+    }
 
-        match data {
-            Some(json) => {
-                let artifact: CachedArtifact = serde_json::from_str(&json).map_err(|e| {
-                    AppError::Internal(anyhow::anyhow!("Failed to deserialize artifact: {}", e))
-                })?;
+    /// Reads the primary, since a read replica may not have the latest store yet
+    async fn get_or_expired_strong(&self, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        self.get_from(&self.writes, key).await
+    }
 
-                // Check if expired
-                if let Some(expires_at) = artifact.expires_at {
-                    if expires_at <= Utc::now() {
-                        // Delete expired entry
-                        let _ = self.delete(key).await;
-                        return Ok(None);
-                    }
-                }
+    /// `GET` of every key in one pipeline
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<CachedArtifact>>, AppError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.reads.get().await?;
+
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.get(self.build_redis_key(key));
+        }
+        let replies: Vec<Option<Vec<u8>>> = pipe
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis GET failed", e))?;
 
-                Ok(Some(artifact))
+        let mut records = Vec::with_capacity(keys.len());
+        let mut expired = Vec::new();
+        for (key, data) in keys.iter().zip(replies) {
+            let Some(raw) = data else {
+                records.push(None);
+                continue;
+            };
+            let artifact = self.decode_entry(&raw).await?;
+            if artifact.is_none() {
+                expired.push(key.clone());
             }
-            None => Ok(None),
+            records.push(artifact);
+        }
+
+        if !expired.is_empty() {
+            let _ = self.delete_many(&expired).await;
         }
+        Ok(records)
     }
 
     async fn set(
@@ -136,60 +1175,113 @@ impl CacheBackend for RedisCache {
         artifact: ArtifactPayload,
         expires_at: Option<DateTime<Utc>>,
     ) -> Result<CachedArtifact, AppError> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis connection failed: {}", e)))?;
+        let mut conn = self.writes.get().await?;
 
         let now = Utc::now();
-        let cached = CachedArtifact {
+        let mut cached = CachedArtifact {
             key: key.clone(),
             artifact,
             stored_at: now,
             expires_at,
         };
 
-        let json = serde_json::to_string(&cached).map_err(|e| {
-            AppError::Internal(anyhow::anyhow!("Failed to serialize artifact: {}", e))
-        })?;
+        let entry = entry_format::encode(&mut cached, self.format, &self.dictionaries)?;
 
         let redis_key = self.build_redis_key(&key);
 
         if let Some(exp) = expires_at {
             let ttl = (exp - now).num_seconds();
             if ttl > 0 {
-                conn.set_ex::<_, _, ()>(&redis_key, json, ttl as u64)
+                conn.set_ex::<_, _, ()>(&redis_key, entry, ttl as u64)
                     .await
-                    .map_err(|e| {
-                        AppError::Internal(anyhow::anyhow!("Redis SETEX failed: {}", e))
-                    })?;
+                    .map_err(|e| redis_error("Redis SETEX failed", e))?;
             } else {
                 // Already expired, don't store
                 return Err(AppError::bad_request("Artifact already expired"));
             }
         } else {
-            conn.set::<_, _, ()>(&redis_key, json)
+            conn.set::<_, _, ()>(&redis_key, entry)
                 .await
-                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis SET failed: {}", e)))?;
+                .map_err(|e| redis_error("Redis SET failed", e))?;
         }
 
         Ok(cached)
     }
 
-    async fn delete(&self, key: &str) -> Result<bool, AppError> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
+    async fn set_conditional(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+        mode: StoreMode,
+    ) -> Result<SetOutcome, AppError> {
+        let now = Utc::now();
+        let ttl = match expires_at {
+            Some(exp) if (exp - now).num_seconds() > 0 => (exp - now).num_seconds(),
+            Some(_) => return Err(AppError::bad_request("Artifact already expired")),
+            None => 0,
+        };
+
+        let mut cached = CachedArtifact {
+            key: key.clone(),
+            artifact,
+            stored_at: now,
+            expires_at,
+        };
+        let entry = entry_format::encode(&mut cached, self.format, &self.dictionaries)?;
+        // Compared with the entry's `stored_at` as serialized
+        let expected_stored_at = match mode {
+            StoreMode::IfMatch(version) => serialized_stored_at(version),
+            _ => String::new(),
+        };
+
+        let mut conn = self.writes.get().await?;
+        let (status, existing): (i64, Vec<u8>) = redis::Script::new(SET_CONDITIONAL_SCRIPT)
+            .key(self.build_redis_key(&key))
+            .arg(entry)
+            .arg(ttl)
+            .arg(mode.as_str())
+            .arg(&cached.artifact.hash)
+            .arg(expected_stored_at)
+            .invoke_async(&mut conn)
             .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis connection failed: {}", e)))?;
+            .map_err(|e| redis_error("Redis conditional SET failed", e))?;
+
+        match status {
+            1 => Ok(SetOutcome::Created(cached)),
+            2 => Ok(SetOutcome::Updated(cached)),
+            3 => Err(no_entry_to_match()),
+            _ => match self.decode_entry(&existing).await? {
+                Some(existing) => Ok(SetOutcome::Kept(existing)),
+                None if matches!(mode, StoreMode::IfMatch(_)) => Err(no_entry_to_match()),
+                // Past its expiry but not yet removed by Redis
+                None => Ok(SetOutcome::Created(
+                    self.set(key, cached.artifact, cached.expires_at).await?,
+                )),
+            },
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, AppError> {
+        let mut conn = self.writes.get().await?;
 
         let redis_key = self.build_redis_key(key);
         let deleted: i32 = conn
             .del(&redis_key)
             .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis DEL failed: {}", e)))?;
+            .map_err(|e| redis_error("Redis DEL failed", e))?;
+
+        Ok(deleted > 0)
+    }
 
+    async fn delete_if_match(&self, key: &str, version: EntryVersion) -> Result<bool, AppError> {
+        let mut conn = self.writes.get().await?;
+        let deleted: i32 = redis::Script::new(DELETE_IF_MATCH_SCRIPT)
+            .key(self.build_redis_key(key))
+            .arg(serialized_stored_at(version))
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis conditional DEL failed", e))?;
         Ok(deleted > 0)
     }
 
@@ -198,28 +1290,22 @@ impl CacheBackend for RedisCache {
             return Ok(0);
         }
 
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis connection failed: {}", e)))?;
+        let mut conn = self.writes.get().await?;
 
         let redis_keys: Vec<String> = keys.iter().map(|k| self.build_redis_key(k)).collect();
 
-        let deleted: usize = conn
-            .del(&redis_keys)
+        // UNLINK frees values in the background instead of blocking Redis
+        let deleted: usize = redis::cmd("UNLINK")
+            .arg(&redis_keys)
+            .query_async(&mut conn)
             .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis DEL failed: {}", e)))?;
+            .map_err(|e| redis_error("Redis UNLINK failed", e))?;
 
         Ok(deleted)
     }
 
     async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis connection failed: {}", e)))?;
+        let mut conn = self.scans.get().await?;
 
         let search_pattern = format!("scedge:artifact:{}", pattern);
         let mut keys = Vec::new();
@@ -234,7 +1320,7 @@ impl CacheBackend for RedisCache {
                 .arg(100)
                 .query_async(&mut conn)
                 .await
-                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis SCAN failed: {}", e)))?;
+                .map_err(|e| redis_error("Redis SCAN failed", e))?;
 
             // Strip the "scedge:artifact:" prefix
             for key in batch {
@@ -251,43 +1337,4053 @@ impl CacheBackend for RedisCache {
 
         Ok(keys)
     }
+
+    async fn scan_page(
+        &self,
+        pattern: &str,
+        cursor: Option<&str>,
+        count: usize,
+    ) -> Result<(Option<String>, Vec<String>), AppError> {
+        let cursor: u64 = match cursor {
+            Some(raw) => raw
+                .parse()
+                .map_err(|_| AppError::bad_request("invalid scan cursor"))?,
+            None => 0,
+        };
+
+        let mut conn = self.scans.get().await?;
+
+        let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(self.build_redis_key(pattern))
+            .arg("COUNT")
+            .arg(count.max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis SCAN failed", e))?;
+
+        let keys = batch
+            .into_iter()
+            .filter_map(|key| key.strip_prefix("scedge:artifact:").map(str::to_string))
+            .collect();
+
+        Ok(((next != 0).then(|| next.to_string()), keys))
+    }
+
+    fn scan_entries(
+        self: Arc<Self>,
+        pattern: String,
+    ) -> BoxStream<'static, Result<CachedArtifact, AppError>> {
+        let state = RedisScan {
+            pattern: self.build_redis_key(&pattern),
+            cache: self,
+            conn: None,
+            cursor: 0,
+            done: false,
+        };
+
+        // Each step runs one SCAN page and fetches its values with a single MGET
+        stream::unfold(state, |mut state| async move {
+            if state.done {
+                return None;
+            }
+
+            let batch = state.next_page().await;
+            if batch.is_err() {
+                state.done = true;
+            }
+            Some((batch, state))
+        })
+        .flat_map(|batch| match batch {
+            Ok(entries) => stream::iter(entries.into_iter().map(Ok)).boxed(),
+            Err(err) => stream::once(async move { Err(err) }).boxed(),
+        })
+        .boxed()
+    }
+
+    async fn index_add(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        if members.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.writes.get().await?;
+
+        conn.sadd::<_, _, ()>(self.build_index_key(index), members)
+            .await
+            .map_err(|e| redis_error("Redis SADD failed", e))
+    }
+
+    async fn index_remove(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        if members.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.writes.get().await?;
+
+        conn.srem::<_, _, ()>(self.build_index_key(index), members)
+            .await
+            .map_err(|e| redis_error("Redis SREM failed", e))
+    }
+
+    async fn index_members(&self, index: &str) -> Result<Vec<String>, AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.smembers(self.build_index_key(index))
+            .await
+            .map_err(|e| redis_error("Redis SMEMBERS failed", e))
+    }
+
+    async fn index_clear(&self, index: &str) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.del::<_, ()>(self.build_index_key(index))
+            .await
+            .map_err(|e| redis_error("Redis DEL failed", e))
+    }
+
+    /// `SCAN`s `scedge:index:*`, then `SSCAN`s (or `ZSCAN`s) each index and
+    /// removes the members whose artifact is gone, a page at a time
+    async fn prune_indexes(&self) -> Result<u64, AppError> {
+        let mut conn = self.scans.get().await?;
+
+        let modified = self.build_index_key(MODIFIED_INDEX);
+        let mut pruned = 0;
+        let mut cursor = 0;
+        loop {
+            let (next, indexes): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg("scedge:index:*")
+                .arg("COUNT")
+                .arg(SCAN_PAGE_SIZE)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| redis_error("Redis SCAN failed", e))?;
+            for index in indexes {
+                pruned += self
+                    .prune_index(&mut conn, &index, index == modified)
+                    .await?;
+            }
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Sorted set `scedge:index:modified` scored by write time in milliseconds
+    async fn index_modified(&self, key: &str, at: DateTime<Utc>) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.zadd::<_, _, _, ()>(
+            self.build_index_key(MODIFIED_INDEX),
+            key,
+            at.timestamp_millis(),
+        )
+        .await
+        .map_err(|e| redis_error("Redis ZADD failed", e))
+    }
+
+    async fn unindex_modified(&self, keys: &[String]) -> Result<(), AppError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.writes.get().await?;
+
+        conn.zrem::<_, _, ()>(self.build_index_key(MODIFIED_INDEX), keys)
+            .await
+            .map_err(|e| redis_error("Redis ZREM failed", e))
+    }
+
+    async fn modified_since(&self, since: DateTime<Utc>) -> Result<Vec<String>, AppError> {
+        let mut conn = self.scans.get().await?;
+
+        conn.zrangebyscore(
+            self.build_index_key(MODIFIED_INDEX),
+            since.timestamp_millis(),
+            "+inf",
+        )
+        .await
+        .map_err(|e| redis_error("Redis ZRANGEBYSCORE failed", e))
+    }
+
+    async fn exists_many(&self, keys: &[String]) -> Result<Vec<bool>, AppError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.reads.get().await?;
+
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.exists(self.build_redis_key(key));
+        }
+
+        pipe.query_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis EXISTS failed", e))
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<EntryMetadata>, AppError> {
+        let mut conn = self.writes.get().await?;
+
+        let found: Option<(String, Vec<u8>)> = redis::Script::new(METADATA_SCRIPT)
+            .key(self.build_redis_key(key))
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis metadata lookup failed", e))?;
+
+        let Some((json, expiry)) = found else {
+            return Ok(None);
+        };
+        let mut metadata: EntryMetadata = serde_json::from_str(&json).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to deserialize metadata: {}", e))
+        })?;
+        if !expiry.is_empty() {
+            metadata.expires_at = entry_format::decode_expiry(&expiry)?;
+        }
+
+        if self.is_expired(metadata.expires_at, Utc::now()) {
+            return Ok(None);
+        }
+
+        Ok(Some(metadata))
+    }
+
+    async fn control_get(&self, name: &str) -> Result<Option<String>, AppError> {
+        let mut conn = self.reads.get().await?;
+
+        conn.get(self.build_control_key(name))
+            .await
+            .map_err(|e| redis_error("Redis GET failed", e))
+    }
+
+    async fn control_set(&self, name: &str, value: &str) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.set::<_, _, ()>(self.build_control_key(name), value)
+            .await
+            .map_err(|e| redis_error("Redis SET failed", e))
+    }
+
+    async fn control_delete(&self, name: &str) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.del::<_, ()>(self.build_control_key(name))
+            .await
+            .map_err(|e| redis_error("Redis DEL failed", e))
+    }
+
+    async fn dictionary_store(&self, tenant: &str, dictionary: &[u8]) -> Result<u32, AppError> {
+        let mut conn = self.writes.get().await?;
+
+        let version: u32 = conn
+            .incr(self.build_dictionary_key(tenant, "version"), 1)
+            .await
+            .map_err(|e| redis_error("Redis INCR failed", e))?;
+        conn.hset::<_, _, _, ()>(
+            self.build_dictionary_key(tenant, "versions"),
+            version,
+            dictionary,
+        )
+        .await
+        .map_err(|e| redis_error("Redis HSET failed", e))?;
+
+        Ok(version)
+    }
+
+    async fn dictionary_load(
+        &self,
+        tenant: &str,
+        version: u32,
+    ) -> Result<Option<Vec<u8>>, AppError> {
+        let mut conn = self.reads.get().await?;
+
+        conn.hget(self.build_dictionary_key(tenant, "versions"), version)
+            .await
+            .map_err(|e| redis_error("Redis HGET failed", e))
+    }
+
+    async fn dictionary_latest(&self, tenant: &str) -> Result<Option<(u32, Vec<u8>)>, AppError> {
+        let mut conn = self.reads.get().await?;
+
+        let version: Option<u32> = conn
+            .get(self.build_dictionary_key(tenant, "version"))
+            .await
+            .map_err(|e| redis_error("Redis GET failed", e))?;
+        let Some(version) = version else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .dictionary_load(tenant, version)
+            .await?
+            .map(|dictionary| (version, dictionary)))
+    }
+
+    async fn wal_append(&self, config: &WalConfig, entries: &[WalEntry]) -> Result<(), AppError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.writes.get().await?;
+
+        let mut pipe = redis::pipe();
+        for entry in entries {
+            let command = pipe
+                .cmd("XADD")
+                .arg(&config.stream)
+                .arg("MAXLEN")
+                .arg("~")
+                .arg(config.max_len)
+                .arg("*");
+            for (field, value) in entry.fields() {
+                command.arg(field).arg(value);
+            }
+            command.ignore();
+        }
+
+        pipe.query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis XADD failed", e))
+    }
+
+    async fn hashes_many(&self, keys: &[String]) -> Result<Vec<Option<EntryHash>>, AppError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.writes.get().await?;
+
+        let script = redis::Script::new(HASHES_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(self.build_redis_key(key));
+        }
+
+        let hashes: Vec<Option<String>> = invocation
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis hash lookup failed", e))?;
+        // An entry whose policy cannot be read reports no hash
+        Ok(hashes
+            .into_iter()
+            .map(|json| json.and_then(|json| serde_json::from_str(&json).ok()))
+            .collect())
+    }
+
+    async fn purge_referencing_hash(
+        &self,
+        keys: &[String],
+        hash: &str,
+    ) -> Result<Vec<String>, AppError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.writes.get().await?;
+
+        let script = redis::Script::new(SUPERSEDE_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(self.build_redis_key(key));
+        }
+        let purged: Vec<String> = invocation
+            .arg(hash)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis provenance purge failed", e))?;
+
+        Ok(purged
+            .into_iter()
+            .filter_map(|key| key.strip_prefix("scedge:artifact:").map(str::to_string))
+            .collect())
+    }
+
+    /// `DBSIZE`, which also counts Scedge's index, outbox, and ledger keys
+    async fn key_count(&self) -> Result<u64, AppError> {
+        let mut conn = self.reads.get().await?;
+
+        redis::cmd("DBSIZE")
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis DBSIZE failed", e))
+    }
+
+    async fn touch_many(
+        &self,
+        tenant: &str,
+        keys: &[String],
+        ttl_seconds: u64,
+    ) -> Result<Vec<Option<DateTime<Utc>>>, AppError> {
+        if keys.is_empty() || ttl_seconds == 0 {
+            return Ok(vec![None; keys.len()]);
+        }
+
+        let mut conn = self.writes.get().await?;
+
+        let expires_at = Utc::now() + Duration::seconds(ttl_seconds as i64);
+        let encoded = serde_json::to_string(&expires_at).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to serialize expiry: {}", e))
+        })?;
+
+        let script = redis::Script::new(TOUCH_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(self.build_redis_key(key));
+        }
+        invocation
+            .arg(ttl_seconds)
+            .arg(encoded)
+            .arg(tenant)
+            .arg(&entry_format::encode_expiry(Some(expires_at))[..]);
+
+        let touched: Vec<i64> = invocation
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis touch failed", e))?;
+
+        Ok(touched
+            .into_iter()
+            .map(|flag| (flag == 1).then_some(expires_at))
+            .collect())
+    }
+
+    async fn mark_event_processed(
+        &self,
+        event_id: &str,
+        retention: std::time::Duration,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.writes.get().await?;
+
+        let recorded: Option<String> = redis::cmd("SET")
+            .arg(self.build_event_key(event_id))
+            .arg(Utc::now().to_rfc3339())
+            .arg("NX")
+            .arg("PX")
+            .arg(retention.as_millis().max(1) as u64)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis SET NX failed", e))?;
+
+        Ok(recorded.is_some())
+    }
+
+    async fn unmark_event_processed(&self, event_id: &str) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.del::<_, ()>(self.build_event_key(event_id))
+            .await
+            .map_err(|e| redis_error("Redis DEL failed", e))
+    }
+
+    async fn outbox_push(&self, entry: String) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.rpush::<_, _, ()>(OUTBOX_KEY, entry)
+            .await
+            .map_err(|e| redis_error("Redis RPUSH failed", e))
+    }
+
+    async fn outbox_claim(&self) -> Result<Option<String>, AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.lmove(
+            OUTBOX_KEY,
+            OUTBOX_IN_FLIGHT_KEY,
+            redis::Direction::Left,
+            redis::Direction::Right,
+        )
+        .await
+        .map_err(|e| redis_error("Redis LMOVE failed", e))
+    }
+
+    async fn outbox_ack(&self, entry: &str) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        conn.lrem::<_, _, ()>(OUTBOX_IN_FLIGHT_KEY, 1, entry)
+            .await
+            .map_err(|e| redis_error("Redis LREM failed", e))
+    }
+
+    async fn outbox_dead_letter(&self, entry: &str) -> Result<(), AppError> {
+        let mut conn = self.writes.get().await?;
+
+        redis::pipe()
+            .atomic()
+            .lrem(OUTBOX_IN_FLIGHT_KEY, 1, entry)
+            .ignore()
+            .rpush(OUTBOX_DEAD_KEY, entry)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(|e| redis_error("Redis dead-letter failed", e))
+    }
+
+    async fn outbox_recover(&self) -> Result<usize, AppError> {
+        let mut conn = self.writes.get().await?;
+
+        // Newest first onto the head, so the queue keeps the original order
+        let mut recovered = 0;
+        loop {
+            let moved: Option<String> = conn
+                .lmove(
+                    OUTBOX_IN_FLIGHT_KEY,
+                    OUTBOX_KEY,
+                    redis::Direction::Right,
+                    redis::Direction::Left,
+                )
+                .await
+                .map_err(|e| redis_error("Redis LMOVE failed", e))?;
+            if moved.is_none() {
+                return Ok(recovered);
+            }
+            recovered += 1;
+        }
+    }
+
+    /// Test the connections of every pool
+    async fn ping(&self) -> Result<(), AppError> {
+        for pool in [&self.writes, &self.reads, &self.scans] {
+            let mut conn = pool.get().await?;
+            redis::cmd("PING")
+                .query_async::<_, String>(&mut conn)
+                .await
+                .map_err(|e| redis_error("Redis PING failed", e))?;
+        }
+
+        Ok(())
+    }
 }
 
-/// Cache wrapper that can use different backends
-#[derive(Clone)]
-pub struct Cache {
-    backend: Arc<dyn CacheBackend>,
+/// Entries held by the in-memory backend
+#[derive(Default)]
+pub struct CacheState {
+    entries: HashMap<String, CachedArtifact>,
+    /// Bookkeeping of every entry in `entries`
+    slots: HashMap<String, Slot>,
+    /// Every entry by its [`Rank`], the next to evict first
+    eviction_order: BTreeSet<(Rank, String)>,
+    /// Entries with an expiry or maximum age, the first to expire first
+    deadlines: BTreeSet<(DateTime<Utc>, String)>,
+    /// Approximate bytes held, tracked for [`MemoryCache::with_max_bytes`]
+    bytes: usize,
+    /// Logical clock ordering reads for LRU eviction, advanced under the read lock
+    clock: AtomicU64,
+    indexes: HashMap<String, HashSet<String>>,
+    processed_events: HashMap<String, DateTime<Utc>>,
+    outbox: VecDeque<String>,
+    outbox_in_flight: Vec<String>,
+    outbox_dead: Vec<String>,
+    control: HashMap<String, String>,
 }
 
-impl Cache {
-    pub fn new(backend: impl CacheBackend + 'static) -> Self {
-        Self {
-            backend: Arc::new(backend),
+/// Bookkeeping of one in-memory entry
+struct Slot {
+    /// Tracked for [`MemoryCache::with_max_age`]
+    inserted_at: DateTime<Utc>,
+    /// Clock value of the last read or write
+    last_access: AtomicU64,
+    /// Position in `eviction_order`; under LRU, reads since then are only
+    /// applied once the entry comes up for eviction
+    rank: Rank,
+    /// Position in `deadlines`
+    deadline: Option<DateTime<Utc>>,
+    /// Approximate size; zero unless the cache is bounded by bytes
+    size: usize,
+}
+
+/// Eviction order of an entry, the lowest evicted first
+#[derive(Debug, Clone, Copy)]
+enum Rank {
+    /// Compute cost, then expiry, for [`EvictionPolicy::Cost`]
+    Cost(f64, DateTime<Utc>),
+    /// Clock value of an access, for [`EvictionPolicy::Lru`]
+    Access(u64),
+}
+
+impl Ord for Rank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (Rank::Cost(cost, expiry), Rank::Cost(other_cost, other_expiry)) => cost
+                .total_cmp(other_cost)
+                .then_with(|| expiry.cmp(other_expiry)),
+            (Rank::Access(access), Rank::Access(other_access)) => access.cmp(other_access),
+            (Rank::Cost(..), Rank::Access(_)) => std::cmp::Ordering::Less,
+            (Rank::Access(_), Rank::Cost(..)) => std::cmp::Ordering::Greater,
         }
     }
+}
 
-    pub async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
-        self.backend.get(key).await
+impl PartialOrd for Rank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    pub async fn set(
-        &self,
-        key: String,
-        artifact: ArtifactPayload,
-        expires_at: Option<DateTime<Utc>>,
-    ) -> Result<CachedArtifact, AppError> {
-        self.backend.set(key, artifact, expires_at).await
+impl PartialEq for Rank {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
     }
+}
 
-    pub async fn delete(&self, key: &str) -> Result<bool, AppError> {
-        self.backend.delete(key).await
+impl Eq for Rank {}
+
+/// Allowance for the fields of a record not counted by [`estimated_size`]
+const RECORD_OVERHEAD: usize = 256;
+
+/// Approximate size of a record without serializing it: its key, hash,
+/// tags, dependencies, and answer, plus [`RECORD_OVERHEAD`]
+fn estimated_size(record: &CachedArtifact) -> usize {
+    let artifact = &record.artifact;
+    let strings = |values: &[String]| values.iter().map(String::len).sum::<usize>();
+    RECORD_OVERHEAD
+        + record.key.len()
+        + artifact.hash.len()
+        + strings(&artifact.tags)
+        + strings(&artifact.depends_on)
+        + value_size(&artifact.answer)
+}
+
+/// Approximate size of a JSON value: its strings and object keys, and eight
+/// bytes for every other value
+fn value_size(value: &Value) -> usize {
+    match value {
+        Value::String(text) => text.len(),
+        Value::Array(items) => 8 + items.iter().map(value_size).sum::<usize>(),
+        Value::Object(fields) => {
+            8 + fields
+                .iter()
+                .map(|(name, value)| name.len() + value_size(value))
+                .sum::<usize>()
+        }
+        _ => 8,
     }
+}
 
-    pub async fn delete_many(&self, keys: &[String]) -> Result<usize, AppError> {
-        self.backend.delete_many(keys).await
+/// Which entry a full [`MemoryCache`] evicts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// The artifact cheapest to regenerate (lowest `metrics.compute_cost`,
+    /// missing costs count as zero), breaking ties by the earliest expiry
+    #[default]
+    Cost,
+    /// The least recently read or written entry
+    Lru,
+}
+
+impl std::str::FromStr for EvictionPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "cost" => Ok(EvictionPolicy::Cost),
+            "lru" => Ok(EvictionPolicy::Lru),
+            other => Err(format!("unknown eviction policy {}", other)),
+        }
     }
+}
 
-    pub async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError> {
-        self.backend.scan_by_pattern(pattern).await
+/// In-memory cache backend
+///
+/// Expired entries are dropped lazily on read and by
+/// [`CacheBackend::sweep_expired`]. Pattern scans use the same glob
+/// syntax as Redis `MATCH` (`*`, `?`, and `\` escapes).
+///
+/// When bounded with [`MemoryCache::with_max_entries`] or
+/// [`MemoryCache::with_max_bytes`], inserting into a full cache first drops
+/// expired entries, then evicts entries chosen by the [`EvictionPolicy`] until
+/// the new one fits. Evictions are counted in `scedge_memory_evictions_total`
+/// when [`MemoryCache::with_metrics`] is set.
+///
+/// Expiry, maximum age, and the event ledger follow the [`Clock`] given to
+/// [`MemoryCache::with_clock`], the system clock by default.
+#[derive(Clone, Default)]
+pub struct MemoryCache {
+    state: Arc<RwLock<CacheState>>,
+    max_entries: Option<usize>,
+    max_bytes: Option<usize>,
+    max_age: Option<std::time::Duration>,
+    eviction: EvictionPolicy,
+    metrics: Option<Metrics>,
+    clock: Clock,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bound the number of cached artifacts
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Bound the approximate size of cached artifacts, estimated from their
+    /// key, answer, and other strings
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Choose which entry a full cache evicts
+    pub fn with_eviction(mut self, eviction: EvictionPolicy) -> Self {
+        self.eviction = eviction;
+        self
+    }
+
+    /// Count evictions in `metrics`
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Drop entries `max_age` after they were inserted, even if they expire later
+    pub fn with_max_age(mut self, max_age: std::time::Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Read the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: impl Into<Clock>) -> Self {
+        self.clock = clock.into();
+        self
+    }
+
+    /// Insert a record as is, keeping its `stored_at`
+    async fn insert(&self, record: CachedArtifact) {
+        let size = match self.max_bytes {
+            Some(_) => estimated_size(&record),
+            None => 0,
+        };
+
+        let mut state = self.state.write().await;
+        state.remove_entry(&record.key);
+
+        // An entry over the whole byte budget is not kept at all
+        if self.max_bytes.is_some_and(|max_bytes| size > max_bytes) {
+            self.record_evictions("bytes", 1);
+            return;
+        }
+        self.make_room(&mut state, size);
+
+        let access = state.tick();
+        let slot = self.slot(&record, self.clock.now(), access, size);
+        state.insert_entry(record, slot);
+    }
+
+    /// Bookkeeping of `record`, inserted at `inserted_at` and last accessed
+    /// at `access`
+    fn slot(
+        &self,
+        record: &CachedArtifact,
+        inserted_at: DateTime<Utc>,
+        access: u64,
+        size: usize,
+    ) -> Slot {
+        let rank = match self.eviction {
+            EvictionPolicy::Cost => Rank::Cost(
+                record.artifact.compute_cost().unwrap_or(0.0),
+                record.expires_at.unwrap_or(DateTime::<Utc>::MAX_UTC),
+            ),
+            EvictionPolicy::Lru => Rank::Access(access),
+        };
+        let max_age_deadline = self
+            .max_age
+            .and_then(|max_age| Duration::from_std(max_age).ok())
+            .and_then(|max_age| inserted_at.checked_add_signed(max_age));
+        let deadline = match (record.expires_at, max_age_deadline) {
+            (Some(expires_at), Some(max_age_deadline)) => Some(expires_at.min(max_age_deadline)),
+            (expires_at, max_age_deadline) => expires_at.or(max_age_deadline),
+        };
+        Slot {
+            inserted_at,
+            last_access: AtomicU64::new(access),
+            rank,
+            deadline,
+            size,
+        }
+    }
+
+    /// Drop every cached artifact, keeping indexes, ledgers, and control records
+    pub async fn clear_entries(&self) {
+        let mut state = self.state.write().await;
+        state.entries.clear();
+        state.slots.clear();
+        state.eviction_order.clear();
+        state.deadlines.clear();
+        state.bytes = 0;
+    }
+
+    /// Whether an entry is past its expiry or [`MemoryCache::with_max_age`]
+    fn is_expired(&self, entry: &CachedArtifact, slot: Option<&Slot>, now: DateTime<Utc>) -> bool {
+        entry.expires_at.is_some_and(|exp| exp <= now) || self.is_past_max_age(slot, now)
+    }
+
+    fn is_past_max_age(&self, slot: Option<&Slot>, now: DateTime<Utc>) -> bool {
+        self.max_age.is_some_and(|max_age| {
+            slot.is_some_and(|slot| {
+                (now - slot.inserted_at)
+                    .to_std()
+                    .is_ok_and(|age| age >= max_age)
+            })
+        })
+    }
+
+    /// Whether a read drops the entry rather than returning it
+    fn drops_on_read(
+        &self,
+        entry: &CachedArtifact,
+        slot: Option<&Slot>,
+        now: DateTime<Utc>,
+        keep_expired: bool,
+    ) -> bool {
+        self.is_past_max_age(slot, now)
+            || (!keep_expired && entry.expires_at.is_some_and(|exp| exp <= now))
+    }
+
+    /// Read `key`, dropping it once past its expiry unless `keep_expired`
+    ///
+    /// Entries past [`MemoryCache::with_max_age`] are always dropped. Reads
+    /// share the read lock; only dropping an entry takes the write lock.
+    async fn read(&self, key: &str, keep_expired: bool) -> Option<StoredEntry> {
+        let now = self.clock.now();
+        {
+            let state = self.state.read().await;
+            let artifact = state.entries.get(key)?;
+            let slot = state.slots.get(key);
+            if !self.drops_on_read(artifact, slot, now, keep_expired) {
+                if artifact.expires_at.is_some_and(|exp| exp <= now) {
+                    return Some(StoredEntry::Expired(artifact.clone()));
+                }
+                if let Some(slot) = slot {
+                    slot.last_access.store(state.tick(), Ordering::Relaxed);
+                }
+                return Some(StoredEntry::Live(artifact.clone()));
+            }
+        }
+
+        // The entry may have been replaced before the write lock was taken
+        let mut state = self.state.write().await;
+        if state
+            .entries
+            .get(key)
+            .is_some_and(|entry| self.drops_on_read(entry, state.slots.get(key), now, keep_expired))
+        {
+            state.remove_entry(key);
+        }
+        None
+    }
+
+    /// Evict entries until one more of `size` bytes fits
+    fn make_room(&self, state: &mut CacheState, size: usize) {
+        let over_entries = |state: &CacheState| {
+            self.max_entries
+                .is_some_and(|max| state.entries.len() >= max.max(1))
+        };
+        let over_bytes =
+            |state: &CacheState| self.max_bytes.is_some_and(|max| state.bytes + size > max);
+        if !over_entries(state) && !over_bytes(state) {
+            return;
+        }
+
+        // Expired entries go first and do not count as evictions
+        state.drop_expired(self.clock.now());
+
+        let (mut by_entries, mut by_bytes) = (0, 0);
+        loop {
+            if over_entries(state) {
+                by_entries += 1;
+            } else if over_bytes(state) {
+                by_bytes += 1;
+            } else {
+                break;
+            }
+            let Some(victim) = state.next_victim() else {
+                break;
+            };
+            state.remove_entry(&victim);
+        }
+        self.record_evictions("entries", by_entries);
+        self.record_evictions("bytes", by_bytes);
+    }
+
+    fn record_evictions(&self, reason: &str, count: u64) {
+        if let Some(metrics) = &self.metrics {
+            if count > 0 {
+                metrics.record_memory_evictions(reason, count);
+            }
+        }
+    }
+}
+
+impl CacheState {
+    /// Advance the logical clock
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Add an entry and its bookkeeping
+    fn insert_entry(&mut self, record: CachedArtifact, slot: Slot) {
+        self.bytes += slot.size;
+        self.eviction_order.insert((slot.rank, record.key.clone()));
+        if let Some(deadline) = slot.deadline {
+            self.deadlines.insert((deadline, record.key.clone()));
+        }
+        self.slots.insert(record.key.clone(), slot);
+        self.entries.insert(record.key.clone(), record);
+    }
+
+    /// Remove an entry and its bookkeeping
+    fn remove_entry(&mut self, key: &str) -> Option<CachedArtifact> {
+        if let Some(slot) = self.slots.remove(key) {
+            self.bytes = self.bytes.saturating_sub(slot.size);
+            self.eviction_order.remove(&(slot.rank, key.to_string()));
+            if let Some(deadline) = slot.deadline {
+                self.deadlines.remove(&(deadline, key.to_string()));
+            }
+        }
+        self.entries.remove(key)
+    }
+
+    /// Remove the entries past their deadline at `now`, returning how many
+    fn drop_expired(&mut self, now: DateTime<Utc>) -> usize {
+        let mut dropped = 0;
+        while let Some((deadline, key)) = self.deadlines.first() {
+            if *deadline > now {
+                break;
+            }
+            let key = key.clone();
+            self.remove_entry(&key);
+            dropped += 1;
+        }
+        dropped
+    }
+
+    /// Entry to evict next
+    ///
+    /// Under LRU an entry read since it was ranked is ranked again by its
+    /// last access and the next one considered.
+    fn next_victim(&mut self) -> Option<String> {
+        loop {
+            let (rank, key) = self.eviction_order.first()?.clone();
+            let Some(slot) = self.slots.get_mut(&key) else {
+                self.eviction_order.pop_first();
+                continue;
+            };
+            let last_access = *slot.last_access.get_mut();
+            match rank {
+                Rank::Access(ranked) if ranked < last_access => {
+                    self.eviction_order.pop_first();
+                    slot.rank = Rank::Access(last_access);
+                    self.eviction_order.insert((slot.rank, key));
+                }
+                _ => return Some(key),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
+        Ok(match self.read(key, false).await {
+            Some(StoredEntry::Live(artifact)) => Some(artifact),
+            _ => None,
+        })
+    }
+
+    async fn get_or_expired(&self, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        Ok(self.read(key, true).await)
+    }
+
+    async fn set(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<CachedArtifact, AppError> {
+        let now = self.clock.now();
+        if expires_at.is_some_and(|exp| exp <= now) {
+            return Err(AppError::bad_request("Artifact already expired"));
+        }
+
+        let cached = CachedArtifact {
+            key,
+            artifact,
+            stored_at: now,
+            expires_at,
+        };
+        self.insert(cached.clone()).await;
+        Ok(cached)
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, AppError> {
+        Ok(self.delete_many(&[key.to_string()]).await? > 0)
+    }
+
+    async fn delete_many(&self, keys: &[String]) -> Result<usize, AppError> {
+        let mut state = self.state.write().await;
+        Ok(keys
+            .iter()
+            .filter(|key| state.remove_entry(key).is_some())
+            .count())
+    }
+
+    async fn delete_if_match(&self, key: &str, version: EntryVersion) -> Result<bool, AppError> {
+        let mut state = self.state.write().await;
+        if !state
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.version() == version)
+        {
+            return Ok(false);
+        }
+        Ok(state.remove_entry(key).is_some())
+    }
+
+    async fn touch_many(
+        &self,
+        tenant: &str,
+        keys: &[String],
+        ttl_seconds: u64,
+    ) -> Result<Vec<Option<DateTime<Utc>>>, AppError> {
+        if ttl_seconds == 0 {
+            return Ok(vec![None; keys.len()]);
+        }
+
+        let now = self.clock.now();
+        let expires_at = now + Duration::seconds(ttl_seconds as i64);
+        let mut state = self.state.write().await;
+        let state = &mut *state;
+        Ok(keys
+            .iter()
+            .map(|key| {
+                let entry = state.entries.get(key)?;
+                let slot = state.slots.get(key)?;
+                if entry.artifact.policy.tenant != tenant || self.is_expired(entry, Some(slot), now)
+                {
+                    return None;
+                }
+                let (inserted_at, access, size) = (
+                    slot.inserted_at,
+                    slot.last_access.load(Ordering::Relaxed),
+                    slot.size,
+                );
+
+                // Re-rank under the new expiry
+                let mut record = state.remove_entry(key)?;
+                record.expires_at = Some(expires_at);
+                let slot = self.slot(&record, inserted_at, access, size);
+                state.insert_entry(record, slot);
+                Some(expires_at)
+            })
+            .collect())
+    }
+
+    async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError> {
+        let state = self.state.read().await;
+        Ok(state
+            .entries
+            .keys()
+            .filter(|key| glob_match(pattern, key))
+            .cloned()
+            .collect())
+    }
+
+    fn scan_entries(
+        self: Arc<Self>,
+        pattern: String,
+    ) -> BoxStream<'static, Result<CachedArtifact, AppError>> {
+        stream::once(async move {
+            let now = self.clock.now();
+            let state = self.state.read().await;
+            state
+                .entries
+                .values()
+                .filter(|entry| glob_match(&pattern, &entry.key))
+                .filter(|entry| match entry.expires_at {
+                    Some(exp) => exp > now,
+                    None => true,
+                })
+                .cloned()
+                .map(Ok)
+                .collect::<Vec<_>>()
+        })
+        .flat_map(stream::iter)
+        .boxed()
+    }
+
+    async fn index_add(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        let mut state = self.state.write().await;
+        state
+            .indexes
+            .entry(index.to_string())
+            .or_default()
+            .extend(members.iter().cloned());
+        Ok(())
+    }
+
+    async fn index_remove(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        let mut state = self.state.write().await;
+        if let Some(set) = state.indexes.get_mut(index) {
+            for member in members {
+                set.remove(member);
+            }
+            if set.is_empty() {
+                state.indexes.remove(index);
+            }
+        }
+        Ok(())
+    }
+
+    async fn index_members(&self, index: &str) -> Result<Vec<String>, AppError> {
+        let state = self.state.read().await;
+        Ok(state
+            .indexes
+            .get(index)
+            .map(|members| members.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn index_clear(&self, index: &str) -> Result<(), AppError> {
+        self.state.write().await.indexes.remove(index);
+        Ok(())
+    }
+
+    async fn prune_indexes(&self) -> Result<u64, AppError> {
+        let mut state = self.state.write().await;
+        let state = &mut *state;
+        let mut pruned = 0;
+        for members in state.indexes.values_mut() {
+            let before = members.len();
+            members.retain(|member| state.entries.contains_key(member));
+            pruned += before - members.len();
+        }
+        state.indexes.retain(|_, members| !members.is_empty());
+        Ok(pruned as u64)
+    }
+
+    async fn mark_event_processed(
+        &self,
+        event_id: &str,
+        retention: std::time::Duration,
+    ) -> Result<bool, AppError> {
+        let now = self.clock.now();
+        let retention = Duration::from_std(retention)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid retention: {}", e)))?;
+
+        let mut state = self.state.write().await;
+        state
+            .processed_events
+            .retain(|_, expires_at| *expires_at > now);
+
+        if state.processed_events.contains_key(event_id) {
+            return Ok(false);
+        }
+        state
+            .processed_events
+            .insert(event_id.to_string(), now + retention);
+        Ok(true)
+    }
+
+    async fn unmark_event_processed(&self, event_id: &str) -> Result<(), AppError> {
+        self.state.write().await.processed_events.remove(event_id);
+        Ok(())
+    }
+
+    async fn sweep_expired(&self) -> Result<u64, AppError> {
+        let now = self.clock.now();
+        let mut state = self.state.write().await;
+        let swept = state.drop_expired(now);
+        state
+            .processed_events
+            .retain(|_, expires_at| *expires_at > now);
+        Ok(swept as u64)
+    }
+
+    async fn outbox_push(&self, entry: String) -> Result<(), AppError> {
+        self.state.write().await.outbox.push_back(entry);
+        Ok(())
+    }
+
+    async fn outbox_claim(&self) -> Result<Option<String>, AppError> {
+        let mut state = self.state.write().await;
+        let entry = state.outbox.pop_front();
+        if let Some(entry) = &entry {
+            state.outbox_in_flight.push(entry.clone());
+        }
+        Ok(entry)
+    }
+
+    async fn outbox_ack(&self, entry: &str) -> Result<(), AppError> {
+        let mut state = self.state.write().await;
+        if let Some(index) = state.outbox_in_flight.iter().position(|e| e == entry) {
+            state.outbox_in_flight.remove(index);
+        }
+        Ok(())
+    }
+
+    async fn outbox_dead_letter(&self, entry: &str) -> Result<(), AppError> {
+        let mut state = self.state.write().await;
+        if let Some(index) = state.outbox_in_flight.iter().position(|e| e == entry) {
+            let entry = state.outbox_in_flight.remove(index);
+            state.outbox_dead.push(entry);
+        }
+        Ok(())
+    }
+
+    async fn outbox_recover(&self) -> Result<usize, AppError> {
+        let mut state = self.state.write().await;
+        let in_flight = std::mem::take(&mut state.outbox_in_flight);
+        let recovered = in_flight.len();
+        for entry in in_flight.into_iter().rev() {
+            state.outbox.push_front(entry);
+        }
+        Ok(recovered)
+    }
+
+    async fn control_get(&self, name: &str) -> Result<Option<String>, AppError> {
+        Ok(self.state.read().await.control.get(name).cloned())
+    }
+
+    async fn control_set(&self, name: &str, value: &str) -> Result<(), AppError> {
+        self.state
+            .write()
+            .await
+            .control
+            .insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    async fn control_delete(&self, name: &str) -> Result<(), AppError> {
+        self.state.write().await.control.remove(name);
+        Ok(())
+    }
+}
+
+/// In-memory L1 in front of a shared backend such as [`RedisCache`]
+///
+/// Hot keys are served from a bounded [`MemoryCache`] on this node without a
+/// network round trip; the backend stays the source of truth. Reads fill the
+/// L1, writes go through to the backend and then the L1, and every delete,
+/// purge, and touch drops the affected keys from the L1. Purges applied by
+/// other nodes directly in the backend are only seen here once the L1 copy
+/// reaches its maximum age.
+pub struct TieredCache<B> {
+    l1: MemoryCache,
+    l2: Arc<B>,
+    admission: Option<Admission>,
+}
+
+impl<B: CacheBackend> TieredCache<B> {
+    /// Front `l2` with an L1 of `capacity` entries held at most `max_age`
+    pub fn new(l2: B, capacity: usize, max_age: std::time::Duration) -> Self {
+        Self::with_l1(
+            l2,
+            MemoryCache::new()
+                .with_max_entries(capacity)
+                .with_max_age(max_age),
+        )
+    }
+
+    /// Front `l2` with a configured L1
+    pub fn with_l1(l2: B, l1: MemoryCache) -> Self {
+        Self {
+            l1,
+            l2: Arc::new(l2),
+            admission: None,
+        }
+    }
+
+    /// Keep keys without predicted reuse out of the L1
+    pub fn with_admission(mut self, admission: Admission) -> Self {
+        self.admission = Some(admission);
+        self
+    }
+
+    /// Copy `record` into the L1 if admission control lets it in, otherwise
+    /// drop any older copy there
+    async fn fill_l1(&self, record: &CachedArtifact) -> Result<(), AppError> {
+        if self
+            .admission
+            .as_ref()
+            .is_none_or(|admission| admission.admits_into_l1(&record.key))
+        {
+            self.l1.insert(record.clone()).await;
+        } else {
+            self.l1.delete(&record.key).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: CacheBackend> CacheBackend for TieredCache<B> {
+    async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
+        if let Some(record) = self.l1.get(key).await? {
+            return Ok(Some(record));
+        }
+        let record = self.l2.get(key).await?;
+        if let Some(record) = &record {
+            self.fill_l1(record).await?;
+        }
+        Ok(record)
+    }
+
+    async fn get_or_expired(&self, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        if let Some(record) = self.l1.get(key).await? {
+            return Ok(Some(StoredEntry::Live(record)));
+        }
+        let entry = self.l2.get_or_expired(key).await?;
+        if let Some(StoredEntry::Live(record)) = &entry {
+            self.fill_l1(record).await?;
+        }
+        Ok(entry)
+    }
+
+    /// Skips the L1, then refreshes it with what the L2 holds
+    async fn get_or_expired_strong(&self, key: &str) -> Result<Option<StoredEntry>, AppError> {
+        let entry = self.l2.get_or_expired_strong(key).await?;
+        match &entry {
+            Some(StoredEntry::Live(record)) => self.fill_l1(record).await?,
+            _ => {
+                self.l1.delete(key).await?;
+            }
+        }
+        Ok(entry)
+    }
+
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<CachedArtifact>>, AppError> {
+        let mut records = self.l1.get_many(keys).await?;
+        let (positions, missing): (Vec<usize>, Vec<String>) = records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.is_none())
+            .map(|(position, _)| (position, keys[position].clone()))
+            .unzip();
+        if missing.is_empty() {
+            return Ok(records);
+        }
+
+        for (position, record) in positions.into_iter().zip(self.l2.get_many(&missing).await?) {
+            if let Some(record) = &record {
+                self.fill_l1(record).await?;
+            }
+            records[position] = record;
+        }
+        Ok(records)
+    }
+
+    async fn set(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<CachedArtifact, AppError> {
+        let cached = self.l2.set(key, artifact, expires_at).await?;
+        self.fill_l1(&cached).await?;
+        Ok(cached)
+    }
+
+    async fn set_conditional(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+        mode: StoreMode,
+    ) -> Result<SetOutcome, AppError> {
+        let outcome = self
+            .l2
+            .set_conditional(key, artifact, expires_at, mode)
+            .await?;
+        self.fill_l1(outcome.record()).await?;
+        Ok(outcome)
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, AppError> {
+        self.l1.delete(key).await?;
+        self.l2.delete(key).await
+    }
+
+    async fn delete_many(&self, keys: &[String]) -> Result<usize, AppError> {
+        self.l1.delete_many(keys).await?;
+        self.l2.delete_many(keys).await
+    }
+
+    async fn delete_if_match(&self, key: &str, version: EntryVersion) -> Result<bool, AppError> {
+        self.l1.delete_if_match(key, version).await?;
+        self.l2.delete_if_match(key, version).await
+    }
+
+    async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError> {
+        self.l2.scan_by_pattern(pattern).await
+    }
+
+    fn scan_entries(
+        self: Arc<Self>,
+        pattern: String,
+    ) -> BoxStream<'static, Result<CachedArtifact, AppError>> {
+        self.l2.clone().scan_entries(pattern)
+    }
+
+    async fn scan_page(
+        &self,
+        pattern: &str,
+        cursor: Option<&str>,
+        count: usize,
+    ) -> Result<(Option<String>, Vec<String>), AppError> {
+        self.l2.scan_page(pattern, cursor, count).await
+    }
+
+    async fn index_add(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        self.l2.index_add(index, members).await
+    }
+
+    async fn index_remove(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        self.l2.index_remove(index, members).await
+    }
+
+    async fn index_modified(&self, key: &str, at: DateTime<Utc>) -> Result<(), AppError> {
+        self.l2.index_modified(key, at).await
+    }
+
+    async fn unindex_modified(&self, keys: &[String]) -> Result<(), AppError> {
+        self.l2.unindex_modified(keys).await
+    }
+
+    async fn modified_since(&self, since: DateTime<Utc>) -> Result<Vec<String>, AppError> {
+        self.l2.modified_since(since).await
+    }
+
+    async fn index_members(&self, index: &str) -> Result<Vec<String>, AppError> {
+        self.l2.index_members(index).await
+    }
+
+    async fn index_clear(&self, index: &str) -> Result<(), AppError> {
+        self.l2.index_clear(index).await
+    }
+
+    async fn prune_indexes(&self) -> Result<u64, AppError> {
+        self.l2.prune_indexes().await
+    }
+
+    async fn metadata(&self, key: &str) -> Result<Option<EntryMetadata>, AppError> {
+        self.l2.metadata(key).await
+    }
+
+    async fn exists_many(&self, keys: &[String]) -> Result<Vec<bool>, AppError> {
+        self.l2.exists_many(keys).await
+    }
+
+    async fn hashes_many(&self, keys: &[String]) -> Result<Vec<Option<EntryHash>>, AppError> {
+        self.l2.hashes_many(keys).await
+    }
+
+    async fn purge_referencing_hash(
+        &self,
+        keys: &[String],
+        hash: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let purged = self.l2.purge_referencing_hash(keys, hash).await?;
+        self.l1.delete_many(&purged).await?;
+        Ok(purged)
+    }
+
+    async fn key_count(&self) -> Result<u64, AppError> {
+        self.l2.key_count().await
+    }
+
+    /// Sweeps both tiers; only artifacts expired in the L2 are counted
+    async fn sweep_expired(&self) -> Result<u64, AppError> {
+        self.l1.sweep_expired().await?;
+        self.l2.sweep_expired().await
+    }
+
+    async fn flush_local(&self) -> Result<(), AppError> {
+        self.l1.clear_entries().await;
+        self.l2.flush_local().await
+    }
+
+    async fn touch_many(
+        &self,
+        tenant: &str,
+        keys: &[String],
+        ttl_seconds: u64,
+    ) -> Result<Vec<Option<DateTime<Utc>>>, AppError> {
+        self.l1.delete_many(keys).await?;
+        self.l2.touch_many(tenant, keys, ttl_seconds).await
+    }
+
+    async fn mark_event_processed(
+        &self,
+        event_id: &str,
+        retention: std::time::Duration,
+    ) -> Result<bool, AppError> {
+        self.l2.mark_event_processed(event_id, retention).await
+    }
+
+    async fn unmark_event_processed(&self, event_id: &str) -> Result<(), AppError> {
+        self.l2.unmark_event_processed(event_id).await
+    }
+
+    async fn outbox_push(&self, entry: String) -> Result<(), AppError> {
+        self.l2.outbox_push(entry).await
+    }
+
+    async fn outbox_claim(&self) -> Result<Option<String>, AppError> {
+        self.l2.outbox_claim().await
+    }
+
+    async fn outbox_ack(&self, entry: &str) -> Result<(), AppError> {
+        self.l2.outbox_ack(entry).await
+    }
+
+    async fn outbox_dead_letter(&self, entry: &str) -> Result<(), AppError> {
+        self.l2.outbox_dead_letter(entry).await
+    }
+
+    async fn outbox_recover(&self) -> Result<usize, AppError> {
+        self.l2.outbox_recover().await
+    }
+
+    async fn control_get(&self, name: &str) -> Result<Option<String>, AppError> {
+        self.l2.control_get(name).await
+    }
+
+    async fn control_set(&self, name: &str, value: &str) -> Result<(), AppError> {
+        self.l2.control_set(name, value).await
+    }
+
+    async fn control_delete(&self, name: &str) -> Result<(), AppError> {
+        self.l2.control_delete(name).await
+    }
+
+    async fn dictionary_store(&self, tenant: &str, dictionary: &[u8]) -> Result<u32, AppError> {
+        self.l2.dictionary_store(tenant, dictionary).await
+    }
+
+    async fn dictionary_load(
+        &self,
+        tenant: &str,
+        version: u32,
+    ) -> Result<Option<Vec<u8>>, AppError> {
+        self.l2.dictionary_load(tenant, version).await
+    }
+
+    async fn dictionary_latest(&self, tenant: &str) -> Result<Option<(u32, Vec<u8>)>, AppError> {
+        self.l2.dictionary_latest(tenant).await
+    }
+
+    async fn wal_append(&self, config: &WalConfig, entries: &[WalEntry]) -> Result<(), AppError> {
+        self.l2.wal_append(config, entries).await
+    }
+
+    async fn ping(&self) -> Result<(), AppError> {
+        self.l2.ping().await
+    }
+}
+
+/// SQLite cache backend for single-node deployments without Redis
+///
+/// Artifacts, index sets, the processed-event ledger, and the outbox live in
+/// one database file. Expired artifacts are hidden from reads immediately and
+/// deleted by [`CacheBackend::sweep_expired`]. Queries run on the blocking
+/// thread pool behind a single connection.
+#[derive(Clone)]
+pub struct SqliteCache {
+    conn: Arc<std::sync::Mutex<rusqlite::Connection>>,
+}
+
+const SQLITE_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS artifacts (
+    key TEXT PRIMARY KEY,
+    record TEXT NOT NULL,
+    expires_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS artifacts_expires_at ON artifacts (expires_at);
+CREATE TABLE IF NOT EXISTS indexes (
+    name TEXT NOT NULL,
+    member TEXT NOT NULL,
+    PRIMARY KEY (name, member)
+);
+CREATE TABLE IF NOT EXISTS processed_events (
+    event_id TEXT PRIMARY KEY,
+    expires_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS outbox_in_flight (
+    id INTEGER PRIMARY KEY,
+    entry TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS outbox_dead (
+    id INTEGER PRIMARY KEY,
+    entry TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS control (
+    name TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+"#;
+
+impl SqliteCache {
+    /// Open or create the database at `path`
+    pub fn open(path: &std::path::Path) -> Result<Self, AppError> {
+        let conn = rusqlite::Connection::open(path).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!(
+                "Failed to open SQLite database {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .and_then(|_| conn.execute_batch(SQLITE_SCHEMA))
+            .map_err(|e| sqlite_error("SQLite schema setup failed", e))?;
+
+        Ok(Self {
+            conn: Arc::new(std::sync::Mutex::new(conn)),
+        })
+    }
+
+    /// Run `op` against the connection on the blocking thread pool
+    async fn with_conn<T, F>(&self, op: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+            op(&mut conn)
+        })
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("SQLite task failed: {}", e)))?
+        .map_err(|e| sqlite_error("SQLite query failed", e))
+    }
+}
+
+fn sqlite_error(context: &str, err: rusqlite::Error) -> AppError {
+    AppError::Internal(anyhow::anyhow!("{}: {}", context, err))
+}
+
+/// Literal text every key matching the glob `pattern` starts with
+fn glob_literal_prefix(pattern: &str) -> String {
+    let mut prefix = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '?' | '[' => break,
+            '\\' => match chars.next() {
+                Some(escaped) => prefix.push(escaped),
+                None => break,
+            },
+            c => prefix.push(c),
+        }
+    }
+    prefix
+}
+
+#[async_trait]
+impl CacheBackend for SqliteCache {
+    async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
+        let key = key.to_string();
+        let now = Utc::now().timestamp_millis();
+        let record: Option<String> = self
+            .with_conn(move |conn| {
+                conn.query_row(
+                    "SELECT record FROM artifacts
+                     WHERE key = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+                    rusqlite::params![key, now],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await?;
+
+        record
+            .map(|raw| {
+                serde_json::from_str(&raw).map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Failed to decode cached artifact: {}", e))
+                })
+            })
+            .transpose()
+    }
+
+    async fn set(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<CachedArtifact, AppError> {
+        let now = Utc::now();
+        if expires_at.is_some_and(|exp| exp <= now) {
+            return Err(AppError::bad_request("Artifact already expired"));
+        }
+
+        let cached = CachedArtifact {
+            key,
+            artifact,
+            stored_at: now,
+            expires_at,
+        };
+        let record = serde_json::to_string(&cached).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to encode cached artifact: {}", e))
+        })?;
+
+        let key = cached.key.clone();
+        let expires_at = expires_at.map(|exp| exp.timestamp_millis());
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO artifacts (key, record, expires_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key) DO UPDATE SET record = excluded.record,
+                     expires_at = excluded.expires_at",
+                rusqlite::params![key, record, expires_at],
+            )
+        })
+        .await?;
+
+        Ok(cached)
+    }
+
+    async fn set_conditional(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+        mode: StoreMode,
+    ) -> Result<SetOutcome, AppError> {
+        let now = Utc::now();
+        if expires_at.is_some_and(|exp| exp <= now) {
+            return Err(AppError::bad_request("Artifact already expired"));
+        }
+
+        let cached = CachedArtifact {
+            key,
+            artifact,
+            stored_at: now,
+            expires_at,
+        };
+        let record = serde_json::to_string(&cached).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to encode cached artifact: {}", e))
+        })?;
+
+        let now = now.timestamp_millis();
+        let expires_at = expires_at.map(|exp| exp.timestamp_millis());
+        // The check and the write share one write transaction, so concurrent
+        // conditional stores cannot interleave between them
+        self.with_conn(move |conn| {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            let existing: Option<String> = tx
+                .query_row(
+                    "SELECT record FROM artifacts
+                     WHERE key = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+                    rusqlite::params![cached.key, now],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let existing = match existing
+                .map(|raw| serde_json::from_str::<CachedArtifact>(&raw))
+                .transpose()
+            {
+                Ok(existing) => existing,
+                Err(e) => {
+                    return Ok(Err(AppError::Internal(anyhow::anyhow!(
+                        "Failed to decode cached artifact: {}",
+                        e
+                    ))))
+                }
+            };
+            if existing.is_none() && matches!(mode, StoreMode::IfMatch(_)) {
+                return Ok(Err(no_entry_to_match()));
+            }
+            if let Some(existing) = existing
+                .clone()
+                .filter(|existing| mode.keeps(existing, &cached.artifact))
+            {
+                return Ok(Ok(SetOutcome::Kept(existing)));
+            }
+
+            tx.execute(
+                "INSERT INTO artifacts (key, record, expires_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key) DO UPDATE SET record = excluded.record,
+                     expires_at = excluded.expires_at",
+                rusqlite::params![cached.key, record, expires_at],
+            )?;
+            tx.commit()?;
+            Ok(Ok(match existing {
+                Some(_) => SetOutcome::Updated(cached),
+                None => SetOutcome::Created(cached),
+            }))
+        })
+        .await?
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, AppError> {
+        let key = key.to_string();
+        let deleted = self
+            .with_conn(move |conn| conn.execute("DELETE FROM artifacts WHERE key = ?1", [key]))
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    async fn delete_if_match(&self, key: &str, version: EntryVersion) -> Result<bool, AppError> {
+        let key = key.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            let existing: Option<String> = tx
+                .query_row(
+                    "SELECT record FROM artifacts WHERE key = ?1",
+                    [&key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let matches = existing
+                .and_then(|raw| serde_json::from_str::<CachedArtifact>(&raw).ok())
+                .is_some_and(|record| record.version() == version);
+            let deleted = match matches {
+                true => tx.execute("DELETE FROM artifacts WHERE key = ?1", [&key])?,
+                false => 0,
+            };
+            tx.commit()?;
+            Ok(deleted > 0)
+        })
+        .await
+    }
+
+    /// Moves `expires_at` in the column and in the record JSON, one `UPDATE`
+    /// per key
+    async fn touch_many(
+        &self,
+        tenant: &str,
+        keys: &[String],
+        ttl_seconds: u64,
+    ) -> Result<Vec<Option<DateTime<Utc>>>, AppError> {
+        if keys.is_empty() || ttl_seconds == 0 {
+            return Ok(vec![None; keys.len()]);
+        }
+
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(ttl_seconds as i64);
+        let serialized = serde_json::to_value(expires_at)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to serialize expiry")))?;
+        let keys = keys.to_vec();
+        let tenant = tenant.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            let mut touched = Vec::with_capacity(keys.len());
+            {
+                let mut statement = tx.prepare_cached(
+                    "UPDATE artifacts
+                     SET expires_at = ?2, record = json_set(record, '$.expires_at', ?3)
+                     WHERE key = ?1
+                         AND json_extract(record, '$.artifact.policy.tenant') = ?4
+                         AND (expires_at IS NULL OR expires_at > ?5)",
+                )?;
+                for key in &keys {
+                    let updated = statement.execute(rusqlite::params![
+                        key,
+                        expires_at.timestamp_millis(),
+                        serialized,
+                        tenant,
+                        now.timestamp_millis()
+                    ])?;
+                    touched.push((updated > 0).then_some(expires_at));
+                }
+            }
+            tx.commit()?;
+            Ok(touched)
+        })
+        .await
+    }
+
+    async fn delete_many(&self, keys: &[String]) -> Result<usize, AppError> {
+        let keys = keys.to_vec();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            let mut deleted = 0;
+            {
+                let mut statement = tx.prepare_cached("DELETE FROM artifacts WHERE key = ?1")?;
+                for key in &keys {
+                    deleted += statement.execute([key])?;
+                }
+            }
+            tx.commit()?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError> {
+        let pattern = pattern.to_string();
+        let prefix = glob_literal_prefix(&pattern);
+        let now = Utc::now().timestamp_millis();
+        let keys: Vec<String> = self
+            .with_conn(move |conn| {
+                let mut statement = conn.prepare_cached(
+                    "SELECT key FROM artifacts
+                     WHERE substr(key, 1, length(?1)) = ?1
+                       AND (expires_at IS NULL OR expires_at > ?2)",
+                )?;
+                let rows = statement.query_map(rusqlite::params![prefix, now], |row| row.get(0))?;
+                rows.collect()
+            })
+            .await?;
+
+        Ok(keys
+            .into_iter()
+            .filter(|key| glob_match(&pattern, key))
+            .collect())
+    }
+
+    async fn index_add(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        if members.is_empty() {
+            return Ok(());
+        }
+
+        let index = index.to_string();
+        let members = members.to_vec();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut statement = tx.prepare_cached(
+                    "INSERT OR IGNORE INTO indexes (name, member) VALUES (?1, ?2)",
+                )?;
+                for member in &members {
+                    statement.execute([&index, member])?;
+                }
+            }
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn index_remove(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        if members.is_empty() {
+            return Ok(());
+        }
+
+        let index = index.to_string();
+        let members = members.to_vec();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut statement =
+                    tx.prepare_cached("DELETE FROM indexes WHERE name = ?1 AND member = ?2")?;
+                for member in &members {
+                    statement.execute([&index, member])?;
+                }
+            }
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn index_members(&self, index: &str) -> Result<Vec<String>, AppError> {
+        let index = index.to_string();
+        self.with_conn(move |conn| {
+            let mut statement =
+                conn.prepare_cached("SELECT member FROM indexes WHERE name = ?1")?;
+            let rows = statement.query_map([index], |row| row.get(0))?;
+            rows.collect()
+        })
+        .await
+    }
+
+    async fn index_clear(&self, index: &str) -> Result<(), AppError> {
+        let index = index.to_string();
+        self.with_conn(move |conn| conn.execute("DELETE FROM indexes WHERE name = ?1", [index]))
+            .await?;
+        Ok(())
+    }
+
+    async fn prune_indexes(&self) -> Result<u64, AppError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM indexes WHERE member NOT IN (SELECT key FROM artifacts)",
+                [],
+            )
+        })
+        .await
+        .map(|count| count as u64)
+    }
+
+    async fn key_count(&self) -> Result<u64, AppError> {
+        let now = Utc::now().timestamp_millis();
+        self.with_conn(move |conn| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM artifacts WHERE expires_at IS NULL OR expires_at > ?1",
+                [now],
+                |row| row.get::<_, i64>(0),
+            )
+        })
+        .await
+        .map(|count| count as u64)
+    }
+
+    async fn sweep_expired(&self) -> Result<u64, AppError> {
+        let now = Utc::now().timestamp_millis();
+        self.with_conn(move |conn| {
+            conn.execute("DELETE FROM processed_events WHERE expires_at <= ?1", [now])?;
+            conn.execute(
+                "DELETE FROM artifacts WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+                [now],
+            )
+        })
+        .await
+        .map(|count| count as u64)
+    }
+
+    async fn mark_event_processed(
+        &self,
+        event_id: &str,
+        retention: std::time::Duration,
+    ) -> Result<bool, AppError> {
+        let event_id = event_id.to_string();
+        let now = Utc::now().timestamp_millis();
+        let expires_at = now.saturating_add(retention.as_millis() as i64);
+        let inserted = self
+            .with_conn(move |conn| {
+                conn.execute(
+                    "DELETE FROM processed_events WHERE event_id = ?1 AND expires_at <= ?2",
+                    rusqlite::params![event_id, now],
+                )?;
+                conn.execute(
+                    "INSERT OR IGNORE INTO processed_events (event_id, expires_at) VALUES (?1, ?2)",
+                    rusqlite::params![event_id, expires_at],
+                )
+            })
+            .await?;
+        Ok(inserted > 0)
+    }
+
+    async fn unmark_event_processed(&self, event_id: &str) -> Result<(), AppError> {
+        let event_id = event_id.to_string();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "DELETE FROM processed_events WHERE event_id = ?1",
+                [event_id],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn outbox_push(&self, entry: String) -> Result<(), AppError> {
+        self.with_conn(move |conn| conn.execute("INSERT INTO outbox (entry) VALUES (?1)", [entry]))
+            .await?;
+        Ok(())
+    }
+
+    async fn outbox_claim(&self) -> Result<Option<String>, AppError> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let claimed: Option<(i64, String)> = tx
+                .query_row(
+                    "DELETE FROM outbox WHERE id = (SELECT MIN(id) FROM outbox) RETURNING id, entry",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            if let Some((id, entry)) = &claimed {
+                tx.execute(
+                    "INSERT INTO outbox_in_flight (id, entry) VALUES (?1, ?2)",
+                    rusqlite::params![id, entry],
+                )?;
+            }
+            tx.commit()?;
+            Ok(claimed.map(|(_, entry)| entry))
+        })
+        .await
+    }
+
+    async fn outbox_ack(&self, entry: &str) -> Result<(), AppError> {
+        let entry = entry.to_string();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "DELETE FROM outbox_in_flight
+                 WHERE id = (SELECT MIN(id) FROM outbox_in_flight WHERE entry = ?1)",
+                [entry],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn outbox_dead_letter(&self, entry: &str) -> Result<(), AppError> {
+        let entry = entry.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO outbox_dead (id, entry)
+                 SELECT id, entry FROM outbox_in_flight
+                 WHERE id = (SELECT MIN(id) FROM outbox_in_flight WHERE entry = ?1)",
+                [&entry],
+            )?;
+            tx.execute(
+                "DELETE FROM outbox_in_flight
+                 WHERE id = (SELECT MIN(id) FROM outbox_in_flight WHERE entry = ?1)",
+                [&entry],
+            )?;
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn outbox_recover(&self) -> Result<usize, AppError> {
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            // Original ids put them back ahead of newer notifications
+            let recovered = tx.execute(
+                "INSERT INTO outbox (id, entry) SELECT id, entry FROM outbox_in_flight",
+                [],
+            )?;
+            tx.execute("DELETE FROM outbox_in_flight", [])?;
+            tx.commit()?;
+            Ok(recovered)
+        })
+        .await
+    }
+
+    async fn control_get(&self, name: &str) -> Result<Option<String>, AppError> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            conn.query_row("SELECT value FROM control WHERE name = ?1", [name], |row| {
+                row.get(0)
+            })
+            .optional()
+        })
+        .await
+    }
+
+    async fn control_set(&self, name: &str, value: &str) -> Result<(), AppError> {
+        let (name, value) = (name.to_string(), value.to_string());
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO control (name, value) VALUES (?1, ?2)
+                 ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+                [name, value],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn control_delete(&self, name: &str) -> Result<(), AppError> {
+        let name = name.to_string();
+        self.with_conn(move |conn| conn.execute("DELETE FROM control WHERE name = ?1", [name]))
+            .await?;
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<(), AppError> {
+        self.with_conn(|conn| conn.query_row("SELECT 1", [], |row| row.get::<_, i64>(0)))
+            .await?;
+        Ok(())
+    }
+}
+
+/// RocksDB cache backend for disk-heavy single nodes, behind the `rocksdb` feature
+///
+/// Artifacts live on local disk, so the cache can outgrow RAM. Every value is
+/// prefixed with its expiry (milliseconds since the epoch, big-endian); reads
+/// hide expired values and a compaction filter drops them from disk. Column
+/// families hold artifacts, index sets, the processed-event ledger, the
+/// outbox, and control records.
+#[cfg(feature = "rocksdb")]
+#[derive(Clone)]
+pub struct RocksDbCache {
+    db: Arc<rocksdb::DB>,
+    /// Next outbox sequence; the lock also serializes stores, deletes,
+    /// check-and-set writes, and touches, and index additions with index
+    /// pruning
+    sequence: Arc<std::sync::Mutex<u64>>,
+}
+
+#[cfg(feature = "rocksdb")]
+const ROCKS_ARTIFACTS: &str = "artifacts";
+#[cfg(feature = "rocksdb")]
+const ROCKS_INDEXES: &str = "indexes";
+#[cfg(feature = "rocksdb")]
+const ROCKS_EVENTS: &str = "processed_events";
+#[cfg(feature = "rocksdb")]
+const ROCKS_OUTBOX: &str = "outbox";
+#[cfg(feature = "rocksdb")]
+const ROCKS_OUTBOX_IN_FLIGHT: &str = "outbox_in_flight";
+#[cfg(feature = "rocksdb")]
+const ROCKS_OUTBOX_DEAD: &str = "outbox_dead";
+#[cfg(feature = "rocksdb")]
+const ROCKS_CONTROL: &str = "control";
+
+/// Expiry stored for values that never expire
+#[cfg(feature = "rocksdb")]
+const ROCKS_NO_EXPIRY: i64 = i64::MAX;
+
+#[cfg(feature = "rocksdb")]
+fn rocks_encode(expires_at: Option<DateTime<Utc>>, body: &[u8]) -> Vec<u8> {
+    let expires_at = expires_at.map_or(ROCKS_NO_EXPIRY, |exp| exp.timestamp_millis());
+    let mut value = Vec::with_capacity(8 + body.len());
+    value.extend_from_slice(&expires_at.to_be_bytes());
+    value.extend_from_slice(body);
+    value
+}
+
+/// Body of a stored value, `None` once it has expired at `now_ms`
+#[cfg(feature = "rocksdb")]
+fn rocks_decode(value: &[u8], now_ms: i64) -> Option<&[u8]> {
+    let (expiry, body) = value.split_first_chunk::<8>()?;
+    (i64::from_be_bytes(*expiry) > now_ms).then_some(body)
+}
+
+/// Compaction filter dropping expired values
+#[cfg(feature = "rocksdb")]
+fn rocks_expiry_filter(
+    _level: u32,
+    _key: &[u8],
+    value: &[u8],
+) -> rocksdb::compaction_filter::Decision {
+    use rocksdb::compaction_filter::Decision;
+    match rocks_decode(value, Utc::now().timestamp_millis()) {
+        Some(_) => Decision::Keep,
+        None => Decision::Remove,
+    }
+}
+
+/// Key of one index set member: `{index}\0{member}`
+#[cfg(feature = "rocksdb")]
+fn rocks_index_key(index: &str, member: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(index.len() + member.len() + 1);
+    key.extend_from_slice(index.as_bytes());
+    key.push(0);
+    key.extend_from_slice(member.as_bytes());
+    key
+}
+
+#[cfg(feature = "rocksdb")]
+fn rocks_error(context: &str, err: rocksdb::Error) -> AppError {
+    AppError::Internal(anyhow::anyhow!("{}: {}", context, err))
+}
+
+#[cfg(feature = "rocksdb")]
+impl RocksDbCache {
+    /// Open or create the database at `path`
+    pub fn open(path: &std::path::Path) -> Result<Self, AppError> {
+        let mut db_options = rocksdb::Options::default();
+        db_options.create_if_missing(true);
+        db_options.create_missing_column_families(true);
+
+        let expiring = |name: &str| {
+            let mut options = rocksdb::Options::default();
+            options.set_compaction_filter("scedge_expiry", rocks_expiry_filter);
+            rocksdb::ColumnFamilyDescriptor::new(name, options)
+        };
+        let plain =
+            |name: &str| rocksdb::ColumnFamilyDescriptor::new(name, rocksdb::Options::default());
+
+        let db = rocksdb::DB::open_cf_descriptors(
+            &db_options,
+            path,
+            vec![
+                expiring(ROCKS_ARTIFACTS),
+                expiring(ROCKS_EVENTS),
+                plain(ROCKS_INDEXES),
+                plain(ROCKS_OUTBOX),
+                plain(ROCKS_OUTBOX_IN_FLIGHT),
+                plain(ROCKS_OUTBOX_DEAD),
+                plain(ROCKS_CONTROL),
+            ],
+        )
+        .map_err(|e| {
+            AppError::Internal(anyhow::anyhow!(
+                "Failed to open RocksDB at {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        // Sequences stay unique across the queue, in-flight, and dead-letter families
+        let mut next_sequence = 0;
+        for name in [ROCKS_OUTBOX, ROCKS_OUTBOX_IN_FLIGHT, ROCKS_OUTBOX_DEAD] {
+            let cf = db
+                .cf_handle(name)
+                .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Missing RocksDB {}", name)))?;
+            if let Some(entry) = db.iterator_cf(cf, rocksdb::IteratorMode::End).next() {
+                let (key, _) = entry.map_err(|e| rocks_error("RocksDB read failed", e))?;
+                if let Some(sequence) = key.first_chunk::<8>() {
+                    next_sequence = next_sequence.max(u64::from_be_bytes(*sequence) + 1);
+                }
+            }
+        }
+
+        Ok(Self {
+            db: Arc::new(db),
+            sequence: Arc::new(std::sync::Mutex::new(next_sequence)),
+        })
+    }
+
+    /// Run `op` against the database on the blocking thread pool
+    async fn with_db<T, F>(&self, op: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&RocksDbCache) -> Result<T, AppError> + Send + 'static,
+    {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || op(&cache))
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("RocksDB task failed: {}", e)))?
+    }
+
+    fn cf(&self, name: &str) -> Result<&rocksdb::ColumnFamily, AppError> {
+        self.db.cf_handle(name).ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("Missing RocksDB column family {}", name))
+        })
+    }
+
+    /// Oldest in-flight outbox record holding `entry`
+    fn find_in_flight(&self, entry: &str) -> Result<Option<(Box<[u8]>, Box<[u8]>)>, AppError> {
+        for record in self.db.iterator_cf(
+            self.cf(ROCKS_OUTBOX_IN_FLIGHT)?,
+            rocksdb::IteratorMode::Start,
+        ) {
+            let (key, value) = record.map_err(|e| rocks_error("RocksDB read failed", e))?;
+            if *value == *entry.as_bytes() {
+                return Ok(Some((key, value)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Keys of a column family starting with `prefix`, with their values
+    fn scan_prefix(
+        &self,
+        cf_name: &str,
+        prefix: &[u8],
+    ) -> Result<Vec<(Box<[u8]>, Box<[u8]>)>, AppError> {
+        let cf = self.cf(cf_name)?;
+        let mut entries = Vec::new();
+        for entry in self.db.iterator_cf(
+            cf,
+            rocksdb::IteratorMode::From(prefix, rocksdb::Direction::Forward),
+        ) {
+            let (key, value) = entry.map_err(|e| rocks_error("RocksDB scan failed", e))?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            entries.push((key, value));
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+#[async_trait]
+impl CacheBackend for RocksDbCache {
+    async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
+        let key = key.to_string();
+        self.with_db(move |cache| {
+            let Some(value) = cache
+                .db
+                .get_cf(cache.cf(ROCKS_ARTIFACTS)?, key.as_bytes())
+                .map_err(|e| rocks_error("RocksDB get failed", e))?
+            else {
+                return Ok(None);
+            };
+            rocks_decode(&value, Utc::now().timestamp_millis())
+                .map(|body| {
+                    serde_json::from_slice(body).map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!(
+                            "Failed to decode cached artifact: {}",
+                            e
+                        ))
+                    })
+                })
+                .transpose()
+        })
+        .await
+    }
+
+    async fn set(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<CachedArtifact, AppError> {
+        let now = Utc::now();
+        if expires_at.is_some_and(|exp| exp <= now) {
+            return Err(AppError::bad_request("Artifact already expired"));
+        }
+
+        let cached = CachedArtifact {
+            key,
+            artifact,
+            stored_at: now,
+            expires_at,
+        };
+        let body = serde_json::to_vec(&cached).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to encode cached artifact: {}", e))
+        })?;
+
+        let key = cached.key.clone();
+        self.with_db(move |cache| {
+            // Not between a touch's read and write
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            cache
+                .db
+                .put_cf(
+                    cache.cf(ROCKS_ARTIFACTS)?,
+                    key.as_bytes(),
+                    rocks_encode(expires_at, &body),
+                )
+                .map_err(|e| rocks_error("RocksDB put failed", e))
+        })
+        .await?;
+
+        Ok(cached)
+    }
+
+    async fn set_conditional(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+        mode: StoreMode,
+    ) -> Result<SetOutcome, AppError> {
+        let now = Utc::now();
+        if expires_at.is_some_and(|exp| exp <= now) {
+            return Err(AppError::bad_request("Artifact already expired"));
+        }
+
+        let cached = CachedArtifact {
+            key,
+            artifact,
+            stored_at: now,
+            expires_at,
+        };
+        let body = serde_json::to_vec(&cached).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to encode cached artifact: {}", e))
+        })?;
+
+        self.with_db(move |cache| {
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let artifacts = cache.cf(ROCKS_ARTIFACTS)?;
+            let existing = cache
+                .db
+                .get_cf(artifacts, cached.key.as_bytes())
+                .map_err(|e| rocks_error("RocksDB get failed", e))?;
+            let existing: Option<CachedArtifact> = existing
+                .as_deref()
+                .and_then(|value| rocks_decode(value, now.timestamp_millis()))
+                .map(|body| {
+                    serde_json::from_slice(body).map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!(
+                            "Failed to decode cached artifact: {}",
+                            e
+                        ))
+                    })
+                })
+                .transpose()?;
+            if existing.is_none() && matches!(mode, StoreMode::IfMatch(_)) {
+                return Err(no_entry_to_match());
+            }
+            if let Some(existing) = existing
+                .clone()
+                .filter(|existing| mode.keeps(existing, &cached.artifact))
+            {
+                return Ok(SetOutcome::Kept(existing));
+            }
+
+            cache
+                .db
+                .put_cf(
+                    artifacts,
+                    cached.key.as_bytes(),
+                    rocks_encode(expires_at, &body),
+                )
+                .map_err(|e| rocks_error("RocksDB put failed", e))?;
+            Ok(match existing {
+                Some(_) => SetOutcome::Updated(cached),
+                None => SetOutcome::Created(cached),
+            })
+        })
+        .await
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, AppError> {
+        Ok(self.delete_many(&[key.to_string()]).await? > 0)
+    }
+
+    async fn delete_if_match(&self, key: &str, version: EntryVersion) -> Result<bool, AppError> {
+        let key = key.to_string();
+        self.with_db(move |cache| {
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let artifacts = cache.cf(ROCKS_ARTIFACTS)?;
+            let existing = cache
+                .db
+                .get_cf(artifacts, key.as_bytes())
+                .map_err(|e| rocks_error("RocksDB get failed", e))?;
+            // Expired values are compared too; that is what they are deleted for
+            let matches = existing
+                .as_deref()
+                .and_then(|value| rocks_decode(value, i64::MIN))
+                .and_then(|body| serde_json::from_slice::<CachedArtifact>(body).ok())
+                .is_some_and(|record| record.version() == version);
+            if !matches {
+                return Ok(false);
+            }
+            cache
+                .db
+                .delete_cf(artifacts, key.as_bytes())
+                .map_err(|e| rocks_error("RocksDB delete failed", e))?;
+            Ok(true)
+        })
+        .await
+    }
+
+    /// Rewrites each entry with its new expiry under the `sequence` lock, so
+    /// no store lands in between
+    async fn touch_many(
+        &self,
+        tenant: &str,
+        keys: &[String],
+        ttl_seconds: u64,
+    ) -> Result<Vec<Option<DateTime<Utc>>>, AppError> {
+        if keys.is_empty() || ttl_seconds == 0 {
+            return Ok(vec![None; keys.len()]);
+        }
+
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(ttl_seconds as i64);
+        let keys = keys.to_vec();
+        let tenant = tenant.to_string();
+        self.with_db(move |cache| {
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let artifacts = cache.cf(ROCKS_ARTIFACTS)?;
+            let mut batch = rocksdb::WriteBatch::default();
+            let mut touched = Vec::with_capacity(keys.len());
+            for key in &keys {
+                let existing = cache
+                    .db
+                    .get_cf(artifacts, key.as_bytes())
+                    .map_err(|e| rocks_error("RocksDB get failed", e))?;
+                let record = existing
+                    .as_deref()
+                    .and_then(|value| rocks_decode(value, now.timestamp_millis()))
+                    .and_then(|body| serde_json::from_slice::<CachedArtifact>(body).ok())
+                    .filter(|record| record.artifact.policy.tenant == tenant);
+                let Some(mut record) = record else {
+                    touched.push(None);
+                    continue;
+                };
+                record.expires_at = Some(expires_at);
+                let body = serde_json::to_vec(&record).map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Failed to encode cached artifact: {}", e))
+                })?;
+                batch.put_cf(
+                    artifacts,
+                    key.as_bytes(),
+                    rocks_encode(Some(expires_at), &body),
+                );
+                touched.push(Some(expires_at));
+            }
+            cache
+                .db
+                .write(batch)
+                .map_err(|e| rocks_error("RocksDB write failed", e))?;
+            Ok(touched)
+        })
+        .await
+    }
+
+    async fn delete_many(&self, keys: &[String]) -> Result<usize, AppError> {
+        let keys = keys.to_vec();
+        self.with_db(move |cache| {
+            // Not between a touch's read and write, which would restore the key
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let cf = cache.cf(ROCKS_ARTIFACTS)?;
+            let now = Utc::now().timestamp_millis();
+            let mut batch = rocksdb::WriteBatch::default();
+            let mut deleted = 0;
+            for key in &keys {
+                let live = cache
+                    .db
+                    .get_cf(cf, key.as_bytes())
+                    .map_err(|e| rocks_error("RocksDB get failed", e))?
+                    .is_some_and(|value| rocks_decode(&value, now).is_some());
+                if live {
+                    deleted += 1;
+                }
+                batch.delete_cf(cf, key.as_bytes());
+            }
+            cache
+                .db
+                .write(batch)
+                .map_err(|e| rocks_error("RocksDB delete failed", e))?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError> {
+        let pattern = pattern.to_string();
+        self.with_db(move |cache| {
+            let now = Utc::now().timestamp_millis();
+            let prefix = glob_literal_prefix(&pattern);
+            Ok(cache
+                .scan_prefix(ROCKS_ARTIFACTS, prefix.as_bytes())?
+                .into_iter()
+                .filter(|(_, value)| rocks_decode(value, now).is_some())
+                .filter_map(|(key, _)| String::from_utf8(key.into_vec()).ok())
+                .filter(|key| glob_match(&pattern, key))
+                .collect())
+        })
+        .await
+    }
+
+    async fn index_add(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        let index = index.to_string();
+        let members = members.to_vec();
+        self.with_db(move |cache| {
+            let cf = cache.cf(ROCKS_INDEXES)?;
+            // Serialized with `prune_indexes`
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let mut batch = rocksdb::WriteBatch::default();
+            for member in &members {
+                batch.put_cf(cf, rocks_index_key(&index, member), b"");
+            }
+            cache
+                .db
+                .write(batch)
+                .map_err(|e| rocks_error("RocksDB index write failed", e))
+        })
+        .await
+    }
+
+    async fn index_remove(&self, index: &str, members: &[String]) -> Result<(), AppError> {
+        let index = index.to_string();
+        let members = members.to_vec();
+        self.with_db(move |cache| {
+            let cf = cache.cf(ROCKS_INDEXES)?;
+            let mut batch = rocksdb::WriteBatch::default();
+            for member in &members {
+                batch.delete_cf(cf, rocks_index_key(&index, member));
+            }
+            cache
+                .db
+                .write(batch)
+                .map_err(|e| rocks_error("RocksDB index delete failed", e))
+        })
+        .await
+    }
+
+    async fn index_members(&self, index: &str) -> Result<Vec<String>, AppError> {
+        let prefix = rocks_index_key(index, "");
+        self.with_db(move |cache| {
+            Ok(cache
+                .scan_prefix(ROCKS_INDEXES, &prefix)?
+                .into_iter()
+                .filter_map(|(key, _)| String::from_utf8(key[prefix.len()..].to_vec()).ok())
+                .collect())
+        })
+        .await
+    }
+
+    async fn index_clear(&self, index: &str) -> Result<(), AppError> {
+        let prefix = rocks_index_key(index, "");
+        self.with_db(move |cache| {
+            let cf = cache.cf(ROCKS_INDEXES)?;
+            let mut batch = rocksdb::WriteBatch::default();
+            for (key, _) in cache.scan_prefix(ROCKS_INDEXES, &prefix)? {
+                batch.delete_cf(cf, key);
+            }
+            cache
+                .db
+                .write(batch)
+                .map_err(|e| rocks_error("RocksDB index delete failed", e))
+        })
+        .await
+    }
+
+    /// Checks and removes members under the `sequence` lock, a page at a
+    /// time, so an `index_add` following a store cannot be undone
+    async fn prune_indexes(&self) -> Result<u64, AppError> {
+        self.with_db(|cache| {
+            let indexes = cache.cf(ROCKS_INDEXES)?;
+            let artifacts = cache.cf(ROCKS_ARTIFACTS)?;
+            let mut pruned = 0;
+            let mut entries = cache
+                .db
+                .iterator_cf(indexes, rocksdb::IteratorMode::Start)
+                .peekable();
+            while entries.peek().is_some() {
+                let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Utc::now().timestamp_millis();
+                let mut batch = rocksdb::WriteBatch::default();
+                for entry in entries.by_ref().take(SCAN_PAGE_SIZE) {
+                    let (key, _) = entry.map_err(|e| rocks_error("RocksDB scan failed", e))?;
+                    let Some(split) = key.iter().position(|&b| b == 0) else {
+                        continue;
+                    };
+                    let live = cache
+                        .db
+                        .get_cf(artifacts, &key[split + 1..])
+                        .map_err(|e| rocks_error("RocksDB get failed", e))?
+                        .is_some_and(|value| rocks_decode(&value, now).is_some());
+                    if !live {
+                        batch.delete_cf(indexes, &key);
+                        pruned += 1;
+                    }
+                }
+                cache
+                    .db
+                    .write(batch)
+                    .map_err(|e| rocks_error("RocksDB index delete failed", e))?;
+            }
+            Ok(pruned)
+        })
+        .await
+    }
+
+    async fn mark_event_processed(
+        &self,
+        event_id: &str,
+        retention: std::time::Duration,
+    ) -> Result<bool, AppError> {
+        let event_id = event_id.to_string();
+        self.with_db(move |cache| {
+            let cf = cache.cf(ROCKS_EVENTS)?;
+            let now = Utc::now();
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let recorded = cache
+                .db
+                .get_cf(cf, event_id.as_bytes())
+                .map_err(|e| rocks_error("RocksDB get failed", e))?
+                .is_some_and(|value| rocks_decode(&value, now.timestamp_millis()).is_some());
+            if recorded {
+                return Ok(false);
+            }
+            let expires_at = now + Duration::milliseconds(retention.as_millis() as i64);
+            cache
+                .db
+                .put_cf(cf, event_id.as_bytes(), rocks_encode(Some(expires_at), b""))
+                .map_err(|e| rocks_error("RocksDB put failed", e))?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn unmark_event_processed(&self, event_id: &str) -> Result<(), AppError> {
+        let event_id = event_id.to_string();
+        self.with_db(move |cache| {
+            cache
+                .db
+                .delete_cf(cache.cf(ROCKS_EVENTS)?, event_id.as_bytes())
+                .map_err(|e| rocks_error("RocksDB delete failed", e))
+        })
+        .await
+    }
+
+    async fn outbox_push(&self, entry: String) -> Result<(), AppError> {
+        self.with_db(move |cache| {
+            let mut next = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            cache
+                .db
+                .put_cf(
+                    cache.cf(ROCKS_OUTBOX)?,
+                    next.to_be_bytes(),
+                    entry.as_bytes(),
+                )
+                .map_err(|e| rocks_error("RocksDB put failed", e))?;
+            *next += 1;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn outbox_claim(&self) -> Result<Option<String>, AppError> {
+        self.with_db(|cache| {
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let cf = cache.cf(ROCKS_OUTBOX)?;
+            let Some(entry) = cache
+                .db
+                .iterator_cf(cf, rocksdb::IteratorMode::Start)
+                .next()
+            else {
+                return Ok(None);
+            };
+            let (key, value) = entry.map_err(|e| rocks_error("RocksDB read failed", e))?;
+            let mut batch = rocksdb::WriteBatch::default();
+            batch.delete_cf(cf, &key);
+            batch.put_cf(cache.cf(ROCKS_OUTBOX_IN_FLIGHT)?, &key, &value);
+            cache
+                .db
+                .write(batch)
+                .map_err(|e| rocks_error("RocksDB write failed", e))?;
+            Ok(Some(String::from_utf8_lossy(&value).into_owned()))
+        })
+        .await
+    }
+
+    async fn outbox_ack(&self, entry: &str) -> Result<(), AppError> {
+        let entry = entry.to_string();
+        self.with_db(move |cache| {
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let in_flight = cache.cf(ROCKS_OUTBOX_IN_FLIGHT)?;
+            if let Some((key, _)) = cache.find_in_flight(&entry)? {
+                cache
+                    .db
+                    .delete_cf(in_flight, key)
+                    .map_err(|e| rocks_error("RocksDB delete failed", e))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn outbox_dead_letter(&self, entry: &str) -> Result<(), AppError> {
+        let entry = entry.to_string();
+        self.with_db(move |cache| {
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((key, value)) = cache.find_in_flight(&entry)? {
+                let mut batch = rocksdb::WriteBatch::default();
+                batch.delete_cf(cache.cf(ROCKS_OUTBOX_IN_FLIGHT)?, &key);
+                batch.put_cf(cache.cf(ROCKS_OUTBOX_DEAD)?, &key, value);
+                cache
+                    .db
+                    .write(batch)
+                    .map_err(|e| rocks_error("RocksDB write failed", e))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn outbox_recover(&self) -> Result<usize, AppError> {
+        self.with_db(|cache| {
+            let _guard = cache.sequence.lock().unwrap_or_else(|e| e.into_inner());
+            let in_flight = cache.cf(ROCKS_OUTBOX_IN_FLIGHT)?;
+            let outbox = cache.cf(ROCKS_OUTBOX)?;
+            // Original sequences put them back ahead of newer notifications
+            let mut batch = rocksdb::WriteBatch::default();
+            let mut recovered = 0;
+            for entry in cache
+                .db
+                .iterator_cf(in_flight, rocksdb::IteratorMode::Start)
+            {
+                let (key, value) = entry.map_err(|e| rocks_error("RocksDB read failed", e))?;
+                batch.delete_cf(in_flight, &key);
+                batch.put_cf(outbox, &key, &value);
+                recovered += 1;
+            }
+            cache
+                .db
+                .write(batch)
+                .map_err(|e| rocks_error("RocksDB write failed", e))?;
+            Ok(recovered)
+        })
+        .await
+    }
+
+    async fn control_get(&self, name: &str) -> Result<Option<String>, AppError> {
+        let name = name.to_string();
+        self.with_db(move |cache| {
+            Ok(cache
+                .db
+                .get_cf(cache.cf(ROCKS_CONTROL)?, name.as_bytes())
+                .map_err(|e| rocks_error("RocksDB get failed", e))?
+                .map(|value| String::from_utf8_lossy(&value).into_owned()))
+        })
+        .await
+    }
+
+    async fn control_set(&self, name: &str, value: &str) -> Result<(), AppError> {
+        let (name, value) = (name.to_string(), value.to_string());
+        self.with_db(move |cache| {
+            cache
+                .db
+                .put_cf(cache.cf(ROCKS_CONTROL)?, name.as_bytes(), value.as_bytes())
+                .map_err(|e| rocks_error("RocksDB put failed", e))
+        })
+        .await
+    }
+
+    async fn control_delete(&self, name: &str) -> Result<(), AppError> {
+        let name = name.to_string();
+        self.with_db(move |cache| {
+            cache
+                .db
+                .delete_cf(cache.cf(ROCKS_CONTROL)?, name.as_bytes())
+                .map_err(|e| rocks_error("RocksDB delete failed", e))
+        })
+        .await
+    }
+}
+
+/// Escape glob metacharacters so `raw` only matches itself in a scan pattern
+///
+/// Every identifier interpolated into a `scan_by_pattern` pattern (tenant ids,
+/// prefixes, ...) must go through this, otherwise a tenant named `*` would scan
+/// every other tenant's keys.
+pub fn escape_glob(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Scan pattern matching every key of a tenant
+pub fn tenant_pattern(tenant: &str) -> String {
+    format!("{}:*", escape_glob(tenant))
+}
+
+/// Redis-compatible glob matching supporting `*`, `?`, and `\` escapes
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            Some('\\') if pattern.get(p + 1) == Some(&text[t]) => {
+                p += 2;
+                t += 1;
+                continue;
+            }
+            Some(c) if *c != '\\' && *c == text[t] => {
+                p += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((star_p, star_t)) => {
+                p = star_p + 1;
+                t = star_t + 1;
+                backtrack = Some((star_p, star_t + 1));
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Secondary index of a tenant's artifacts declaring `depends_on` a key or
+/// hash; scoped per tenant so cascades never cross into other tenants
+fn dependents_index(tenant: &str, reference: &str) -> String {
+    format!("depends:{}:{}", tenant, reference)
+}
+
+/// Control record of the encryption keys registered for a tenant at runtime
+fn tenant_keys_record(tenant: &str) -> String {
+    format!("keys:tenant:{}", tenant)
+}
+
+/// Index of the keys stored with a family; families are scoped per tenant
+fn family_index(tenant: &str, family: &str) -> String {
+    format!("family:{}:{}", tenant, family)
+}
+
+/// Sorted index of keys by last write time, kept by backends that support it
+const MODIFIED_INDEX: &str = "modified";
+
+/// Index of every key stored under a tenant's `{tenant}:` prefix
+fn tenant_index(tenant: &str) -> String {
+    format!("tenant:{}", tenant)
+}
+
+/// Index of the keys with a provenance source in the capsule
+fn capsule_index(capsule_id: &str) -> String {
+    format!("capsule:{}", capsule_id)
+}
+
+/// Index of the keys whose artifact hash or any provenance hash is `hash`
+fn references_index(hash: &str) -> String {
+    format!("references:{}", hash)
+}
+
+/// Index of the keys stored with a content hash, per tenant
+fn hash_index(tenant: &str, hash: &str) -> String {
+    format!("hash:{}:{}", tenant, hash)
+}
+
+/// Control record marking that entries stored before the secondary indexes
+/// existed have been indexed
+const INDEX_BACKFILL_RECORD: &str = "indexes:backfilled";
+
+/// Keys requested per backend page during a bounded scan
+const SCAN_PAGE_SIZE: usize = 100;
+
+/// Budget for scans triggered by API requests
+///
+/// Keeps a broad pattern (e.g. a `*` provenance purge) from holding the node
+/// busy: a scan stops once it has collected `max_keys` keys or run for
+/// `max_duration`, and reports a cursor to resume from.
+#[derive(Debug, Clone, Copy)]
+pub struct ScanLimits {
+    pub max_keys: usize,
+    pub max_duration: std::time::Duration,
+}
+
+impl Default for ScanLimits {
+    fn default() -> Self {
+        Self {
+            max_keys: 100_000,
+            max_duration: std::time::Duration::from_secs(5),
+        }
+    }
+}
+
+/// Keys from a bounded scan
+#[derive(Debug, Clone)]
+pub struct ScanPage {
+    pub keys: Vec<String>,
+    /// Cursor to resume from; `None` when the scan completed
+    pub cursor: Option<String>,
+}
+
+/// Cache wrapper that can use different backends
+///
+/// Deletions cascade to artifacts that declared a dependency on the deleted
+/// key; see [`Cache::purge_dependents`].
+#[derive(Clone)]
+pub struct Cache {
+    backend: Arc<dyn CacheBackend>,
+    key_filter: Option<Arc<KeyFilter>>,
+    keyring: Option<Keyring>,
+    dictionaries: Option<Dictionaries>,
+    scan_limits: ScanLimits,
+    wal: Option<WalConfig>,
+    stale: Option<Arc<StaleCopies>>,
+    rendered: Option<Arc<RenderedResponses>>,
+    ttl_tuner: Option<TtlTuner>,
+    /// Set once every entry is known to be indexed; see
+    /// [`Cache::backfill_indexes`]
+    indexes_backfilled: Arc<AtomicBool>,
+}
+
+impl Cache {
+    pub fn new(backend: impl CacheBackend + 'static) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            key_filter: None,
+            keyring: None,
+            dictionaries: None,
+            scan_limits: ScanLimits::default(),
+            wal: None,
+            stale: None,
+            rendered: None,
+            ttl_tuner: None,
+            indexes_backfilled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Log every store and purge to a capped stream
+    pub fn with_wal(mut self, wal: WalConfig) -> Self {
+        self.wal = Some(wal);
+        self
+    }
+
+    async fn log_mutations(&self, entries: Vec<WalEntry>) {
+        let Some(wal) = &self.wal else {
+            return;
+        };
+        if let Err(err) = self.backend.wal_append(wal, &entries).await {
+            tracing::warn!(error = %err, entries = entries.len(), "Failed to append to write-ahead log");
+        }
+    }
+
+    /// Encrypt answers at rest for tenants with keys in the keyring
+    pub fn with_keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = Some(keyring);
+        self
+    }
+
+    /// Train and load the compression dictionaries of tenants
+    ///
+    /// The backend compresses with them; see
+    /// [`RedisCache::with_dictionaries`].
+    pub fn with_dictionaries(mut self, dictionaries: Dictionaries) -> Self {
+        self.dictionaries = Some(dictionaries);
+        self
+    }
+
+    /// Encrypt an answer for storage
+    ///
+    /// Returns the original answer alongside when it was rewritten.
+    fn seal(
+        &self,
+        mut artifact: ArtifactPayload,
+    ) -> Result<(ArtifactPayload, Option<Value>), AppError> {
+        let Some(keyring) = &self.keyring else {
+            return Ok((artifact, None));
+        };
+
+        let plain = std::mem::take(&mut artifact.answer);
+        artifact.answer = keyring.encrypt(&artifact.policy.tenant, plain.clone())?;
+        Ok((artifact, Some(plain)))
+    }
+
+    /// Decrypt a stored answer
+    async fn open(&self, mut record: CachedArtifact) -> Result<CachedArtifact, AppError> {
+        let tenant = record.artifact.policy.tenant.clone();
+        if let Some(keyring) = &self.keyring {
+            // Another node may have registered a key this one has not seen
+            if keyring
+                .missing_key(&tenant, &record.artifact.answer)
+                .is_some()
+            {
+                self.load_tenant_keys(&tenant).await?;
+            }
+            let answer = std::mem::take(&mut record.artifact.answer);
+            record.artifact.answer = keyring.decrypt(&tenant, answer)?;
+        }
+        Ok(record)
+    }
+
+    /// Persist an encryption key registered at runtime, so restarts and
+    /// sibling nodes sharing the backend can decrypt entries sealed with it
+    pub async fn persist_tenant_key(&self, tenant: &str, key: &TenantKey) -> Result<(), AppError> {
+        let mut keys = self.persisted_tenant_keys(tenant).await?;
+        if keys.iter().any(|persisted| persisted.key_id == key.key_id) {
+            return Ok(());
+        }
+        keys.push(key.clone());
+        let raw = serde_json::to_string(&keys).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to encode tenant keys: {}", e))
+        })?;
+        self.backend
+            .control_set(&tenant_keys_record(tenant), &raw)
+            .await
+    }
+
+    /// Register the keys persisted for a tenant, in the order they were
+    /// registered, returning how many there were
+    pub async fn load_tenant_keys(&self, tenant: &str) -> Result<usize, AppError> {
+        let Some(keyring) = &self.keyring else {
+            return Ok(0);
+        };
+        let keys = self.persisted_tenant_keys(tenant).await?;
+        for key in &keys {
+            keyring.add_key(tenant, key)?;
+        }
+        Ok(keys.len())
+    }
+
+    async fn persisted_tenant_keys(&self, tenant: &str) -> Result<Vec<TenantKey>, AppError> {
+        let name = tenant_keys_record(tenant);
+        match self.backend.control_get(&name).await? {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Invalid control record {}: {}", name, e))
+            }),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Activate the newest stored dictionary of a tenant
+    pub async fn load_dictionary(&self, tenant: &str) -> Result<Option<u32>, AppError> {
+        let Some(dictionaries) = &self.dictionaries else {
+            return Ok(None);
+        };
+        let Some((version, dictionary)) = self.backend.dictionary_latest(tenant).await? else {
+            return Ok(None);
+        };
+        dictionaries.add(tenant, version, dictionary);
+        Ok(Some(version))
+    }
+
+    /// Train and activate a compression dictionary from a tenant's cached answers
+    ///
+    /// Samples at most `max_samples` entries. Existing entries keep the
+    /// dictionary they were written with; only later stores use the new one.
+    pub async fn train_dictionary(
+        &self,
+        tenant: &str,
+        max_samples: usize,
+        max_size: usize,
+    ) -> Result<TrainedDictionary, AppError> {
+        let dictionaries = self.dictionaries.as_ref().ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("Compression dictionaries are not enabled"))
+        })?;
+
+        let mut entries = self.scan_entries(tenant_pattern(tenant));
+        let mut samples = Vec::new();
+        while samples.len() < max_samples {
+            let Some(entry) = entries.next().await else {
+                break;
+            };
+            let entry = entry?;
+            if entry.artifact.policy.tenant != tenant {
+                continue;
+            }
+            let sample = serde_json::to_vec(&entry.artifact.answer).map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to encode answer: {}", e))
+            })?;
+            samples.push(sample);
+        }
+
+        let sampled = samples.len();
+        let dictionary =
+            tokio::task::spawn_blocking(move || compression::train(&samples, max_size))
+                .await
+                .map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Training task failed: {}", e))
+                })??;
+
+        let version = self.backend.dictionary_store(tenant, &dictionary).await?;
+        let size_bytes = dictionary.len();
+        dictionaries.add(tenant, version, dictionary);
+
+        Ok(TrainedDictionary {
+            version,
+            size_bytes,
+            samples: sampled,
+        })
+    }
+
+    /// Budget applied to [`Cache::scan_bounded`]
+    pub fn with_scan_limits(mut self, scan_limits: ScanLimits) -> Self {
+        self.scan_limits = scan_limits;
+        self
+    }
+
+    /// Answer lookups for keys absent from the bloom filter without a backend read
+    pub fn with_key_filter(mut self, key_filter: Arc<KeyFilter>) -> Self {
+        self.key_filter = Some(key_filter);
+        self
+    }
+
+    /// Keep last-known-good copies for serving when the backend fails
+    pub fn with_stale_copies(mut self, stale: Arc<StaleCopies>) -> Self {
+        self.stale = Some(stale);
+        self
+    }
+
+    /// Keep serialized lookups of hot keys, dropped whenever their key changes
+    pub fn with_rendered_responses(mut self, rendered: Arc<RenderedResponses>) -> Self {
+        self.rendered = Some(rendered);
+        self
+    }
+
+    /// Rendered lookups of hot keys, when enabled
+    pub fn rendered(&self) -> Option<&RenderedResponses> {
+        self.rendered.as_deref()
+    }
+
+    /// Drop rendered lookups of keys whose entries changed or were removed
+    fn forget_rendered(&self, keys: &[String]) {
+        if let Some(rendered) = &self.rendered {
+            rendered.forget(keys);
+        }
+    }
+
+    /// Count stores and purged keys per tenant for default TTL tuning
+    pub fn with_ttl_tuner(mut self, ttl_tuner: TtlTuner) -> Self {
+        self.ttl_tuner = Some(ttl_tuner);
+        self
+    }
+
+    /// Keep `record` for [`stale_copy`](Self::stale_copy)
+    pub fn remember_stale(&self, record: &CachedArtifact) {
+        if let Some(stale) = &self.stale {
+            stale.remember(record);
+        }
+    }
+
+    /// Last-known-good copy of `key`, however old
+    pub fn stale_copy(&self, key: &str) -> Option<CachedArtifact> {
+        self.stale.as_ref().and_then(|stale| stale.get(key))
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
+        self.get_live(key, Consistency::Eventual).await
+    }
+
+    /// Fetch many keys in one backend round trip, in order
+    pub async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<CachedArtifact>>, AppError> {
+        let (positions, candidates): (Vec<usize>, Vec<String>) = keys
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| {
+                self.key_filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.might_contain(key))
+            })
+            .map(|(position, key)| (position, key.clone()))
+            .unzip();
+
+        let mut records = vec![None; keys.len()];
+        if candidates.is_empty() {
+            return Ok(records);
+        }
+        for (position, record) in positions
+            .into_iter()
+            .zip(self.backend.get_many(&candidates).await?)
+        {
+            if let Some(record) = record {
+                records[position] = Some(self.open(record).await?);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Read the backend directly, skipping node-local shortcuts
+    ///
+    /// The bloom filter only learns keys stored through this node until its
+    /// next rebuild, and the L1 keeps entries purged by siblings until they
+    /// reach their maximum age, so [`Cache::get`] can miss a sibling's write
+    /// for a while.
+    pub async fn get_strong(&self, key: &str) -> Result<Option<CachedArtifact>, AppError> {
+        self.get_live(key, Consistency::Strong).await
+    }
+
+    /// Live entry at `key`, deleting one past its expiry
+    async fn get_live(
+        &self,
+        key: &str,
+        consistency: Consistency,
+    ) -> Result<Option<CachedArtifact>, AppError> {
+        match self.read(key, consistency).await? {
+            Some(StoredEntry::Live(record)) => Ok(Some(record)),
+            Some(StoredEntry::Expired(record)) => {
+                // Best effort; the native TTL or a sweep removes it otherwise
+                let _ = self.discard_expired(&record).await;
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Read `key`, returning an entry past its expiry as
+    /// [`StoredEntry::Expired`] while the backend still holds it
+    ///
+    /// [`Cache::get`] and [`Cache::get_strong`] delete such an entry and report
+    /// a miss; lookups read through here to apply the tenant's `expired_reads`
+    /// choice instead. Eventual reads may answer from the bloom filter or the
+    /// L1; strong reads go to the authoritative copy.
+    pub async fn read(
+        &self,
+        key: &str,
+        consistency: Consistency,
+    ) -> Result<Option<StoredEntry>, AppError> {
+        let entry = match consistency {
+            Consistency::Eventual => {
+                if let Some(filter) = &self.key_filter {
+                    if !filter.might_contain(key) {
+                        return Ok(None);
+                    }
+                }
+                self.backend.get_or_expired(key).await?
+            }
+            Consistency::Strong => self.backend.get_or_expired_strong(key).await?,
+        };
+        Ok(match entry {
+            Some(StoredEntry::Live(record)) => Some(StoredEntry::Live(self.open(record).await?)),
+            Some(StoredEntry::Expired(record)) => {
+                Some(StoredEntry::Expired(self.open(record).await?))
+            }
+            None => None,
+        })
+    }
+
+    pub async fn set(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<CachedArtifact, AppError> {
+        let (sealed, plain) = self.seal(artifact)?;
+        let mut cached = self.backend.set(key, sealed, expires_at).await?;
+        if let Some(plain) = plain {
+            cached.artifact.answer = plain;
+        }
+        self.record_store(&cached).await?;
+        Ok(cached)
+    }
+
+    /// Store `artifact` at `key` unless `mode` keeps the live entry there
+    pub async fn set_conditional(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+        mode: StoreMode,
+    ) -> Result<SetOutcome, AppError> {
+        let (sealed, plain) = self.seal(artifact)?;
+        let outcome = self
+            .backend
+            .set_conditional(key, sealed, expires_at, mode)
+            .await?;
+        let (mut cached, updated) = match outcome {
+            SetOutcome::Kept(existing) => return Ok(SetOutcome::Kept(self.open(existing).await?)),
+            SetOutcome::Created(cached) => (cached, false),
+            SetOutcome::Updated(cached) => (cached, true),
+        };
+        if let Some(plain) = plain {
+            cached.artifact.answer = plain;
+        }
+        self.record_store(&cached).await?;
+        Ok(if updated {
+            SetOutcome::Updated(cached)
+        } else {
+            SetOutcome::Created(cached)
+        })
+    }
+
+    /// Return the live entry at `key`, or store `artifact` there if none
+    ///
+    /// Concurrent hydrations of a missed key all end up serving the entry of
+    /// whichever stored first. The flag is `true` when `artifact` was stored.
+    pub async fn get_or_set(
+        &self,
+        key: String,
+        artifact: ArtifactPayload,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(CachedArtifact, bool), AppError> {
+        let outcome = self
+            .set_conditional(key, artifact, expires_at, StoreMode::IfAbsent)
+            .await?;
+        let stored = !matches!(outcome, SetOutcome::Kept(_));
+        Ok((outcome.into_record(), stored))
+    }
+
+    /// Log and index a newly stored entry
+    async fn record_store(&self, cached: &CachedArtifact) -> Result<(), AppError> {
+        self.forget_rendered(std::slice::from_ref(&cached.key));
+        if let Some(filter) = &self.key_filter {
+            filter.insert(&cached.key);
+        }
+        if let Some(ttl_tuner) = &self.ttl_tuner {
+            ttl_tuner.record_store(&cached.artifact.policy.tenant);
+        }
+        self.log_mutations(vec![WalEntry::new(
+            WalOp::Store,
+            &cached.key,
+            Some(cached.artifact.hash.clone()),
+        )])
+        .await;
+
+        self.index_entry(cached).await
+    }
+
+    /// Add an entry to every secondary index
+    ///
+    /// Index entries are never pruned on overwrite; a stale entry only causes
+    /// an extra invalidation, never a missed one.
+    async fn index_entry(&self, cached: &CachedArtifact) -> Result<(), AppError> {
+        let member = [cached.key.clone()];
+        let tenant = key_tenant(&cached.key);
+        for reference in &cached.artifact.depends_on {
+            self.backend
+                .index_add(&dependents_index(tenant, reference), &member)
+                .await?;
+        }
+        if let Some(family) = &cached.artifact.family {
+            self.backend
+                .index_add(
+                    &family_index(&cached.artifact.policy.tenant, family),
+                    &member,
+                )
+                .await?;
+        }
+        self.backend
+            .index_add(&tenant_index(key_tenant(&cached.key)), &member)
+            .await?;
+        self.backend
+            .index_modified(&cached.key, cached.stored_at)
+            .await?;
+        let mut references: Vec<&str> = cached
+            .artifact
+            .provenance
+            .iter()
+            .filter_map(|source| source.hash.as_deref())
+            .collect();
+        references.push(&cached.artifact.hash);
+        references.sort_unstable();
+        references.dedup();
+        for reference in references {
+            self.backend
+                .index_add(&references_index(reference), &member)
+                .await?;
+        }
+        let mut capsules: Vec<&str> = cached
+            .artifact
+            .provenance
+            .iter()
+            .filter_map(|source| source.capsule())
+            .collect();
+        capsules.sort_unstable();
+        capsules.dedup();
+        for capsule in capsules {
+            self.backend
+                .index_add(&capsule_index(capsule), &member)
+                .await?;
+        }
+        self.backend
+            .index_add(
+                &hash_index(&cached.artifact.policy.tenant, &cached.artifact.hash),
+                &member,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Live artifacts of a tenant whose content hash is `hash`, sorted by key
+    ///
+    /// Resolved through the hash index. Keys since overwritten with another
+    /// hash, purged, or expired are skipped.
+    pub async fn get_by_hash(
+        &self,
+        tenant: &str,
+        hash: &str,
+    ) -> Result<Vec<CachedArtifact>, AppError> {
+        let mut keys = self
+            .backend
+            .index_members(&hash_index(tenant, hash))
+            .await?;
+        keys.sort_unstable();
+
+        Ok(self
+            .get_many(&keys)
+            .await?
+            .into_iter()
+            .flatten()
+            .filter(|record| {
+                record.artifact.policy.tenant == tenant && record.artifact.hash == hash
+            })
+            .collect())
+    }
+
+    /// Remove an entry read past its expiry, unless a store replaced it since
+    ///
+    /// Unlike [`Cache::delete`] this is not a purge: dependents, indexes, and
+    /// the write-ahead log are left alone, as when the native TTL drops it.
+    pub async fn discard_expired(&self, record: &CachedArtifact) -> Result<bool, AppError> {
+        let deleted = self
+            .backend
+            .delete_if_match(&record.key, record.version())
+            .await?;
+        if deleted {
+            self.forget_rendered(std::slice::from_ref(&record.key));
+        }
+        Ok(deleted)
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<bool, AppError> {
+        let deleted = self.backend.delete(key).await?;
+        if let Some(stale) = &self.stale {
+            stale.forget(&[key.to_string()]);
+        }
+        self.forget_rendered(&[key.to_string()]);
+        if deleted {
+            self.log_mutations(vec![WalEntry::new(WalOp::Purge, key, None)])
+                .await;
+        }
+        self.unindex_deleted(&[key.to_string()]).await;
+        self.purge_key_dependents(&[key.to_string()]).await?;
+        Ok(deleted)
+    }
+
+    pub async fn delete_many(&self, keys: &[String]) -> Result<usize, AppError> {
+        let deleted = self.backend.delete_many(keys).await?;
+        self.record_purges(keys).await;
+        let cascaded = self.purge_key_dependents(keys).await?;
+        Ok(deleted + cascaded)
+    }
+
+    /// [`purge_dependents`](Self::purge_dependents) of deleted keys, each
+    /// within the tenant owning it
+    async fn purge_key_dependents(&self, keys: &[String]) -> Result<usize, AppError> {
+        let mut by_tenant: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for key in keys {
+            by_tenant
+                .entry(key_tenant(key))
+                .or_default()
+                .push(key.clone());
+        }
+        let mut purged = 0;
+        for (tenant, keys) in by_tenant {
+            purged += self.purge_dependents(tenant, keys).await?;
+        }
+        Ok(purged)
+    }
+
+    /// Log purged keys to the write-ahead log and drop their stale copies
+    async fn record_purges(&self, keys: &[String]) {
+        if let Some(stale) = &self.stale {
+            stale.forget(keys);
+        }
+        self.forget_rendered(keys);
+        if let Some(ttl_tuner) = &self.ttl_tuner {
+            ttl_tuner.record_invalidated(keys);
+        }
+        self.unindex_deleted(keys).await;
+        if self.wal.is_some() {
+            let entries = keys
+                .iter()
+                .map(|key| WalEntry::new(WalOp::Purge, key, None))
+                .collect();
+            self.log_mutations(entries).await;
+        }
+    }
+
+    /// Drop deleted keys from their tenant indexes and the modified-at index
+    ///
+    /// A failure only leaves members behind, which later purges and syncs
+    /// skip as absent keys, so it is logged rather than returned.
+    async fn unindex_deleted(&self, keys: &[String]) {
+        if let Err(err) = self.backend.unindex_modified(keys).await {
+            tracing::warn!(error = %err, "Failed to prune modified-at index");
+        }
+
+        let mut per_tenant: HashMap<&str, Vec<String>> = HashMap::new();
+        for key in keys {
+            per_tenant
+                .entry(key_tenant(key))
+                .or_default()
+                .push(key.clone());
+        }
+        for (tenant, members) in per_tenant {
+            if let Err(err) = self
+                .backend
+                .index_remove(&tenant_index(tenant), &members)
+                .await
+            {
+                tracing::warn!(tenant, error = %err, "Failed to prune tenant index");
+            }
+        }
+    }
+
+    /// Keys last written at or after `since`, oldest first
+    pub async fn modified_since(&self, since: DateTime<Utc>) -> Result<Vec<String>, AppError> {
+        self.backend.modified_since(since).await
+    }
+
+    /// Keys stored under a tenant's prefix, read from the tenant index
+    ///
+    /// Keys that expired since they were stored are still listed until a purge
+    /// or the next [`Cache::prune_indexes`] removes them. Until
+    /// [`Cache::backfill_indexes`] has run, the tenant's keys are scanned as
+    /// well, so entries stored before the index existed are not missed.
+    pub async fn tenant_keys(&self, tenant: &str) -> Result<Vec<String>, AppError> {
+        let mut keys = self.backend.index_members(&tenant_index(tenant)).await?;
+        if !self.indexes_backfilled().await? {
+            keys.extend(
+                self.backend
+                    .scan_by_pattern(&tenant_pattern(tenant))
+                    .await?,
+            );
+            keys.sort_unstable();
+            keys.dedup();
+        }
+        Ok(keys)
+    }
+
+    /// Add every stored entry to the secondary indexes, once per keyspace
+    ///
+    /// Entries stored before an index was introduced are not listed in it.
+    /// This scan indexes them and records [`INDEX_BACKFILL_RECORD`] in the
+    /// control namespace, so later starts skip it. Returns the number of
+    /// entries indexed, or `None` when the backfill had already run.
+    pub async fn backfill_indexes(&self) -> Result<Option<usize>, AppError> {
+        if self.indexes_backfilled().await? {
+            return Ok(None);
+        }
+
+        let mut entries = self.backend.clone().scan_entries("*".to_string());
+        let mut indexed = 0;
+        while let Some(entry) = entries.next().await {
+            self.index_entry(&entry?).await?;
+            indexed += 1;
+        }
+
+        self.backend
+            .control_set(INDEX_BACKFILL_RECORD, &Utc::now().to_rfc3339())
+            .await?;
+        self.indexes_backfilled.store(true, Ordering::Release);
+        Ok(Some(indexed))
+    }
+
+    /// Whether the index backfill has run, on this node or another
+    async fn indexes_backfilled(&self) -> Result<bool, AppError> {
+        if self.indexes_backfilled.load(Ordering::Acquire) {
+            return Ok(true);
+        }
+        let backfilled = self
+            .backend
+            .control_get(INDEX_BACKFILL_RECORD)
+            .await?
+            .is_some();
+        if backfilled {
+            self.indexes_backfilled.store(true, Ordering::Release);
+        }
+        Ok(backfilled)
+    }
+
+    /// Delete every artifact of a tenant, cascading to dependents
+    pub async fn purge_tenant(&self, tenant: &str) -> Result<usize, AppError> {
+        let keys = self.tenant_keys(tenant).await?;
+        self.delete_many(&keys).await
+    }
+
+    /// Delete every artifact of a tenant's family, cascading to dependents
+    pub async fn purge_family(&self, tenant: &str, family: &str) -> Result<usize, AppError> {
+        let members = self.take_family(tenant, family).await?;
+        self.delete_many(&members).await
+    }
+
+    /// Remove a family's index, returning the keys it listed
+    pub async fn take_family(&self, tenant: &str, family: &str) -> Result<Vec<String>, AppError> {
+        let index = family_index(tenant, family);
+        let members = self.backend.index_members(&index).await?;
+        self.backend.index_clear(&index).await?;
+        Ok(members)
+    }
+
+    /// Remove a tenant's keys from a capsule's index, returning them
+    ///
+    /// Keys since overwritten with sources outside the capsule are still
+    /// listed; purging them costs an extra miss, never a missed revocation.
+    pub async fn take_capsule(
+        &self,
+        tenant: &str,
+        capsule_id: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let index = capsule_index(capsule_id);
+        let members: Vec<String> = self
+            .backend
+            .index_members(&index)
+            .await?
+            .into_iter()
+            .filter(|key| key_tenant(key) == tenant)
+            .collect();
+        self.backend.index_remove(&index, &members).await?;
+        Ok(members)
+    }
+
+    /// Delete artifacts whose hash or any provenance hash is `hash`, limited
+    /// to one tenant's keys when `tenant` is given
+    ///
+    /// Candidates come from the reference index; checking that they still
+    /// reference the hash and deleting them happen in one backend call.
+    /// Dependents of the deleted keys are then purged as usual. Until
+    /// [`Cache::backfill_indexes`] has run, keys are scanned for candidates as
+    /// well, so entries stored before the index existed are not missed.
+    pub async fn purge_referencing_hash(
+        &self,
+        tenant: Option<&str>,
+        hash: &str,
+    ) -> Result<usize, AppError> {
+        let index = references_index(hash);
+        let mut candidates: Vec<String> = self
+            .backend
+            .index_members(&index)
+            .await?
+            .into_iter()
+            .filter(|key| tenant.is_none_or(|tenant| key_tenant(key) == tenant))
+            .collect();
+        if !self.indexes_backfilled().await? {
+            let pattern = tenant.map_or_else(|| "*".to_string(), tenant_pattern);
+            candidates.extend(self.backend.scan_by_pattern(&pattern).await?);
+            candidates.sort_unstable();
+            candidates.dedup();
+        }
+        let purged = self
+            .backend
+            .purge_referencing_hash(&candidates, hash)
+            .await?;
+        // Candidates left behind were overwritten or expired and no longer
+        // reference the hash
+        self.backend.index_remove(&index, &candidates).await?;
+        self.record_purges(&purged).await;
+        let cascaded = self.purge_key_dependents(&purged).await?;
+        Ok(purged.len() + cascaded)
+    }
+
+    /// Delete every artifact of `tenant` that transitively depends on the
+    /// given keys or provenance hashes, returning the number of dependents
+    /// removed
+    pub async fn purge_dependents(
+        &self,
+        tenant: &str,
+        references: Vec<String>,
+    ) -> Result<usize, AppError> {
+        let mut queue: VecDeque<String> = references.into();
+        let mut visited = HashSet::new();
+        let mut purged = 0;
+
+        while let Some(reference) = queue.pop_front() {
+            if !visited.insert(reference.clone()) {
+                continue;
+            }
+
+            let index = dependents_index(tenant, &reference);
+            let dependents = self.backend.index_members(&index).await?;
+            if dependents.is_empty() {
+                continue;
+            }
+
+            self.backend.index_clear(&index).await?;
+            purged += self.backend.delete_many(&dependents).await?;
+            self.record_purges(&dependents).await;
+            queue.extend(dependents);
+        }
+
+        Ok(purged)
+    }
+
+    pub async fn scan_by_pattern(&self, pattern: &str) -> Result<Vec<String>, AppError> {
+        self.backend.scan_by_pattern(pattern).await
+    }
+
+    /// Scan keys matching `pattern` within the configured [`ScanLimits`]
+    ///
+    /// Starts at `cursor` (as returned by a previous page, `None` for the
+    /// beginning) and stops early once the key or time budget is spent. The
+    /// returned page carries the cursor to resume from when it is partial.
+    pub async fn scan_bounded(
+        &self,
+        pattern: &str,
+        cursor: Option<&str>,
+    ) -> Result<ScanPage, AppError> {
+        let start = std::time::Instant::now();
+        let mut cursor = cursor.map(str::to_string);
+        let mut keys = Vec::new();
+
+        loop {
+            let remaining = self.scan_limits.max_keys.saturating_sub(keys.len());
+            let (next, batch) = self
+                .backend
+                .scan_page(pattern, cursor.as_deref(), remaining.min(SCAN_PAGE_SIZE))
+                .await?;
+            keys.extend(batch);
+            cursor = next;
+
+            if cursor.is_none() {
+                return Ok(ScanPage { keys, cursor });
+            }
+            if keys.len() >= self.scan_limits.max_keys
+                || start.elapsed() >= self.scan_limits.max_duration
+            {
+                tracing::warn!(
+                    pattern,
+                    keys = keys.len(),
+                    elapsed_ms = start.elapsed().as_millis() as u64,
+                    "Scan budget exhausted, returning partial result"
+                );
+                return Ok(ScanPage { keys, cursor });
+            }
+        }
+    }
+
+    /// Stream every live artifact whose key matches `pattern`
+    pub fn scan_entries(
+        &self,
+        pattern: impl Into<String>,
+    ) -> BoxStream<'static, Result<CachedArtifact, AppError>> {
+        let entries = self.backend.clone().scan_entries(pattern.into());
+        if self.keyring.is_none() {
+            return entries;
+        }
+        let cache = self.clone();
+        entries
+            .then(move |entry| {
+                let cache = cache.clone();
+                async move { cache.open(entry?).await }
+            })
+            .boxed()
+    }
+
+    /// Re-seal a tenant's entries that are not under its active key
+    ///
+    /// Runs after a key rotation so retired keys can eventually be dropped.
+    /// Each entry is rewritten with its original expiry, and only while it
+    /// still holds the revision that was read: entries stored or deleted
+    /// since are skipped rather than overwritten. A rewrite resets
+    /// `stored_at`, and with it the entry's [`EntryVersion`].
+    pub async fn reencrypt_tenant(&self, tenant: &str) -> Result<usize, AppError> {
+        let Some(keyring) = &self.keyring else {
+            return Ok(0);
+        };
+
+        let mut entries = self.backend.clone().scan_entries(tenant_pattern(tenant));
+        let mut rewritten = 0;
+
+        while let Some(entry) = entries.next().await {
+            let mut record = entry?;
+            if record.artifact.policy.tenant != tenant
+                || !keyring.needs_reencryption(tenant, &record.artifact.answer)
+            {
+                continue;
+            }
+
+            let version = record.version();
+            let answer = std::mem::take(&mut record.artifact.answer);
+            let plain = keyring.decrypt(tenant, answer)?;
+            record.artifact.answer = keyring.encrypt(tenant, plain)?;
+            self.forget_rendered(std::slice::from_ref(&record.key));
+            match self
+                .backend
+                .set_conditional(
+                    record.key.clone(),
+                    record.artifact,
+                    record.expires_at,
+                    StoreMode::IfMatch(version),
+                )
+                .await
+            {
+                Ok(SetOutcome::Updated(_)) => rewritten += 1,
+                // Replaced since the scan; the new store was sealed on its own
+                Ok(_) => {
+                    tracing::debug!(key = %record.key, "Skipping entry changed during re-encryption")
+                }
+                // Deleted since the scan
+                Err(AppError::PreconditionFailed(_)) => {}
+                // Entries expiring mid-job are rejected by the backend; skip them
+                Err(err) => {
+                    tracing::warn!(key = %record.key, error = %err, "Failed to re-encrypt entry")
+                }
+            }
+        }
+
+        Ok(rewritten)
+    }
+
+    /// Tenant and expiry of a key, without the artifact body
+    pub async fn metadata(&self, key: &str) -> Result<Option<EntryMetadata>, AppError> {
+        if let Some(filter) = &self.key_filter {
+            if !filter.might_contain(key) {
+                return Ok(None);
+            }
+        }
+        self.backend.metadata(key).await
+    }
+
+    /// Number of keys held by the backend; see [`CacheBackend::key_count`]
+    pub async fn key_count(&self) -> Result<u64, AppError> {
+        self.backend.key_count().await
+    }
+
+    /// Delete expired entries; see [`CacheBackend::sweep_expired`]
+    pub async fn sweep_expired(&self) -> Result<u64, AppError> {
+        self.backend.sweep_expired().await
+    }
+
+    /// Remove index members whose entry is gone; see
+    /// [`CacheBackend::prune_indexes`]
+    pub async fn prune_indexes(&self) -> Result<u64, AppError> {
+        self.backend.prune_indexes().await
+    }
+
+    /// Drop everything this node holds on top of the backend
+    ///
+    /// Clears the L1, rendered lookups, and last-known-good copies, then
+    /// rebuilds the bloom filter from a key scan. Used when invalidations may
+    /// have been missed, so none of these can be trusted.
+    pub async fn flush_local(&self) -> Result<(), AppError> {
+        self.backend.flush_local().await?;
+        if let Some(rendered) = &self.rendered {
+            rendered.clear();
+        }
+        if let Some(stale) = &self.stale {
+            stale.clear();
+        }
+        if let Some(filter) = &self.key_filter {
+            filter.rebuild(self).await?;
+        }
+        Ok(())
+    }
+
+    /// Extend the expiry of a tenant's keys; see [`CacheBackend::touch_many`]
+    pub async fn touch_many(
+        &self,
+        tenant: &str,
+        keys: &[String],
+        ttl_seconds: u64,
+    ) -> Result<Vec<Option<DateTime<Utc>>>, AppError> {
+        self.forget_rendered(keys);
+        self.backend.touch_many(tenant, keys, ttl_seconds).await
+    }
+
+    pub async fn exists_many(&self, keys: &[String]) -> Result<Vec<bool>, AppError> {
+        self.backend.exists_many(keys).await
+    }
+
+    pub async fn hashes_many(&self, keys: &[String]) -> Result<Vec<Option<EntryHash>>, AppError> {
+        self.backend.hashes_many(keys).await
+    }
+
+    pub async fn mark_event_processed(
+        &self,
+        event_id: &str,
+        retention: std::time::Duration,
+    ) -> Result<bool, AppError> {
+        self.backend.mark_event_processed(event_id, retention).await
+    }
+
+    pub async fn unmark_event_processed(&self, event_id: &str) -> Result<(), AppError> {
+        self.backend.unmark_event_processed(event_id).await
+    }
+
+    pub async fn outbox_push(&self, entry: String) -> Result<(), AppError> {
+        self.backend.outbox_push(entry).await
+    }
+
+    pub async fn outbox_claim(&self) -> Result<Option<String>, AppError> {
+        self.backend.outbox_claim().await
+    }
+
+    pub async fn outbox_ack(&self, entry: &str) -> Result<(), AppError> {
+        self.backend.outbox_ack(entry).await
+    }
+
+    pub async fn outbox_dead_letter(&self, entry: &str) -> Result<(), AppError> {
+        self.backend.outbox_dead_letter(entry).await
+    }
+
+    pub async fn outbox_recover(&self) -> Result<usize, AppError> {
+        self.backend.outbox_recover().await
+    }
+
+    pub async fn control_get(&self, name: &str) -> Result<Option<String>, AppError> {
+        self.backend.control_get(name).await
+    }
+
+    pub async fn control_set(&self, name: &str, value: &str) -> Result<(), AppError> {
+        self.backend.control_set(name, value).await
+    }
+
+    pub async fn control_delete(&self, name: &str) -> Result<(), AppError> {
+        self.backend.control_delete(name).await
+    }
+
+    pub async fn ping(&self) -> Result<(), AppError> {
+        self.backend.ping().await
     }
 }
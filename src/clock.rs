@@ -0,0 +1,67 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wall clock consulted for expiry, injectable for deterministic tests.
+//!
+//! Backends read the time through a [`Clock`] rather than `Utc::now()`
+//! directly, so a simulation can drive a [`MemoryCache`](crate::cache::MemoryCache)
+//! through hours of TTLs and ledger retention with a [`ManualClock`] without
+//! sleeping.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Source of the current time
+#[derive(Clone, Default)]
+pub enum Clock {
+    /// The system clock
+    #[default]
+    System,
+    /// A clock moved only by its [`ManualClock`] handles
+    Manual(ManualClock),
+}
+
+impl Clock {
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Manual(clock) => clock.now(),
+        }
+    }
+}
+
+impl From<ManualClock> for Clock {
+    fn from(clock: ManualClock) -> Self {
+        Clock::Manual(clock)
+    }
+}
+
+/// Clock that stands still until advanced; clones share the same time
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+    /// Clock stopped at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += by;
+    }
+
+    /// Move the clock to `now`, backwards included
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Control-plane command channel.
+//!
+//! Fleet-wide operations should not need SSH or an exposed admin port. When
+//! `SCEDGE_COMMAND_SECRET` is set, each node subscribes to
+//! `{SCEDGE_COMMAND_SUBJECT_PREFIX}.{node_id}` and `{prefix}.all` on the event
+//! bus and executes the [`NodeCommand`]s it receives.
+//!
+//! A command message is a JWT signed with HS256 using the command secret. Its
+//! claims carry the command next to `exp` and a `jti`. Each `jti` is recorded
+//! per node in the processed-event ledger until the token expires, so a
+//! captured command cannot be replayed, while a `{prefix}.all` command still
+//! runs on every node sharing the backend. Unsigned, expired, or replayed messages are
+//! dropped and logged.
+//!
+//! Requests with a reply subject receive a [`CommandReply`]. Every executed
+//! command is written to the `scedge::audit` log.
+
+use std::sync::atomic::Ordering;
+
+use async_nats::Client;
+use futures_util::StreamExt;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::api::AppState;
+use crate::config::{read_tenants_file, CommandConfig};
+use crate::error::AppError;
+use crate::invalidation::Invalidator;
+use crate::overrides::{self, NodeOverrides};
+use crate::policy::install_tenant;
+
+/// Operation requested by the control plane
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum NodeCommand {
+    /// Re-read the tenants file and apply every tenant in it
+    ReloadConfig,
+    /// Purge every artifact of a tenant
+    FlushTenant { tenant: String },
+    /// Reject or accept writes
+    SetReadOnly { enabled: bool },
+    /// Replace the log filter with `EnvFilter` directives
+    SetLogLevel { filter: String },
+}
+
+impl NodeCommand {
+    fn name(&self) -> &'static str {
+        match self {
+            NodeCommand::ReloadConfig => "reload_config",
+            NodeCommand::FlushTenant { .. } => "flush_tenant",
+            NodeCommand::SetReadOnly { .. } => "set_read_only",
+            NodeCommand::SetLogLevel { .. } => "set_log_level",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CommandClaims {
+    exp: usize,
+    jti: String,
+    #[serde(flatten)]
+    command: NodeCommand,
+}
+
+/// Outcome sent to a command's reply subject
+#[derive(Debug, Serialize)]
+pub struct CommandReply {
+    pub node_id: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// Subscriber executing signed commands addressed to this node
+pub struct CommandChannel {
+    client: Client,
+    state: AppState,
+    config: CommandConfig,
+    node_id: String,
+}
+
+impl CommandChannel {
+    pub fn new(client: Client, state: AppState, config: CommandConfig, node_id: String) -> Self {
+        Self {
+            client,
+            state,
+            config,
+            node_id,
+        }
+    }
+
+    /// Subscribe to this node's subject and the fleet-wide one
+    pub async fn spawn(self) -> Result<tokio::task::JoinHandle<()>, AppError> {
+        let mut subscribers = Vec::new();
+        for target in [self.node_id.as_str(), "all"] {
+            let subject = format!("{}.{}", self.config.subject_prefix, target);
+            subscribers.push(
+                self.client.subscribe(subject).await.map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Failed to subscribe: {}", e))
+                })?,
+            );
+        }
+        let mut messages = futures_util::stream::select_all(subscribers);
+
+        Ok(tokio::spawn(async move {
+            while let Some(message) = messages.next().await {
+                let reply = match self.execute(&message.payload).await {
+                    Ok(detail) => CommandReply {
+                        node_id: self.node_id.clone(),
+                        ok: true,
+                        detail,
+                    },
+                    Err(err) => {
+                        tracing::warn!(error = %err, subject = %message.subject, "Rejected control-plane command");
+                        CommandReply {
+                            node_id: self.node_id.clone(),
+                            ok: false,
+                            detail: err.to_string(),
+                        }
+                    }
+                };
+
+                if let Some(reply_subject) = message.reply {
+                    let payload = serde_json::to_vec(&reply).unwrap_or_default();
+                    if let Err(err) = self.client.publish(reply_subject, payload.into()).await {
+                        tracing::warn!(error = %err, "Failed to reply to control-plane command");
+                    }
+                }
+            }
+            tracing::warn!("Control-plane command subscription closed");
+        }))
+    }
+
+    /// Verify a signed command and run it
+    async fn execute(&self, payload: &[u8]) -> Result<String, AppError> {
+        let token = std::str::from_utf8(payload)
+            .map_err(|_| AppError::bad_request("Command is not UTF-8"))?
+            .trim();
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_exp = true;
+        let claims = decode::<CommandClaims>(
+            token,
+            &DecodingKey::from_secret(self.config.secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|e| AppError::unauthorized(format!("Invalid command signature: {}", e)))?
+        .claims;
+
+        let remaining = (claims.exp as i64 - chrono::Utc::now().timestamp()).max(1) as u64;
+        let first_delivery = self
+            .state
+            .cache
+            .mark_event_processed(
+                &format!("command:{}:{}", self.node_id, claims.jti),
+                std::time::Duration::from_secs(remaining),
+            )
+            .await?;
+        if !first_delivery {
+            return Err(AppError::bad_request(format!(
+                "Command {} was already executed",
+                claims.jti
+            )));
+        }
+
+        let detail = self.apply(&claims.command).await?;
+        tracing::info!(
+            target: "scedge::audit",
+            command = claims.command.name(),
+            jti = %claims.jti,
+            detail = %detail,
+            "Control-plane command executed"
+        );
+        Ok(detail)
+    }
+
+    async fn apply(&self, command: &NodeCommand) -> Result<String, AppError> {
+        let state = &self.state;
+        match command {
+            NodeCommand::ReloadConfig => {
+                let path = self
+                    .config
+                    .tenant_keys_path
+                    .as_ref()
+                    .ok_or_else(|| AppError::bad_request("No tenants file configured"))?;
+                let tenants = read_tenants_file(path).map_err(AppError::Internal)?;
+                let count = tenants.len();
+                for tenant in tenants {
+                    install_tenant(
+                        tenant,
+                        &state.policy,
+                        &state.keyring,
+                        &state.plugins,
+                        &state.cache,
+                    )
+                    .await?;
+                }
+                Ok(format!("reloaded {} tenants", count))
+            }
+            NodeCommand::FlushTenant { tenant } => {
+                let purged = Invalidator::new(state.cache.clone())
+                    .invalidate_tenant(tenant)
+                    .await?;
+                state.metrics.record_cache_purge(purged);
+                Ok(format!("purged {} keys", purged))
+            }
+            NodeCommand::SetReadOnly { enabled } => {
+                overrides::save_node(
+                    &state.cache,
+                    &self.node_id,
+                    &NodeOverrides {
+                        read_only: *enabled,
+                    },
+                )
+                .await?;
+                state.read_only.store(*enabled, Ordering::Release);
+                Ok(format!("read-only {}", if *enabled { "on" } else { "off" }))
+            }
+            NodeCommand::SetLogLevel { filter } => {
+                let log_level = state
+                    .log_level
+                    .as_ref()
+                    .ok_or_else(|| AppError::bad_request("Log level is not adjustable"))?;
+                log_level.set(filter)?;
+                Ok(format!("log filter {}", filter))
+            }
+        }
+    }
+}
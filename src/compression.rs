@@ -0,0 +1,174 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-tenant zstd dictionary compression of artifact answers.
+//!
+//! Artifacts of one tenant tend to be highly templated JSON, which plain zstd
+//! compresses poorly at artifact sizes. An operator trains a dictionary from a
+//! sample of a tenant's cached answers (`POST /admin/tenants/{id}/dictionary`).
+//! It is stored versioned in the backend, and from then on every answer the
+//! tenant stores is compressed with the newest dictionary.
+//!
+//! Every trained version stays in the backend, so entries compressed under an
+//! older dictionary, or by another node that trained one this node has not
+//! seen yet, are decoded by loading that version on first use.
+//!
+//! Tenants without a dictionary can still have large answers compressed with
+//! plain zstd: with `SCEDGE_COMPRESSION_MIN_BYTES` set, answers serializing to
+//! at least that many bytes are compressed under version [`PLAIN_VERSION`].
+//!
+//! Compressed answers are kept outside the answer value, in entries of their
+//! own format (see [`entry_format`](crate::entry_format)), so any answer a
+//! producer stores round-trips unchanged. Entries are compressed as the
+//! backend writes them, after encryption, so encrypted answers gain little.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::{Arc, RwLock};
+
+use crate::error::AppError;
+
+/// Dictionary version of answers compressed without a dictionary
+///
+/// Stored versions start at 1.
+pub const PLAIN_VERSION: u32 = 0;
+
+/// zstd level used with and without trained dictionaries
+const COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(Default)]
+struct TenantDictionaries {
+    active: Option<u32>,
+    versions: HashMap<u32, Arc<Vec<u8>>>,
+}
+
+/// Trained dictionaries of every tenant, shared by the cache and admin API
+#[derive(Clone, Default)]
+pub struct Dictionaries {
+    tenants: Arc<RwLock<HashMap<String, TenantDictionaries>>>,
+    /// Serialized size from which answers of tenants without a dictionary
+    /// are compressed
+    min_plain_bytes: Option<usize>,
+}
+
+impl Dictionaries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compress answers of at least `min_bytes` for tenants without a dictionary
+    pub fn with_min_plain_bytes(mut self, min_bytes: usize) -> Self {
+        self.min_plain_bytes = Some(min_bytes);
+        self
+    }
+
+    /// Register a dictionary version, activating it when it is the newest
+    pub fn add(&self, tenant: &str, version: u32, dictionary: Vec<u8>) {
+        let mut tenants = self.tenants.write().unwrap_or_else(|e| e.into_inner());
+        let dictionaries = tenants.entry(tenant.to_string()).or_default();
+        dictionaries.versions.insert(version, Arc::new(dictionary));
+        if dictionaries.active.is_none_or(|active| version > active) {
+            dictionaries.active = Some(version);
+        }
+    }
+
+    /// Version new writes for `tenant` are compressed with
+    pub fn active_version(&self, tenant: &str) -> Option<u32> {
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        tenants
+            .get(tenant)
+            .and_then(|dictionaries| dictionaries.active)
+    }
+
+    /// Whether answers of `tenant` may be compressed at all
+    pub fn compresses(&self, tenant: &str) -> bool {
+        self.min_plain_bytes.is_some() || self.active_version(tenant).is_some()
+    }
+
+    /// Compress a serialized answer with the tenant's active dictionary, or
+    /// without one when it is large enough
+    ///
+    /// Returns the dictionary version with the zstd frame, or `None` when the
+    /// answer is stored as is.
+    pub fn compress(&self, tenant: &str, plain: &[u8]) -> Result<Option<(u32, Vec<u8>)>, AppError> {
+        let (version, frame) = match self.active(tenant) {
+            Some((version, dictionary)) => (
+                version,
+                zstd::bulk::Compressor::with_dictionary(COMPRESSION_LEVEL, &dictionary)
+                    .and_then(|mut compressor| compressor.compress(plain)),
+            ),
+            None if self.min_plain_bytes.is_some_and(|min| plain.len() >= min) => (
+                PLAIN_VERSION,
+                zstd::bulk::compress(plain, COMPRESSION_LEVEL),
+            ),
+            None => return Ok(None),
+        };
+        let frame =
+            frame.map_err(|e| AppError::Internal(anyhow::anyhow!("Compression failed: {}", e)))?;
+        Ok(Some((version, frame)))
+    }
+
+    /// Whether answers compressed under `version` can be decompressed
+    pub fn has_version(&self, tenant: &str, version: u32) -> bool {
+        version == PLAIN_VERSION || self.version(tenant, version).is_some()
+    }
+
+    /// Expand a frame written by [`Dictionaries::compress`]
+    pub fn decompress(
+        &self,
+        tenant: &str,
+        version: u32,
+        frame: &[u8],
+    ) -> Result<Vec<u8>, AppError> {
+        let dictionary = match version {
+            PLAIN_VERSION => None,
+            version => Some(self.version(tenant, version).ok_or_else(|| {
+                AppError::Internal(anyhow::anyhow!(
+                    "Unknown dictionary {} for tenant {}",
+                    version,
+                    tenant
+                ))
+            })?),
+        };
+
+        let mut plain = Vec::new();
+        match &dictionary {
+            Some(dictionary) => zstd::stream::Decoder::with_dictionary(frame, dictionary)
+                .and_then(|mut decoder| decoder.read_to_end(&mut plain)),
+            None => zstd::stream::Decoder::new(frame)
+                .and_then(|mut decoder| decoder.read_to_end(&mut plain)),
+        }
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Decompression failed: {}", e)))?;
+        Ok(plain)
+    }
+
+    fn active(&self, tenant: &str) -> Option<(u32, Arc<Vec<u8>>)> {
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        let dictionaries = tenants.get(tenant)?;
+        let active = dictionaries.active?;
+        Some((active, dictionaries.versions[&active].clone()))
+    }
+
+    fn version(&self, tenant: &str, version: u32) -> Option<Arc<Vec<u8>>> {
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        tenants
+            .get(tenant)
+            .and_then(|dictionaries| dictionaries.versions.get(&version))
+            .cloned()
+    }
+}
+
+/// Outcome of [`Cache::train_dictionary`](crate::cache::Cache::train_dictionary)
+#[derive(Debug, Clone)]
+pub struct TrainedDictionary {
+    pub version: u32,
+    pub size_bytes: usize,
+    /// Cached answers the dictionary was trained on
+    pub samples: usize,
+}
+
+/// Train a dictionary of at most `max_size` bytes from sample answers
+pub fn train(samples: &[Vec<u8>], max_size: usize) -> Result<Vec<u8>, AppError> {
+    zstd::dict::from_samples(samples, max_size)
+        .map_err(|e| AppError::bad_request(format!("Dictionary training failed: {}", e)))
+}
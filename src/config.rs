@@ -8,31 +8,137 @@
 //! - Redis connection settings
 //! - TTL defaults
 //! - Tenant authentication
+//! - Admin API token
 //! - Feature flags (metrics, event bus)
 
 use std::env;
 use std::fs;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
+use crate::cache::{EvictionPolicy, ScanLimits, DEFAULT_REDIS_POOL_SIZE};
+use crate::entry_format::EntryFormat;
+use crate::events::JetStreamConsumer;
 use crate::policy::TenantConfig;
+use crate::wal::WalConfig;
 
 #[derive(Debug, Clone)]
 pub struct AppConfig {
-    pub listen_addr: SocketAddr,
+    /// Addresses served, at least one
+    pub listeners: Vec<ListenerConfig>,
     pub default_ttl: Duration,
+    pub clock_skew_tolerance: Duration,
+    pub cache_backend: CacheBackendKind,
+    /// In-memory L1 in front of Redis
+    pub l1: Option<L1Config>,
     pub redis_url: String,
     pub tenant_keys_path: Option<PathBuf>,
     pub jwt_secret: Option<String>,
+    pub admin_token: Option<String>,
     pub event_bus_enabled: bool,
     pub event_bus_channel: String,
     pub event_bus_url: String,
+    pub event_bus_redis_channel: Option<String>,
+    pub event_ledger_retention: Duration,
+    pub event_lag: Option<EventLagConfig>,
+    pub wal: Option<WalConfig>,
+    pub purge_queue: PurgeQueueConfig,
+    pub artifact_events_subject: String,
+    pub outbox: OutboxConfig,
+    pub policy_events_subject: Option<String>,
     pub metrics_enabled: bool,
+    pub pushgateway: Option<PushgatewayConfig>,
+    pub node: NodeConfig,
+    pub heartbeat: Option<HeartbeatConfig>,
+    pub commands: Option<CommandConfig>,
+    pub ready_when_degraded: bool,
+    pub verify_hashes: bool,
+    /// Honor `X-Scedge-Debug: timings` on lookups
+    pub debug_timings: bool,
+    /// How long a rotated API key stays valid
+    pub api_key_grace: Duration,
+    /// How long a request waits for a slot of its tenant's `max_concurrency`
+    pub bulkhead_timeout: Duration,
+    /// Most keys a single `POST /lookup/batch` may ask for
+    pub max_batch_lookup_keys: usize,
+    /// How long in-flight requests may run after a shutdown signal
+    pub shutdown_drain_timeout: Duration,
+    /// How often expired entries are reaped and the cache size gauge refreshed
+    pub expiry_sweep_interval: Duration,
+    /// WASM policy plugin applied to every tenant
+    pub policy_plugin: Option<PathBuf>,
+    /// Fuel budget of one plugin call
+    pub policy_plugin_fuel: u64,
+    /// Linear memory budget of one plugin call
+    pub policy_plugin_max_memory_bytes: usize,
     pub upstream: Option<UpstreamConfig>,
+    pub outbound: OutboundConfig,
+    pub peers: Option<PeerConfig>,
+    pub key_filter: Option<KeyFilterConfig>,
+    pub admission: AdmissionConfig,
+    /// Last-known-good copies kept for tenants with `serve_stale_on_error`
+    pub stale_copies_capacity: usize,
+    /// Serialized answer size from which answers are zstd-compressed
+    pub compression_min_bytes: Option<usize>,
+    pub scan_limits: ScanLimits,
+    pub xfetch_beta: f64,
+    pub ttl_autotune: Option<TtlAutotuneConfig>,
+    /// Sibling whose cache is copied at startup
+    pub sync: Option<SyncConfig>,
+    pub warmup: Option<WarmupConfig>,
+    pub redis_pools: RedisPoolsConfig,
+    /// Format new Redis entries are written in
+    pub redis_entry_format: EntryFormat,
+    /// Shadow instance receiving a sample of lookups
+    pub mirror: Option<MirrorConfig>,
+}
+
+/// Shadow Scedge instance replaying a sample of production lookups
+#[derive(Debug, Clone)]
+pub struct MirrorConfig {
+    /// Base URL of the shadow, e.g. `http://scedge-shadow:8080`
+    pub target: String,
+    /// Share of lookups mirrored, from 0 to 100
+    pub percent: f64,
+    /// Bearer token sent in place of the caller's credentials
+    pub token: Option<String>,
+    /// Mirrored requests outstanding before further samples are dropped
+    pub max_in_flight: usize,
+    pub timeout: Duration,
+}
+
+/// Endpoints and sizes of the Redis backend's per-workload connection pools
+///
+/// Writes always go to `SCEDGE_REDIS_URL`.
+#[derive(Debug, Clone)]
+pub struct RedisPoolsConfig {
+    /// Endpoint for lookups, e.g. a replica
+    pub read_url: String,
+    /// Endpoint for key scans
+    pub scan_url: String,
+    pub read_size: usize,
+    pub write_size: usize,
+    pub scan_size: usize,
+}
+
+/// Storage behind the cache, chosen by `SCEDGE_CACHE_BACKEND`
+#[derive(Debug, Clone)]
+pub enum CacheBackendKind {
+    /// Redis at `SCEDGE_REDIS_URL`
+    Redis,
+    /// SQLite database file, for single-node deployments without Redis
+    Sqlite {
+        path: PathBuf,
+        /// How often expired artifacts are deleted from the file
+        sweep_interval: Duration,
+    },
+    /// RocksDB directory, for caches larger than RAM on a single node
+    #[cfg(feature = "rocksdb")]
+    RocksDb { path: PathBuf },
 }
 
 #[derive(Debug, Deserialize)]
@@ -40,29 +146,391 @@ struct TenantsFile {
     tenants: Vec<TenantConfig>,
 }
 
+/// Invalidation backlog threshold reflected in readiness
+#[derive(Debug, Clone)]
+pub struct EventLagConfig {
+    /// Pending events above which the node is considered stale
+    pub max_pending: u64,
+    /// Report not-ready instead of degraded while over the threshold
+    pub fail_readiness: bool,
+    pub interval: Duration,
+    pub jetstream: Option<JetStreamConsumer>,
+}
+
+/// Periodic push of metrics to a Prometheus Pushgateway
+#[derive(Debug, Clone)]
+pub struct PushgatewayConfig {
+    pub url: String,
+    pub job: String,
+    pub interval: Duration,
+    /// Grouping labels identifying this node (e.g. `instance`, `site`)
+    pub labels: Vec<(String, String)>,
+}
+
+/// Identity this node reports to the control plane
+#[derive(Debug, Clone)]
+pub struct NodeConfig {
+    /// Explicit node ID; otherwise one is persisted at `id_path`
+    pub id: Option<String>,
+    pub id_path: PathBuf,
+    pub region: Option<String>,
+}
+
+/// Where and how often fleet heartbeats are published
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    /// NATS subject, published through the event bus connection
+    pub subject: Option<String>,
+    /// HTTP registry endpoint receiving `POST`ed heartbeats
+    pub url: Option<String>,
+    pub interval: Duration,
+}
+
+/// Signed control-plane commands received over the event bus
+#[derive(Debug, Clone)]
+pub struct CommandConfig {
+    /// HS256 secret command tokens must be signed with
+    pub secret: String,
+    /// Subjects are `{prefix}.{node_id}` and `{prefix}.all`
+    pub subject_prefix: String,
+    /// Tenants file re-read by `reload_config`
+    pub tenant_keys_path: Option<PathBuf>,
+}
+
+/// Worker pool applying purges resolved from graph events
+#[derive(Debug, Clone)]
+pub struct PurgeQueueConfig {
+    /// Key batches held before enqueueing waits
+    pub capacity: usize,
+    pub workers: usize,
+    /// Maximum keys per `UNLINK`
+    pub batch_size: usize,
+}
+
+/// Retries of outbox notifications that failed to deliver
+#[derive(Debug, Clone)]
+pub struct OutboxConfig {
+    /// Delivery attempts before a notification is dead-lettered
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled after each failure
+    pub retry_base: Duration,
+    /// Longest delay between retries
+    pub retry_max: Duration,
+}
+
+/// Bounds and thresholds of per-tenant default TTL tuning
+#[derive(Debug, Clone)]
+pub struct TtlAutotuneConfig {
+    pub min_ttl: Duration,
+    pub max_ttl: Duration,
+    /// How often each tenant's TTL is reconsidered
+    pub interval: Duration,
+    /// Hit ratio under which the TTL is lengthened
+    pub target_hit_ratio: f64,
+    /// Purged keys per store over which the TTL is shortened
+    pub max_invalidation_ratio: f64,
+}
+
+/// Sibling node a new node copies its working set from at startup
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// Base URL of the sibling, e.g. `http://scedge-2:8080`
+    pub source: String,
+    /// Admin token of the sibling
+    pub token: Option<String>,
+    /// Only copy entries modified within this window; everything when unset
+    pub window: Option<Duration>,
+}
+
+/// Address the node serves HTTP on
+#[derive(Debug, Clone)]
+pub struct ListenerConfig {
+    pub addr: SocketAddr,
+    /// Terminate TLS with this certificate; plain HTTP when unset
+    pub tls: Option<ListenerTls>,
+    /// Serve the admin endpoints; once any listener does, the others do not
+    pub admin: bool,
+}
+
+/// PEM files of a TLS listener
+#[derive(Debug, Clone)]
+pub struct ListenerTls {
+    /// Certificate chain, leaf first
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl std::str::FromStr for ListenerConfig {
+    type Err = anyhow::Error;
+
+    /// Parse `{addr}` followed by space-separated options: `cert={path}`,
+    /// `key={path}`, and `admin`, e.g. `[::]:8443 cert=tls.crt key=tls.key`
+    fn from_str(spec: &str) -> Result<Self> {
+        let mut parts = spec.split_whitespace();
+        let addr = parts.next().unwrap_or_default();
+        let addr = addr
+            .parse()
+            .with_context(|| format!("invalid listen address `{}`", addr))?;
+
+        let (mut cert_path, mut key_path, mut admin) = (None, None, false);
+        for option in parts {
+            match option.split_once('=') {
+                Some(("cert", path)) => cert_path = Some(PathBuf::from(path)),
+                Some(("key", path)) => key_path = Some(PathBuf::from(path)),
+                None if option == "admin" => admin = true,
+                _ => anyhow::bail!("unknown listener option `{}`", option),
+            }
+        }
+        let tls = match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Some(ListenerTls {
+                cert_path,
+                key_path,
+            }),
+            (None, None) => None,
+            _ => anyhow::bail!("listener {} needs both cert= and key= for TLS", addr),
+        };
+
+        Ok(Self { addr, tls, admin })
+    }
+}
+
+/// Keys hydrated from upstream at startup before the node reports ready
+#[derive(Debug, Clone)]
+pub struct WarmupConfig {
+    /// JSON manifest of the keys, see [`crate::warmup::WarmupManifest`]
+    pub manifest: PathBuf,
+    /// Keys hydrated at once
+    pub concurrency: usize,
+    /// How long warm-up may hold back readiness
+    pub timeout: Duration,
+}
+
+/// Node-local L1 of hot artifacts in front of the Redis backend
+#[derive(Debug, Clone)]
+pub struct L1Config {
+    /// Maximum artifacts held in memory
+    pub capacity: usize,
+    /// Maximum approximate bytes held in memory; unbounded when unset
+    pub max_bytes: Option<usize>,
+    /// Which artifact a full L1 evicts
+    pub eviction: EvictionPolicy,
+    /// How long an L1 copy is served before Redis is read again
+    pub max_age: Duration,
+    /// Hot keys whose lookups are kept serialized; 0 disables rendering
+    pub rendered_capacity: usize,
+    /// Lookups of a key before its hits are rendered
+    pub render_min_hits: u8,
+}
+
+/// Per-tenant bloom filters of cached keys consulted before backend reads
+#[derive(Debug, Clone)]
+pub struct KeyFilterConfig {
+    /// Expected number of keys per tenant
+    pub capacity: usize,
+    pub false_positive_rate: f64,
+    pub rebuild_interval: Duration,
+}
+
+/// Lookup frequency sketch for tenants with `admission_control`
+#[derive(Debug, Clone)]
+pub struct AdmissionConfig {
+    /// Counters per sketch row
+    pub sketch_width: usize,
+    /// Lookups of a key before it is cached
+    pub min_accesses: u8,
+}
+
 #[derive(Debug, Clone)]
 pub struct UpstreamConfig {
     pub base_url: String,
     pub timeout: Duration,
+    pub ttl_precedence: TtlPrecedence,
+    pub client_tls: Option<ClientTlsConfig>,
+    pub outbound: OutboundConfig,
+}
+
+/// Which side wins when both the upstream response headers and the artifact
+/// declare a lifetime for a hydrated entry
+///
+/// `no-store`, `no-cache`, and `private` responses are never cached either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtlPrecedence {
+    /// Artifact `expires_at`/`ttl_seconds` first; headers only fill the gap
+    Artifact,
+    /// `Cache-Control`/`Expires` first; the artifact TTL is the fallback
+    Headers,
+}
+
+impl TtlPrecedence {
+    fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "artifact" => Ok(Self::Artifact),
+            "headers" => Ok(Self::Headers),
+            other => anyhow::bail!(
+                "SCEDGE_UPSTREAM_TTL_PRECEDENCE must be `artifact` or `headers`, got `{}`",
+                other
+            ),
+        }
+    }
+}
+
+/// Proxy and trust settings shared by the upstream and webhook clients
+#[derive(Debug, Clone, Default)]
+pub struct OutboundConfig {
+    /// HTTP(S) proxy for all outbound requests; credentials may be embedded in the URL
+    pub proxy_url: Option<String>,
+    /// Comma-separated hosts, domains, and CIDRs that bypass the proxy
+    pub no_proxy: Option<String>,
+    /// PEM bundle of extra root CAs, e.g. a TLS-intercepting proxy's private CA
+    pub ca_bundle: Option<PathBuf>,
+}
+
+/// Client certificate presented on outbound mTLS connections
+///
+/// Works with SPIFFE X.509-SVIDs written to disk by a workload API helper:
+/// the files are re-read whenever they change, so rotated SVIDs are picked up
+/// without a restart.
+#[derive(Debug, Clone)]
+pub struct ClientTlsConfig {
+    /// PEM certificate chain (leaf first)
+    pub cert_path: PathBuf,
+    /// PEM PKCS#8 private key
+    pub key_path: PathBuf,
+    /// Optional PEM trust bundle for verifying the server (e.g. SPIFFE bundle)
+    pub ca_path: Option<PathBuf>,
+    /// How often the files are checked for rotation
+    pub reload_interval: Duration,
+}
+
+/// Sibling nodes queried on a local miss before upstream
+#[derive(Debug, Clone)]
+pub struct PeerConfig {
+    pub peers: Vec<String>,
+    /// Maximum number of peers queried per miss
+    pub fanout: usize,
+    pub timeout: Duration,
+    /// Delay before each additional hedged peer request
+    pub hedge_delay: Duration,
 }
 
 impl AppConfig {
     pub fn from_env() -> Result<Self> {
-        let listen_addr: SocketAddr = env::var("SCEDGE_ADDR")
-            .or_else(|_| env::var("SCEDGE_PORT").map(|p| format!("0.0.0.0:{}", p)))
-            .unwrap_or_else(|_| "0.0.0.0:8080".to_string())
-            .parse()
-            .context("invalid SCEDGE_ADDR or SCEDGE_PORT")?;
+        let listeners = match env::var("SCEDGE_LISTEN") {
+            Ok(raw) if !raw.trim().is_empty() => raw
+                .split(',')
+                .map(str::trim)
+                .filter(|spec| !spec.is_empty())
+                .map(|spec| spec.parse())
+                .collect::<Result<Vec<ListenerConfig>>>()
+                .context("invalid SCEDGE_LISTEN")?,
+            _ => {
+                let addr: SocketAddr = env::var("SCEDGE_ADDR")
+                    .or_else(|_| env::var("SCEDGE_PORT").map(|p| format!("0.0.0.0:{}", p)))
+                    .unwrap_or_else(|_| "0.0.0.0:8080".to_string())
+                    .parse()
+                    .context("invalid SCEDGE_ADDR or SCEDGE_PORT")?;
+                vec![ListenerConfig {
+                    addr,
+                    tls: None,
+                    admin: false,
+                }]
+            }
+        };
+        if listeners.is_empty() {
+            anyhow::bail!("SCEDGE_LISTEN must list at least one address");
+        }
 
         let default_ttl = parse_duration("SCEDGE_DEFAULT_TTL", 86400)?;
 
+        let clock_skew_tolerance = parse_duration("SCEDGE_CLOCK_SKEW_TOLERANCE_SECS", 5)?;
+
         let redis_url =
             env::var("SCEDGE_REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
 
+        let cache_backend = match env::var("SCEDGE_CACHE_BACKEND")
+            .unwrap_or_else(|_| "redis".to_string())
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "redis" => CacheBackendKind::Redis,
+            "sqlite" => CacheBackendKind::Sqlite {
+                path: env::var("SCEDGE_SQLITE_PATH")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("scedge-cache.db")),
+                sweep_interval: parse_duration("SCEDGE_SQLITE_SWEEP_SECS", 60)?,
+            },
+            #[cfg(feature = "rocksdb")]
+            "rocksdb" => CacheBackendKind::RocksDb {
+                path: env::var("SCEDGE_ROCKSDB_PATH")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("scedge-rocksdb")),
+            },
+            #[cfg(not(feature = "rocksdb"))]
+            "rocksdb" => {
+                anyhow::bail!(
+                    "SCEDGE_CACHE_BACKEND=rocksdb needs a build with the `rocksdb` feature"
+                )
+            }
+            other => anyhow::bail!(
+                "SCEDGE_CACHE_BACKEND must be `redis`, `sqlite`, or `rocksdb`, got `{}`",
+                other
+            ),
+        };
+
+        let l1 =
+            match env::var("SCEDGE_L1_CAPACITY") {
+                Ok(raw) => {
+                    let capacity: usize = raw
+                        .parse()
+                        .context("SCEDGE_L1_CAPACITY must be a non-negative integer")?;
+                    if capacity == 0 {
+                        None
+                    } else {
+                        Some(L1Config {
+                            capacity,
+                            max_bytes: match env::var("SCEDGE_L1_MAX_BYTES") {
+                                Ok(value) => Some(value.parse::<usize>().context(
+                                    "SCEDGE_L1_MAX_BYTES must be a non-negative integer",
+                                )?)
+                                .filter(|max_bytes| *max_bytes > 0),
+                                Err(_) => None,
+                            },
+                            eviction: env::var("SCEDGE_L1_EVICTION")
+                                .unwrap_or_else(|_| "cost".to_string())
+                                .parse()
+                                .map_err(|_| {
+                                    anyhow::anyhow!("SCEDGE_L1_EVICTION must be cost or lru")
+                                })?,
+                            max_age: parse_duration("SCEDGE_L1_MAX_AGE_SECS", 5)?,
+                            rendered_capacity: env::var("SCEDGE_L1_RENDERED_CAPACITY")
+                                .unwrap_or_else(|_| "0".to_string())
+                                .parse()
+                                .context(
+                                    "SCEDGE_L1_RENDERED_CAPACITY must be a non-negative integer",
+                                )?,
+                            render_min_hits: env::var("SCEDGE_L1_RENDER_MIN_HITS")
+                                .unwrap_or_else(|_| "16".to_string())
+                                .parse()
+                                .ok()
+                                .filter(|hits| *hits > 0)
+                                .context(
+                                    "SCEDGE_L1_RENDER_MIN_HITS must be an integer from 1 to 255",
+                                )?,
+                        })
+                    }
+                }
+                Err(_) => None,
+            };
+
         let tenant_keys_path = env::var("SCEDGE_TENANT_KEYS_PATH").ok().map(PathBuf::from);
 
         let jwt_secret = env::var("SCEDGE_JWT_SECRET").ok();
 
+        let admin_token = env::var("SCEDGE_ADMIN_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty());
+
         let event_bus_enabled = env::var("SCEDGE_EVENT_BUS_ENABLED")
             .unwrap_or_else(|_| "true".to_string())
             .parse()
@@ -74,60 +542,493 @@ impl AppConfig {
         let event_bus_url = env::var("SCEDGE_EVENT_BUS_URL")
             .unwrap_or_else(|_| "nats://127.0.0.1:4222".to_string());
 
+        let event_bus_redis_channel = env::var("SCEDGE_EVENT_BUS_REDIS_CHANNEL")
+            .ok()
+            .filter(|channel| !channel.trim().is_empty());
+
+        let event_ledger_retention = parse_duration("SCEDGE_EVENT_LEDGER_RETENTION_SECS", 86400)?;
+
+        let event_lag = match env::var("SCEDGE_EVENT_LAG_MAX_PENDING") {
+            Ok(raw) if !raw.trim().is_empty() => {
+                let max_pending = raw
+                    .trim()
+                    .parse()
+                    .context("SCEDGE_EVENT_LAG_MAX_PENDING must be an integer")?;
+                let fail_readiness = env::var("SCEDGE_EVENT_LAG_NOT_READY")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .unwrap_or(false);
+                let jetstream = match (
+                    env::var("SCEDGE_EVENT_BUS_JETSTREAM_STREAM").ok(),
+                    env::var("SCEDGE_EVENT_BUS_JETSTREAM_CONSUMER").ok(),
+                ) {
+                    (Some(stream), Some(consumer)) => Some(JetStreamConsumer { stream, consumer }),
+                    (None, None) => None,
+                    _ => anyhow::bail!(
+                        "SCEDGE_EVENT_BUS_JETSTREAM_STREAM and SCEDGE_EVENT_BUS_JETSTREAM_CONSUMER must be set together"
+                    ),
+                };
+                Some(EventLagConfig {
+                    max_pending,
+                    fail_readiness,
+                    interval: parse_duration("SCEDGE_EVENT_LAG_INTERVAL_SECS", 5)?,
+                    jetstream,
+                })
+            }
+            _ => None,
+        };
+
+        let wal_enabled = env::var("SCEDGE_WAL_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+        let wal = if wal_enabled {
+            Some(WalConfig {
+                stream: env::var("SCEDGE_WAL_STREAM").unwrap_or_else(|_| "scedge:wal".to_string()),
+                max_len: env::var("SCEDGE_WAL_MAX_LEN")
+                    .unwrap_or_else(|_| "100000".to_string())
+                    .parse()
+                    .ok()
+                    .filter(|max_len| *max_len > 0)
+                    .context("SCEDGE_WAL_MAX_LEN must be a positive integer")?,
+            })
+        } else {
+            None
+        };
+
+        let purge_queue = PurgeQueueConfig {
+            capacity: parse_positive("SCEDGE_PURGE_QUEUE_CAPACITY", 1024)?,
+            workers: parse_positive("SCEDGE_PURGE_WORKERS", 2)?,
+            batch_size: parse_positive("SCEDGE_PURGE_BATCH_SIZE", 500)?,
+        };
+
+        let artifact_events_subject = env::var("SCEDGE_ARTIFACT_EVENTS_SUBJECT")
+            .unwrap_or_else(|_| "scedge.artifacts".to_string());
+
+        let outbox = OutboxConfig {
+            max_attempts: parse_positive("SCEDGE_OUTBOX_MAX_ATTEMPTS", 8)? as u32,
+            retry_base: parse_duration("SCEDGE_OUTBOX_RETRY_BASE_SECS", 1)?,
+            retry_max: parse_duration("SCEDGE_OUTBOX_RETRY_MAX_SECS", 300)?,
+        };
+
+        let policy_events_subject = env::var("SCEDGE_POLICY_EVENTS_SUBJECT")
+            .ok()
+            .filter(|subject| !subject.trim().is_empty());
+
         let metrics_enabled = env::var("SCEDGE_METRICS_ENABLED")
             .unwrap_or_else(|_| "true".to_string())
             .parse()
             .unwrap_or(true);
 
+        let pushgateway = match env::var("SCEDGE_PUSHGATEWAY_URL") {
+            Ok(url) if !url.trim().is_empty() => {
+                let job =
+                    env::var("SCEDGE_PUSHGATEWAY_JOB").unwrap_or_else(|_| "scedge".to_string());
+                let interval = parse_duration("SCEDGE_PUSHGATEWAY_INTERVAL_SECS", 15)?;
+                let mut labels =
+                    parse_labels(&env::var("SCEDGE_PUSHGATEWAY_LABELS").unwrap_or_default())?;
+                if !labels.iter().any(|(key, _)| key == "instance") {
+                    if let Ok(host) = env::var("HOSTNAME") {
+                        labels.push(("instance".to_string(), host));
+                    }
+                }
+                Some(PushgatewayConfig {
+                    url,
+                    job,
+                    interval,
+                    labels,
+                })
+            }
+            _ => None,
+        };
+
+        let node = NodeConfig {
+            id: env::var("SCEDGE_NODE_ID")
+                .ok()
+                .filter(|id| !id.trim().is_empty()),
+            id_path: env::var("SCEDGE_NODE_ID_PATH")
+                .unwrap_or_else(|_| ".scedge-node-id".to_string())
+                .into(),
+            region: env::var("SCEDGE_REGION")
+                .ok()
+                .filter(|region| !region.trim().is_empty()),
+        };
+
+        let heartbeat_subject = env::var("SCEDGE_HEARTBEAT_SUBJECT")
+            .ok()
+            .filter(|subject| !subject.trim().is_empty());
+        let heartbeat_url = env::var("SCEDGE_HEARTBEAT_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+        let heartbeat = if heartbeat_subject.is_some() || heartbeat_url.is_some() {
+            Some(HeartbeatConfig {
+                subject: heartbeat_subject,
+                url: heartbeat_url,
+                interval: parse_duration("SCEDGE_HEARTBEAT_INTERVAL_SECS", 30)?,
+            })
+        } else {
+            None
+        };
+
+        let commands = env::var("SCEDGE_COMMAND_SECRET")
+            .ok()
+            .filter(|secret| !secret.trim().is_empty())
+            .map(|secret| CommandConfig {
+                secret,
+                subject_prefix: env::var("SCEDGE_COMMAND_SUBJECT_PREFIX")
+                    .unwrap_or_else(|_| "scedge.commands".to_string()),
+                tenant_keys_path: tenant_keys_path.clone(),
+            });
+
+        let ready_when_degraded = env::var("SCEDGE_READY_WHEN_DEGRADED")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let verify_hashes = env::var("SCEDGE_VERIFY_ARTIFACT_HASHES")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let debug_timings = env::var("SCEDGE_DEBUG_TIMINGS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let api_key_grace = parse_duration("SCEDGE_API_KEY_GRACE_SECS", 86400)?;
+        let bulkhead_timeout = parse_duration_ms("SCEDGE_BULKHEAD_TIMEOUT_MS", 1000)?;
+        let max_batch_lookup_keys = parse_positive("SCEDGE_BATCH_LOOKUP_MAX_KEYS", 1000)?;
+        let shutdown_drain_timeout = parse_duration("SCEDGE_SHUTDOWN_DRAIN_SECS", 30)?;
+        // SQLite deployments keep their own interval
+        let expiry_sweep_interval = match &cache_backend {
+            CacheBackendKind::Sqlite { sweep_interval, .. } => *sweep_interval,
+            _ => parse_duration("SCEDGE_EXPIRY_SWEEP_SECS", 60)?,
+        };
+
+        let outbound = OutboundConfig {
+            proxy_url: env::var("SCEDGE_OUTBOUND_PROXY")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            no_proxy: env::var("SCEDGE_OUTBOUND_NO_PROXY")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            ca_bundle: env::var("SCEDGE_OUTBOUND_CA_BUNDLE")
+                .ok()
+                .map(PathBuf::from),
+        };
+
+        let policy_plugin = env::var("SCEDGE_POLICY_PLUGIN")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(PathBuf::from);
+        let policy_plugin_fuel = env::var("SCEDGE_POLICY_PLUGIN_FUEL")
+            .unwrap_or_else(|_| "10000000".to_string())
+            .parse()
+            .context("SCEDGE_POLICY_PLUGIN_FUEL must be an integer")?;
+        let policy_plugin_max_memory_bytes = env::var("SCEDGE_POLICY_PLUGIN_MAX_MEMORY_BYTES")
+            .unwrap_or_else(|_| "16777216".to_string())
+            .parse()
+            .context("SCEDGE_POLICY_PLUGIN_MAX_MEMORY_BYTES must be an integer")?;
+
         let upstream = match env::var("SCEDGE_UPSTREAM_URL") {
             Ok(url) if !url.trim().is_empty() => {
                 let timeout = parse_duration("SCEDGE_UPSTREAM_TIMEOUT_SECS", 5)?;
+                let ttl_precedence = TtlPrecedence::parse(
+                    &env::var("SCEDGE_UPSTREAM_TTL_PRECEDENCE")
+                        .unwrap_or_else(|_| "artifact".to_string()),
+                )?;
+                let client_tls = match (
+                    env::var("SCEDGE_UPSTREAM_CLIENT_CERT").ok(),
+                    env::var("SCEDGE_UPSTREAM_CLIENT_KEY").ok(),
+                ) {
+                    (Some(cert), Some(key)) => Some(ClientTlsConfig {
+                        cert_path: PathBuf::from(cert),
+                        key_path: PathBuf::from(key),
+                        ca_path: env::var("SCEDGE_UPSTREAM_CA_BUNDLE").ok().map(PathBuf::from),
+                        reload_interval: parse_duration("SCEDGE_UPSTREAM_TLS_RELOAD_SECS", 30)?,
+                    }),
+                    (None, None) => None,
+                    _ => anyhow::bail!(
+                        "SCEDGE_UPSTREAM_CLIENT_CERT and SCEDGE_UPSTREAM_CLIENT_KEY must be set together"
+                    ),
+                };
                 Some(UpstreamConfig {
                     base_url: url,
                     timeout,
+                    ttl_precedence,
+                    client_tls,
+                    outbound: outbound.clone(),
                 })
             }
             _ => None,
         };
 
+        let peers = match env::var("SCEDGE_PEERS") {
+            Ok(raw) if !raw.trim().is_empty() => {
+                let peers = raw
+                    .split(',')
+                    .map(|peer| peer.trim().to_string())
+                    .filter(|peer| !peer.is_empty())
+                    .collect();
+                let fanout = env::var("SCEDGE_PEER_FANOUT")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .context("SCEDGE_PEER_FANOUT must be a positive integer")?;
+                let timeout = parse_duration_ms("SCEDGE_PEER_TIMEOUT_MS", 50)?;
+                let hedge_delay = parse_duration_ms("SCEDGE_PEER_HEDGE_MS", 10)?;
+                Some(PeerConfig {
+                    peers,
+                    fanout,
+                    timeout,
+                    hedge_delay,
+                })
+            }
+            _ => None,
+        };
+
+        let key_filter_enabled = env::var("SCEDGE_BLOOM_FILTER_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let key_filter = if key_filter_enabled {
+            let capacity = env::var("SCEDGE_BLOOM_CAPACITY")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse()
+                .context("SCEDGE_BLOOM_CAPACITY must be a positive integer")?;
+            let false_positive_rate: f64 = env::var("SCEDGE_BLOOM_FP_RATE")
+                .unwrap_or_else(|_| "0.01".to_string())
+                .parse()
+                .context("SCEDGE_BLOOM_FP_RATE must be a number")?;
+            if !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+                anyhow::bail!("SCEDGE_BLOOM_FP_RATE must be between 0 and 1");
+            }
+            Some(KeyFilterConfig {
+                capacity,
+                false_positive_rate,
+                rebuild_interval: parse_duration("SCEDGE_BLOOM_REBUILD_SECS", 300)?,
+            })
+        } else {
+            None
+        };
+
+        let admission = AdmissionConfig {
+            sketch_width: parse_positive("SCEDGE_ADMISSION_SKETCH_WIDTH", 65536)?,
+            min_accesses: env::var("SCEDGE_ADMISSION_MIN_ACCESSES")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .ok()
+                .filter(|accesses| *accesses > 0)
+                .context("SCEDGE_ADMISSION_MIN_ACCESSES must be an integer from 1 to 255")?,
+        };
+
+        let stale_copies_capacity = parse_positive("SCEDGE_STALE_COPIES_CAPACITY", 10000)?;
+
+        let compression_min_bytes = match env::var("SCEDGE_COMPRESSION_MIN_BYTES") {
+            Ok(value) => Some(
+                value
+                    .parse::<usize>()
+                    .context("SCEDGE_COMPRESSION_MIN_BYTES must be a non-negative integer")?,
+            )
+            .filter(|min_bytes| *min_bytes > 0),
+            Err(_) => None,
+        };
+
+        let scan_max_keys: usize = env::var("SCEDGE_SCAN_MAX_KEYS")
+            .unwrap_or_else(|_| "100000".to_string())
+            .parse()
+            .ok()
+            .filter(|max_keys| *max_keys > 0)
+            .context("SCEDGE_SCAN_MAX_KEYS must be a positive integer")?;
+        let scan_limits = ScanLimits {
+            max_keys: scan_max_keys,
+            max_duration: parse_duration_ms("SCEDGE_SCAN_MAX_DURATION_MS", 5000)?,
+        };
+
+        let xfetch_beta: f64 = env::var("SCEDGE_XFETCH_BETA")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .context("SCEDGE_XFETCH_BETA must be a number")?;
+
+        let ttl_autotune_enabled = env::var("SCEDGE_TTL_AUTOTUNE_ENABLED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        let ttl_autotune = if ttl_autotune_enabled {
+            let min_ttl = parse_duration("SCEDGE_TTL_AUTOTUNE_MIN_SECS", 60)?;
+            let max_ttl = parse_duration("SCEDGE_TTL_AUTOTUNE_MAX_SECS", 604800)?;
+            if min_ttl > max_ttl {
+                anyhow::bail!(
+                    "SCEDGE_TTL_AUTOTUNE_MIN_SECS must not exceed SCEDGE_TTL_AUTOTUNE_MAX_SECS"
+                );
+            }
+            let target_hit_ratio: f64 = env::var("SCEDGE_TTL_AUTOTUNE_TARGET_HIT_RATIO")
+                .unwrap_or_else(|_| "0.8".to_string())
+                .parse()
+                .context("SCEDGE_TTL_AUTOTUNE_TARGET_HIT_RATIO must be a number")?;
+            if !(0.0..=1.0).contains(&target_hit_ratio) {
+                anyhow::bail!("SCEDGE_TTL_AUTOTUNE_TARGET_HIT_RATIO must be between 0 and 1");
+            }
+            let max_invalidation_ratio: f64 =
+                env::var("SCEDGE_TTL_AUTOTUNE_MAX_INVALIDATION_RATIO")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()
+                    .context("SCEDGE_TTL_AUTOTUNE_MAX_INVALIDATION_RATIO must be a number")?;
+            Some(TtlAutotuneConfig {
+                min_ttl,
+                max_ttl,
+                interval: parse_duration("SCEDGE_TTL_AUTOTUNE_INTERVAL_SECS", 300)?,
+                target_hit_ratio,
+                max_invalidation_ratio,
+            })
+        } else {
+            None
+        };
+
+        let redis_pools = RedisPoolsConfig {
+            read_url: env::var("SCEDGE_REDIS_READ_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .unwrap_or_else(|| redis_url.clone()),
+            scan_url: env::var("SCEDGE_REDIS_SCAN_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .unwrap_or_else(|| redis_url.clone()),
+            read_size: parse_positive("SCEDGE_REDIS_READ_POOL_SIZE", DEFAULT_REDIS_POOL_SIZE)?,
+            write_size: parse_positive("SCEDGE_REDIS_WRITE_POOL_SIZE", DEFAULT_REDIS_POOL_SIZE)?,
+            scan_size: parse_positive("SCEDGE_REDIS_SCAN_POOL_SIZE", DEFAULT_REDIS_POOL_SIZE)?,
+        };
+
+        let redis_entry_format = env::var("SCEDGE_REDIS_ENTRY_FORMAT")
+            .unwrap_or_else(|_| "json".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("SCEDGE_REDIS_ENTRY_FORMAT must be json or msgpack"))?;
+
+        let mirror = match env::var("SCEDGE_MIRROR_URL") {
+            Ok(target) if !target.trim().is_empty() => {
+                let percent: f64 = env::var("SCEDGE_MIRROR_PERCENT")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .parse()
+                    .ok()
+                    .filter(|percent| (0.0..=100.0).contains(percent))
+                    .context("SCEDGE_MIRROR_PERCENT must be a number from 0 to 100")?;
+                Some(MirrorConfig {
+                    target: target.trim().trim_end_matches('/').to_string(),
+                    percent,
+                    token: env::var("SCEDGE_MIRROR_TOKEN")
+                        .ok()
+                        .filter(|token| !token.trim().is_empty()),
+                    max_in_flight: parse_positive("SCEDGE_MIRROR_MAX_IN_FLIGHT", 64)?,
+                    timeout: parse_duration_ms("SCEDGE_MIRROR_TIMEOUT_MS", 1000)?,
+                })
+            }
+            _ => None,
+        };
+
+        let sync = match env::var("SCEDGE_SYNC_FROM") {
+            Ok(source) if !source.trim().is_empty() => Some(SyncConfig {
+                source: source.trim().trim_end_matches('/').to_string(),
+                token: env::var("SCEDGE_SYNC_TOKEN")
+                    .ok()
+                    .filter(|token| !token.trim().is_empty())
+                    .or_else(|| admin_token.clone()),
+                window: match env::var("SCEDGE_SYNC_WINDOW_SECS") {
+                    Ok(_) => Some(parse_duration("SCEDGE_SYNC_WINDOW_SECS", 0)?),
+                    Err(_) => None,
+                },
+            }),
+            _ => None,
+        };
+
+        let warmup = match env::var("SCEDGE_WARMUP_MANIFEST") {
+            Ok(manifest) if !manifest.trim().is_empty() => Some(WarmupConfig {
+                manifest: PathBuf::from(manifest.trim()),
+                concurrency: parse_positive("SCEDGE_WARMUP_CONCURRENCY", 8)?,
+                timeout: parse_duration("SCEDGE_WARMUP_TIMEOUT_SECS", 300)?,
+            }),
+            _ => None,
+        };
+
         Ok(Self {
-            listen_addr,
+            listeners,
             default_ttl,
+            clock_skew_tolerance,
+            cache_backend,
+            l1,
             redis_url,
             tenant_keys_path,
             jwt_secret,
+            admin_token,
             event_bus_enabled,
             event_bus_channel,
             event_bus_url,
+            event_bus_redis_channel,
+            event_ledger_retention,
+            event_lag,
+            wal,
+            purge_queue,
+            artifact_events_subject,
+            outbox,
+            policy_events_subject,
             metrics_enabled,
+            pushgateway,
+            node,
+            heartbeat,
+            commands,
+            ready_when_degraded,
+            verify_hashes,
+            debug_timings,
+            api_key_grace,
+            bulkhead_timeout,
+            max_batch_lookup_keys,
+            shutdown_drain_timeout,
+            expiry_sweep_interval,
+            policy_plugin,
+            policy_plugin_fuel,
+            policy_plugin_max_memory_bytes,
             upstream,
+            outbound,
+            peers,
+            key_filter,
+            admission,
+            stale_copies_capacity,
+            compression_min_bytes,
+            scan_limits,
+            xfetch_beta,
+            ttl_autotune,
+            sync,
+            warmup,
+            redis_pools,
+            redis_entry_format,
+            mirror,
         })
     }
 
-    pub fn listen_addr(&self) -> SocketAddr {
-        self.listen_addr
-    }
-
     pub fn default_ttl(&self) -> Duration {
         self.default_ttl
     }
 
     /// Load tenant configurations from file
     pub fn load_tenants(&self) -> Result<Vec<TenantConfig>> {
-        if let Some(path) = &self.tenant_keys_path {
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("Failed to read tenant keys file: {:?}", path))?;
-
-            let file: TenantsFile = serde_json::from_str(&content)
-                .with_context(|| format!("Failed to parse tenant keys file: {:?}", path))?;
-
-            Ok(file.tenants)
-        } else {
-            Ok(Vec::new())
+        match &self.tenant_keys_path {
+            Some(path) => read_tenants_file(path),
+            None => Ok(Vec::new()),
         }
     }
 }
 
+/// Parse the tenants file at `path`
+pub fn read_tenants_file(path: &Path) -> Result<Vec<TenantConfig>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read tenant keys file: {:?}", path))?;
+
+    let file: TenantsFile = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse tenant keys file: {:?}", path))?;
+
+    Ok(file.tenants)
+}
+
 fn parse_duration(env_key: &str, default_secs: u64) -> Result<Duration> {
     let raw = env::var(env_key).unwrap_or_else(|_| default_secs.to_string());
     let secs: u64 = raw
@@ -136,3 +1037,34 @@ fn parse_duration(env_key: &str, default_secs: u64) -> Result<Duration> {
 
     Ok(Duration::from_secs(secs))
 }
+
+fn parse_positive(env_key: &str, default: usize) -> Result<usize> {
+    env::var(env_key)
+        .unwrap_or_else(|_| default.to_string())
+        .parse()
+        .ok()
+        .filter(|value| *value > 0)
+        .with_context(|| format!("{env_key} must be a positive integer"))
+}
+
+fn parse_duration_ms(env_key: &str, default_ms: u64) -> Result<Duration> {
+    let raw = env::var(env_key).unwrap_or_else(|_| default_ms.to_string());
+    let millis: u64 = raw
+        .parse()
+        .with_context(|| format!("{env_key} must be an integer number of milliseconds"))?;
+
+    Ok(Duration::from_millis(millis))
+}
+
+/// Parse `key=value` pairs separated by commas
+fn parse_labels(raw: &str) -> Result<Vec<(String, String)>> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            pair.split_once('=')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .with_context(|| format!("invalid label `{pair}`, expected key=value"))
+        })
+        .collect()
+}
@@ -0,0 +1,186 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Operator console for Scedge Core.
+//!
+//! A single static page embedded in the binary for small edge sites without
+//! Grafana. The page itself holds no data; it asks for the admin token and
+//! reads everything through admin-authenticated endpoints:
+//!
+//! - `GET /console` - The console page (404 when the admin API is disabled)
+//! - `GET /admin/console/summary` - Node health, per-tenant hit ratios, and
+//!   recent invalidations
+//! - `GET /admin/keys?key=...` - Key inspector returning the stored record
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::Html;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::admin::require_admin;
+use crate::api::{readiness, AppState};
+use crate::error::AppError;
+use crate::events::EventEnvelope;
+use crate::keys::validate_key;
+use crate::model::{CachedArtifact, ReadinessResponse};
+
+/// Number of graph events kept for the console
+const RECENT_INVALIDATIONS: usize = 50;
+
+/// Graph event received by this node
+#[derive(Debug, Clone, Serialize)]
+pub struct InvalidationRecord {
+    pub received_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub envelope: EventEnvelope,
+}
+
+/// Bounded history of graph events, newest first
+#[derive(Clone, Default)]
+pub struct RecentInvalidations {
+    records: Arc<Mutex<VecDeque<InvalidationRecord>>>,
+}
+
+impl RecentInvalidations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, envelope: EventEnvelope) {
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.push_front(InvalidationRecord {
+            received_at: Utc::now(),
+            envelope,
+        });
+        records.truncate(RECENT_INVALIDATIONS);
+    }
+
+    pub fn snapshot(&self) -> Vec<InvalidationRecord> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.iter().cloned().collect()
+    }
+
+    /// Record every event published on the internal graph event channel
+    pub fn spawn_recorder(
+        &self,
+        mut receiver: broadcast::Receiver<EventEnvelope>,
+    ) -> tokio::task::JoinHandle<()> {
+        let recent = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(envelope) => recent.record(envelope),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TenantHitRatio {
+    pub tenant: String,
+    pub hits: u64,
+    pub misses: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_ratio: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsoleSummary {
+    pub version: &'static str,
+    pub health: ReadinessResponse,
+    pub tenants: Vec<TenantHitRatio>,
+    pub recent_invalidations: Vec<InvalidationRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InspectQuery {
+    pub key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyInspection {
+    pub record: CachedArtifact,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_remaining_seconds: Option<u64>,
+}
+
+/// Serve the embedded console page
+pub async fn handle_console(State(state): State<AppState>) -> Result<Html<&'static str>, AppError> {
+    if state.admin_token.is_none() {
+        return Err(AppError::not_found("console disabled"));
+    }
+
+    Ok(Html(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/static/console.html"
+    ))))
+}
+
+/// Node health, per-tenant hit ratios, and recent invalidations
+pub async fn handle_console_summary(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ConsoleSummary>, AppError> {
+    require_admin(&state, &headers)?;
+
+    let (_, Json(health)) = readiness(State(state.clone())).await;
+
+    let tenants = state
+        .metrics
+        .tenant_lookup_counts()
+        .into_iter()
+        .map(|(tenant, (hits, misses))| {
+            let total = hits + misses;
+            TenantHitRatio {
+                tenant,
+                hits,
+                misses,
+                hit_ratio: (total > 0).then(|| hits as f64 / total as f64),
+            }
+        })
+        .collect();
+
+    Ok(Json(ConsoleSummary {
+        version: env!("CARGO_PKG_VERSION"),
+        health,
+        tenants,
+        recent_invalidations: state.recent_invalidations.snapshot(),
+    }))
+}
+
+/// Return the stored record for a key, bypassing tenant checks
+pub async fn handle_key_inspect(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<InspectQuery>,
+) -> Result<Json<KeyInspection>, AppError> {
+    require_admin(&state, &headers)?;
+    validate_key(&query.key)?;
+
+    let record = state
+        .cache
+        .get(&query.key)
+        .await?
+        .ok_or_else(|| AppError::not_found("key not cached"))?;
+
+    tracing::info!(
+        target: "scedge::audit",
+        key = %query.key,
+        tenant = %record.artifact.policy.tenant,
+        "Key inspected"
+    );
+
+    Ok(Json(KeyInspection {
+        ttl_remaining_seconds: record.ttl_remaining_seconds(Utc::now()),
+        record,
+    }))
+}
@@ -0,0 +1,259 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-tenant encryption of artifact answers at rest.
+//!
+//! For tenants with encryption keys, the `answer` of every stored artifact is
+//! replaced by an envelope `{"$encrypted": "{key_id}:{base64(nonce || ciphertext)}"}`
+//! sealed with AES-256-GCM. The key id prefix keeps entries readable across
+//! rotations: every registered key version stays available for decryption,
+//! while new writes use the most recently registered one. After a rotation,
+//! [`Cache::reencrypt_tenant`](crate::cache::Cache::reencrypt_tenant) rewrites
+//! entries still sealed under older keys.
+//!
+//! Policy, provenance, and hashes stay in the clear so purges, invalidation,
+//! and presence checks work without decrypting.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// Field holding the sealed answer inside an encrypted envelope
+pub const ENVELOPE_FIELD: &str = "$encrypted";
+
+/// Length of an AES-GCM nonce in bytes
+const NONCE_LEN: usize = 12;
+
+/// One version of a tenant's encryption key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantKey {
+    pub key_id: String,
+    /// Base64-encoded 256-bit key
+    pub key: String,
+}
+
+impl TenantKey {
+    /// Decoded key, validating the id and length
+    fn material(&self) -> Result<Vec<u8>, AppError> {
+        if self.key_id.is_empty() || self.key_id.contains(':') {
+            return Err(AppError::bad_request(
+                "key_id must be non-empty and must not contain ':'",
+            ));
+        }
+        let material = BASE64
+            .decode(self.key.trim())
+            .map_err(|_| AppError::bad_request("key must be base64-encoded"))?;
+        if material.len() != 32 {
+            return Err(AppError::bad_request("key must be 32 bytes (AES-256)"));
+        }
+        Ok(material)
+    }
+}
+
+struct TenantKeys {
+    active: String,
+    ciphers: HashMap<String, KeyVersion>,
+}
+
+struct KeyVersion {
+    /// Raw key, compared when the version is registered again
+    material: Vec<u8>,
+    cipher: Aes256Gcm,
+}
+
+/// Encryption keys of every tenant, shared by the cache and admin API
+#[derive(Clone, Default)]
+pub struct Keyring {
+    tenants: Arc<RwLock<HashMap<String, TenantKeys>>>,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a key version for a tenant and make it the active one
+    ///
+    /// Key ids must not contain `:` and cannot be re-registered with
+    /// different material. Registering a known version with the same
+    /// material is a no-op, so reloads can install a tenant's keys again.
+    pub fn add_key(&self, tenant: &str, key: &TenantKey) -> Result<(), AppError> {
+        let material = key.material()?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&material));
+
+        let mut tenants = self.tenants.write().unwrap_or_else(|e| e.into_inner());
+        let keys = tenants
+            .entry(tenant.to_string())
+            .or_insert_with(|| TenantKeys {
+                active: key.key_id.clone(),
+                ciphers: HashMap::new(),
+            });
+
+        if let Some(existing) = keys.ciphers.get(&key.key_id) {
+            if existing.material == material {
+                return Ok(());
+            }
+            return Err(AppError::bad_request(format!(
+                "key {} is already registered",
+                key.key_id
+            )));
+        }
+
+        keys.ciphers
+            .insert(key.key_id.clone(), KeyVersion { material, cipher });
+        keys.active = key.key_id.clone();
+        Ok(())
+    }
+
+    /// Check that [`add_key`](Self::add_key) would accept `key`, without
+    /// registering it
+    pub fn check_key(&self, tenant: &str, key: &TenantKey) -> Result<(), AppError> {
+        let material = key.material()?;
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        match tenants
+            .get(tenant)
+            .and_then(|keys| keys.ciphers.get(&key.key_id))
+        {
+            Some(existing) if existing.material != material => Err(AppError::bad_request(format!(
+                "key {} is already registered",
+                key.key_id
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Id of the key sealing `answer` when it is not registered for `tenant`
+    pub fn missing_key(&self, tenant: &str, answer: &Value) -> Option<String> {
+        let (key_id, _) = envelope(answer)?.split_once(':')?;
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        let known = tenants
+            .get(tenant)
+            .is_some_and(|keys| keys.ciphers.contains_key(key_id));
+        (!known).then(|| key_id.to_string())
+    }
+
+    /// Id of the key new writes for `tenant` are sealed with
+    pub fn active_key_id(&self, tenant: &str) -> Option<String> {
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        tenants.get(tenant).map(|keys| keys.active.clone())
+    }
+
+    /// Seal an answer with the tenant's active key; tenants without keys pass through
+    pub fn encrypt(&self, tenant: &str, answer: Value) -> Result<Value, AppError> {
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        let Some(keys) = tenants.get(tenant) else {
+            return Ok(answer);
+        };
+        let cipher = &keys.ciphers[&keys.active].cipher;
+
+        let plaintext = serde_json::to_vec(&answer)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to encode answer: {}", e)))?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Encryption failed: {}", e)))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+
+        Ok(serde_json::json!({
+            ENVELOPE_FIELD: format!("{}:{}", keys.active, BASE64.encode(sealed))
+        }))
+    }
+
+    /// Open an encrypted envelope; plain answers pass through
+    pub fn decrypt(&self, tenant: &str, answer: Value) -> Result<Value, AppError> {
+        let Some(sealed) = envelope(&answer) else {
+            return Ok(answer);
+        };
+        let (key_id, payload) = sealed
+            .split_once(':')
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Malformed encrypted envelope")))?;
+
+        let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+        let cipher = tenants
+            .get(tenant)
+            .and_then(|keys| keys.ciphers.get(key_id))
+            .map(|version| &version.cipher)
+            .ok_or_else(|| {
+                AppError::Internal(anyhow::anyhow!(
+                    "Unknown encryption key {} for tenant {}",
+                    key_id,
+                    tenant
+                ))
+            })?;
+
+        let sealed = BASE64
+            .decode(payload)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Malformed envelope: {}", e)))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Malformed encrypted envelope"
+            )));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Decryption failed: {}", e)))?;
+
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to decode answer: {}", e)))
+    }
+
+    /// Whether an answer is sealed under a key other than the tenant's active one
+    pub fn needs_reencryption(&self, tenant: &str, answer: &Value) -> bool {
+        let Some(sealed) = envelope(answer) else {
+            return self.active_key_id(tenant).is_some();
+        };
+        let key_id = sealed.split_once(':').map(|(id, _)| id);
+        key_id != self.active_key_id(tenant).as_deref()
+    }
+}
+
+/// Sealed payload of an encrypted envelope
+fn envelope(answer: &Value) -> Option<&str> {
+    let object = answer.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    object.get(ENVELOPE_FIELD)?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key_id: &str, byte: u8) -> TenantKey {
+        TenantKey {
+            key_id: key_id.to_string(),
+            key: BASE64.encode([byte; 32]),
+        }
+    }
+
+    #[test]
+    fn registering_the_same_key_again_is_a_no_op() {
+        let keyring = Keyring::new();
+        keyring.add_key("acme", &key("2025-01", 1)).unwrap();
+        keyring.add_key("acme", &key("2025-02", 2)).unwrap();
+
+        keyring.add_key("acme", &key("2025-01", 1)).unwrap();
+        assert_eq!(keyring.active_key_id("acme").as_deref(), Some("2025-02"));
+    }
+
+    #[test]
+    fn a_key_id_cannot_change_material() {
+        let keyring = Keyring::new();
+        keyring.add_key("acme", &key("2025-01", 1)).unwrap();
+
+        assert!(keyring.add_key("acme", &key("2025-01", 9)).is_err());
+    }
+}
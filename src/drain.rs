@@ -0,0 +1,138 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounded draining of in-flight requests on shutdown.
+//!
+//! Every request is counted in `scedge_in_flight_requests` while it runs. On
+//! SIGTERM the node stops accepting connections, reports `not_ready`, and sets
+//! `scedge_draining`; requests already running get `SCEDGE_SHUTDOWN_DRAIN_SECS`
+//! to finish. Once that deadline passes, requests still running and any that
+//! arrive on open connections are answered with `503 Service Unavailable`, so a
+//! slow upstream cannot hold a deploy hostage.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use tokio::sync::watch;
+
+use crate::api::AppState;
+use crate::error::AppError;
+use crate::metrics::Metrics;
+
+/// How often drain progress is logged
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Serving,
+    Draining,
+    Expired,
+}
+
+/// Shutdown state shared by the request middleware and the server
+#[derive(Clone)]
+pub struct Drain {
+    metrics: Metrics,
+    phase: Arc<watch::Sender<Phase>>,
+}
+
+/// Counts a request as in flight until dropped
+struct InFlight {
+    metrics: Metrics,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.metrics.in_flight_requests.dec();
+    }
+}
+
+impl Drain {
+    pub fn new(metrics: Metrics) -> Self {
+        Self {
+            metrics,
+            phase: Arc::new(watch::Sender::new(Phase::Serving)),
+        }
+    }
+
+    /// Whether shutdown has begun
+    pub fn is_draining(&self) -> bool {
+        *self.phase.borrow() != Phase::Serving
+    }
+
+    /// Requests currently being served
+    pub fn in_flight(&self) -> i64 {
+        self.metrics.in_flight_requests.get()
+    }
+
+    /// Start draining, cutting off requests still running after `timeout`
+    pub fn begin(&self, timeout: Duration) {
+        if self.is_draining() {
+            return;
+        }
+        self.phase.send_replace(Phase::Draining);
+        self.metrics.draining.set(1);
+        tracing::info!(
+            in_flight = self.in_flight(),
+            timeout_secs = timeout.as_secs(),
+            "Draining in-flight requests"
+        );
+
+        let drain = self.clone();
+        tokio::spawn(async move {
+            let deadline = tokio::time::Instant::now() + timeout;
+            let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+            progress.tick().await;
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(deadline) => break,
+                    _ = progress.tick() => {
+                        tracing::info!(in_flight = drain.in_flight(), "Drain in progress");
+                    }
+                }
+            }
+
+            drain.phase.send_replace(Phase::Expired);
+            let in_flight = drain.in_flight();
+            if in_flight > 0 {
+                tracing::warn!(in_flight, "Drain deadline passed, cutting off requests");
+            }
+        });
+    }
+
+    /// Resolves once the drain deadline has passed
+    async fn expired(&self) {
+        let mut phase = self.phase.subscribe();
+        // The sender lives in `self`, so waiting cannot fail
+        let _ = phase.wait_for(|phase| *phase == Phase::Expired).await;
+    }
+}
+
+/// Middleware counting in-flight requests and cutting them off after the
+/// drain deadline
+pub async fn drain_requests(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let drain = &state.drain;
+    if *drain.phase.borrow() == Phase::Expired {
+        return shutting_down();
+    }
+
+    drain.metrics.in_flight_requests.inc();
+    let _in_flight = InFlight {
+        metrics: drain.metrics.clone(),
+    };
+    tokio::select! {
+        response = next.run(request) => response,
+        _ = drain.expired() => shutting_down(),
+    }
+}
+
+fn shutting_down() -> Response {
+    AppError::unavailable("node is shutting down").into_response()
+}
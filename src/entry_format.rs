@@ -0,0 +1,1181 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Storage formats of cached entries in Redis.
+//!
+//! Entries were always JSON strings. With `SCEDGE_REDIS_ENTRY_FORMAT=msgpack`
+//! they are written as MessagePack instead, behind a fixed header:
+//!
+//! ```text
+//! [MSGPACK_VERSION] [expiry: EXPIRY_LEN bytes] [MessagePack map: key, artifact, stored_at]
+//! ```
+//!
+//! JSON entries start with `{`, so both formats are read regardless of the
+//! setting, and existing entries keep working while the cache turns over.
+//!
+//! Answers that [`Dictionaries`] compresses are written in a third format,
+//! whatever the setting, with the zstd frame after the rest of the entry:
+//!
+//! ```text
+//! [COMPRESSED_VERSION] [expiry] [dictionary: u32] [body length: u32] [MessagePack body] [frame]
+//! ```
+//!
+//! The body has a null answer, so scripts read its fields as in the
+//! MessagePack format.
+//!
+//! Server-side scripts decode both formats (Redis bundles `cmsgpack`). The
+//! expiry lives in the header rather than the body, so touches overwrite it at
+//! a fixed offset without decoding the entry; see [`encode_expiry`]. The body
+//! is written by a serde serializer that follows `serde_json`'s data model, so
+//! scripts see the same fields in either format.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use serde::Deserialize;
+
+use crate::compression::{Dictionaries, PLAIN_VERSION};
+use crate::error::AppError;
+use crate::model::{ArtifactPayload, CachedArtifact};
+
+/// First byte of MessagePack entries
+pub const MSGPACK_VERSION: u8 = 1;
+
+/// First byte of entries with a compressed answer
+pub const COMPRESSED_VERSION: u8 = 2;
+
+/// Length of the expiry header: a presence flag, seconds and nanoseconds
+pub const EXPIRY_LEN: usize = 13;
+
+/// Nesting limit when decoding, so a corrupt entry cannot exhaust the stack
+const MAX_DEPTH: usize = 128;
+
+/// Format new entries are written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EntryFormat {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl FromStr for EntryFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "json" => Ok(EntryFormat::Json),
+            "msgpack" => Ok(EntryFormat::MessagePack),
+            other => Err(format!("unknown entry format {}", other)),
+        }
+    }
+}
+
+/// Fields of a MessagePack entry other than the expiry
+#[derive(serde::Serialize)]
+struct Body<'a> {
+    key: &'a str,
+    artifact: &'a ArtifactPayload,
+    stored_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct OwnedBody {
+    key: String,
+    artifact: ArtifactPayload,
+    stored_at: DateTime<Utc>,
+}
+
+/// Serialize an entry in `format`, or compressed when `dictionaries` compress
+/// its answer
+///
+/// The answer is moved out of `cached` while the rest of a compressed entry is
+/// serialized, and put back before returning.
+pub fn encode(
+    cached: &mut CachedArtifact,
+    format: EntryFormat,
+    dictionaries: &Dictionaries,
+) -> Result<Vec<u8>, AppError> {
+    if let Some(entry) = encode_compressed(cached, dictionaries)? {
+        return Ok(entry);
+    }
+
+    if format == EntryFormat::Json {
+        return serde_json::to_vec(cached).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to serialize artifact: {}", e))
+        });
+    }
+
+    let mut out = vec![MSGPACK_VERSION];
+    out.extend(encode_expiry(cached.expires_at));
+    out.extend(encode_body(cached)?);
+    Ok(out)
+}
+
+fn encode_compressed(
+    cached: &mut CachedArtifact,
+    dictionaries: &Dictionaries,
+) -> Result<Option<Vec<u8>>, AppError> {
+    let tenant = &cached.artifact.policy.tenant;
+    if !dictionaries.compresses(tenant) {
+        return Ok(None);
+    }
+    let plain = serde_json::to_vec(&cached.artifact.answer)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to encode answer: {}", e)))?;
+    let Some((version, frame)) = dictionaries.compress(tenant, &plain)? else {
+        return Ok(None);
+    };
+
+    let answer = std::mem::take(&mut cached.artifact.answer);
+    let body = encode_body(cached);
+    cached.artifact.answer = answer;
+    let body = body?;
+
+    let mut out = Vec::with_capacity(1 + EXPIRY_LEN + 8 + body.len() + frame.len());
+    out.push(COMPRESSED_VERSION);
+    out.extend(encode_expiry(cached.expires_at));
+    out.extend(version.to_be_bytes());
+    out.extend((body.len() as u32).to_be_bytes());
+    out.extend(body);
+    out.extend(frame);
+    Ok(Some(out))
+}
+
+/// MessagePack map of an entry's fields other than the expiry
+fn encode_body(cached: &CachedArtifact) -> Result<Vec<u8>, AppError> {
+    let mut encoder = Encoder { out: Vec::new() };
+    Body {
+        key: &cached.key,
+        artifact: &cached.artifact,
+        stored_at: cached.stored_at,
+    }
+    .serialize(&mut encoder)
+    .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize artifact: {}", e)))?;
+    Ok(encoder.out)
+}
+
+fn malformed(e: String) -> AppError {
+    AppError::Internal(anyhow::anyhow!("Failed to deserialize artifact: {}", e))
+}
+
+/// Deserialize an entry written in any format
+///
+/// Fails for compressed entries whose dictionary is not registered; see
+/// [`missing_dictionary`].
+pub fn decode(raw: &[u8], dictionaries: &Dictionaries) -> Result<CachedArtifact, AppError> {
+    match raw.split_first() {
+        Some((&MSGPACK_VERSION, rest)) => {
+            if rest.len() < EXPIRY_LEN {
+                return Err(malformed("truncated MessagePack entry".to_string()));
+            }
+            let (expiry, body) = rest.split_at(EXPIRY_LEN);
+            decode_body(expiry, body)
+        }
+        Some((&COMPRESSED_VERSION, rest)) => {
+            let compressed = split_compressed(rest)?;
+            let mut cached = decode_body(compressed.expiry, compressed.body)?;
+            let plain = dictionaries.decompress(
+                &cached.artifact.policy.tenant,
+                compressed.version,
+                compressed.frame,
+            )?;
+            cached.artifact.answer = serde_json::from_slice(&plain)
+                .map_err(|e| malformed(format!("compressed answer: {}", e)))?;
+            Ok(cached)
+        }
+        _ => serde_json::from_slice(raw).map_err(|e| malformed(e.to_string())),
+    }
+}
+
+/// Tenant and version of the dictionary a compressed entry needs when it is
+/// not registered in `dictionaries`
+pub fn missing_dictionary(raw: &[u8], dictionaries: &Dictionaries) -> Option<(String, u32)> {
+    let (&COMPRESSED_VERSION, rest) = raw.split_first()? else {
+        return None;
+    };
+    let compressed = split_compressed(rest).ok()?;
+    if compressed.version == PLAIN_VERSION {
+        return None;
+    }
+    let tenant = decode_body(compressed.expiry, compressed.body)
+        .ok()?
+        .artifact
+        .policy
+        .tenant;
+    (!dictionaries.has_version(&tenant, compressed.version)).then_some((tenant, compressed.version))
+}
+
+/// Parts of a compressed entry after its first byte
+struct Compressed<'a> {
+    expiry: &'a [u8],
+    version: u32,
+    body: &'a [u8],
+    frame: &'a [u8],
+}
+
+fn split_compressed(rest: &[u8]) -> Result<Compressed<'_>, AppError> {
+    let truncated = || malformed("truncated compressed entry".to_string());
+    if rest.len() < EXPIRY_LEN + 8 {
+        return Err(truncated());
+    }
+    let (expiry, rest) = rest.split_at(EXPIRY_LEN);
+    let (version, rest) = rest.split_at(4);
+    let (body_len, rest) = rest.split_at(4);
+    let version = u32::from_be_bytes(version.try_into().map_err(|_| truncated())?);
+    let body_len = u32::from_be_bytes(body_len.try_into().map_err(|_| truncated())?) as usize;
+    if rest.len() < body_len {
+        return Err(truncated());
+    }
+    let (body, frame) = rest.split_at(body_len);
+    Ok(Compressed {
+        expiry,
+        version,
+        body,
+        frame,
+    })
+}
+
+fn decode_body(expiry: &[u8], body: &[u8]) -> Result<CachedArtifact, AppError> {
+    let expires_at = decode_expiry(expiry)?;
+    let mut decoder = Decoder {
+        input: body,
+        depth: 0,
+    };
+    let body = OwnedBody::deserialize(&mut decoder).map_err(|e| malformed(e.0))?;
+    if !decoder.input.is_empty() {
+        return Err(malformed("trailing bytes in MessagePack entry".to_string()));
+    }
+    Ok(CachedArtifact {
+        key: body.key,
+        artifact: body.artifact,
+        stored_at: body.stored_at,
+        expires_at,
+    })
+}
+
+/// Expiry header of MessagePack entries, as overwritten by touches
+///
+/// A presence flag, then the Unix timestamp as big-endian `i64` seconds and
+/// `u32` nanoseconds.
+pub fn encode_expiry(expires_at: Option<DateTime<Utc>>) -> [u8; EXPIRY_LEN] {
+    let mut header = [0; EXPIRY_LEN];
+    if let Some(expires_at) = expires_at {
+        header[0] = 1;
+        header[1..9].copy_from_slice(&expires_at.timestamp().to_be_bytes());
+        header[9..].copy_from_slice(&expires_at.timestamp_subsec_nanos().to_be_bytes());
+    }
+    header
+}
+
+/// Read an expiry header written by [`encode_expiry`]
+pub fn decode_expiry(header: &[u8]) -> Result<Option<DateTime<Utc>>, AppError> {
+    let invalid = || AppError::Internal(anyhow::anyhow!("Invalid expiry header in entry"));
+    let header: &[u8; EXPIRY_LEN] = header.try_into().map_err(|_| invalid())?;
+    if header[0] == 0 {
+        return Ok(None);
+    }
+    let secs = i64::from_be_bytes(header[1..9].try_into().map_err(|_| invalid())?);
+    let nanos = u32::from_be_bytes(header[9..].try_into().map_err(|_| invalid())?);
+    DateTime::from_timestamp(secs, nanos)
+        .map(Some)
+        .ok_or_else(invalid)
+}
+
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// MessagePack serializer following `serde_json`'s representation of enums
+struct Encoder {
+    out: Vec<u8>,
+}
+
+impl Encoder {
+    fn write_len(&mut self, len: usize, fix: u8, fix_max: usize, markers: [u8; 3]) {
+        if len <= fix_max {
+            self.out.push(fix | len as u8);
+        } else if len <= 0xff && markers[0] != 0 {
+            self.out.extend_from_slice(&[markers[0], len as u8]);
+        } else if len <= 0xffff {
+            self.out.push(markers[1]);
+            self.out.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            self.out.push(markers[2]);
+            self.out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+
+    fn write_array_len(&mut self, len: usize) {
+        self.write_len(len, 0x90, 15, [0, 0xdc, 0xdd]);
+    }
+
+    fn write_map_len(&mut self, len: usize) {
+        self.write_len(len, 0x80, 15, [0, 0xde, 0xdf]);
+    }
+
+    fn write_str(&mut self, text: &str) {
+        self.write_len(text.len(), 0xa0, 31, [0xd9, 0xda, 0xdb]);
+        self.out.extend_from_slice(text.as_bytes());
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        match n {
+            0..=0x7f => self.out.push(n as u8),
+            0x80..=0xff => self.out.extend_from_slice(&[0xcc, n as u8]),
+            0x100..=0xffff => {
+                self.out.push(0xcd);
+                self.out.extend_from_slice(&(n as u16).to_be_bytes());
+            }
+            0x1_0000..=0xffff_ffff => {
+                self.out.push(0xce);
+                self.out.extend_from_slice(&(n as u32).to_be_bytes());
+            }
+            _ => {
+                self.out.push(0xcf);
+                self.out.extend_from_slice(&n.to_be_bytes());
+            }
+        }
+    }
+
+    fn write_i64(&mut self, n: i64) {
+        match n {
+            0.. => self.write_u64(n as u64),
+            -32..=-1 => self.out.push(n as i8 as u8),
+            -0x80..=-33 => self.out.extend_from_slice(&[0xd0, n as i8 as u8]),
+            -0x8000..=-0x81 => {
+                self.out.push(0xd1);
+                self.out.extend_from_slice(&(n as i16).to_be_bytes());
+            }
+            -0x8000_0000..=-0x8001 => {
+                self.out.push(0xd2);
+                self.out.extend_from_slice(&(n as i32).to_be_bytes());
+            }
+            _ => {
+                self.out.push(0xd3);
+                self.out.extend_from_slice(&n.to_be_bytes());
+            }
+        }
+    }
+
+    /// Start an array or map, deferring the header when the length is unknown
+    fn compound(&mut self, len: Option<usize>, map: bool) -> Compound<'_> {
+        match len {
+            Some(len) if map => self.write_map_len(len),
+            Some(len) => self.write_array_len(len),
+            None => {}
+        }
+        Compound {
+            pending: len.is_none().then_some((self.out.len(), 0)),
+            map,
+            encoder: self,
+        }
+    }
+
+    /// Start `{variant: ...}`, as `serde_json` writes non-unit variants
+    fn variant(&mut self, variant: &str) {
+        self.write_map_len(1);
+        self.write_str(variant);
+    }
+}
+
+/// An array or map being serialized
+///
+/// `pending` holds the start offset and element count of a collection whose
+/// length was not known up front; its header is inserted when it ends.
+struct Compound<'a> {
+    encoder: &'a mut Encoder,
+    pending: Option<(usize, usize)>,
+    map: bool,
+}
+
+impl Compound<'_> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        if let Some((_, count)) = &mut self.pending {
+            *count += 1;
+        }
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        let Some((start, count)) = self.pending else {
+            return Ok(());
+        };
+        let mut header = Encoder { out: Vec::new() };
+        if self.map {
+            header.write_map_len(count);
+        } else {
+            header.write_array_len(count);
+        }
+        self.encoder.out.splice(start..start, header.out);
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Encoder {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.out.push(if v { 0xc3 } else { 0xc2 });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.write_i64(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.write_u64(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.out.push(0xca);
+        self.out.extend_from_slice(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.out.push(0xcb);
+        self.out.extend_from_slice(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.write_str(v.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.write_str(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        let len = v.len();
+        if len <= 0xff {
+            self.out.extend_from_slice(&[0xc4, len as u8]);
+        } else if len <= 0xffff {
+            self.out.push(0xc5);
+            self.out.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            self.out.push(0xc6);
+            self.out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        self.out.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.out.push(0xc0);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.variant(variant);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a>, Error> {
+        Ok(self.compound(len, false))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>, Error> {
+        Ok(self.compound(Some(len), false))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        Ok(self.compound(Some(len), false))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        self.variant(variant);
+        Ok(self.compound(Some(len), false))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a>, Error> {
+        Ok(self.compound(len, true))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Compound<'a>, Error> {
+        Ok(self.compound(Some(len), true))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        self.variant(variant);
+        Ok(self.compound(Some(len), true))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.element(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.element(key)?;
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.element(key)?;
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+/// Self-describing MessagePack deserializer with a nesting limit
+struct Decoder<'de> {
+    input: &'de [u8],
+    depth: usize,
+}
+
+impl<'de> Decoder<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        if self.input.len() < len {
+            return Err(Error("truncated MessagePack entry".to_string()));
+        }
+        let (head, tail) = self.input.split_at(len);
+        self.input = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    fn read_len(&mut self, width: usize) -> Result<usize, Error> {
+        Ok(match width {
+            1 => self.take_array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.take_array()?) as usize,
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn read_str(&mut self, len: usize) -> Result<&'de str, Error> {
+        std::str::from_utf8(self.take(len)?).map_err(|e| Error(e.to_string()))
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.input
+            .first()
+            .copied()
+            .ok_or_else(|| Error("truncated MessagePack entry".to_string()))
+    }
+
+    /// Enter an array or map, failing past [`MAX_DEPTH`]
+    fn nested<V, F>(&mut self, visit: F) -> Result<V, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<V, Error>,
+    {
+        if self.depth == MAX_DEPTH {
+            return Err(Error("MessagePack entry nested too deeply".to_string()));
+        }
+        self.depth += 1;
+        let visited = visit(self);
+        self.depth -= 1;
+        visited
+    }
+
+    fn visit_array<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.nested(|decoder| {
+            let mut access = Elements {
+                decoder,
+                remaining: len,
+            };
+            let value = visitor.visit_seq(&mut access)?;
+            match access.remaining {
+                0 => Ok(value),
+                _ => Err(de::Error::invalid_length(len, &"fewer elements in array")),
+            }
+        })
+    }
+
+    fn visit_map<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.nested(|decoder| {
+            let mut access = Elements {
+                decoder,
+                remaining: len,
+            };
+            let value = visitor.visit_map(&mut access)?;
+            match access.remaining {
+                0 => Ok(value),
+                _ => Err(de::Error::invalid_length(len, &"fewer entries in map")),
+            }
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Decoder<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let marker = self.take_array::<1>()?[0];
+        match marker {
+            0x00..=0x7f => visitor.visit_u64(marker as u64),
+            0x80..=0x8f => self.visit_map((marker & 0x0f) as usize, visitor),
+            0x90..=0x9f => self.visit_array((marker & 0x0f) as usize, visitor),
+            0xa0..=0xbf => visitor.visit_borrowed_str(self.read_str((marker & 0x1f) as usize)?),
+            0xc0 => visitor.visit_unit(),
+            0xc2 => visitor.visit_bool(false),
+            0xc3 => visitor.visit_bool(true),
+            0xc4..=0xc6 => {
+                let len = self.read_len(1 << (marker - 0xc4))?;
+                visitor.visit_borrowed_bytes(self.take(len)?)
+            }
+            0xca => visitor.visit_f32(f32::from_be_bytes(self.take_array()?)),
+            0xcb => visitor.visit_f64(f64::from_be_bytes(self.take_array()?)),
+            0xcc => visitor.visit_u64(self.take_array::<1>()?[0] as u64),
+            0xcd => visitor.visit_u64(u16::from_be_bytes(self.take_array()?) as u64),
+            0xce => visitor.visit_u64(u32::from_be_bytes(self.take_array()?) as u64),
+            0xcf => visitor.visit_u64(u64::from_be_bytes(self.take_array()?)),
+            0xd0 => visitor.visit_i64(self.take_array::<1>()?[0] as i8 as i64),
+            0xd1 => visitor.visit_i64(i16::from_be_bytes(self.take_array()?) as i64),
+            0xd2 => visitor.visit_i64(i32::from_be_bytes(self.take_array()?) as i64),
+            0xd3 => visitor.visit_i64(i64::from_be_bytes(self.take_array()?)),
+            0xd9..=0xdb => {
+                let len = self.read_len(1 << (marker - 0xd9))?;
+                visitor.visit_borrowed_str(self.read_str(len)?)
+            }
+            0xdc | 0xdd => {
+                let len = self.read_len(if marker == 0xdc { 2 } else { 4 })?;
+                self.visit_array(len, visitor)
+            }
+            0xde | 0xdf => {
+                let len = self.read_len(if marker == 0xde { 2 } else { 4 })?;
+                self.visit_map(len, visitor)
+            }
+            0xe0..=0xff => visitor.visit_i64(marker as i8 as i64),
+            other => Err(Error(format!(
+                "unsupported MessagePack marker {:#04x}",
+                other
+            ))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.peek()? == 0xc0 {
+            self.input = &self.input[1..];
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.peek()? {
+            // `{variant: value}`
+            0x81 => {
+                self.input = &self.input[1..];
+                self.nested(|decoder| visitor.visit_enum(decoder))
+            }
+            _ => {
+                let variant: &'de str = Deserialize::deserialize(&mut *self)?;
+                visitor.visit_enum(variant.into_deserializer())
+            }
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Decoder<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant = seed.deserialize(&mut *self)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Decoder<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
+
+/// Elements of an array, or entries of a map, still to be read
+struct Elements<'a, 'de> {
+    decoder: &'a mut Decoder<'de>,
+    remaining: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.decoder)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Timelike};
+    use serde_json::json;
+
+    use super::*;
+
+    fn entry(expires_at: Option<DateTime<Utc>>) -> CachedArtifact {
+        let artifact = ArtifactPayload::builder()
+            .answer(json!({
+                "text": "hello",
+                "scores": [1, -2, 300, -70000, 1.5, 5_000_000_000u64],
+                "empty": [],
+                "nested": { "flag": true, "none": null, "map": {} },
+            }))
+            .tenant("acme")
+            .depends_on("acme:answers:source")
+            .build()
+            .expect("artifact is valid");
+        CachedArtifact {
+            key: "acme:answers:greeting".to_string(),
+            artifact,
+            stored_at: Utc
+                .with_ymd_and_hms(2025, 1, 1, 0, 0, 0)
+                .unwrap()
+                .with_nanosecond(123_456_789)
+                .unwrap(),
+            expires_at,
+        }
+    }
+
+    fn assert_round_trip(format: EntryFormat, expires_at: Option<DateTime<Utc>>) {
+        let dictionaries = Dictionaries::new();
+        let mut cached = entry(expires_at);
+        let encoded = encode(&mut cached, format, &dictionaries).unwrap();
+        let decoded = decode(&encoded, &dictionaries).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&cached).unwrap()
+        );
+        assert_eq!(decoded.stored_at, cached.stored_at);
+        assert_eq!(decoded.expires_at, cached.expires_at);
+    }
+
+    #[test]
+    fn json_entries_round_trip() {
+        assert_round_trip(EntryFormat::Json, None);
+        assert_round_trip(
+            EntryFormat::Json,
+            Some(Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap()),
+        );
+    }
+
+    #[test]
+    fn msgpack_entries_round_trip() {
+        assert_round_trip(EntryFormat::MessagePack, None);
+        assert_round_trip(
+            EntryFormat::MessagePack,
+            Some(Utc.timestamp_opt(1_735_776_000, 987_654_321).unwrap()),
+        );
+    }
+
+    #[test]
+    fn msgpack_entries_start_with_the_expiry_header() {
+        let expires_at = Utc.timestamp_opt(1_735_776_000, 5).unwrap();
+        let encoded = encode(
+            &mut entry(Some(expires_at)),
+            EntryFormat::MessagePack,
+            &Dictionaries::new(),
+        )
+        .unwrap();
+
+        assert_eq!(encoded[0], MSGPACK_VERSION);
+        assert_eq!(
+            &encoded[1..1 + EXPIRY_LEN],
+            &encode_expiry(Some(expires_at))
+        );
+    }
+
+    #[test]
+    fn expiry_headers_round_trip() {
+        let expires_at = Utc.timestamp_opt(-86_400, 999_999_999).unwrap();
+        assert_eq!(
+            decode_expiry(&encode_expiry(Some(expires_at))).unwrap(),
+            Some(expires_at)
+        );
+        assert_eq!(decode_expiry(&encode_expiry(None)).unwrap(), None);
+        assert!(decode_expiry(&[1; 4]).is_err());
+    }
+
+    #[test]
+    fn overwriting_the_expiry_header_moves_the_expiry() {
+        // What the touch script does, without decoding the body
+        let mut cached = entry(None);
+        let mut encoded =
+            encode(&mut cached, EntryFormat::MessagePack, &Dictionaries::new()).unwrap();
+        let expires_at = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        encoded[1..1 + EXPIRY_LEN].copy_from_slice(&encode_expiry(Some(expires_at)));
+
+        let touched = decode(&encoded, &Dictionaries::new()).unwrap();
+        assert_eq!(touched.expires_at, Some(expires_at));
+        assert_eq!(touched.stored_at, cached.stored_at);
+        assert_eq!(touched.artifact.answer, cached.artifact.answer);
+    }
+
+    #[test]
+    fn deeply_nested_entries_are_rejected() {
+        let mut encoded = vec![MSGPACK_VERSION];
+        encoded.extend(encode_expiry(None));
+        encoded.extend([0x83, 0xa3]);
+        encoded.extend(b"key");
+        encoded.extend([0xa1, b'k', 0xa8]);
+        encoded.extend(b"artifact");
+        encoded.extend([0x81, 0xa6]);
+        encoded.extend(b"answer");
+        encoded.extend(std::iter::repeat_n(0x91, 100_000));
+        encoded.push(0xc0);
+
+        let err = decode(&encoded, &Dictionaries::new()).unwrap_err();
+        assert!(
+            format!("{:?}", err).contains("nested too deeply"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn truncated_entries_are_rejected() {
+        let dictionaries = Dictionaries::new();
+        let encoded = encode(&mut entry(None), EntryFormat::MessagePack, &dictionaries).unwrap();
+        assert!(decode(&encoded[..encoded.len() - 1], &dictionaries).is_err());
+        assert!(decode(&encoded[..5], &dictionaries).is_err());
+    }
+
+    #[test]
+    fn large_answers_are_compressed_behind_their_own_format() {
+        let dictionaries = Dictionaries::new().with_min_plain_bytes(16);
+        let mut cached = entry(Some(Utc.timestamp_opt(1_735_776_000, 0).unwrap()));
+        let encoded = encode(&mut cached, EntryFormat::Json, &dictionaries).unwrap();
+
+        assert_eq!(encoded[0], COMPRESSED_VERSION);
+        assert_eq!(
+            &encoded[1..1 + EXPIRY_LEN],
+            &encode_expiry(cached.expires_at)
+        );
+        assert_eq!(cached.artifact.answer["text"], "hello");
+        let decoded = decode(&encoded, &dictionaries).unwrap();
+        assert_eq!(decoded.artifact.answer, cached.artifact.answer);
+        assert_eq!(decoded.expires_at, cached.expires_at);
+
+        // Plain zstd needs no dictionary to decode
+        assert_eq!(
+            decode(&encoded, &Dictionaries::new())
+                .unwrap()
+                .artifact
+                .answer,
+            cached.artifact.answer
+        );
+    }
+
+    #[test]
+    fn small_answers_are_not_compressed() {
+        let dictionaries = Dictionaries::new().with_min_plain_bytes(1 << 20);
+        let encoded = encode(&mut entry(None), EntryFormat::MessagePack, &dictionaries).unwrap();
+        assert_eq!(encoded[0], MSGPACK_VERSION);
+    }
+
+    #[test]
+    fn answers_shaped_like_the_old_envelope_round_trip() {
+        let dictionaries = Dictionaries::new().with_min_plain_bytes(1);
+        let mut cached = entry(None);
+        cached.artifact.answer = json!({ "$zstd": "z:not-base64" });
+        let encoded = encode(&mut cached, EntryFormat::Json, &dictionaries).unwrap();
+
+        let decoded = decode(&encoded, &dictionaries).unwrap();
+        assert_eq!(decoded.artifact.answer, json!({ "$zstd": "z:not-base64" }));
+    }
+
+    #[test]
+    fn entries_compressed_with_a_dictionary_need_it_to_decode() {
+        let samples: Vec<Vec<u8>> = (0..200)
+            .map(|i| format!(r#"{{"text":"answer number {}","source":"kb"}}"#, i).into_bytes())
+            .collect();
+        let dictionary = crate::compression::train(&samples, 1024).unwrap();
+        let writer = Dictionaries::new();
+        writer.add("acme", 3, dictionary.clone());
+
+        let mut cached = entry(None);
+        let encoded = encode(&mut cached, EntryFormat::MessagePack, &writer).unwrap();
+        assert_eq!(encoded[0], COMPRESSED_VERSION);
+
+        let reader = Dictionaries::new();
+        assert!(decode(&encoded, &reader).is_err());
+        assert_eq!(
+            missing_dictionary(&encoded, &reader),
+            Some(("acme".to_string(), 3))
+        );
+
+        reader.add("acme", 3, dictionary);
+        assert_eq!(missing_dictionary(&encoded, &reader), None);
+        assert_eq!(
+            decode(&encoded, &reader).unwrap().artifact.answer,
+            cached.artifact.answer
+        );
+    }
+}
@@ -9,12 +9,32 @@ use axum::Json;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::policy::{PolicyDenial, PolicyRule};
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("{0}")]
     BadRequest(String),
+    #[error("{message}")]
+    PolicyDenied {
+        tenant: String,
+        rule: PolicyRule,
+        message: String,
+    },
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
     #[error("{0}")]
     NotFound(String),
+    /// An `If-Match` store found another version, or no entry
+    #[error("{0}")]
+    PreconditionFailed(String),
+    #[error("{0}")]
+    Unavailable(String),
+    /// The cache backend could not be reached; distinct from a miss
+    #[error("cache backend unavailable")]
+    BackendUnavailable(#[source] anyhow::Error),
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
 }
@@ -29,23 +49,90 @@ impl AppError {
         Self::BadRequest(message.into())
     }
 
+    pub fn policy_denied<T: Into<String>>(tenant: &str, rule: PolicyRule, message: T) -> Self {
+        Self::PolicyDenied {
+            tenant: tenant.to_string(),
+            rule,
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthorized<T: Into<String>>(message: T) -> Self {
+        Self::Unauthorized(message.into())
+    }
+
+    pub fn forbidden<T: Into<String>>(message: T) -> Self {
+        Self::Forbidden(message.into())
+    }
+
     pub fn not_found<T: Into<String>>(message: T) -> Self {
         Self::NotFound(message.into())
     }
-}
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let status = match self {
+    pub fn precondition_failed<T: Into<String>>(message: T) -> Self {
+        Self::PreconditionFailed(message.into())
+    }
+
+    pub fn unavailable<T: Into<String>>(message: T) -> Self {
+        Self::Unavailable(message.into())
+    }
+
+    pub fn backend_unavailable(source: anyhow::Error) -> Self {
+        Self::BackendUnavailable(source)
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
             AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::PolicyDenied { .. } => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            AppError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::BackendUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Machine-readable kind of error, reported per item by batch endpoints;
+    /// policy denials report the rule that rejected the request
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::PolicyDenied { rule, .. } => rule.as_str(),
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::PreconditionFailed(_) => "precondition_failed",
+            AppError::Unavailable(_) => "unavailable",
+            AppError::BackendUnavailable(_) => "backend_unavailable",
+            AppError::Internal(_) => "internal",
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+
+        let denial = match &self {
+            AppError::PolicyDenied { tenant, rule, .. } => Some(PolicyDenial {
+                tenant: tenant.clone(),
+                rule: *rule,
+            }),
+            _ => None,
         };
 
         let body = ErrorBody {
             error: self.to_string(),
         };
 
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if let Some(denial) = denial {
+            // Picked up by the denial tracking middleware to publish POLICY_DENIED
+            response.extensions_mut().insert(denial);
+        }
+        response
     }
 }
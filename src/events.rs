@@ -3,19 +3,70 @@
 
 //! Event bus integration for graph-aware cache invalidation.
 //!
-//! Listens to Redis Pub/Sub events from SynaGraph for intelligent cache invalidation:
+//! Receives events from SynaGraph for intelligent cache invalidation:
 //! - SUPERSEDED_BY: Invalidate artifacts with old provenance hashes
 //! - REVOKE_CAPSULE: Remove all artifacts from a revoked knowledge capsule
 //! - INVALIDATE_TENANT: Clear all cache entries for a tenant
+//! - INVALIDATE_FAMILY: Drop every artifact of a family (e.g. a conversation)
 //! - UPDATE_TTL: Adjust TTL for matching artifacts
+//!
+//! Transports (NATS, Redis Pub/Sub, HTTP `/invalidate`) only decode events and
+//! publish them onto a single internal `tokio::broadcast` channel. One
+//! [`InvalidationEngine`] subscribes to that channel and applies the events to
+//! the cache, so adding a transport never touches invalidation logic. There is
+//! no Kafka transport yet.
+//!
+//! When the engine falls so far behind that the channel drops events, it can
+//! no longer tell which entries those events would have purged. It then
+//! flushes everything the node holds on top of the backend (see
+//! [`Cache::flush_local`]) and reports not-ready through [`EventResync`] until
+//! the flush has completed.
+//!
+//! Outgoing graph events go through the [`EventPublisher`] on `AppState`,
+//! which buffers them and publishes over the shared NATS client.
+//!
+//! Events carrying an `event_id` are recorded in a processed-event ledger in
+//! the cache backend for a retention window, so JetStream redeliveries and
+//! replays are skipped instead of purging twice.
+//!
+//! Payloads may carry an optional W3C `traceparent` next to the event `type`.
+//! The engine handles each such event inside an `invalidation` span recording
+//! the upstream `trace_id` and `parent_span_id`, so the purges a SynaGraph
+//! update caused can be found under its trace.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_nats::{Client, Subscriber};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
+use tracing::Instrument;
 
 use crate::cache::Cache;
 use crate::error::AppError;
+use crate::invalidation::Invalidator;
+use crate::policy::PolicyEvent;
+use crate::purge_queue::PurgeQueue;
+
+/// Capacity of the internal graph event channel
+const GRAPH_EVENT_CAPACITY: usize = 1024;
+
+/// Delay between attempts of a failed resync flush
+const RESYNC_RETRY: Duration = Duration::from_secs(1);
+
+/// Create the internal broadcast channel shared by all transports
+pub fn graph_event_channel() -> broadcast::Sender<EventEnvelope> {
+    let (sender, _) = broadcast::channel(GRAPH_EVENT_CAPACITY);
+    sender
+}
+
+/// Decode a transport payload into a graph event
+pub fn parse_event(payload: &[u8]) -> Result<EventEnvelope, AppError> {
+    serde_json::from_slice(payload)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to parse event: {}", e)))
+}
 
 /// Event types from SynaGraph
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +82,8 @@ pub enum GraphEvent {
     RevokeCapsule { capsule_id: String, tenant: String },
     /// Invalidate all artifacts for a tenant
     InvalidateTenant { tenant: String },
+    /// Invalidate all artifacts stored with a family
+    InvalidateFamily { family: String, tenant: String },
     /// Update TTL for artifacts matching a pattern
     UpdateTtl {
         pattern: String,
@@ -39,6 +92,59 @@ pub enum GraphEvent {
     },
 }
 
+/// Graph event together with the trace context it was published under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    #[serde(flatten)]
+    pub event: GraphEvent,
+    /// Publisher-assigned id used to drop duplicate deliveries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+    /// W3C trace context (`00-{trace_id}-{span_id}-{flags}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traceparent: Option<String>,
+}
+
+impl From<GraphEvent> for EventEnvelope {
+    fn from(event: GraphEvent) -> Self {
+        Self {
+            event,
+            event_id: None,
+            traceparent: None,
+        }
+    }
+}
+
+impl EventEnvelope {
+    /// Span covering the invalidation work triggered by this event
+    fn span(&self) -> tracing::Span {
+        match self.traceparent.as_deref().and_then(parse_traceparent) {
+            Some((trace_id, parent_span_id)) => {
+                tracing::info_span!("invalidation", trace_id, parent_span_id)
+            }
+            None => tracing::info_span!("invalidation"),
+        }
+    }
+}
+
+/// Extract `(trace_id, parent_span_id)` from a W3C `traceparent` value
+fn parse_traceparent(value: &str) -> Option<(&str, &str)> {
+    let mut parts = value.trim().split('-');
+    let (version, trace_id, span_id, flags) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+
+    let is_hex = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit());
+    let valid = is_hex(version, 2)
+        && version != "ff"
+        && is_hex(trace_id, 32)
+        && is_hex(span_id, 16)
+        && is_hex(flags, 2)
+        && trace_id.bytes().any(|b| b != b'0')
+        && span_id.bytes().any(|b| b != b'0');
+
+    valid.then_some((trace_id, span_id))
+}
+
 /// Event bus configuration
 #[derive(Clone)]
 pub struct EventBusConfig {
@@ -55,18 +161,20 @@ impl Default for EventBusConfig {
     }
 }
 
-/// Event bus for receiving invalidation events from SynaGraph
+/// NATS transport receiving invalidation events from SynaGraph
 pub struct EventBus {
     config: EventBusConfig,
-    cache: Cache,
+    events: broadcast::Sender<EventEnvelope>,
+    client: Option<Client>,
     shutdown_tx: Option<mpsc::Sender<()>>,
 }
 
 impl EventBus {
-    pub fn new(config: EventBusConfig, cache: Cache) -> Self {
+    pub fn new(config: EventBusConfig, events: broadcast::Sender<EventEnvelope>) -> Self {
         Self {
             config,
-            cache,
+            events,
+            client: None,
             shutdown_tx: None,
         }
     }
@@ -84,12 +192,13 @@ impl EventBus {
 
         let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
         self.shutdown_tx = Some(shutdown_tx.clone());
+        self.client = Some(client.clone());
 
-        let cache = self.cache.clone();
+        let events = self.events.clone();
         let subject = self.config.channel.clone();
 
         tokio::spawn(async move {
-            if let Err(e) = Self::listen_loop(client, subscriber, cache, shutdown_rx).await {
+            if let Err(e) = Self::listen_loop(client, subscriber, events, shutdown_rx).await {
                 tracing::error!(error = %e, subject = %subject, "Event bus listener error");
             }
         });
@@ -101,7 +210,7 @@ impl EventBus {
     async fn listen_loop(
         client: Client,
         mut subscriber: Subscriber,
-        cache: Cache,
+        events: broadcast::Sender<EventEnvelope>,
         mut shutdown_rx: mpsc::Receiver<()>,
     ) -> Result<(), AppError> {
         let _client_guard = client;
@@ -110,20 +219,15 @@ impl EventBus {
             tokio::select! {
                 maybe_msg = subscriber.next() => {
                     match maybe_msg {
-                        Some(msg) => {
-                            let payload_bytes = msg.payload;
-                            let payload = match std::str::from_utf8(&payload_bytes) {
-                                Ok(text) => text,
-                                Err(error) => {
-                                    tracing::error!(%error, "Received non-UTF8 event payload");
-                                    continue;
-                                }
-                            };
-
-                            if let Err(err) = Self::handle_event(payload, &cache).await {
-                                tracing::error!(error = %err, payload, "Failed to handle event");
+                        Some(msg) => match parse_event(&msg.payload) {
+                            Ok(event) => {
+                                let _ = events.send(event);
                             }
-                        }
+                            Err(err) => {
+                                let payload = String::from_utf8_lossy(&msg.payload);
+                                tracing::error!(error = %err, payload = %payload, "Failed to handle event");
+                            }
+                        },
                         None => {
                             tracing::warn!("Event bus subscription closed");
                             break;
@@ -140,10 +244,120 @@ impl EventBus {
         Ok(())
     }
 
-    async fn handle_event(payload: &str, cache: &Cache) -> Result<(), AppError> {
-        let event: GraphEvent = serde_json::from_str(payload)
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to parse event: {}", e)))?;
+    /// Handle to the connected NATS client, available once started
+    pub fn client(&self) -> Option<Client> {
+        self.client.clone()
+    }
+
+    /// Stop the event bus
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+    }
+}
+
+/// Default retention of processed event ids
+const DEFAULT_LEDGER_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
 
+/// Invalidation engine applying graph events from every transport to the cache
+#[derive(Clone)]
+pub struct InvalidationEngine {
+    cache: Cache,
+    invalidator: Invalidator,
+    ledger_retention: Duration,
+    resync: EventResync,
+}
+
+impl InvalidationEngine {
+    pub fn new(cache: Cache) -> Self {
+        Self {
+            invalidator: Invalidator::new(cache.clone()),
+            cache,
+            ledger_retention: DEFAULT_LEDGER_RETENTION,
+            resync: EventResync::default(),
+        }
+    }
+
+    /// Report resyncs after dropped events through `resync`
+    pub fn with_resync(mut self, resync: EventResync) -> Self {
+        self.resync = resync;
+        self
+    }
+
+    /// Delete matched keys through a purge worker pool
+    pub fn with_purge_queue(mut self, purge_queue: PurgeQueue) -> Self {
+        self.invalidator = self.invalidator.with_purge_queue(purge_queue);
+        self
+    }
+
+    /// How long processed event ids are remembered for deduplication
+    pub fn with_ledger_retention(mut self, retention: Duration) -> Self {
+        self.ledger_retention = retention;
+        self
+    }
+
+    /// Consume the internal event channel until it closes
+    pub fn spawn(
+        self,
+        mut events: broadcast::Receiver<EventEnvelope>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(envelope) => {
+                        let span = envelope.span();
+                        self.handle_envelope(envelope).instrument(span).await;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::error!(
+                            skipped,
+                            "Invalidation engine dropped graph events; flushing node-local caches"
+                        );
+                        self.resync.start(self.cache.clone());
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Apply an event once, consulting the processed-event ledger when it has an id
+    pub async fn handle_envelope(&self, envelope: EventEnvelope) {
+        let Some(event_id) = envelope.event_id else {
+            if let Err(err) = self.handle_event(envelope.event).await {
+                tracing::error!(error = %err, "Failed to handle event");
+            }
+            return;
+        };
+
+        match self
+            .cache
+            .mark_event_processed(&event_id, self.ledger_retention)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::debug!(event_id = %event_id, "Skipping already processed event");
+                return;
+            }
+            Err(err) => {
+                // Prefer a possible double purge over dropping the event
+                tracing::warn!(event_id = %event_id, error = %err, "Event ledger unavailable");
+            }
+        }
+
+        if let Err(err) = self.handle_event(envelope.event).await {
+            tracing::error!(event_id = %event_id, error = %err, "Failed to handle event");
+            // Let a redelivery retry the event
+            if let Err(err) = self.cache.unmark_event_processed(&event_id).await {
+                tracing::warn!(event_id = %event_id, error = %err, "Failed to release event id");
+            }
+        }
+    }
+
+    /// Apply a single graph event to the cache
+    pub async fn handle_event(&self, event: GraphEvent) -> Result<(), AppError> {
         match event {
             GraphEvent::SupersededBy {
                 old_hash,
@@ -152,68 +366,34 @@ impl EventBus {
             } => {
                 tracing::info!(old_hash, new_hash, tenant, "Handling SUPERSEDED_BY event");
 
-                // Find all artifacts with the old hash and purge them
-                let pattern = format!("{}:*", tenant);
-                let keys = cache.scan_by_pattern(&pattern).await?;
-
-                let mut purged = 0;
-                for key in keys {
-                    if let Ok(Some(artifact)) = cache.get(&key).await {
-                        // Check if any provenance hash matches
-                        let has_old_hash = artifact
-                            .artifact
-                            .provenance
-                            .iter()
-                            .any(|p| p.hash.as_deref() == Some(&old_hash));
-
-                        if has_old_hash || artifact.artifact.hash == old_hash {
-                            cache.delete(&key).await?;
-                            purged += 1;
-                        }
-                    }
-                }
-
+                let purged = self.invalidator.supersede(&tenant, &old_hash).await?;
                 tracing::info!(purged, "Purged artifacts with superseded hash");
             }
 
             GraphEvent::RevokeCapsule { capsule_id, tenant } => {
                 tracing::info!(capsule_id, tenant, "Handling REVOKE_CAPSULE event");
 
-                // Purge all artifacts related to this capsule
-                let pattern = format!("{}:*", tenant);
-                let keys = cache.scan_by_pattern(&pattern).await?;
-
-                let mut purged = 0;
-                for key in keys {
-                    if let Ok(Some(artifact)) = cache.get(&key).await {
-                        // Check if any provenance source contains the capsule_id
-                        let has_capsule = artifact
-                            .artifact
-                            .provenance
-                            .iter()
-                            .any(|p| p.source.contains(&capsule_id));
-
-                        if has_capsule {
-                            cache.delete(&key).await?;
-                            purged += 1;
-                        }
-                    }
-                }
-
+                let purged = self
+                    .invalidator
+                    .revoke_capsule(&tenant, &capsule_id)
+                    .await?;
                 tracing::info!(purged, "Purged artifacts for revoked capsule");
             }
 
             GraphEvent::InvalidateTenant { tenant } => {
                 tracing::info!(tenant, "Handling INVALIDATE_TENANT event");
 
-                // Purge all artifacts for this tenant
-                let pattern = format!("{}:*", tenant);
-                let keys = cache.scan_by_pattern(&pattern).await?;
-
-                let purged = cache.delete_many(&keys).await?;
+                let purged = self.invalidator.invalidate_tenant(&tenant).await?;
                 tracing::info!(purged, "Purged all artifacts for tenant");
             }
 
+            GraphEvent::InvalidateFamily { family, tenant } => {
+                tracing::info!(family, tenant, "Handling INVALIDATE_FAMILY event");
+
+                let purged = self.invalidator.invalidate_family(&tenant, &family).await?;
+                tracing::info!(purged, "Purged artifacts for family");
+            }
+
             GraphEvent::UpdateTtl {
                 pattern,
                 tenant,
@@ -234,39 +414,317 @@ impl EventBus {
 
         Ok(())
     }
+}
 
-    /// Stop the event bus
-    pub async fn stop(&mut self) {
-        if let Some(tx) = self.shutdown_tx.take() {
-            let _ = tx.send(()).await;
+/// Redis Pub/Sub transport feeding the internal graph event channel
+pub async fn start_redis_transport(
+    redis_url: &str,
+    channel: String,
+    events: broadcast::Sender<EventEnvelope>,
+) -> Result<tokio::task::JoinHandle<()>, AppError> {
+    let client = redis::Client::open(redis_url)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create Redis client: {}", e)))?;
+
+    let mut pubsub = client.get_async_pubsub().await.map_err(|e| {
+        AppError::Internal(anyhow::anyhow!("Failed to connect to Redis Pub/Sub: {}", e))
+    })?;
+
+    pubsub
+        .subscribe(&channel)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to subscribe: {}", e)))?;
+
+    tracing::info!(channel = %channel, "Redis Pub/Sub transport started");
+
+    Ok(tokio::spawn(async move {
+        let mut messages = pubsub.into_on_message();
+        while let Some(msg) = messages.next().await {
+            match parse_event(msg.get_payload_bytes()) {
+                Ok(event) => {
+                    let _ = events.send(event);
+                }
+                Err(err) => {
+                    tracing::error!(error = %err, channel = %channel, "Failed to handle event");
+                }
+            }
         }
+        tracing::warn!(channel = %channel, "Redis Pub/Sub subscription closed");
+    }))
+}
+
+/// Capacity of the outgoing graph event buffer
+const PUBLISH_BUFFER: usize = 1024;
+
+/// Attempts made to publish one event before it is dropped
+const PUBLISH_ATTEMPTS: u32 = 5;
+
+/// Delay before the first publish retry, doubled on each further attempt
+const PUBLISH_RETRY_BASE: Duration = Duration::from_millis(100);
+
+/// Handle for publishing graph events to the event bus
+///
+/// Events are buffered and published by one background task over the shared
+/// NATS client, retrying with exponential backoff, so callers never wait on
+/// the bus or open a connection of their own.
+#[derive(Clone)]
+pub struct EventPublisher {
+    sender: mpsc::Sender<EventEnvelope>,
+}
+
+impl EventPublisher {
+    /// Start publishing buffered events to `subject`
+    pub fn spawn(client: Client, subject: String) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<EventEnvelope>(PUBLISH_BUFFER);
+
+        tokio::spawn(async move {
+            while let Some(envelope) = receiver.recv().await {
+                let payload = match serde_json::to_vec(&envelope) {
+                    Ok(payload) => payload,
+                    Err(error) => {
+                        tracing::error!(%error, "Failed to serialize graph event");
+                        continue;
+                    }
+                };
+
+                let mut attempt = 0;
+                loop {
+                    attempt += 1;
+                    match client
+                        .publish(subject.clone(), payload.clone().into())
+                        .await
+                    {
+                        Ok(()) => break,
+                        Err(error) if attempt < PUBLISH_ATTEMPTS => {
+                            tracing::debug!(%error, attempt, "Retrying graph event publish");
+                            tokio::time::sleep(PUBLISH_RETRY_BASE * 2u32.pow(attempt - 1)).await;
+                        }
+                        Err(error) => {
+                            tracing::error!(
+                                %error,
+                                subject = %subject,
+                                event_id = ?envelope.event_id,
+                                "Dropping graph event after repeated publish failures"
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// Queue an event for publishing; fails when the buffer is full
+    pub fn publish(&self, event: impl Into<EventEnvelope>) -> Result<(), AppError> {
+        self.sender
+            .try_send(event.into())
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to queue graph event: {}", e)))
     }
 }
 
-/// Publish an event to the event bus (for testing or internal use)
-pub async fn publish_event(
+/// Forward internal policy events (e.g. `POLICY_DENIED`) to a NATS subject
+pub async fn forward_policy_events(
     bus_url: &str,
-    channel: &str,
-    event: &GraphEvent,
-) -> Result<(), AppError> {
+    subject: String,
+    mut events: broadcast::Receiver<PolicyEvent>,
+) -> Result<tokio::task::JoinHandle<()>, AppError> {
     let client = async_nats::connect(bus_url)
         .await
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to connect to NATS: {}", e)))?;
 
-    let payload = serde_json::to_string(event)
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize event: {}", e)))?;
+    Ok(tokio::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Policy event forwarder lagged behind");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let payload = match serde_json::to_vec(&event) {
+                Ok(payload) => payload,
+                Err(error) => {
+                    tracing::error!(%error, "Failed to serialize policy event");
+                    continue;
+                }
+            };
+
+            if let Err(error) = client.publish(subject.clone(), payload.into()).await {
+                tracing::warn!(%error, subject = %subject, "Failed to publish policy event");
+            }
+        }
+    }))
+}
 
-    let subject = channel.to_string();
+/// Tracks the flush that follows events dropped by the internal channel
+///
+/// Every dropped batch requests a flush of the node-local caches. The node is
+/// resyncing until a flush that started after the latest request completes;
+/// failed flushes are retried.
+#[derive(Clone, Default)]
+pub struct EventResync {
+    state: Arc<ResyncState>,
+}
 
-    client
-        .publish(subject, payload.into_bytes().into())
-        .await
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to publish event: {}", e)))?;
+#[derive(Default)]
+struct ResyncState {
+    requested: AtomicU64,
+    completed: AtomicU64,
+    running: AtomicBool,
+}
 
-    client
-        .flush()
-        .await
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to flush NATS client: {}", e)))?;
+impl EventResync {
+    /// Whether a requested flush has not completed yet
+    pub fn is_resyncing(&self) -> bool {
+        self.state.completed.load(Ordering::Acquire) < self.state.requested.load(Ordering::Acquire)
+    }
 
-    Ok(())
+    /// Request a flush of `cache`'s node-local layers, running it unless one
+    /// is already running; the running one picks the request up
+    pub fn start(&self, cache: Cache) {
+        self.state.requested.fetch_add(1, Ordering::AcqRel);
+        if self.state.running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            loop {
+                let generation = state.requested.load(Ordering::Acquire);
+                match cache.flush_local().await {
+                    Ok(()) => state.completed.store(generation, Ordering::Release),
+                    Err(err) => {
+                        tracing::warn!(error = %err, "Failed to flush node-local caches");
+                        tokio::time::sleep(RESYNC_RETRY).await;
+                        continue;
+                    }
+                }
+                if state.requested.load(Ordering::Acquire) == generation {
+                    state.running.store(false, Ordering::Release);
+                    // A request may have slipped in before `running` was cleared
+                    if state.requested.load(Ordering::Acquire) == generation
+                        || state.running.swap(true, Ordering::AcqRel)
+                    {
+                        break;
+                    }
+                }
+            }
+            tracing::info!("Node-local caches flushed after dropped graph events");
+        });
+    }
+}
+
+/// JetStream consumer whose pending count is reported as event-bus lag
+#[derive(Debug, Clone)]
+pub struct JetStreamConsumer {
+    pub stream: String,
+    pub consumer: String,
+}
+
+/// Samples the invalidation backlog so readiness can reflect stale caches
+///
+/// The backlog is the JetStream consumer's `num_pending` (when configured)
+/// plus the events queued on the internal channel but not yet applied. The
+/// latest sample is kept in memory, so readiness checks and the response
+/// middleware never wait on NATS.
+#[derive(Clone)]
+pub struct EventLagMonitor {
+    pending: Arc<AtomicU64>,
+    threshold: u64,
+    fail_readiness: bool,
+}
+
+impl EventLagMonitor {
+    pub fn new(threshold: u64) -> Self {
+        Self {
+            pending: Arc::new(AtomicU64::new(0)),
+            threshold,
+            fail_readiness: false,
+        }
+    }
+
+    /// Report not-ready (instead of degraded) while lagging
+    pub fn with_fail_readiness(mut self, fail_readiness: bool) -> Self {
+        self.fail_readiness = fail_readiness;
+        self
+    }
+
+    pub fn fail_readiness(&self) -> bool {
+        self.fail_readiness
+    }
+
+    /// Events waiting to be applied as of the last sample
+    pub fn pending(&self) -> u64 {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// Whether the backlog exceeds the configured threshold
+    pub fn is_lagging(&self) -> bool {
+        self.pending() > self.threshold
+    }
+
+    /// Sample the backlog every `interval`
+    pub fn spawn(
+        &self,
+        events: broadcast::Sender<EventEnvelope>,
+        jetstream: Option<(Client, JetStreamConsumer)>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let pending = self.pending.clone();
+        let threshold = self.threshold;
+
+        tokio::spawn(async move {
+            let context =
+                jetstream.map(|(client, consumer)| (async_nats::jetstream::new(client), consumer));
+            let mut ticker = tokio::time::interval(interval);
+            let mut was_lagging = false;
+
+            loop {
+                ticker.tick().await;
+
+                let mut backlog = events.len() as u64;
+                if let Some((context, consumer)) = &context {
+                    match jetstream_pending(context, consumer).await {
+                        Ok(remote) => backlog += remote,
+                        Err(err) => {
+                            tracing::warn!(error = %err, "Failed to read JetStream consumer lag")
+                        }
+                    }
+                }
+                pending.store(backlog, Ordering::Relaxed);
+
+                let lagging = backlog > threshold;
+                if lagging != was_lagging {
+                    if lagging {
+                        tracing::warn!(backlog, threshold, "Invalidation backlog above threshold");
+                    } else {
+                        tracing::info!(backlog, threshold, "Invalidation backlog recovered");
+                    }
+                    was_lagging = lagging;
+                }
+            }
+        })
+    }
+}
+
+async fn jetstream_pending(
+    context: &async_nats::jetstream::Context,
+    consumer: &JetStreamConsumer,
+) -> Result<u64, AppError> {
+    let stream = context
+        .get_stream(&consumer.stream)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to get stream: {}", e)))?;
+    let info = stream
+        .consumer_info(&consumer.consumer)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to get consumer info: {}", e)))?;
+    Ok(info.num_pending + info.num_ack_pending as u64)
 }
@@ -0,0 +1,197 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Node identity and fleet registration heartbeats.
+//!
+//! Every node has a stable ID so the control plane can tell edge nodes apart
+//! across restarts. It is taken from `SCEDGE_NODE_ID` or, when unset, read
+//! from `SCEDGE_NODE_ID_PATH`, where a random ID is written on first start.
+//!
+//! With a heartbeat subject or URL configured, the node periodically
+//! publishes a [`Heartbeat`] describing itself: version, region, tenants
+//! served, key count, and hit ratio since startup. Heartbeats go to a NATS
+//! subject, an HTTP registry endpoint (`POST`, JSON body), or both. A failed
+//! publish is logged and retried on the next tick.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cache::Cache;
+use crate::config::{HeartbeatConfig, NodeConfig, OutboundConfig};
+use crate::error::AppError;
+use crate::metrics::Metrics;
+use crate::outbound;
+use crate::policy::PolicyEngine;
+
+/// Stable identity of this node
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeIdentity {
+    pub node_id: String,
+    pub version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+impl NodeIdentity {
+    /// Use the configured ID, or the one persisted at the ID path, generating
+    /// and persisting one when neither exists
+    pub fn resolve(config: &NodeConfig) -> Result<Self, AppError> {
+        let node_id = match &config.id {
+            Some(id) => id.clone(),
+            None => match std::fs::read_to_string(&config.id_path) {
+                Ok(stored) if !stored.trim().is_empty() => stored.trim().to_string(),
+                _ => {
+                    let generated = format!("node-{}", hex::encode(rand::random::<[u8; 8]>()));
+                    std::fs::write(&config.id_path, &generated).map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!(
+                            "Failed to persist node ID to {}: {}",
+                            config.id_path.display(),
+                            e
+                        ))
+                    })?;
+                    generated
+                }
+            },
+        };
+
+        Ok(Self {
+            node_id,
+            version: env!("CARGO_PKG_VERSION"),
+            region: config.region.clone(),
+        })
+    }
+}
+
+/// Periodic self-description published to the control plane
+#[derive(Debug, Clone, Serialize)]
+pub struct Heartbeat {
+    #[serde(flatten)]
+    pub node: NodeIdentity,
+    pub tenants: Vec<String>,
+    /// Keys in the backend, including Scedge's own index keys for Redis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_count: Option<u64>,
+    /// Cache hits over lookups since startup; absent before the first lookup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_ratio: Option<f64>,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// Publishes heartbeats for this node
+pub struct HeartbeatPublisher {
+    node: NodeIdentity,
+    cache: Cache,
+    policy: PolicyEngine,
+    metrics: Metrics,
+    nats: Option<(async_nats::Client, String)>,
+    http: Option<(reqwest::Client, String)>,
+}
+
+impl HeartbeatPublisher {
+    /// Publisher for the configured sinks; the NATS subject is only used when
+    /// an event bus client is available
+    pub fn new(
+        node: NodeIdentity,
+        cache: Cache,
+        policy: PolicyEngine,
+        metrics: Metrics,
+        config: &HeartbeatConfig,
+        event_bus: Option<async_nats::Client>,
+        outbound: &OutboundConfig,
+    ) -> Result<Self, AppError> {
+        let nats = event_bus.zip(config.subject.clone());
+        if config.subject.is_some() && nats.is_none() {
+            tracing::warn!("Heartbeat subject configured without an event bus; skipping NATS");
+        }
+
+        let http = match &config.url {
+            Some(url) => {
+                let client = outbound::client_builder(outbound)?
+                    .timeout(config.interval)
+                    .build()
+                    .map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!(
+                            "Failed to build heartbeat client: {}",
+                            e
+                        ))
+                    })?;
+                Some((client, url.clone()))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            node,
+            cache,
+            policy,
+            metrics,
+            nats,
+            http,
+        })
+    }
+
+    /// Publish immediately, then every `interval`
+    pub fn spawn(self, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let heartbeat = self.heartbeat().await;
+                if let Err(err) = self.publish(&heartbeat).await {
+                    tracing::warn!(error = %err, "Failed to publish heartbeat");
+                }
+            }
+        })
+    }
+
+    async fn heartbeat(&self) -> Heartbeat {
+        let key_count = match self.cache.key_count().await {
+            Ok(count) => Some(count),
+            Err(err) => {
+                tracing::debug!(error = %err, "Failed to count keys for heartbeat");
+                None
+            }
+        };
+
+        let hits = self.metrics.cache_hits.get();
+        let lookups = hits + self.metrics.cache_misses.get();
+
+        Heartbeat {
+            node: self.node.clone(),
+            tenants: self.policy.tenant_ids().await,
+            key_count,
+            hit_ratio: (lookups > 0).then(|| hits as f64 / lookups as f64),
+            sent_at: Utc::now(),
+        }
+    }
+
+    async fn publish(&self, heartbeat: &Heartbeat) -> Result<(), AppError> {
+        let payload = serde_json::to_vec(heartbeat).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to encode heartbeat: {}", e))
+        })?;
+
+        if let Some((client, subject)) = &self.nats {
+            client
+                .publish(subject.clone(), payload.clone().into())
+                .await
+                .map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Heartbeat publish failed: {}", e))
+                })?;
+        }
+
+        if let Some((client, url)) = &self.http {
+            client
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(payload)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Heartbeat registry request failed: {}", e))
+                })?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,613 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! gRPC hydrator for SynaGraph deployments that expose the protobuf Lookup
+//! service instead of the HTTP API.
+//!
+//! Selected by a `grpc://` (cleartext HTTP/2) or `grpcs://` (TLS) upstream
+//! URL. Lookups call `synagraph.v1.SynaGraph/Lookup` as described in
+//! `proto/synagraph.proto`. The reply carries the same JSON record as the HTTP
+//! API, together with the freshness the HTTP API expresses in headers.
+//!
+//! The upstream timeout is sent as the call's `grpc-timeout` deadline and
+//! enforced locally as well, so a server that ignores the deadline cannot
+//! hold a lookup longer. Status codes map onto hydration results:
+//!
+//! - `NOT_FOUND` is an upstream miss
+//! - `DEADLINE_EXCEEDED`, `RESOURCE_EXHAUSTED`, and `UNAVAILABLE` are
+//!   [`AppError::Unavailable`]
+//! - anything else is an internal error
+//!
+//! `grpcs://` verifies the server against the system roots plus the outbound
+//! and upstream CA bundles, and presents the upstream client certificate when
+//! one is configured. The outbound proxy does not apply to gRPC connections.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::client::conn::http2::SendRequest;
+use hyper::header::{HeaderMap, CONTENT_TYPE, TE};
+use hyper::{Method, Request, StatusCode, Uri};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use crate::config::{ClientTlsConfig, UpstreamConfig};
+use crate::error::AppError;
+use crate::model::LookupResponse;
+use crate::upstream::{HttpFreshness, Hydrator, UpstreamRecord};
+
+const LOOKUP_PATH: &str = "/synagraph.v1.SynaGraph/Lookup";
+const HEALTH_PATH: &str = "/grpc.health.v1.Health/Check";
+
+/// Largest reply accepted, matching gRPC's default receive limit
+const MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+
+const STATUS_OK: u32 = 0;
+const STATUS_DEADLINE_EXCEEDED: u32 = 4;
+const STATUS_NOT_FOUND: u32 = 5;
+const STATUS_RESOURCE_EXHAUSTED: u32 = 8;
+const STATUS_UNIMPLEMENTED: u32 = 12;
+const STATUS_UNAVAILABLE: u32 = 14;
+
+/// `grpc.health.v1.HealthCheckResponse.ServingStatus.SERVING`
+const HEALTH_SERVING: u64 = 1;
+
+/// Whether an upstream URL selects the gRPC hydrator
+pub fn is_grpc_url(url: &str) -> bool {
+    let url = url.trim().to_ascii_lowercase();
+    url.starts_with("grpc://") || url.starts_with("grpcs://")
+}
+
+/// Hydrator calling SynaGraph's protobuf Lookup service over HTTP/2
+pub struct GrpcHydrator {
+    /// `host:port` the connection is opened to
+    authority: String,
+    /// Host name verified against the server certificate
+    host: String,
+    timeout: Duration,
+    outbound_ca: Option<PathBuf>,
+    /// Present for `grpcs://`; rebuilt when the client certificate rotates
+    tls: RwLock<Option<TlsConnector>>,
+    /// Multiplexed connection shared by all calls, reopened once it closes
+    sender: Mutex<Option<SendRequest<Full<Bytes>>>>,
+}
+
+/// A finished unary call
+struct Reply {
+    code: u32,
+    message: String,
+    body: Bytes,
+}
+
+impl GrpcHydrator {
+    /// Construct a hydrator for a `grpc://` or `grpcs://` upstream URL
+    pub fn try_new(config: &UpstreamConfig) -> Result<Self, AppError> {
+        let invalid = |reason: &str| {
+            AppError::Internal(anyhow!(
+                "Invalid gRPC upstream URL {}: {}",
+                config.base_url,
+                reason
+            ))
+        };
+
+        let uri: Uri = config
+            .base_url
+            .trim()
+            .parse()
+            .map_err(|_| invalid("not a URL"))?;
+        let secure = match uri.scheme_str().map(str::to_ascii_lowercase).as_deref() {
+            Some("grpc") => false,
+            Some("grpcs") => true,
+            _ => return Err(invalid("scheme must be grpc or grpcs")),
+        };
+        let host = uri.host().ok_or_else(|| invalid("missing host"))?;
+        if !matches!(uri.path(), "" | "/") || uri.query().is_some() {
+            return Err(invalid("gRPC service paths are fixed; remove the path"));
+        }
+        let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+        let outbound_ca = config.outbound.ca_bundle.clone();
+        let tls = match secure {
+            true => Some(tls_connector(
+                outbound_ca.as_deref(),
+                config.client_tls.as_ref(),
+            )?),
+            false => None,
+        };
+
+        if config.outbound.proxy_url.is_some() {
+            tracing::warn!("SCEDGE_OUTBOUND_PROXY does not apply to the gRPC upstream");
+        }
+
+        Ok(Self {
+            authority: format!("{}:{}", host, port),
+            host: host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string(),
+            timeout: config.timeout,
+            outbound_ca,
+            tls: RwLock::new(tls),
+            sender: Mutex::new(None),
+        })
+    }
+
+    /// Run a unary call within the upstream deadline
+    async fn call(&self, path: &str, message: &[u8]) -> Result<Reply, AppError> {
+        match tokio::time::timeout(self.timeout, self.exchange(path, message)).await {
+            Ok(result) => result,
+            Err(_) => Err(AppError::unavailable(format!(
+                "Upstream {} exceeded its {}ms deadline",
+                path,
+                self.timeout.as_millis()
+            ))),
+        }
+    }
+
+    async fn exchange(&self, path: &str, message: &[u8]) -> Result<Reply, AppError> {
+        let secure = self.tls.read().unwrap_or_else(|e| e.into_inner()).is_some();
+        let uri = format!(
+            "{}://{}{}",
+            if secure { "https" } else { "http" },
+            self.authority,
+            path
+        );
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(CONTENT_TYPE, "application/grpc")
+            .header(TE, "trailers")
+            .header("grpc-timeout", grpc_timeout(self.timeout))
+            .body(Full::new(frame(message)))
+            .map_err(|e| AppError::Internal(anyhow!("Invalid gRPC request: {}", e)))?;
+
+        let mut sender = self.sender().await?;
+        let response = match sender.send_request(request).await {
+            Ok(response) => response,
+            Err(err) => {
+                self.reset();
+                return Err(AppError::unavailable(format!(
+                    "Upstream request failed: {}",
+                    err
+                )));
+            }
+        };
+
+        let (parts, body) = response.into_parts();
+        if parts.status != StatusCode::OK {
+            let message = format!("Upstream returned HTTP status {}", parts.status);
+            return Err(match parts.status {
+                StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+                | StatusCode::TOO_MANY_REQUESTS => AppError::unavailable(message),
+                _ => AppError::Internal(anyhow!(message)),
+            });
+        }
+
+        let collected = body.collect().await.map_err(|e| {
+            self.reset();
+            AppError::unavailable(format!("Upstream response failed: {}", e))
+        })?;
+        let trailers = collected.trailers().cloned().unwrap_or_default();
+        let body = collected.to_bytes();
+
+        // Trailers-only responses carry the status in the headers
+        let (code, message) = grpc_status(&trailers)
+            .or_else(|| grpc_status(&parts.headers))
+            .ok_or_else(|| AppError::Internal(anyhow!("Upstream reply has no grpc-status")))?;
+
+        Ok(Reply {
+            code,
+            message,
+            body,
+        })
+    }
+
+    /// The shared connection, opening one if there is none or it closed
+    async fn sender(&self) -> Result<SendRequest<Full<Bytes>>, AppError> {
+        let cached = self
+            .sender
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .filter(|sender| !sender.is_closed());
+        if let Some(mut sender) = cached {
+            if sender.ready().await.is_ok() {
+                return Ok(sender);
+            }
+        }
+
+        let sender = self.connect().await?;
+        *self.sender.lock().unwrap_or_else(|e| e.into_inner()) = Some(sender.clone());
+        Ok(sender)
+    }
+
+    async fn connect(&self) -> Result<SendRequest<Full<Bytes>>, AppError> {
+        let unavailable = |e: &dyn std::fmt::Display| {
+            AppError::unavailable(format!(
+                "Failed to connect to upstream {}: {}",
+                self.authority, e
+            ))
+        };
+
+        let stream = TcpStream::connect(&self.authority)
+            .await
+            .map_err(|e| unavailable(&e))?;
+        let _ = stream.set_nodelay(true);
+
+        let tls = self.tls.read().unwrap_or_else(|e| e.into_inner()).clone();
+        match tls {
+            Some(connector) => {
+                let name = ServerName::try_from(self.host.clone()).map_err(|e| {
+                    AppError::Internal(anyhow!("Invalid upstream host {}: {}", self.host, e))
+                })?;
+                let stream = connector
+                    .connect(name, stream)
+                    .await
+                    .map_err(|e| unavailable(&e))?;
+                handshake(TokioIo::new(stream)).await
+            }
+            None => handshake(TokioIo::new(stream)).await,
+        }
+        .map_err(|e| unavailable(&e))
+    }
+
+    /// Drop the shared connection so the next call reconnects
+    fn reset(&self) {
+        self.sender.lock().unwrap_or_else(|e| e.into_inner()).take();
+    }
+}
+
+#[async_trait]
+impl Hydrator for GrpcHydrator {
+    async fn lookup(
+        &self,
+        key: &str,
+        tenant: Option<&str>,
+    ) -> Result<Option<UpstreamRecord>, AppError> {
+        let mut request = Vec::new();
+        put_bytes(&mut request, 1, key.as_bytes());
+        if let Some(tenant) = tenant {
+            put_bytes(&mut request, 2, tenant.as_bytes());
+        }
+
+        let reply = self.call(LOOKUP_PATH, &request).await?;
+        match reply.code {
+            STATUS_OK => {}
+            STATUS_NOT_FOUND => {
+                tracing::debug!(key, "Upstream returned miss");
+                return Ok(None);
+            }
+            code => return Err(status_error(code, &reply.message)),
+        }
+
+        let reply = LookupReply::decode(&unframe(&reply.body)?)?;
+        let record = serde_json::from_slice::<LookupResponse>(&reply.record)
+            .map_err(|e| AppError::Internal(anyhow!("Failed to parse upstream response: {}", e)))?;
+
+        Ok(Some(UpstreamRecord {
+            record,
+            freshness: HttpFreshness {
+                no_store: reply.no_store,
+                ttl_seconds: reply.max_age_seconds,
+            },
+        }))
+    }
+
+    /// Standard `grpc.health.v1` check
+    ///
+    /// A server without the health service still counts as reachable.
+    async fn health(&self) -> Result<(), AppError> {
+        let reply = self.call(HEALTH_PATH, &[]).await?;
+        match reply.code {
+            STATUS_OK => {}
+            STATUS_UNIMPLEMENTED => return Ok(()),
+            code => return Err(status_error(code, &reply.message)),
+        }
+
+        let mut status = 0;
+        decode_fields(&unframe(&reply.body)?, |field, value| {
+            if let (1, Field::Varint(value)) = (field, value) {
+                status = value;
+            }
+        })?;
+        if status != HEALTH_SERVING {
+            return Err(AppError::unavailable(format!(
+                "Upstream health check returned serving status {}",
+                status
+            )));
+        }
+        Ok(())
+    }
+
+    fn reload_identity(&self, tls: &ClientTlsConfig) -> Result<(), AppError> {
+        let mut current = self.tls.write().unwrap_or_else(|e| e.into_inner());
+        if current.is_none() {
+            // Cleartext connections present no certificate
+            return Ok(());
+        }
+        *current = Some(tls_connector(self.outbound_ca.as_deref(), Some(tls))?);
+        drop(current);
+        self.reset();
+        Ok(())
+    }
+}
+
+/// Open an HTTP/2 connection and drive it in the background
+async fn handshake<T>(io: T) -> Result<SendRequest<Full<Bytes>>, hyper::Error>
+where
+    T: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+{
+    let (sender, connection) =
+        hyper::client::conn::http2::handshake(TokioExecutor::new(), io).await?;
+    tokio::spawn(async move {
+        if let Err(err) = connection.await {
+            tracing::debug!(error = %err, "gRPC upstream connection closed");
+        }
+    });
+    Ok(sender)
+}
+
+/// Build the TLS connector: system roots plus configured bundles, ALPN `h2`
+fn tls_connector(
+    outbound_ca: Option<&Path>,
+    client_tls: Option<&ClientTlsConfig>,
+) -> Result<TlsConnector, AppError> {
+    let mut roots = RootCertStore::empty();
+    match rustls_native_certs::load_native_certs() {
+        Ok(certs) => {
+            roots.add_parsable_certificates(certs);
+        }
+        Err(err) => tracing::warn!(error = %err, "Failed to load system root certificates"),
+    }
+    for path in outbound_ca
+        .into_iter()
+        .chain(client_tls.and_then(|tls| tls.ca_path.as_deref()))
+    {
+        for cert in read_certs(path)? {
+            roots
+                .add(cert)
+                .map_err(|e| AppError::Internal(anyhow!("Invalid CA certificate: {}", e)))?;
+        }
+    }
+
+    let builder = ClientConfig::builder().with_root_certificates(roots);
+    let mut config = match client_tls {
+        Some(tls) => {
+            let certs = read_certs(&tls.cert_path)?;
+            let key = rustls_pemfile::private_key(&mut open(&tls.key_path)?)
+                .ok()
+                .flatten()
+                .ok_or_else(|| {
+                    AppError::Internal(anyhow!("Invalid client key {}", tls.key_path.display()))
+                })?;
+            builder
+                .with_client_auth_cert(certs, key)
+                .map_err(|e| AppError::Internal(anyhow!("Invalid client certificate: {}", e)))?
+        }
+        None => builder.with_no_client_auth(),
+    };
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, AppError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| AppError::Internal(anyhow!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, AppError> {
+    rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Internal(anyhow!("Invalid certificate {}: {}", path.display(), e)))
+}
+
+fn status_error(code: u32, message: &str) -> AppError {
+    let message = format!("Upstream returned gRPC status {}: {}", code, message);
+    match code {
+        STATUS_DEADLINE_EXCEEDED | STATUS_RESOURCE_EXHAUSTED | STATUS_UNAVAILABLE => {
+            AppError::unavailable(message)
+        }
+        _ => AppError::Internal(anyhow!(message)),
+    }
+}
+
+/// `grpc-timeout` value: at most eight digits, so long deadlines use seconds
+fn grpc_timeout(timeout: Duration) -> String {
+    let millis = timeout.as_millis().max(1);
+    if millis < 100_000_000 {
+        format!("{}m", millis)
+    } else {
+        format!("{}S", timeout.as_secs().min(99_999_999))
+    }
+}
+
+fn grpc_status(headers: &HeaderMap) -> Option<(u32, String)> {
+    let code = headers.get("grpc-status")?.to_str().ok()?.parse().ok()?;
+    let message = headers
+        .get("grpc-message")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    Some((code, message))
+}
+
+/// Length-prefix an uncompressed message
+fn frame(message: &[u8]) -> Bytes {
+    let mut framed = Vec::with_capacity(message.len() + 5);
+    framed.push(0);
+    framed.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    framed.extend_from_slice(message);
+    Bytes::from(framed)
+}
+
+/// The single message of a unary reply
+fn unframe(body: &[u8]) -> Result<Vec<u8>, AppError> {
+    let malformed = |reason: &str| AppError::Internal(anyhow!("Malformed gRPC reply: {}", reason));
+
+    if body.len() < 5 {
+        return Err(malformed("missing message"));
+    }
+    if body[0] != 0 {
+        return Err(malformed("compressed messages are not supported"));
+    }
+    let len = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+    if len > MAX_MESSAGE_BYTES {
+        return Err(malformed("message too large"));
+    }
+    if body.len() - 5 != len {
+        return Err(malformed("length prefix does not match the message"));
+    }
+    Ok(body[5..].to_vec())
+}
+
+/// `synagraph.v1.LookupReply`
+#[derive(Debug, Default, PartialEq)]
+struct LookupReply {
+    /// The record as JSON, in the HTTP API's lookup response format
+    record: Vec<u8>,
+    /// Remaining lifetime, the gRPC counterpart of `Cache-Control: max-age`
+    max_age_seconds: Option<u64>,
+    /// The record must not be cached
+    no_store: bool,
+}
+
+impl LookupReply {
+    fn decode(message: &[u8]) -> Result<Self, AppError> {
+        let mut reply = Self::default();
+        decode_fields(message, |field, value| match (field, value) {
+            (1, Field::Bytes(bytes)) => reply.record = bytes.to_vec(),
+            (2, Field::Varint(value)) => reply.max_age_seconds = Some(value),
+            (3, Field::Varint(value)) => reply.no_store = value != 0,
+            _ => {}
+        })?;
+        Ok(reply)
+    }
+}
+
+/// A decoded protobuf field value
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_bytes(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+    put_varint(buf, (u64::from(field) << 3) | 2);
+    put_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn read_varint(buf: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = buf.split_first()?;
+        *buf = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Visit every field of a message; unknown fields are passed through for
+/// the caller to ignore
+fn decode_fields<'a>(
+    mut buf: &'a [u8],
+    mut visit: impl FnMut(u32, Field<'a>),
+) -> Result<(), AppError> {
+    let malformed = || AppError::Internal(anyhow!("Malformed protobuf message"));
+
+    while !buf.is_empty() {
+        let tag = read_varint(&mut buf).ok_or_else(malformed)?;
+        let field = u32::try_from(tag >> 3).map_err(|_| malformed())?;
+        let value = match tag & 7 {
+            0 => Field::Varint(read_varint(&mut buf).ok_or_else(malformed)?),
+            1 | 5 => {
+                let width = if tag & 7 == 1 { 8 } else { 4 };
+                buf = buf.get(width..).ok_or_else(malformed)?;
+                Field::Fixed
+            }
+            2 => {
+                let len = read_varint(&mut buf).ok_or_else(malformed)?;
+                let len = usize::try_from(len).map_err(|_| malformed())?;
+                let (bytes, rest) = (buf.get(..len).ok_or_else(malformed)?, &buf[len..]);
+                buf = rest;
+                Field::Bytes(bytes)
+            }
+            _ => return Err(malformed()),
+        };
+        visit(field, value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varints_round_trip() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = Vec::new();
+            put_varint(&mut buf, value);
+            assert_eq!(read_varint(&mut buf.as_slice()), Some(value));
+        }
+    }
+
+    #[test]
+    fn lookup_reply_skips_unknown_fields() {
+        let mut message = Vec::new();
+        put_bytes(&mut message, 1, br#"{"key":"k"}"#);
+        // Unknown fixed64 and length-delimited fields
+        message.push((9 << 3) | 1);
+        message.extend_from_slice(&[0; 8]);
+        put_bytes(&mut message, 10, b"future");
+        message.push(2 << 3);
+        put_varint(&mut message, 60);
+
+        let reply = LookupReply::decode(&message).expect("reply decodes");
+        assert_eq!(reply.record, br#"{"key":"k"}"#);
+        assert_eq!(reply.max_age_seconds, Some(60));
+        assert!(!reply.no_store);
+    }
+
+    #[test]
+    fn truncated_messages_are_rejected() {
+        let mut message = Vec::new();
+        put_bytes(&mut message, 1, b"record");
+        message.truncate(message.len() - 1);
+        assert!(LookupReply::decode(&message).is_err());
+
+        assert!(unframe(&[0, 0, 0, 0, 4, 1]).is_err());
+        assert!(unframe(&[1, 0, 0, 0, 0]).is_err());
+        assert_eq!(unframe(&frame(b"ok")).unwrap(), b"ok");
+    }
+
+    #[test]
+    fn long_deadlines_switch_to_seconds() {
+        assert_eq!(grpc_timeout(Duration::from_secs(5)), "5000m");
+        assert_eq!(grpc_timeout(Duration::from_secs(200_000)), "200000S");
+    }
+}
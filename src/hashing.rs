@@ -0,0 +1,108 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Canonical artifact hashes.
+//!
+//! An artifact hash covers its `answer` serialized as canonical JSON: object
+//! keys sorted by code point, no insignificant whitespace, and strings and
+//! numbers written as `serde_json` writes them. Two producers holding the same
+//! answer therefore compute the same hash regardless of key order or
+//! formatting.
+//!
+//! Hashes are written as `{algorithm}:{hex digest}` (e.g. `sha256:9f86...`).
+//! Producers can compute them locally or through `POST /hash`, which uses this
+//! module, so Scedge and its producers agree on the computation by
+//! construction. Hashes without a known algorithm prefix are opaque version
+//! tags and are never verified.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Digest used for a canonical artifact hash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "sha256" => Some(HashAlgorithm::Sha256),
+            "blake3" => Some(HashAlgorithm::Blake3),
+            _ => None,
+        }
+    }
+
+    fn digest(&self, bytes: &[u8]) -> String {
+        match self {
+            HashAlgorithm::Sha256 => hex::encode(Sha256::digest(bytes)),
+            HashAlgorithm::Blake3 => blake3::hash(bytes).to_hex().to_string(),
+        }
+    }
+}
+
+/// Serialize a JSON value canonically
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            // Sort explicitly: `serde_json` preserves insertion order when any
+            // dependency enables its `preserve_order` feature
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// Canonical hash of an artifact answer, as `{algorithm}:{hex digest}`
+pub fn artifact_hash(answer: &Value, algorithm: HashAlgorithm) -> String {
+    let digest = algorithm.digest(canonical_json(answer).as_bytes());
+    format!("{}:{}", algorithm.as_str(), digest)
+}
+
+/// Check a declared hash against an answer
+///
+/// Returns `None` for opaque hashes without a known algorithm prefix.
+pub fn verify(hash: &str, answer: &Value) -> Option<bool> {
+    let (prefix, digest) = hash.split_once(':')?;
+    let algorithm = HashAlgorithm::from_prefix(prefix)?;
+    Some(algorithm.digest(canonical_json(answer).as_bytes()) == digest.to_ascii_lowercase())
+}
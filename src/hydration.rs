@@ -0,0 +1,172 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-tenant limits on concurrent upstream hydrations.
+//!
+//! A flood of long-tail misses can keep every upstream slot of a tenant busy,
+//! so the background refreshes that keep its hot keys warm would queue
+//! behind requests that will never be repeated. Tenants with
+//! `max_hydrations` get that many concurrent upstream fetches; waiters are
+//! ordered by [`HydrationPriority`], and within a priority by arrival.
+//!
+//! Early refreshes and misses of keys matching the tenant's `pinned_keys`
+//! patterns are [`HydrationPriority::Hot`] and take the next free slot ahead
+//! of every waiting cold miss.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+use crate::error::AppError;
+
+/// Queue position of a waiting hydration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HydrationPriority {
+    /// Miss of a key with no known reuse
+    Cold,
+    /// Refresh-ahead of a hot key or miss of a pinned key
+    Hot,
+}
+
+struct Waiter {
+    priority: HydrationPriority,
+    sequence: u64,
+    grant: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    /// Higher priority first, then earlier arrival
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+struct Slots {
+    in_flight: usize,
+    waiting: BinaryHeap<Waiter>,
+    next_sequence: u64,
+}
+
+/// Hydration slots of one tenant
+pub struct HydrationLimiter {
+    limit: usize,
+    slots: Mutex<Slots>,
+}
+
+impl HydrationLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            slots: Mutex::new(Slots {
+                in_flight: 0,
+                waiting: BinaryHeap::new(),
+                next_sequence: 0,
+            }),
+        }
+    }
+
+    /// Whether every slot is taken, so the next acquire has to wait
+    pub fn saturated(&self) -> bool {
+        let slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        slots.in_flight >= self.limit
+    }
+
+    /// Wait for a slot, overtaking waiters of lower priority
+    pub async fn acquire(
+        self: &Arc<Self>,
+        priority: HydrationPriority,
+    ) -> Result<HydrationPermit, AppError> {
+        let grant = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            if slots.in_flight < self.limit {
+                slots.in_flight += 1;
+                return Ok(HydrationPermit {
+                    limiter: self.clone(),
+                });
+            }
+
+            tracing::debug!(?priority, "Tenant hydrations saturated, waiting for a slot");
+            let (grant, granted) = oneshot::channel();
+            let sequence = slots.next_sequence;
+            slots.next_sequence += 1;
+            slots.waiting.push(Waiter {
+                priority,
+                sequence,
+                grant,
+            });
+            granted
+        };
+
+        let mut waiting = Waiting {
+            limiter: self.clone(),
+            granted: Some(grant),
+        };
+        if let Some(granted) = waiting.granted.as_mut() {
+            granted
+                .await
+                .map_err(|_| AppError::Internal(anyhow::anyhow!("Hydration limiter closed")))?;
+        }
+        waiting.granted = None;
+
+        Ok(HydrationPermit {
+            limiter: self.clone(),
+        })
+    }
+
+    /// Hand the slot to the first live waiter, or free it
+    fn release(&self) {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(waiter) = slots.waiting.pop() {
+            if waiter.grant.send(()).is_ok() {
+                return;
+            }
+        }
+        slots.in_flight = slots.in_flight.saturating_sub(1);
+    }
+}
+
+/// Waiter that returns a slot granted after it stopped waiting
+struct Waiting {
+    limiter: Arc<HydrationLimiter>,
+    granted: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        if let Some(mut granted) = self.granted.take() {
+            granted.close();
+            if granted.try_recv().is_ok() {
+                self.limiter.release();
+            }
+        }
+    }
+}
+
+/// Slot held for the duration of one upstream hydration
+pub struct HydrationPermit {
+    limiter: Arc<HydrationLimiter>,
+}
+
+impl Drop for HydrationPermit {
+    fn drop(&mut self) {
+        self.limiter.release();
+    }
+}
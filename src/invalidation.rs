@@ -0,0 +1,255 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Graph-aware invalidation logic.
+//!
+//! [`Invalidator`] implements the cache side of SynaGraph events independently
+//! of any transport, so it can be driven by the event bus, HTTP handlers, or
+//! exercised directly against the in-memory backend.
+
+use crate::cache::Cache;
+use crate::error::AppError;
+use crate::purge_queue::PurgeQueue;
+
+/// Applies invalidation rules to a cache
+#[derive(Clone)]
+pub struct Invalidator {
+    cache: Cache,
+    purge_queue: Option<PurgeQueue>,
+}
+
+impl Invalidator {
+    pub fn new(cache: Cache) -> Self {
+        Self {
+            cache,
+            purge_queue: None,
+        }
+    }
+
+    /// Hand matching keys to a purge queue instead of deleting them inline
+    ///
+    /// Counts returned by the invalidation methods then include queued keys.
+    pub fn with_purge_queue(mut self, purge_queue: PurgeQueue) -> Self {
+        self.purge_queue = Some(purge_queue);
+        self
+    }
+
+    async fn purge(&self, keys: Vec<String>) -> Result<usize, AppError> {
+        match &self.purge_queue {
+            Some(queue) => queue.enqueue(keys).await,
+            None => self.cache.delete_many(&keys).await,
+        }
+    }
+
+    /// Purge tenant artifacts whose hash or any provenance hash is `old_hash`,
+    /// along with artifacts that declared a dependency on that hash
+    ///
+    /// Matching artifacts are deleted atomically by the backend rather than
+    /// through the purge queue, so none survive the event.
+    pub async fn supersede(&self, tenant: &str, old_hash: &str) -> Result<usize, AppError> {
+        let mut purged = self
+            .cache
+            .purge_referencing_hash(Some(tenant), old_hash)
+            .await?;
+
+        purged += self
+            .cache
+            .purge_dependents(tenant, vec![old_hash.to_string()])
+            .await?;
+
+        Ok(purged)
+    }
+
+    /// Purge tenant artifacts with a provenance source in `capsule_id`
+    pub async fn revoke_capsule(&self, tenant: &str, capsule_id: &str) -> Result<usize, AppError> {
+        let members = self.cache.take_capsule(tenant, capsule_id).await?;
+
+        self.purge(members).await
+    }
+
+    /// Purge every artifact stored with `family`
+    pub async fn invalidate_family(&self, tenant: &str, family: &str) -> Result<usize, AppError> {
+        let members = self.cache.take_family(tenant, family).await?;
+
+        self.purge(members).await
+    }
+
+    /// Purge every artifact of a tenant
+    pub async fn invalidate_tenant(&self, tenant: &str) -> Result<usize, AppError> {
+        let keys = self.cache.tenant_keys(tenant).await?;
+
+        self.purge(keys).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MemoryCache;
+    use crate::model::{ArtifactPayload, ProvenanceInfo};
+
+    fn source(source: &str, hash: Option<&str>, capsule_id: Option<&str>) -> ProvenanceInfo {
+        ProvenanceInfo {
+            source: source.to_string(),
+            hash: hash.map(str::to_string),
+            version: None,
+            generated_at: None,
+            capsule_id: capsule_id.map(str::to_string),
+        }
+    }
+
+    async fn store(cache: &Cache, key: &str, tenant: &str, provenance: Vec<ProvenanceInfo>) {
+        let artifact = provenance
+            .into_iter()
+            .fold(
+                ArtifactPayload::builder()
+                    .answer("hello")
+                    .tenant(tenant)
+                    .hash(format!("{}-hash", key)),
+                |builder, source| builder.provenance(source),
+            )
+            .build()
+            .unwrap();
+        cache.set(key.to_string(), artifact, None).await.unwrap();
+    }
+
+    async fn present(cache: &Cache, key: &str) -> bool {
+        cache.get(key).await.unwrap().is_some()
+    }
+
+    #[tokio::test]
+    async fn provenance_without_a_hash_never_matches() {
+        let cache = Cache::new(MemoryCache::new());
+        store(
+            &cache,
+            "acme:a",
+            "acme",
+            vec![source("doc://a", None, None)],
+        )
+        .await;
+
+        let invalidator = Invalidator::new(cache.clone());
+        assert_eq!(invalidator.supersede("acme", "").await.unwrap(), 0);
+        assert_eq!(invalidator.supersede("acme", "doc://a").await.unwrap(), 0);
+        assert!(present(&cache, "acme:a").await);
+    }
+
+    #[tokio::test]
+    async fn any_of_several_provenance_hashes_matches() {
+        let cache = Cache::new(MemoryCache::new());
+        let sources = vec![
+            source("doc://a", Some("h1"), None),
+            source("doc://b", None, None),
+            source("doc://c", Some("h3"), None),
+        ];
+        store(&cache, "acme:a", "acme", sources).await;
+        store(
+            &cache,
+            "acme:b",
+            "acme",
+            vec![source("doc://d", Some("h4"), None)],
+        )
+        .await;
+
+        let invalidator = Invalidator::new(cache.clone());
+        assert_eq!(invalidator.supersede("acme", "h3").await.unwrap(), 1);
+        assert!(!present(&cache, "acme:a").await);
+        assert!(present(&cache, "acme:b").await);
+    }
+
+    #[tokio::test]
+    async fn the_artifact_hash_itself_matches() {
+        let cache = Cache::new(MemoryCache::new());
+        store(&cache, "acme:a", "acme", Vec::new()).await;
+
+        let invalidator = Invalidator::new(cache.clone());
+        assert_eq!(
+            invalidator.supersede("acme", "acme:a-hash").await.unwrap(),
+            1
+        );
+        assert!(!present(&cache, "acme:a").await);
+    }
+
+    #[tokio::test]
+    async fn hash_matches_stay_within_the_tenant() {
+        let cache = Cache::new(MemoryCache::new());
+        store(
+            &cache,
+            "acme:a",
+            "acme",
+            vec![source("doc://a", Some("shared"), None)],
+        )
+        .await;
+        store(
+            &cache,
+            "globex:a",
+            "globex",
+            vec![source("doc://a", Some("shared"), None)],
+        )
+        .await;
+
+        let invalidator = Invalidator::new(cache.clone());
+        assert_eq!(invalidator.supersede("acme", "shared").await.unwrap(), 1);
+        assert!(!present(&cache, "acme:a").await);
+        assert!(present(&cache, "globex:a").await);
+    }
+
+    #[tokio::test]
+    async fn hash_matches_are_exact() {
+        let cache = Cache::new(MemoryCache::new());
+        store(
+            &cache,
+            "acme:a",
+            "acme",
+            vec![source("doc://a", Some("abc"), None)],
+        )
+        .await;
+
+        let invalidator = Invalidator::new(cache.clone());
+        assert_eq!(invalidator.supersede("acme", "ab").await.unwrap(), 0);
+        assert_eq!(invalidator.supersede("acme", "ABC").await.unwrap(), 0);
+        assert!(present(&cache, "acme:a").await);
+    }
+
+    #[tokio::test]
+    async fn capsules_match_by_id_or_source_segment() {
+        let cache = Cache::new(MemoryCache::new());
+        store(
+            &cache,
+            "acme:id",
+            "acme",
+            vec![source("doc://a", None, Some("cap-42"))],
+        )
+        .await;
+        let from_source = source("synagraph://acme/capsules/cap-42/v3", None, None);
+        store(&cache, "acme:source", "acme", vec![from_source]).await;
+        let prefixed = source("synagraph://acme/capsules/cap-420/v1", None, None);
+        store(&cache, "acme:prefixed", "acme", vec![prefixed]).await;
+
+        let invalidator = Invalidator::new(cache.clone());
+        assert_eq!(
+            invalidator.revoke_capsule("acme", "cap-42").await.unwrap(),
+            2
+        );
+        assert!(!present(&cache, "acme:id").await);
+        assert!(!present(&cache, "acme:source").await);
+        assert!(present(&cache, "acme:prefixed").await);
+    }
+
+    #[tokio::test]
+    async fn an_explicit_capsule_id_overrides_the_source() {
+        let cache = Cache::new(MemoryCache::new());
+        let source = source("synagraph://acme/capsules/cap-1/v1", None, Some("cap-2"));
+        store(&cache, "acme:a", "acme", vec![source]).await;
+
+        let invalidator = Invalidator::new(cache.clone());
+        assert_eq!(
+            invalidator.revoke_capsule("acme", "cap-1").await.unwrap(),
+            0
+        );
+        assert_eq!(
+            invalidator.revoke_capsule("acme", "cap-2").await.unwrap(),
+            1
+        );
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keys for stores that do not name one.
+//!
+//! Producers without a natural key can `POST /store` with the `key` omitted;
+//! the node's [`KeyGenerator`] derives one from the artifact and returns it.
+//! The default [`ContentKeyGenerator`] is deterministic, so storing the same
+//! answer for the same tenant and tags always lands on the same key and the
+//! producer can look it up again without remembering the response. Services
+//! embedding Scedge can install their own generator in
+//! [`AppState`](crate::api::AppState).
+
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::hashing::canonical_json;
+use crate::model::ArtifactPayload;
+
+/// Segment following the tenant in keys derived by [`ContentKeyGenerator`]
+pub const CONTENT_KEY_SEGMENT: &str = "auto";
+
+/// Derives the key of an artifact stored without one
+pub trait KeyGenerator: Send + Sync {
+    /// Key for `artifact`; its first segment must be the artifact's tenant
+    fn generate(&self, artifact: &ArtifactPayload) -> String;
+}
+
+/// `{tenant}:auto:{digest}`, where `digest` is the hex SHA-256 of the
+/// canonical JSON of `{"answer", "tags", "tenant"}` with tags sorted and
+/// deduplicated
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContentKeyGenerator;
+
+impl KeyGenerator for ContentKeyGenerator {
+    fn generate(&self, artifact: &ArtifactPayload) -> String {
+        let tenant = &artifact.policy.tenant;
+        let mut tags = artifact.tags.clone();
+        tags.sort_unstable();
+        tags.dedup();
+
+        let identity = json!({
+            "answer": artifact.answer,
+            "tags": tags,
+            "tenant": tenant,
+        });
+        let digest = hex::encode(Sha256::digest(canonical_json(&identity).as_bytes()));
+        format!("{}:{}:{}", tenant, CONTENT_KEY_SEGMENT, digest)
+    }
+}
@@ -0,0 +1,346 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cache key validation.
+//!
+//! Keys are `:`-separated segments whose first segment is the tenant
+//! (`acme:analytics:report`). Tenant purges and scheduled purges rely on
+//! `SCAN MATCH {tenant}:*`, so keys with empty segments, control characters,
+//! or stray escapes would silently fall outside (or inside) those scans.
+//!
+//! Rules applied to every key accepted by the API:
+//! - between 1 and [`MAX_KEY_LENGTH`] bytes of UTF-8
+//! - no control characters (newlines, tabs, NUL, ...)
+//! - no empty segments (`a::b`, leading or trailing `:`)
+//! - a literal `:` inside a segment is written `\:`, a literal `\` as `\\`
+//!
+//! Tenant ids are restricted to ASCII letters, digits, `-`, `_`, and `.`, so
+//! a tenant's `{tenant}:*` scans can never reach into another tenant whose
+//! id extends it (tenant `a` and keys `a:b:*` of a would-be tenant `a:b`).
+//!
+//! Entries of a non-default namespace (e.g. `staging`) are stored with the
+//! namespace as the second segment (`acme:@staging:analytics:report`), so
+//! tenant-wide scans and purges still cover them. Callers address them with
+//! the plain key and select the namespace per request.
+
+use crate::error::AppError;
+
+/// Maximum key length in bytes
+pub const MAX_KEY_LENGTH: usize = 512;
+
+/// Validate a cache key, returning a `400 Bad Request` error describing the problem
+pub fn validate_key(key: &str) -> Result<(), AppError> {
+    if key.is_empty() {
+        return Err(AppError::bad_request("key is required"));
+    }
+
+    if key.len() > MAX_KEY_LENGTH {
+        return Err(AppError::bad_request(format!(
+            "key exceeds maximum length of {} bytes",
+            MAX_KEY_LENGTH
+        )));
+    }
+
+    let mut segment_len = 0;
+    let mut chars = key.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_control() => {
+                return Err(AppError::bad_request(format!(
+                    "key `{}` contains a control character",
+                    key.escape_debug()
+                )));
+            }
+            '\\' => match chars.next() {
+                Some(':') | Some('\\') => segment_len += 1,
+                _ => {
+                    return Err(AppError::bad_request(format!(
+                        "key `{}` contains an invalid escape; only `\\:` and `\\\\` are allowed",
+                        key
+                    )));
+                }
+            },
+            ':' => {
+                if segment_len == 0 {
+                    return Err(AppError::bad_request(format!(
+                        "key `{}` contains an empty segment",
+                        key
+                    )));
+                }
+                segment_len = 0;
+            }
+            _ => segment_len += 1,
+        }
+    }
+
+    if segment_len == 0 {
+        return Err(AppError::bad_request(format!(
+            "key `{}` contains an empty segment",
+            key
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate every key of a batch, failing on the first invalid one
+pub fn validate_keys<'a>(keys: impl IntoIterator<Item = &'a String>) -> Result<(), AppError> {
+    keys.into_iter().try_for_each(|key| validate_key(key))
+}
+
+/// Maximum tenant id length in bytes
+pub const MAX_TENANT_ID_LENGTH: usize = 64;
+
+/// Validate a tenant id: ASCII letters, digits, `-`, `_`, and `.`
+pub fn validate_tenant_id(tenant: &str) -> Result<(), AppError> {
+    if tenant.is_empty()
+        || tenant.len() > MAX_TENANT_ID_LENGTH
+        || !tenant
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+    {
+        return Err(AppError::bad_request(format!(
+            "tenant id `{}` must be 1 to {} ASCII letters, digits, `-`, `_`, or `.`",
+            tenant.escape_debug(),
+            MAX_TENANT_ID_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Tenant segment of a key (everything before the first `:`)
+pub fn key_tenant(key: &str) -> &str {
+    key.split(':').next().unwrap_or(key)
+}
+
+/// Marker opening the namespace segment of a namespaced key
+pub const NAMESPACE_MARKER: char = '@';
+
+/// Maximum namespace name length in bytes
+pub const MAX_NAMESPACE_LENGTH: usize = 32;
+
+/// Validate a namespace name: lowercase ASCII letters, digits, `-`, and `_`
+pub fn validate_namespace(namespace: &str) -> Result<(), AppError> {
+    if namespace.is_empty()
+        || namespace.len() > MAX_NAMESPACE_LENGTH
+        || !namespace
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+    {
+        return Err(AppError::bad_request(format!(
+            "namespace must be 1 to {} lowercase letters, digits, `-`, or `_`",
+            MAX_NAMESPACE_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Namespace of a stored key, `None` for the default namespace
+pub fn key_namespace(key: &str) -> Option<&str> {
+    key.split(':').nth(1)?.strip_prefix(NAMESPACE_MARKER)
+}
+
+/// Key under which `key` is stored in `namespace`
+pub fn namespaced_key(key: &str, namespace: &str) -> String {
+    match key.split_once(':') {
+        Some((tenant, rest)) => format!("{}:{}{}:{}", tenant, NAMESPACE_MARKER, namespace, rest),
+        None => format!("{}:{}{}", key, NAMESPACE_MARKER, namespace),
+    }
+}
+
+/// Key as callers of its namespace address it
+pub fn strip_namespace(key: &str) -> String {
+    let Some(namespace) = key_namespace(key) else {
+        return key.to_string();
+    };
+    let tenant = key_tenant(key);
+    let rest = &key[tenant.len() + 1 + NAMESPACE_MARKER.len_utf8() + namespace.len()..];
+    match rest.strip_prefix(':') {
+        Some(rest) => format!("{}:{}", tenant, rest),
+        None => tenant.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{glob_match, tenant_pattern};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    const CASES: usize = 500;
+
+    /// Pieces a valid segment is built from, escapes included
+    const UNITS: &[&str] = &[
+        "a", "z", "0", "9", "-", "_", ".", "/", "@", "é", "日", "🙂", "\\:", "\\\\",
+    ];
+
+    const CONTROLS: &[&str] = &["\0", "\t", "\n", "\r", "\x1b", "\x7f", "\u{85}"];
+
+    fn segment(rng: &mut StdRng) -> Vec<&'static str> {
+        (0..rng.gen_range(1..=8))
+            .map(|_| *UNITS.choose(rng).unwrap())
+            .collect()
+    }
+
+    fn segments(rng: &mut StdRng) -> Vec<Vec<&'static str>> {
+        (0..rng.gen_range(1..=6)).map(|_| segment(rng)).collect()
+    }
+
+    fn join(segments: &[Vec<&str>]) -> String {
+        segments
+            .iter()
+            .map(|units| units.concat())
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+
+    /// Insert `piece` between two units of a random segment
+    fn insert_into_segment(rng: &mut StdRng, segments: &mut [Vec<&str>], piece: &'static str) {
+        let units = segments.choose_mut(rng).unwrap();
+        let at = rng.gen_range(0..=units.len());
+        units.insert(at, piece);
+    }
+
+    #[test]
+    fn generated_keys_are_accepted() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..CASES {
+            let key = join(&segments(&mut rng));
+            assert!(validate_key(&key).is_ok(), "{:?} was rejected", key);
+        }
+    }
+
+    #[test]
+    fn empty_segments_are_rejected() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..CASES {
+            let segments = segments(&mut rng);
+            let key = join(&segments);
+            let broken = match rng.gen_range(0..3) {
+                0 => format!(":{}", key),
+                1 => format!("{}:", key),
+                _ => {
+                    let at = rng.gen_range(0..=segments.len());
+                    let (head, tail) = segments.split_at(at);
+                    format!("{}::{}", join(head), join(tail))
+                }
+            };
+            assert!(validate_key(&broken).is_err(), "{:?} was accepted", broken);
+        }
+    }
+
+    #[test]
+    fn control_characters_are_rejected() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..CASES {
+            let mut segments = segments(&mut rng);
+            let control = *CONTROLS.choose(&mut rng).unwrap();
+            insert_into_segment(&mut rng, &mut segments, control);
+            let key = join(&segments);
+            assert!(validate_key(&key).is_err(), "{:?} was accepted", key);
+        }
+    }
+
+    #[test]
+    fn invalid_escapes_are_rejected() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..CASES {
+            let mut segments = segments(&mut rng);
+            let escape = *["\\a", "\\n", "\\*", "\\é", "\\ "]
+                .choose(&mut rng)
+                .unwrap();
+            insert_into_segment(&mut rng, &mut segments, escape);
+            let key = join(&segments);
+            assert!(validate_key(&key).is_err(), "{:?} was accepted", key);
+        }
+
+        // A lone trailing backslash escapes nothing
+        assert!(validate_key("acme:report\\").is_err());
+        // An escaped `:` does not end the segment, so it cannot hide an empty one
+        assert!(validate_key("acme:\\:").is_ok());
+        assert!(validate_key("acme:\\\\:").is_err());
+    }
+
+    #[test]
+    fn keys_up_to_the_length_limit_are_accepted() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..CASES {
+            let mut key = join(&segments(&mut rng));
+            while key.len() < MAX_KEY_LENGTH - 4 {
+                key.push_str(UNITS.choose(&mut rng).unwrap());
+            }
+            while key.len() < MAX_KEY_LENGTH {
+                key.push('a');
+            }
+            assert_eq!(key.len(), MAX_KEY_LENGTH);
+            assert!(validate_key(&key).is_ok(), "{:?} was rejected", key);
+
+            // One more byte, or a multi-byte character straddling the limit, is too long
+            let mut over = key.clone();
+            over.push('a');
+            assert!(validate_key(&over).is_err());
+            // The key ends in padding, so dropping its last byte leaves whole characters
+            let mut straddling = key[..MAX_KEY_LENGTH - 1].to_string();
+            straddling.push('é');
+            assert_eq!(straddling.len(), MAX_KEY_LENGTH + 1);
+            assert!(validate_key(&straddling).is_err());
+        }
+    }
+
+    #[test]
+    fn empty_keys_are_rejected() {
+        assert!(validate_key("").is_err());
+        assert!(validate_key(":").is_err());
+    }
+
+    #[test]
+    fn adversarial_tenant_ids_are_rejected() {
+        let too_long = "a".repeat(MAX_TENANT_ID_LENGTH + 1);
+        for tenant in [
+            "", "a:b", "a:", ":a", "a*", "a?", "a[b]", "a\\b", "a b", "a\n", "a\0", "é", &too_long,
+        ] {
+            assert!(
+                validate_tenant_id(tenant).is_err(),
+                "{:?} was accepted",
+                tenant
+            );
+        }
+        for tenant in ["acme", "Acme-EU", "acme_2", "acme.eu", &too_long[1..]] {
+            assert!(
+                validate_tenant_id(tenant).is_ok(),
+                "{:?} was rejected",
+                tenant
+            );
+        }
+    }
+
+    #[test]
+    fn tenant_patterns_only_match_their_tenant() {
+        // A small alphabet makes tenants that extend each other common
+        const TENANT_CHARS: &[char] = &['a', 'b', '-', '.', '_'];
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..CASES {
+            let mut tenant = || -> String {
+                (0..rng.gen_range(1..=3))
+                    .map(|_| *TENANT_CHARS.choose(&mut rng).unwrap())
+                    .collect()
+            };
+            let (scanned, owner) = (tenant(), tenant());
+            validate_tenant_id(&scanned).unwrap();
+            validate_tenant_id(&owner).unwrap();
+
+            let mut segments = segments(&mut rng);
+            segments.insert(0, vec![owner.as_str()]);
+            let key = join(&segments);
+            assert_eq!(
+                glob_match(&tenant_pattern(&scanned), &key),
+                scanned == owner,
+                "pattern of {:?} against {:?}",
+                scanned,
+                key
+            );
+        }
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scedge Core library.
+//!
+//! Exposes the cache, policy, event bus, and HTTP handler modules used by the
+//! `scedge` binary so they can also be embedded in other services.
+
+pub mod admin;
+pub mod admission;
+pub mod api;
+pub mod bloom;
+pub mod cache;
+pub mod clock;
+pub mod commands;
+pub mod compression;
+pub mod config;
+pub mod console;
+pub mod crypto;
+pub mod drain;
+pub mod entry_format;
+pub mod error;
+pub mod events;
+pub mod fleet;
+pub mod grpc;
+pub mod hashing;
+pub mod hydration;
+pub mod invalidation;
+pub mod keygen;
+pub mod keys;
+pub mod listeners;
+pub mod logging;
+pub mod metrics;
+pub mod mirror;
+pub mod model;
+pub mod outbound;
+pub mod outbox;
+pub mod overrides;
+pub mod peers;
+pub mod plugins;
+pub mod policy;
+pub mod proxy;
+pub mod purge_queue;
+pub mod rendered;
+pub mod routes;
+pub mod scheduler;
+#[cfg(feature = "testing")]
+pub mod simulation;
+pub mod singleflight;
+pub mod stale;
+pub mod sweeper;
+pub mod sync;
+pub mod systemd;
+pub mod templates;
+pub mod tenant;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod ttl_tuner;
+pub mod upstream;
+pub mod wal;
+pub mod warmup;
@@ -0,0 +1,212 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTTP listeners: several addresses, IPv6, and TLS termination.
+//!
+//! `SCEDGE_LISTEN` lists the addresses served, each optionally with its own
+//! certificate. IPv6 sockets are bound IPv6-only, so a dual-stack node lists
+//! both `[::]:8080` and `0.0.0.0:8080` and IPv6-only sites list just the
+//! former. Once any listener is marked `admin`, the admin endpoints are
+//! answered with `404 Not Found` everywhere else, so they can be kept on a
+//! private interface.
+//!
+//! Every listener stops accepting connections on shutdown and waits for its
+//! open connections, which the drain middleware bounds.
+
+use std::fs::File;
+use std::future::Future;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::{ListenerConfig, ListenerTls};
+
+/// Pending connections queued per listener
+const BACKLOG: i32 = 1024;
+
+/// Path prefixes served only on admin listeners, when there are any
+const ADMIN_PATHS: &[&str] = &["/admin/", "/console"];
+
+/// Bound listener, ready to serve
+pub struct Listener {
+    tcp: TcpListener,
+    tls: Option<TlsAcceptor>,
+    admin: bool,
+}
+
+impl Listener {
+    /// Bind `config`'s address and load its certificate, if any
+    pub fn bind(config: &ListenerConfig) -> anyhow::Result<Self> {
+        let tcp = bind(config.addr)
+            .with_context(|| format!("Failed to bind listener {}", config.addr))?;
+        Self::adopt(tcp, config)
+    }
+
+    /// Serve an already bound socket, e.g. one passed by systemd, with
+    /// `config`'s options
+    pub fn adopt(tcp: TcpListener, config: &ListenerConfig) -> anyhow::Result<Self> {
+        let tls = config.tls.as_ref().map(tls_acceptor).transpose()?;
+        Ok(Self {
+            tcp,
+            tls,
+            admin: config.admin,
+        })
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.tcp.local_addr()
+    }
+
+    pub fn is_tls(&self) -> bool {
+        self.tls.is_some()
+    }
+}
+
+/// Serve `app` on every listener until `shutdown` resolves
+pub async fn serve(
+    listeners: Vec<Listener>,
+    app: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    let (stop, stopped) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown.await;
+        stop.send_replace(true);
+    });
+
+    let split_admin = listeners.iter().any(|listener| listener.admin);
+    let mut servers = tokio::task::JoinSet::new();
+    for listener in listeners {
+        let app = if split_admin && !listener.admin {
+            app.clone().layer(middleware::from_fn(hide_admin_routes))
+        } else {
+            app.clone()
+        };
+        let mut stopped = stopped.clone();
+        let shutdown = async move {
+            // The sender lives until the server tasks finish
+            let _ = stopped.wait_for(|stopped| *stopped).await;
+        };
+
+        match listener.tls {
+            Some(tls) => servers.spawn(serve_tls(listener.tcp, tls, app, shutdown)),
+            None => servers.spawn(async move {
+                axum::serve(listener.tcp, app)
+                    .with_graceful_shutdown(shutdown)
+                    .await
+                    .context("Listener failed")
+            }),
+        };
+    }
+
+    while let Some(result) = servers.join_next().await {
+        result.context("Listener task panicked")??;
+    }
+    Ok(())
+}
+
+/// Accept TLS connections until `shutdown`, then wait for open ones
+async fn serve_tls(
+    tcp: TcpListener,
+    tls: TlsAcceptor,
+    app: Router,
+    shutdown: impl Future<Output = ()>,
+) -> anyhow::Result<()> {
+    let graceful = GracefulShutdown::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = tcp.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(error) => {
+                    tracing::warn!(%error, "Failed to accept connection");
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let tls = tls.clone();
+        let service = TowerToHyperService::new(app.clone());
+        let watcher = graceful.watcher();
+        tokio::spawn(async move {
+            let stream = match tls.accept(stream).await {
+                Ok(stream) => stream,
+                Err(error) => {
+                    tracing::debug!(%peer, %error, "TLS handshake failed");
+                    return;
+                }
+            };
+            let builder = auto::Builder::new(TokioExecutor::new());
+            let connection = builder.serve_connection(TokioIo::new(stream), service);
+            if let Err(error) = watcher.watch(connection).await {
+                tracing::debug!(%peer, %error, "Connection closed with an error");
+            }
+        });
+    }
+
+    graceful.shutdown().await;
+    Ok(())
+}
+
+/// Bind a socket, keeping IPv6 sockets off IPv4 so both families can share a port
+fn bind(addr: SocketAddr) -> std::io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(BACKLOG)?;
+    socket.set_nonblocking(true)?;
+    TcpListener::from_std(socket.into())
+}
+
+fn tls_acceptor(tls: &ListenerTls) -> anyhow::Result<TlsAcceptor> {
+    let open = |path: &std::path::Path| {
+        File::open(path)
+            .map(BufReader::new)
+            .with_context(|| format!("Failed to open {}", path.display()))
+    };
+
+    let certs = rustls_pemfile::certs(&mut open(&tls.cert_path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid certificate {}", tls.cert_path.display()))?;
+    let key = rustls_pemfile::private_key(&mut open(&tls.key_path)?)
+        .with_context(|| format!("Invalid private key {}", tls.key_path.display()))?
+        .with_context(|| format!("No private key in {}", tls.key_path.display()))?;
+
+    let mut config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("Invalid listener certificate")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Middleware answering admin paths as if they did not exist
+async fn hide_admin_routes(request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    if ADMIN_PATHS.iter().any(|prefix| path.starts_with(prefix)) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    next.run(request).await
+}
@@ -0,0 +1,63 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracing setup with a log filter that can be swapped at runtime.
+//!
+//! The filter starts from `RUST_LOG` (default `info`). [`LogLevel::set`]
+//! replaces it with new `EnvFilter` directives without restarting the node,
+//! so the cache stays warm while a production node is debugged.
+
+use std::sync::Arc;
+
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::error::AppError;
+
+/// Handle to the active log filter
+#[derive(Clone)]
+pub struct LogLevel {
+    handle: Arc<reload::Handle<EnvFilter, Registry>>,
+}
+
+impl LogLevel {
+    /// Install the global subscriber and return a handle to its filter
+    pub fn init() -> Self {
+        let filter = EnvFilter::try_from_default_env()
+            .or_else(|_| EnvFilter::try_new("info"))
+            .unwrap();
+        let (filter, handle) = reload::Layer::new(filter);
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(
+                fmt::layer()
+                    .with_target(true)
+                    .with_thread_ids(false)
+                    .with_file(true)
+                    .with_line_number(true),
+            )
+            .init();
+
+        Self {
+            handle: Arc::new(handle),
+        }
+    }
+
+    /// Replace the filter with `directives`, e.g. `info,scedge::cache=debug`
+    pub fn set(&self, directives: &str) -> Result<(), AppError> {
+        let filter = EnvFilter::try_new(directives)
+            .map_err(|e| AppError::bad_request(format!("Invalid log filter: {}", e)))?;
+        self.handle
+            .reload(filter)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to swap log filter: {}", e)))
+    }
+
+    /// Directives of the active filter
+    pub fn current(&self) -> Result<String, AppError> {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to read log filter: {}", e)))
+    }
+}
@@ -37,31 +37,48 @@
 //!
 //! See QUICKSTART.md for detailed setup instructions.
 
-mod api;
-mod cache;
-mod config;
-mod error;
-mod events;
-mod metrics;
-mod model;
-mod policy;
-mod upstream;
-
-use axum::response::Html;
-use axum::routing::{get, post};
-use axum::Router;
-use tower_http::trace::TraceLayer;
-use tracing_subscriber::EnvFilter;
-
-use crate::api::{
-    handle_lookup, handle_purge, handle_store, health, metrics as metrics_handler, AppState,
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use scedge::admission::Admission;
+use scedge::api::AppState;
+use scedge::bloom::KeyFilter;
+use scedge::cache::{
+    Cache, CacheBackend, MemoryCache, RedisCache, RedisWorkload, SqliteCache, TieredCache,
+};
+use scedge::commands::CommandChannel;
+use scedge::compression::Dictionaries;
+use scedge::config::{AppConfig, CacheBackendKind};
+use scedge::console::RecentInvalidations;
+use scedge::crypto::Keyring;
+use scedge::drain::Drain;
+use scedge::events::{
+    forward_policy_events, graph_event_channel, start_redis_transport, EventBus, EventBusConfig,
+    EventLagMonitor, EventPublisher, EventResync, InvalidationEngine,
 };
-use crate::cache::{Cache, RedisCache};
-use crate::config::AppConfig;
-use crate::events::{EventBus, EventBusConfig};
-use crate::metrics::Metrics;
-use crate::policy::PolicyEngine;
-use crate::upstream::UpstreamClient;
+use scedge::fleet::{HeartbeatPublisher, NodeIdentity};
+use scedge::keygen::ContentKeyGenerator;
+use scedge::listeners::{self, Listener};
+use scedge::logging::LogLevel;
+use scedge::metrics::{spawn_pusher, Metrics};
+use scedge::mirror::Mirror;
+use scedge::outbox::OutboxWorker;
+use scedge::overrides;
+use scedge::peers::PeerClient;
+use scedge::plugins::PolicyPlugins;
+use scedge::policy::{install_tenant, spawn_policy_audit, PolicyEngine};
+use scedge::purge_queue::PurgeQueue;
+use scedge::rendered::RenderedResponses;
+use scedge::routes::router;
+use scedge::scheduler::PurgeScheduler;
+use scedge::singleflight::Singleflight;
+use scedge::stale::StaleCopies;
+use scedge::sweeper::ExpirySweeper;
+use scedge::sync::copy_from_sibling;
+use scedge::systemd;
+use scedge::ttl_tuner::TtlTuner;
+use scedge::upstream::UpstreamClient;
+use scedge::warmup::{Warmup, WarmupManifest};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -69,7 +86,7 @@ async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
     // Initialize tracing/logging
-    init_tracing();
+    let log_level = LogLevel::init();
 
     tracing::info!("Starting Scedge Core v{}", env!("CARGO_PKG_VERSION"));
 
@@ -77,18 +94,10 @@ async fn main() -> anyhow::Result<()> {
     let config = AppConfig::from_env()?;
     tracing::info!(
         redis_url = %config.redis_url,
-        listen_addr = %config.listen_addr,
+        listeners = config.listeners.len(),
         "Configuration loaded"
     );
 
-    // Initialize Redis cache
-    tracing::info!("Connecting to Redis...");
-    let redis_cache = RedisCache::new(&config.redis_url)?;
-    redis_cache.ping().await?;
-    tracing::info!("Redis connection established");
-
-    let cache = Cache::new(redis_cache);
-
     // Initialize metrics
     let metrics = if config.metrics_enabled {
         tracing::info!("Metrics enabled");
@@ -98,8 +107,139 @@ async fn main() -> anyhow::Result<()> {
         Metrics::default()
     };
 
+    // Lookup frequencies shared by the L1 and rendered responses
+    let admission = Admission::new(config.admission.sketch_width, config.admission.min_accesses)
+        .with_metrics(metrics.clone());
+
+    // Compression dictionaries, shared by the Redis backend and the cache
+    let mut dictionaries = Dictionaries::new();
+    if let Some(min_bytes) = config.compression_min_bytes {
+        dictionaries = dictionaries.with_min_plain_bytes(min_bytes);
+        tracing::info!(min_bytes, "Compressing large answers with zstd");
+    }
+
+    // Initialize the cache backend
+    let cache = match &config.cache_backend {
+        CacheBackendKind::Redis => {
+            tracing::info!("Connecting to Redis...");
+            let pools = &config.redis_pools;
+            let redis_cache = RedisCache::new(&config.redis_url)?
+                .with_pool(RedisWorkload::Read, &pools.read_url, pools.read_size)?
+                .with_pool(RedisWorkload::Write, &config.redis_url, pools.write_size)?
+                .with_pool(RedisWorkload::Scan, &pools.scan_url, pools.scan_size)?
+                .with_clock_skew_tolerance(config.clock_skew_tolerance)
+                .with_entry_format(config.redis_entry_format)
+                .with_dictionaries(dictionaries.clone());
+            redis_cache.ping().await?;
+            tracing::info!("Redis connection established");
+            match &config.l1 {
+                Some(l1) => {
+                    tracing::info!(
+                        capacity = l1.capacity,
+                        max_bytes = ?l1.max_bytes,
+                        eviction = ?l1.eviction,
+                        max_age_secs = l1.max_age.as_secs(),
+                        "L1 memory cache enabled"
+                    );
+                    let mut memory = MemoryCache::new()
+                        .with_max_entries(l1.capacity)
+                        .with_max_age(l1.max_age)
+                        .with_eviction(l1.eviction)
+                        .with_metrics(metrics.clone());
+                    if let Some(max_bytes) = l1.max_bytes {
+                        memory = memory.with_max_bytes(max_bytes);
+                    }
+                    let cache = Cache::new(
+                        TieredCache::with_l1(redis_cache, memory).with_admission(admission.clone()),
+                    );
+                    if l1.rendered_capacity > 0 {
+                        tracing::info!(
+                            capacity = l1.rendered_capacity,
+                            min_hits = l1.render_min_hits,
+                            "Rendered lookups of hot keys enabled"
+                        );
+                        cache.with_rendered_responses(Arc::new(RenderedResponses::new(
+                            l1.rendered_capacity,
+                            l1.max_age,
+                            l1.render_min_hits,
+                        )))
+                    } else {
+                        cache
+                    }
+                }
+                None => Cache::new(redis_cache),
+            }
+        }
+        CacheBackendKind::Sqlite { path, .. } => {
+            let sqlite_cache = SqliteCache::open(path)?;
+            tracing::info!(path = %path.display(), "SQLite cache opened");
+            Cache::new(sqlite_cache)
+        }
+        #[cfg(feature = "rocksdb")]
+        CacheBackendKind::RocksDb { path } => {
+            let rocksdb_cache = scedge::cache::RocksDbCache::open(path)?;
+            tracing::info!(path = %path.display(), "RocksDB cache opened");
+            Cache::new(rocksdb_cache)
+        }
+    };
+
+    let keyring = Keyring::new();
+    let mut cache = cache
+        .with_scan_limits(config.scan_limits)
+        .with_keyring(keyring.clone())
+        .with_dictionaries(dictionaries)
+        .with_stale_copies(Arc::new(StaleCopies::new(config.stale_copies_capacity)));
+    if let Some(wal) = &config.wal {
+        cache = cache.with_wal(wal.clone());
+        tracing::info!(stream = %wal.stream, max_len = wal.max_len, "Write-ahead log enabled");
+    }
+    if let Some(filter_config) = &config.key_filter {
+        let key_filter = Arc::new(KeyFilter::new(
+            filter_config.capacity,
+            filter_config.false_positive_rate,
+        ));
+        cache = cache.with_key_filter(key_filter.clone());
+        key_filter.spawn_rebuild(cache.clone(), filter_config.rebuild_interval);
+        tracing::info!(
+            capacity = filter_config.capacity,
+            false_positive_rate = filter_config.false_positive_rate,
+            "Key bloom filters enabled"
+        );
+    }
+
+    if let Some(push) = config.pushgateway.clone() {
+        tracing::info!(url = %push.url, job = %push.job, "Pushing metrics to Pushgateway");
+        spawn_pusher(metrics.clone(), push)?;
+    }
+
+    let ttl_tuner = config.ttl_autotune.clone().map(|autotune| {
+        tracing::info!(
+            min_secs = autotune.min_ttl.as_secs(),
+            max_secs = autotune.max_ttl.as_secs(),
+            interval_secs = autotune.interval.as_secs(),
+            "Default TTL autotuning enabled"
+        );
+        let ttl_tuner = TtlTuner::new(autotune, config.default_ttl().as_secs());
+        ttl_tuner.spawn(metrics.clone());
+        ttl_tuner
+    });
+    if let Some(ttl_tuner) = &ttl_tuner {
+        cache = cache.with_ttl_tuner(ttl_tuner.clone());
+    }
+
     // Initialize policy engine
-    let policy_engine = PolicyEngine::new(config.jwt_secret.clone());
+    let policy_engine = PolicyEngine::new(config.jwt_secret.clone())
+        .with_bulkhead_timeout(config.bulkhead_timeout)
+        .with_admission(admission.clone());
+
+    let mut plugins = PolicyPlugins::new(
+        config.policy_plugin_fuel,
+        config.policy_plugin_max_memory_bytes,
+    )?;
+    if let Some(path) = &config.policy_plugin {
+        tracing::info!(plugin = %path.display(), "Loading operator policy plugin");
+        plugins = plugins.with_operator_plugin(path)?;
+    }
 
     // Load tenant configurations
     match config.load_tenants() {
@@ -109,8 +249,7 @@ async fn main() -> anyhow::Result<()> {
             } else {
                 tracing::info!(count = tenants.len(), "Loading tenant configurations");
                 for tenant in tenants {
-                    tracing::debug!(tenant_id = %tenant.tenant_id, "Loaded tenant");
-                    policy_engine.add_tenant(tenant).await;
+                    install_tenant(tenant, &policy_engine, &keyring, &plugins, &cache).await?;
                 }
             }
         }
@@ -127,7 +266,9 @@ async fn main() -> anyhow::Result<()> {
                 timeout_secs = cfg.timeout.as_secs(),
                 "Upstream lookup enabled"
             );
-            Some(UpstreamClient::try_new(cfg)?)
+            let client = UpstreamClient::try_new(cfg)?;
+            client.spawn_identity_reload();
+            Some(client)
         }
         None => {
             tracing::info!("Upstream lookup disabled");
@@ -135,18 +276,149 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    // Initialize event bus
-    let _event_bus_guard = if config.event_bus_enabled {
+    // Configure sibling node lookups (between local miss and upstream)
+    let peer_client = match config.peers.clone() {
+        Some(cfg) => {
+            tracing::info!(
+                peers = cfg.peers.len(),
+                fanout = cfg.fanout,
+                timeout_ms = cfg.timeout.as_millis() as u64,
+                "Peer lookup enabled"
+            );
+            Some(PeerClient::try_new(cfg)?)
+        }
+        None => None,
+    };
+
+    // Internal graph event bus: every transport feeds one invalidation engine
+    let graph_events = graph_event_channel();
+    let purge_queue = PurgeQueue::spawn(cache.clone(), metrics.clone(), &config.purge_queue);
+    let event_resync = EventResync::default();
+    InvalidationEngine::new(cache.clone())
+        .with_purge_queue(purge_queue)
+        .with_resync(event_resync.clone())
+        .with_ledger_retention(config.event_ledger_retention)
+        .spawn(graph_events.subscribe());
+    let recent_invalidations = RecentInvalidations::new();
+    recent_invalidations.spawn_recorder(graph_events.subscribe());
+
+    let (_event_bus_guard, event_bus_client) = if config.event_bus_enabled {
         tracing::info!(channel = %config.event_bus_channel, "Starting event bus");
         let event_config = EventBusConfig {
             url: config.event_bus_url.clone(),
             channel: config.event_bus_channel.clone(),
         };
-        let mut event_bus = EventBus::new(event_config, cache.clone());
-        Some(event_bus.start().await?)
+        let mut event_bus = EventBus::new(event_config, graph_events.clone());
+        let guard = event_bus.start().await?;
+        (Some(guard), event_bus.client())
     } else {
         tracing::info!("Event bus disabled");
-        None
+        (None, None)
+    };
+
+    if let Some(channel) = config.event_bus_redis_channel.clone() {
+        start_redis_transport(&config.redis_url, channel, graph_events.clone()).await?;
+    }
+
+    let event_lag = config.event_lag.clone().map(|lag| {
+        tracing::info!(
+            max_pending = lag.max_pending,
+            "Tracking invalidation backlog"
+        );
+        let monitor = EventLagMonitor::new(lag.max_pending).with_fail_readiness(lag.fail_readiness);
+        let jetstream = event_bus_client.clone().zip(lag.jetstream);
+        monitor.spawn(graph_events.clone(), jetstream, lag.interval);
+        monitor
+    });
+
+    // Consume policy events for the audit log, metrics, and optional NATS subject
+    spawn_policy_audit(policy_engine.subscribe(), metrics.clone());
+
+    if let (true, Some(subject)) = (
+        config.event_bus_enabled,
+        config.policy_events_subject.clone(),
+    ) {
+        tracing::info!(subject = %subject, "Forwarding policy events to event bus");
+        forward_policy_events(&config.event_bus_url, subject, policy_engine.subscribe()).await?;
+    }
+
+    // Deliver store notifications queued in the outbox
+    OutboxWorker::new(
+        cache.clone(),
+        event_bus_client.clone(),
+        config.artifact_events_subject.clone(),
+        &config.outbound,
+        metrics.clone(),
+        &config.outbox,
+    )?
+    .spawn();
+
+    let node = NodeIdentity::resolve(&config.node)?;
+    tracing::info!(node_id = %node.node_id, region = ?node.region, "Node identity");
+    if let Some(heartbeat) = &config.heartbeat {
+        tracing::info!(
+            subject = ?heartbeat.subject,
+            url = ?heartbeat.url,
+            interval_secs = heartbeat.interval.as_secs(),
+            "Publishing fleet heartbeats"
+        );
+        HeartbeatPublisher::new(
+            node.clone(),
+            cache.clone(),
+            policy_engine.clone(),
+            metrics.clone(),
+            heartbeat,
+            event_bus_client.clone(),
+            &config.outbound,
+        )?
+        .spawn(heartbeat.interval);
+    }
+
+    let mirror = match config.mirror.clone() {
+        Some(cfg) => {
+            tracing::info!(
+                target = %cfg.target,
+                percent = cfg.percent,
+                "Lookup mirroring enabled"
+            );
+            Some(Mirror::try_new(cfg)?)
+        }
+        None => None,
+    };
+
+    // Index entries stored before the secondary indexes existed; until this
+    // has run, tenant and provenance purges scan for them
+    {
+        let cache = cache.clone();
+        tokio::spawn(async move {
+            match cache.backfill_indexes().await {
+                Ok(Some(indexed)) => tracing::info!(indexed, "Backfilled secondary indexes"),
+                Ok(None) => {}
+                Err(err) => tracing::warn!(error = %err, "Secondary index backfill failed"),
+            }
+        });
+    }
+
+    // Start scheduled purge rules
+    PurgeScheduler::new(cache.clone(), policy_engine.clone(), metrics.clone()).spawn();
+
+    // Reap expired entries the backend does not expire on its own, and prune
+    // index members of expired entries on every backend
+    ExpirySweeper::new(cache.clone(), metrics.clone(), config.expiry_sweep_interval).spawn();
+
+    // Load the warm-up manifest before serving, so a bad one fails startup
+    let warmup_targets = match &config.warmup {
+        Some(warmup) => {
+            if upstream_client.is_none() {
+                tracing::warn!("SCEDGE_WARMUP_MANIFEST set without an upstream; nothing to warm");
+            }
+            Some(WarmupManifest::load(&warmup.manifest)?.targets())
+        }
+        None => None,
+    };
+    let warmup = match &warmup_targets {
+        Some(targets) if upstream_client.is_some() => Warmup::start(targets.len()),
+        _ => Warmup::default(),
     };
 
     // Create application state
@@ -156,59 +428,141 @@ async fn main() -> anyhow::Result<()> {
         policy: policy_engine,
         default_ttl_seconds: config.default_ttl().as_secs(),
         upstream: upstream_client,
+        peers: peer_client,
+        http: reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?,
+        admin_token: config.admin_token.clone(),
+        xfetch_beta: config.xfetch_beta,
+        event_publisher: event_bus_client
+            .clone()
+            .map(|client| EventPublisher::spawn(client, config.event_bus_channel.clone())),
+        event_bus: event_bus_client,
+        graph_events,
+        event_lag,
+        event_resync,
+        recent_invalidations,
+        keyring,
+        ready_when_degraded: config.ready_when_degraded,
+        verify_hashes: config.verify_hashes,
+        debug_timings: config.debug_timings,
+        api_key_grace: config.api_key_grace,
+        max_batch_lookup_keys: config.max_batch_lookup_keys,
+        plugins,
+        admission,
+        read_only: Arc::new(AtomicBool::new(false)),
+        log_level: Some(log_level),
+        ttl_tuner,
+        mirror,
+        hydrations: Singleflight::new(),
+        drain: Drain::new(metrics.clone()),
+        key_generator: Arc::new(ContentKeyGenerator),
+        warmup,
     };
 
+    if let Some(restored) = overrides::load_node(&cache, &node.node_id).await? {
+        if restored.read_only {
+            tracing::warn!("Restored read-only mode from persisted node overrides");
+        }
+        state.read_only.store(restored.read_only, Ordering::Release);
+    }
+
+    if let Some(commands) = config.commands.clone() {
+        match state.event_bus.clone() {
+            Some(client) => {
+                tracing::info!(
+                    subject = %format!("{}.{}", commands.subject_prefix, node.node_id),
+                    "Accepting control-plane commands"
+                );
+                CommandChannel::new(client, state.clone(), commands, node.node_id.clone())
+                    .spawn()
+                    .await?;
+            }
+            None => {
+                tracing::warn!("SCEDGE_COMMAND_SECRET set without an event bus; commands disabled")
+            }
+        }
+    }
+
+    if let Some(sync) = config.sync.clone() {
+        let cache = state.cache.clone();
+        tokio::spawn(async move {
+            tracing::info!(source = %sync.source, "Copying cache from sibling");
+            match copy_from_sibling(&sync, &cache).await {
+                Ok(stored) => tracing::info!(stored, "Cache copied from sibling"),
+                Err(error) => tracing::warn!(%error, "Cache copy from sibling failed"),
+            }
+        });
+    }
+
+    let warming = state.warmup.is_warming();
+    if let (Some(config), Some(targets)) = (&config.warmup, warmup_targets) {
+        state.warmup.spawn(state.clone(), targets, config);
+    }
+
     // Build router
-    let app = Router::new()
-        .route("/healthz", get(health))
-        .route("/health", get(health))
-        .route("/metrics", get(metrics_handler))
-        .route("/lookup", get(handle_lookup))
-        .route("/store", post(handle_store))
-        .route("/purge", post(handle_purge))
-        .route("/", get(index))
-        .layer(TraceLayer::new_for_http())
-        .with_state(state);
-
-    // Start server
-    let listen_addr = config.listen_addr();
-    let listener = tokio::net::TcpListener::bind(listen_addr).await?;
-
-    tracing::info!(%listen_addr, "Scedge Core is running");
+    let drain = state.drain.clone();
+    let app = router(state);
+
+    // Start server; a socket passed by systemd stands in for the first listener
+    let mut listeners = Vec::new();
+    let mut passed = systemd::listen_fds()?;
+    for listener in &config.listeners {
+        listeners.push(match passed.take() {
+            Some(socket) => Listener::adopt(tokio::net::TcpListener::from_std(socket)?, listener)?,
+            None => Listener::bind(listener)?,
+        });
+    }
+
+    for listener in &listeners {
+        tracing::info!(
+            listen_addr = %listener.local_addr()?,
+            tls = listener.is_tls(),
+            "Scedge Core is running"
+        );
+    }
     tracing::info!("Endpoints:");
     tracing::info!("  GET  /healthz        - Health check");
+    tracing::info!("  GET  /readyz         - Readiness check");
     tracing::info!("  GET  /metrics        - Prometheus metrics");
     tracing::info!("  GET  /lookup?key=... - Lookup artifact");
+    tracing::info!("  POST /lookup/batch   - Batch lookup for one tenant");
+    tracing::info!("  GET  /lookup/by-hash - Find artifacts by content hash");
+    tracing::info!("  POST /contains       - Batch presence check");
     tracing::info!("  POST /store          - Store artifact");
+    tracing::info!("  POST /store/batch    - Store many artifacts");
+    tracing::info!("  POST /touch          - Extend artifact TTLs");
+    tracing::info!("  POST /policy/evaluate - Dry-run tenant policy checks");
     tracing::info!("  POST /purge          - Purge artifacts");
+    tracing::info!("  POST /purge/schedules - Register purge schedule");
+    tracing::info!("  GET  /proxy/:tenant/* - Caching proxy to tenant origin");
+    if config.admin_token.is_some() {
+        tracing::info!("  POST /invalidate     - Publish graph event");
+    }
+    if config.admin_token.is_some() {
+        tracing::info!("  POST /admin/tenants/:id/export - Export tenant artifacts");
+        tracing::info!("  DELETE /admin/tenants/:id/data - Erase tenant artifacts");
+        tracing::info!("  POST /admin/tenants/:id/keys - Rotate tenant encryption key");
+        tracing::info!("  GET  /admin/sync     - Stream entries to a new sibling");
+        tracing::info!("  PUT  /admin/loglevel - Replace the log filter");
+        tracing::info!("  GET  /console        - Operator console");
+    }
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // A warm-up reports readiness itself once it finishes
+    if !warming {
+        systemd::notify("READY=1");
+    }
+    listeners::serve(listeners, app, async move {
+        shutdown_signal().await;
+        drain.begin(config.shutdown_drain_timeout);
+    })
+    .await?;
 
     tracing::info!("Scedge Core shut down cleanly");
 
     Ok(())
 }
 
-async fn index() -> Html<&'static str> {
-    Html(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/static/index.html")))
-}
-
-fn init_tracing() {
-    let filter = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new("info"))
-        .unwrap();
-
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(true)
-        .with_thread_ids(false)
-        .with_file(true)
-        .with_line_number(true)
-        .init();
-}
-
 async fn shutdown_signal() {
     let ctrl_c = async {
         if let Err(error) = tokio::signal::ctrl_c().await {
@@ -242,4 +596,5 @@ async fn shutdown_signal() {
     }
 
     tracing::info!("Initiating graceful shutdown...");
+    systemd::notify("STOPPING=1");
 }
@@ -5,9 +5,15 @@
 //!
 //! Tracks cache performance, request patterns, and system health.
 
-use prometheus::{Counter, Histogram, HistogramOpts, IntCounter, IntGauge, Opts, Registry};
+use prometheus::core::Collector;
+use prometheus::{
+    Counter, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry,
+};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use crate::config::PushgatewayConfig;
 use crate::error::AppError;
 
 /// Metrics collector for Scedge
@@ -21,66 +27,127 @@ pub struct Metrics {
     pub cache_stores: IntCounter,
     pub cache_purges: IntCounter,
     pub cache_size: IntGauge,
+    pub purge_queue_depth: IntGauge,
+    pub purge_retries: IntCounter,
+    pub tenant_lookups: IntCounterVec,
+    pub backend_unavailable: IntCounterVec,
 
     // Request metrics
     pub requests_total: Counter,
     pub request_duration: Histogram,
+    pub in_flight_requests: IntGauge,
+    pub draining: IntGauge,
 
     // Upstream hydration metrics
     pub upstream_requests: IntCounter,
     pub upstream_failures: IntCounter,
     pub upstream_latency: Histogram,
+    pub early_refreshes: IntCounter,
+    pub coalesced_hydrations: IntCounter,
+    pub low_score_bypasses: IntCounter,
+    pub stale_on_error: IntCounter,
+    pub admission_rejections: IntCounterVec,
+    pub mirrored_requests: IntCounterVec,
+
+    // Peer lookup metrics
+    pub peer_requests: IntCounter,
+    pub peer_hits: IntCounter,
+
+    // Cost metrics
+    pub compute_cost_saved: Counter,
+
+    // Batch lookup metrics
+    pub batch_lookup_latency: Histogram,
+
+    // Policy metrics
+    pub policy_denials: IntCounterVec,
+    pub auth_failures: IntCounterVec,
+    pub throttled_requests: IntCounterVec,
 
     // Artifact metrics
     pub artifacts_stored: IntCounter,
     pub artifacts_expired: IntCounter,
+    pub memory_evictions: IntCounterVec,
+    pub expired_reads: IntCounterVec,
+    pub outbox_deliveries: IntCounterVec,
+
+    // TTL autotune metrics
+    pub tenant_default_ttl: IntGaugeVec,
+    pub ttl_adjustments: IntCounterVec,
 }
 
 impl Metrics {
     pub fn new() -> Result<Self, AppError> {
-        let registry = Registry::new();
+        Self::new_with_registry(Registry::new(), "scedge")
+    }
+
+    /// Register metrics into an existing registry with a custom name prefix
+    ///
+    /// Lets host applications embedding Scedge expose its metrics from their own
+    /// exporter. With prefix `"edge"`, `scedge_cache_hits_total` becomes
+    /// `edge_cache_hits_total`; an empty prefix drops it entirely.
+    pub fn new_with_registry(registry: Registry, prefix: &str) -> Result<Self, AppError> {
+        let name = |suffix: &str| {
+            if prefix.is_empty() {
+                suffix.to_string()
+            } else {
+                format!("{}_{}", prefix, suffix)
+            }
+        };
 
         // Cache hit/miss counters
         let cache_hits = IntCounter::with_opts(Opts::new(
-            "scedge_cache_hits_total",
+            name("cache_hits_total"),
             "Total number of cache hits",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let cache_misses = IntCounter::with_opts(Opts::new(
-            "scedge_cache_misses_total",
+            name("cache_misses_total"),
             "Total number of cache misses",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let cache_stores = IntCounter::with_opts(Opts::new(
-            "scedge_cache_stores_total",
+            name("cache_stores_total"),
             "Total number of cache stores",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let cache_purges = IntCounter::with_opts(Opts::new(
-            "scedge_cache_purges_total",
+            name("cache_purges_total"),
             "Total number of cache purges",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let cache_size = IntGauge::with_opts(Opts::new(
-            "scedge_cache_size",
+            name("cache_size"),
             "Current number of cached artifacts",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
+        let purge_queue_depth = IntGauge::with_opts(Opts::new(
+            name("purge_queue_depth"),
+            "Keys waiting in the event purge queue",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let purge_retries = IntCounter::with_opts(Opts::new(
+            name("purge_retries_total"),
+            "Failed event purge batches retried by a purge worker",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
         // Request metrics
         let requests_total = Counter::with_opts(Opts::new(
-            "scedge_requests_total",
+            name("requests_total"),
             "Total number of HTTP requests",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let request_duration = Histogram::with_opts(
             HistogramOpts::new(
-                "scedge_request_duration_seconds",
+                name("request_duration_seconds"),
                 "HTTP request duration in seconds",
             )
             .buckets(vec![
@@ -89,22 +156,34 @@ impl Metrics {
         )
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
+        let in_flight_requests = IntGauge::with_opts(Opts::new(
+            name("in_flight_requests"),
+            "HTTP requests currently being served",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let draining = IntGauge::with_opts(Opts::new(
+            name("draining"),
+            "1 while the node drains in-flight requests before shutting down",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
         // Upstream hydration metrics
         let upstream_requests = IntCounter::with_opts(Opts::new(
-            "scedge_upstream_requests_total",
+            name("upstream_requests_total"),
             "Total number of cache miss hydrations attempted against upstream",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let upstream_failures = IntCounter::with_opts(Opts::new(
-            "scedge_upstream_failures_total",
+            name("upstream_failures_total"),
             "Total number of upstream hydration attempts that resulted in an error",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let upstream_latency = Histogram::with_opts(
             HistogramOpts::new(
-                "scedge_upstream_latency_seconds",
+                name("upstream_latency_seconds"),
                 "Duration of upstream hydration requests in seconds",
             )
             .buckets(vec![
@@ -113,19 +192,183 @@ impl Metrics {
         )
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
+        let early_refreshes = IntCounter::with_opts(Opts::new(
+            name("early_refreshes_total"),
+            "Total number of probabilistic early refreshes triggered by cache hits",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let coalesced_hydrations = IntCounter::with_opts(Opts::new(
+            name("coalesced_hydrations_total"),
+            "Total number of hydrations served by another request's upstream call",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let stale_on_error = IntCounter::with_opts(Opts::new(
+            name("stale_on_error_total"),
+            "Lookups served from a stale in-process copy because the backend failed",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let low_score_bypasses = IntCounter::with_opts(Opts::new(
+            name("low_score_bypasses_total"),
+            "Total number of upstream artifacts served without caching due to a low score",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        // Peer lookup metrics
+        let peer_requests = IntCounter::with_opts(Opts::new(
+            name("peer_requests_total"),
+            "Total number of local misses looked up on sibling nodes",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let peer_hits = IntCounter::with_opts(Opts::new(
+            name("peer_hits_total"),
+            "Total number of local misses served by a sibling node",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        // Cost metrics
+        let compute_cost_saved = Counter::with_opts(Opts::new(
+            name("compute_cost_saved_total"),
+            "Total compute cost (artifact compute_cost) avoided by serving cache hits",
+        ))
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        // Batch lookup metrics
+        let batch_lookup_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                name("batch_lookup_latency_seconds"),
+                "Duration of tenant-authenticated batch lookups in seconds",
+            )
+            .buckets(vec![
+                0.001, 0.005, 0.010, 0.025, 0.050, 0.100, 0.250, 0.500, 1.0,
+            ]),
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let tenant_lookups = IntCounterVec::new(
+            Opts::new(
+                name("tenant_lookups_total"),
+                "Total number of lookups by tenant and result",
+            ),
+            &["tenant", "result"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let admission_rejections = IntCounterVec::new(
+            Opts::new(
+                name("admission_rejections_total"),
+                "Artifacts kept out of the L1 because their key had no predicted reuse",
+            ),
+            &["tenant"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let mirrored_requests = IntCounterVec::new(
+            Opts::new(
+                name("mirrored_requests_total"),
+                "Lookups duplicated to the shadow instance",
+            ),
+            &["outcome"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let backend_unavailable = IntCounterVec::new(
+            Opts::new(
+                name("backend_unavailable_total"),
+                "Cache reads that failed because the backend was unreachable, by tenant",
+            ),
+            &["tenant"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        // Policy metrics
+        let policy_denials = IntCounterVec::new(
+            Opts::new(
+                name("policy_denials_total"),
+                "Total number of requests denied by tenant policy",
+            ),
+            &["tenant", "rule"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+        let auth_failures = IntCounterVec::new(
+            Opts::new(
+                name("auth_failures_total"),
+                "Total number of requests rejected for their credentials",
+            ),
+            &["reason"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+        let throttled_requests = IntCounterVec::new(
+            Opts::new(
+                name("throttled_requests_total"),
+                "Total number of requests queued behind a tenant limit",
+            ),
+            &["tenant", "limit"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
         // Artifact metrics
         let artifacts_stored = IntCounter::with_opts(Opts::new(
-            "scedge_artifacts_stored_total",
+            name("artifacts_stored_total"),
             "Total number of artifacts stored",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
         let artifacts_expired = IntCounter::with_opts(Opts::new(
-            "scedge_artifacts_expired_total",
+            name("artifacts_expired_total"),
             "Total number of artifacts expired",
         ))
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
 
+        let memory_evictions = IntCounterVec::new(
+            Opts::new(
+                name("memory_evictions_total"),
+                "Entries evicted from the in-memory cache to stay within its bounds",
+            ),
+            &["reason"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let expired_reads = IntCounterVec::new(
+            Opts::new(
+                name("expired_reads_total"),
+                "Lookups finding an entry past its expiry, by how they were answered",
+            ),
+            &["outcome"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let outbox_deliveries = IntCounterVec::new(
+            Opts::new(
+                name("outbox_deliveries_total"),
+                "Outbox notification delivery attempts, by outcome",
+            ),
+            &["outcome"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        // TTL autotune metrics
+        let tenant_default_ttl = IntGaugeVec::new(
+            Opts::new(
+                name("tenant_default_ttl_seconds"),
+                "Tuned default TTL applied to a tenant's artifacts without ttl_seconds",
+            ),
+            &["tenant"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
+        let ttl_adjustments = IntCounterVec::new(
+            Opts::new(
+                name("ttl_adjustments_total"),
+                "Default TTL tuning decisions by tenant and direction",
+            ),
+            &["tenant", "direction"],
+        )
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create metric: {}", e)))?;
+
         // Register all metrics
         registry
             .register(Box::new(cache_hits.clone()))
@@ -142,12 +385,30 @@ impl Metrics {
         registry
             .register(Box::new(cache_size.clone()))
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(purge_queue_depth.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(purge_retries.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(tenant_lookups.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(backend_unavailable.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
         registry
             .register(Box::new(requests_total.clone()))
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
         registry
             .register(Box::new(request_duration.clone()))
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(in_flight_requests.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(draining.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
         registry
             .register(Box::new(upstream_requests.clone()))
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
@@ -157,12 +418,66 @@ impl Metrics {
         registry
             .register(Box::new(upstream_latency.clone()))
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(early_refreshes.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(coalesced_hydrations.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(low_score_bypasses.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(stale_on_error.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(admission_rejections.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(mirrored_requests.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(peer_requests.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(peer_hits.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(compute_cost_saved.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(batch_lookup_latency.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(policy_denials.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(auth_failures.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(throttled_requests.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
         registry
             .register(Box::new(artifacts_stored.clone()))
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
         registry
             .register(Box::new(artifacts_expired.clone()))
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(memory_evictions.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(expired_reads.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(outbox_deliveries.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(tenant_default_ttl.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
+        registry
+            .register(Box::new(ttl_adjustments.clone()))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to register metric: {}", e)))?;
 
         Ok(Self {
             registry: Arc::new(registry),
@@ -171,13 +486,37 @@ impl Metrics {
             cache_stores,
             cache_purges,
             cache_size,
+            purge_queue_depth,
+            purge_retries,
+            tenant_lookups,
+            backend_unavailable,
             requests_total,
             request_duration,
+            in_flight_requests,
+            draining,
             upstream_requests,
             upstream_failures,
             upstream_latency,
+            early_refreshes,
+            coalesced_hydrations,
+            low_score_bypasses,
+            stale_on_error,
+            admission_rejections,
+            mirrored_requests,
+            peer_requests,
+            peer_hits,
+            compute_cost_saved,
+            batch_lookup_latency,
+            policy_denials,
+            auth_failures,
+            throttled_requests,
             artifacts_stored,
             artifacts_expired,
+            memory_evictions,
+            expired_reads,
+            outbox_deliveries,
+            tenant_default_ttl,
+            ttl_adjustments,
         })
     }
 
@@ -191,6 +530,57 @@ impl Metrics {
         self.cache_misses.inc();
     }
 
+    /// Record a lookup outcome for a tenant
+    pub fn record_tenant_lookup(&self, tenant: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.tenant_lookups
+            .with_label_values(&[tenant, result])
+            .inc();
+    }
+
+    /// Record a cache read that failed because the backend was unreachable
+    ///
+    /// Kept apart from misses so outages do not show up as a falling hit ratio.
+    pub fn record_backend_unavailable(&self, tenant: &str) {
+        self.backend_unavailable.with_label_values(&[tenant]).inc();
+    }
+
+    /// Hit and miss counts per tenant since startup
+    pub fn tenant_lookup_counts(&self) -> BTreeMap<String, (u64, u64)> {
+        let mut counts: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+
+        for family in self.tenant_lookups.collect() {
+            for metric in family.get_metric() {
+                let label = |name: &str| {
+                    metric
+                        .get_label()
+                        .iter()
+                        .find(|pair| pair.get_name() == name)
+                        .map(|pair| pair.get_value().to_string())
+                        .unwrap_or_default()
+                };
+                let value = metric.get_counter().get_value() as u64;
+                let entry = counts.entry(label("tenant")).or_default();
+                match label("result").as_str() {
+                    "hit" => entry.0 += value,
+                    _ => entry.1 += value,
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// Record a default TTL tuning decision and the resulting TTL
+    pub fn record_ttl_decision(&self, tenant: &str, direction: &str, ttl_seconds: u64) {
+        self.ttl_adjustments
+            .with_label_values(&[tenant, direction])
+            .inc();
+        self.tenant_default_ttl
+            .with_label_values(&[tenant])
+            .set(ttl_seconds as i64);
+    }
+
     /// Record a cache store operation
     pub fn record_cache_store(&self) {
         self.cache_stores.inc();
@@ -207,9 +597,53 @@ impl Metrics {
         self.cache_size.set(size);
     }
 
-    /// Record an artifact expiration
-    pub fn record_artifact_expired(&self) {
-        self.artifacts_expired.inc();
+    /// Record keys added to the event purge queue
+    pub fn record_purge_enqueued(&self, keys: usize) {
+        self.purge_queue_depth.add(keys as i64);
+    }
+
+    /// Record keys taken off the event purge queue
+    pub fn record_purge_dequeued(&self, keys: usize) {
+        self.purge_queue_depth.sub(keys as i64);
+    }
+
+    /// Record a failed event purge batch that will be retried
+    pub fn record_purge_retry(&self) {
+        self.purge_retries.inc();
+    }
+
+    /// Record an artifact kept out of the L1 by admission control
+    pub fn record_admission_rejection(&self, tenant: &str) {
+        self.admission_rejections.with_label_values(&[tenant]).inc();
+    }
+
+    /// Record a mirrored lookup as `sent`, `failed`, or `dropped`
+    pub fn record_mirrored_request(&self, outcome: &str) {
+        self.mirrored_requests.with_label_values(&[outcome]).inc();
+    }
+
+    /// Record artifacts removed by the expiry sweeper
+    pub fn record_artifacts_expired(&self, count: u64) {
+        self.artifacts_expired.inc_by(count);
+    }
+
+    /// Record in-memory entries evicted for `entries` or `bytes`
+    pub fn record_memory_evictions(&self, reason: &str, count: u64) {
+        self.memory_evictions
+            .with_label_values(&[reason])
+            .inc_by(count);
+    }
+
+    /// Record a lookup finding an expired entry, answered as `miss`, `stale`,
+    /// or `refreshed`
+    pub fn record_expired_read(&self, outcome: &str) {
+        self.expired_reads.with_label_values(&[outcome]).inc();
+    }
+
+    /// Record an outbox delivery attempt that was `delivered`, will be
+    /// `retried`, or was `dead_lettered`
+    pub fn record_outbox_delivery(&self, outcome: &str) {
+        self.outbox_deliveries.with_label_values(&[outcome]).inc();
     }
 
     /// Record an upstream hydration attempt
@@ -227,6 +661,73 @@ impl Metrics {
         self.upstream_latency.observe(seconds);
     }
 
+    /// Mean upstream hydration latency in seconds, if any were observed
+    pub fn upstream_mean_latency(&self) -> Option<f64> {
+        let count = self.upstream_latency.get_sample_count();
+        if count == 0 {
+            None
+        } else {
+            Some(self.upstream_latency.get_sample_sum() / count as f64)
+        }
+    }
+
+    /// Record a probabilistic early refresh
+    pub fn record_early_refresh(&self) {
+        self.early_refreshes.inc();
+    }
+
+    /// Record a hydration that joined another request's upstream call
+    pub fn record_coalesced_hydration(&self) {
+        self.coalesced_hydrations.inc();
+    }
+
+    /// Record an upstream artifact served without caching because of its score
+    pub fn record_low_score_bypass(&self) {
+        self.low_score_bypasses.inc();
+    }
+
+    /// Record a lookup answered from a stale copy after a backend failure
+    pub fn record_stale_on_error(&self) {
+        self.stale_on_error.inc();
+    }
+
+    /// Record a peer lookup and whether a sibling served it
+    pub fn record_peer_lookup(&self, hit: bool) {
+        self.peer_requests.inc();
+        if hit {
+            self.peer_hits.inc();
+        }
+    }
+
+    /// Record the compute cost avoided by serving a cache hit
+    pub fn record_compute_cost_saved(&self, cost: Option<f64>) {
+        if let Some(cost) = cost.filter(|cost| cost.is_finite() && *cost > 0.0) {
+            self.compute_cost_saved.inc_by(cost);
+        }
+    }
+
+    /// Observe latency for a batch lookup in seconds
+    pub fn record_batch_lookup_latency(&self, seconds: f64) {
+        self.batch_lookup_latency.observe(seconds);
+    }
+
+    /// Record a request denied by tenant policy
+    pub fn record_policy_denied(&self, tenant: &str, rule: &str) {
+        self.policy_denials.with_label_values(&[tenant, rule]).inc();
+    }
+
+    /// Record a request rejected for its credentials
+    pub fn record_auth_failure(&self, reason: &str) {
+        self.auth_failures.with_label_values(&[reason]).inc();
+    }
+
+    /// Record a request that queued behind a tenant limit
+    pub fn record_throttled(&self, tenant: &str, limit: &str) {
+        self.throttled_requests
+            .with_label_values(&[tenant, limit])
+            .inc();
+    }
+
     /// Export metrics in Prometheus format
     pub fn export(&self) -> Result<String, AppError> {
         use prometheus::Encoder;
@@ -253,3 +754,54 @@ impl Default for Metrics {
         Self::new().expect("Failed to create default metrics")
     }
 }
+
+/// Periodically push the registry to a Prometheus Pushgateway
+///
+/// Edge sites behind NAT often cannot be scraped inbound. Each push replaces
+/// the group identified by the job and node labels (`PUT /metrics/job/...`).
+pub fn spawn_pusher(
+    metrics: Metrics,
+    config: PushgatewayConfig,
+) -> Result<tokio::task::JoinHandle<()>, AppError> {
+    let mut url = reqwest::Url::parse(&config.url)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid Pushgateway URL: {}", e)))?;
+    url.path_segments_mut()
+        .map_err(|_| AppError::Internal(anyhow::anyhow!("Pushgateway URL cannot be a base")))?
+        .pop_if_empty()
+        .extend(["metrics", "job", config.job.as_str()])
+        .extend(
+            config
+                .labels
+                .iter()
+                .flat_map(|(key, value)| [key.as_str(), value.as_str()]),
+        );
+
+    let client = reqwest::Client::new();
+
+    Ok(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.interval);
+        loop {
+            interval.tick().await;
+
+            let body = match metrics.export() {
+                Ok(body) => body,
+                Err(err) => {
+                    tracing::warn!(error = %err, "Failed to encode metrics for push");
+                    continue;
+                }
+            };
+
+            let result = client
+                .put(url.clone())
+                .header(reqwest::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+                .body(body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            if let Err(error) = result {
+                tracing::warn!(%error, "Failed to push metrics to Pushgateway");
+            }
+        }
+    }))
+}
@@ -0,0 +1,134 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lookup mirroring to a shadow instance.
+//!
+//! Validating a new backend or policy change against synthetic traffic misses
+//! the key distribution and tenant mix of production. With
+//! `SCEDGE_MIRROR_URL` set, a sampled `SCEDGE_MIRROR_PERCENT` of `GET
+//! /lookup` and `GET /lookup/by-hash` requests is replayed against that
+//! instance in the background, after the original has been answered.
+//!
+//! Mirrored requests keep their path, query, and namespace header but none of
+//! the caller's other headers, so credentials never leave the node;
+//! `SCEDGE_MIRROR_TOKEN` is sent as the bearer token instead. Responses are discarded. At most
+//! `SCEDGE_MIRROR_MAX_IN_FLIGHT` mirrored requests are outstanding; further
+//! samples are dropped rather than queued, so a slow shadow never holds
+//! resources of the production node.
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use reqwest::Client;
+use tokio::sync::Semaphore;
+
+use crate::api::AppState;
+use crate::config::MirrorConfig;
+use crate::error::AppError;
+use crate::metrics::Metrics;
+use crate::tenant::NAMESPACE_HEADER;
+
+/// Header marking a request replayed by a production node
+pub const MIRRORED_HEADER: &str = "x-scedge-mirrored";
+
+/// Routes whose requests are mirrored
+const MIRRORED_ROUTES: &[&str] = &["/lookup", "/lookup/by-hash"];
+
+/// Sampler and client replaying lookups against the shadow instance
+#[derive(Clone)]
+pub struct Mirror {
+    config: MirrorConfig,
+    client: Client,
+    in_flight: Arc<Semaphore>,
+}
+
+impl Mirror {
+    pub fn try_new(config: MirrorConfig) -> Result<Self, AppError> {
+        let client = Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| AppError::Internal(anyhow!("Failed to build mirror client: {}", e)))?;
+
+        Ok(Self {
+            in_flight: Arc::new(Semaphore::new(config.max_in_flight)),
+            config,
+            client,
+        })
+    }
+
+    /// Whether this request falls into the mirrored sample
+    fn sampled(&self) -> bool {
+        rand::random::<f64>() * 100.0 < self.config.percent
+    }
+
+    /// Replay `path_and_query` against the shadow in the background
+    fn replay(&self, path_and_query: String, namespace: Option<String>, metrics: Metrics) {
+        let Ok(permit) = self.in_flight.clone().try_acquire_owned() else {
+            metrics.record_mirrored_request("dropped");
+            return;
+        };
+
+        let mut request = self
+            .client
+            .get(format!("{}{}", self.config.target, path_and_query))
+            .header(MIRRORED_HEADER, "1");
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(namespace) = namespace {
+            request = request.header(NAMESPACE_HEADER, namespace);
+        }
+
+        tokio::spawn(async move {
+            match request.send().await {
+                Ok(_) => metrics.record_mirrored_request("sent"),
+                Err(err) => {
+                    tracing::debug!(path = %path_and_query, error = %err, "Mirrored lookup failed");
+                    metrics.record_mirrored_request("failed");
+                }
+            }
+            drop(permit);
+        });
+    }
+}
+
+/// Middleware duplicating a sample of lookups to the shadow instance
+pub async fn mirror_lookups(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(mirror) = &state.mirror else {
+        return next.run(request).await;
+    };
+
+    let mirrored = request.method() == Method::GET
+        && !request.headers().contains_key(MIRRORED_HEADER)
+        && request
+            .extensions()
+            .get::<MatchedPath>()
+            .is_some_and(|path| MIRRORED_ROUTES.contains(&path.as_str()))
+        && mirror.sampled();
+    let path_and_query = mirrored.then(|| {
+        request
+            .uri()
+            .path_and_query()
+            .map(|pq| pq.as_str().to_string())
+            .unwrap_or_else(|| request.uri().path().to_string())
+    });
+    let namespace = request
+        .headers()
+        .get(NAMESPACE_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+
+    let response = next.run(request).await;
+    if let Some(path_and_query) = path_and_query {
+        mirror.replay(path_and_query, namespace, state.metrics.clone());
+    }
+    response
+}
@@ -4,14 +4,69 @@
 //! Data models and schemas for knowledge artifacts.
 //!
 //! Defines the structure of cached artifacts with policy, provenance, and metrics.
+//! Embedders construct artifacts and store requests with
+//! [`ArtifactPayload::builder`] and [`StoreRequest::builder`], which validate
+//! at build time what `POST /store` would otherwise reject.
 
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::error::AppError;
+use crate::hashing::{self, HashAlgorithm};
+use crate::keys::validate_key;
+use crate::policy::PurgeSchedule;
+
 fn default_confidence() -> f32 {
     1.0
 }
 
+/// Media type of an artifact answer, used by `GET /lookup?raw=true`
+///
+/// Text answers are JSON strings; binary answers are base64-encoded strings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnswerContentType {
+    #[default]
+    #[serde(rename = "application/json")]
+    Json,
+    #[serde(rename = "text/plain")]
+    Text,
+    #[serde(rename = "application/octet-stream")]
+    Binary,
+}
+
+impl AnswerContentType {
+    /// `Content-Type` header of a raw answer
+    pub fn header_value(&self) -> &'static str {
+        match self {
+            AnswerContentType::Json => "application/json",
+            AnswerContentType::Text => "text/plain; charset=utf-8",
+            AnswerContentType::Binary => "application/octet-stream",
+        }
+    }
+
+    /// Raw body of `answer`, failing when it does not fit the content type
+    pub fn encode(&self, answer: &serde_json::Value) -> Result<Vec<u8>, AppError> {
+        match self {
+            AnswerContentType::Json => serde_json::to_vec(answer)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to encode answer: {}", e))),
+            AnswerContentType::Text => answer
+                .as_str()
+                .map(|text| text.as_bytes().to_vec())
+                .ok_or_else(|| AppError::bad_request("text/plain answers must be strings")),
+            AnswerContentType::Binary => answer
+                .as_str()
+                .and_then(|encoded| BASE64.decode(encoded).ok())
+                .ok_or_else(|| {
+                    AppError::bad_request("application/octet-stream answers must be base64 strings")
+                }),
+        }
+    }
+}
+
 /// Policy context for an artifact - defines access control and compliance requirements
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyContext {
@@ -24,6 +79,19 @@ pub struct PolicyContext {
     pub region: Option<String>,
     #[serde(default)]
     pub compliance_tags: Vec<String>,
+    /// JWT `user` claims allowed to read the artifact
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_subjects: Vec<String>,
+    /// JWT scopes, any of which allows reading the artifact
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_scopes: Vec<String>,
+}
+
+impl PolicyContext {
+    /// Whether reads are limited beyond the tenant
+    pub fn is_read_restricted(&self) -> bool {
+        !self.allowed_subjects.is_empty() || !self.allowed_scopes.is_empty()
+    }
 }
 
 /// Provenance information - tracks the source and lineage of knowledge
@@ -36,6 +104,32 @@ pub struct ProvenanceInfo {
     pub version: Option<String>,
     #[serde(default)]
     pub generated_at: Option<DateTime<Utc>>,
+    /// Knowledge capsule the source belongs to, when `source` does not name it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capsule_id: Option<String>,
+}
+
+impl ProvenanceInfo {
+    /// Capsule revoked by `REVOKE_CAPSULE` events naming it
+    ///
+    /// `capsule_id` when set, otherwise the segment following `capsule:` or
+    /// `capsules/` in `source`, e.g. `cap-42` for
+    /// `synagraph://acme/capsules/cap-42/v3`.
+    pub fn capsule(&self) -> Option<&str> {
+        if let Some(capsule_id) = &self.capsule_id {
+            return Some(capsule_id);
+        }
+        let source = self.source.as_str();
+        let start = ["capsule:", "capsules/"]
+            .iter()
+            .filter_map(|marker| source.rfind(marker).map(|at| at + marker.len()))
+            .max()?;
+        let capsule = source[start..]
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or_default();
+        (!capsule.is_empty()).then_some(capsule)
+    }
 }
 
 /// Artifact metrics - confidence scores and quality metrics
@@ -45,6 +139,9 @@ pub struct ArtifactMetrics {
     pub score: f32,
     #[serde(default)]
     pub generated_at: Option<DateTime<Utc>>,
+    /// Cost of regenerating the artifact (GPU-seconds or dollars)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_cost: Option<f64>,
     #[serde(flatten)]
     pub extra: serde_json::Value,
 }
@@ -56,6 +153,10 @@ pub struct ArtifactPayload {
     #[serde(alias = "content")]
     pub answer: serde_json::Value,
 
+    /// How `answer` is served by raw lookups; JSON when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_content_type: Option<AnswerContentType>,
+
     /// Policy context for access control
     pub policy: PolicyContext,
 
@@ -74,16 +175,263 @@ pub struct ArtifactPayload {
     /// Hash/ETag for versioning
     pub hash: String,
 
+    /// Free-form tags used for grouped invalidation
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Keys or provenance hashes this artifact is derived from
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+
+    /// Conversation, document, or other group purged as one unit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+
     /// Additional metadata
     #[serde(default)]
     pub metadata: Option<serde_json::Value>,
 }
 
+impl ArtifactPayload {
+    pub fn builder() -> ArtifactPayloadBuilder {
+        ArtifactPayloadBuilder::default()
+    }
+
+    /// Whether the artifact hash or any provenance hash equals `hash`.
+    /// Provenance entries without a hash never match.
+    pub fn references_hash(&self, hash: &str) -> bool {
+        self.hash == hash
+            || self
+                .provenance
+                .iter()
+                .any(|p| p.hash.as_deref() == Some(hash))
+    }
+
+    /// Whether any provenance source belongs to the capsule
+    pub fn references_capsule(&self, capsule_id: &str) -> bool {
+        self.provenance
+            .iter()
+            .any(|p| p.capsule() == Some(capsule_id))
+    }
+
+    /// Declared quality score, if the artifact carries metrics
+    pub fn score(&self) -> Option<f32> {
+        self.metrics.as_ref().map(|m| m.score)
+    }
+
+    /// Declared regeneration cost, if any
+    pub fn compute_cost(&self) -> Option<f64> {
+        self.metrics.as_ref().and_then(|m| m.compute_cost)
+    }
+}
+
+/// Builder for [`ArtifactPayload`], validated by [`ArtifactPayloadBuilder::build`]
+///
+/// ```
+/// use scedge::model::ArtifactPayload;
+///
+/// let artifact = ArtifactPayload::builder()
+///     .answer(serde_json::json!({ "text": "hello" }))
+///     .tenant("acme")
+///     .ttl(std::time::Duration::from_secs(3600))
+///     .tag("greetings")
+///     .build()?;
+/// assert!(artifact.hash.starts_with("sha256:"));
+/// # Ok::<(), scedge::error::AppError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactPayloadBuilder {
+    answer: Option<serde_json::Value>,
+    answer_content_type: Option<AnswerContentType>,
+    tenant: Option<String>,
+    phi: bool,
+    pii: bool,
+    region: Option<String>,
+    compliance_tags: Vec<String>,
+    allowed_subjects: Vec<String>,
+    allowed_scopes: Vec<String>,
+    provenance: Vec<ProvenanceInfo>,
+    metrics: Option<ArtifactMetrics>,
+    ttl: Option<std::time::Duration>,
+    hash: Option<String>,
+    hash_algorithm: HashAlgorithm,
+    tags: Vec<String>,
+    depends_on: Vec<String>,
+    family: Option<String>,
+    metadata: Option<serde_json::Value>,
+}
+
+impl ArtifactPayloadBuilder {
+    /// Answer or knowledge content (required)
+    pub fn answer(mut self, answer: impl Into<serde_json::Value>) -> Self {
+        self.answer = Some(answer.into());
+        self
+    }
+
+    /// Media type served by raw lookups; text answers must be strings and
+    /// binary answers base64 strings
+    pub fn answer_content_type(mut self, content_type: AnswerContentType) -> Self {
+        self.answer_content_type = Some(content_type);
+        self
+    }
+
+    /// Owning tenant (required)
+    pub fn tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    pub fn phi(mut self, phi: bool) -> Self {
+        self.phi = phi;
+        self
+    }
+
+    pub fn pii(mut self, pii: bool) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    pub fn compliance_tag(mut self, tag: impl Into<String>) -> Self {
+        self.compliance_tags.push(tag.into());
+        self
+    }
+
+    /// Restrict reads to JWTs with this `user` claim, or an allowed scope
+    pub fn allowed_subject(mut self, subject: impl Into<String>) -> Self {
+        self.allowed_subjects.push(subject.into());
+        self
+    }
+
+    /// Restrict reads to JWTs granting this scope, or an allowed subject
+    pub fn allowed_scope(mut self, scope: impl Into<String>) -> Self {
+        self.allowed_scopes.push(scope.into());
+        self
+    }
+
+    pub fn provenance(mut self, provenance: ProvenanceInfo) -> Self {
+        self.provenance.push(provenance);
+        self
+    }
+
+    pub fn metrics(mut self, metrics: ArtifactMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Time-to-live, in whole seconds
+    pub fn ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Explicit hash or version tag; computed from the answer when unset
+    pub fn hash(mut self, hash: impl Into<String>) -> Self {
+        self.hash = Some(hash.into());
+        self
+    }
+
+    /// Digest of the computed hash (SHA-256 by default)
+    pub fn hash_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = algorithm;
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn depends_on(mut self, reference: impl Into<String>) -> Self {
+        self.depends_on.push(reference.into());
+        self
+    }
+
+    pub fn family(mut self, family: impl Into<String>) -> Self {
+        self.family = Some(family.into());
+        self
+    }
+
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Validate the fields and assemble the payload
+    pub fn build(self) -> Result<ArtifactPayload, AppError> {
+        let answer = self
+            .answer
+            .ok_or_else(|| AppError::bad_request("artifact answer is required"))?;
+        if let Some(content_type) = self.answer_content_type {
+            content_type.encode(&answer)?;
+        }
+        let tenant = self
+            .tenant
+            .filter(|tenant| !tenant.trim().is_empty())
+            .ok_or_else(|| AppError::bad_request("artifact tenant is required"))?;
+
+        let ttl_seconds = match self.ttl {
+            Some(ttl) if ttl.as_secs() == 0 => {
+                return Err(AppError::bad_request("ttl must be at least one second"));
+            }
+            ttl => ttl.map(|ttl| ttl.as_secs()),
+        };
+
+        if let Some(metrics) = &self.metrics {
+            if !(0.0..=1.0).contains(&metrics.score) {
+                return Err(AppError::bad_request(
+                    "metrics score must be within 0.0..=1.0",
+                ));
+            }
+        }
+
+        let hash = match self.hash {
+            Some(hash) if hash.trim().is_empty() => {
+                return Err(AppError::bad_request("artifact hash is required"));
+            }
+            Some(hash) => {
+                if hashing::verify(&hash, &answer) == Some(false) {
+                    return Err(AppError::bad_request("artifact hash does not match answer"));
+                }
+                hash
+            }
+            None => hashing::artifact_hash(&answer, self.hash_algorithm),
+        };
+
+        Ok(ArtifactPayload {
+            answer,
+            answer_content_type: self.answer_content_type,
+            policy: PolicyContext {
+                tenant,
+                phi: self.phi,
+                pii: self.pii,
+                region: self.region,
+                compliance_tags: self.compliance_tags,
+                allowed_subjects: self.allowed_subjects,
+                allowed_scopes: self.allowed_scopes,
+            },
+            provenance: self.provenance,
+            metrics: self.metrics,
+            ttl_seconds,
+            hash,
+            tags: self.tags,
+            depends_on: self.depends_on,
+            family: self.family,
+            metadata: self.metadata,
+        })
+    }
+}
+
 impl Default for ArtifactMetrics {
     fn default() -> Self {
         Self {
             score: 1.0,
             generated_at: None,
+            compute_cost: None,
             extra: serde_json::Value::Object(serde_json::Map::new()),
         }
     }
@@ -91,15 +439,160 @@ impl Default for ArtifactMetrics {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoreRequest {
+    /// Cache key; when empty, the node derives one from the artifact
+    #[serde(default)]
     pub key: String,
     pub artifact: ArtifactPayload,
 }
 
+/// Builder for [`StoreRequest`], validated by [`StoreRequestBuilder::build`]
+#[derive(Debug, Clone, Default)]
+pub struct StoreRequestBuilder {
+    key: Option<String>,
+    artifact: Option<ArtifactPayload>,
+}
+
+impl StoreRequest {
+    pub fn builder() -> StoreRequestBuilder {
+        StoreRequestBuilder::default()
+    }
+}
+
+impl StoreRequestBuilder {
+    /// Cache key; without one, the node derives a key from the artifact
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Artifact to store (required)
+    pub fn artifact(mut self, artifact: ArtifactPayload) -> Self {
+        self.artifact = Some(artifact);
+        self
+    }
+
+    /// Validate the key, if any, and assemble the request
+    pub fn build(self) -> Result<StoreRequest, AppError> {
+        let key = self.key.unwrap_or_default();
+        if !key.is_empty() {
+            validate_key(&key)?;
+        }
+        let artifact = self
+            .artifact
+            .ok_or_else(|| AppError::bad_request("artifact is required"))?;
+        Ok(StoreRequest { key, artifact })
+    }
+}
+
+/// Query parameters of `POST /store`
+#[derive(Debug, Default, Deserialize)]
+pub struct StoreQuery {
+    /// Publish an `ARTIFACT_STORED` event and call tenant webhooks after caching
+    #[serde(default)]
+    pub notify: bool,
+    /// When to keep an existing live entry instead of replacing it
+    #[serde(default)]
+    pub mode: StoreMode,
+    /// Version the live entry must have, from the `If-Match` header
+    #[serde(skip)]
+    pub if_match: Option<EntryVersion>,
+}
+
+impl StoreQuery {
+    /// Mode the store runs in, `If-Match` taking precedence
+    pub fn store_mode(&self) -> StoreMode {
+        self.if_match.map_or(self.mode, StoreMode::IfMatch)
+    }
+}
+
+/// Whether a store replaces the live entry at its key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreMode {
+    /// Always replace it
+    #[default]
+    Always,
+    /// Keep any live entry
+    IfAbsent,
+    /// Keep a live entry with the same artifact hash
+    IfHashDiffers,
+    /// Replace only a live entry at this version
+    #[serde(skip)]
+    IfMatch(EntryVersion),
+}
+
+impl StoreMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StoreMode::Always => "always",
+            StoreMode::IfAbsent => "if_absent",
+            StoreMode::IfHashDiffers => "if_hash_differs",
+            StoreMode::IfMatch(_) => "if_match",
+        }
+    }
+
+    /// Whether `existing` is kept rather than replaced by `artifact`
+    pub fn keeps(self, existing: &CachedArtifact, artifact: &ArtifactPayload) -> bool {
+        match self {
+            StoreMode::Always => false,
+            StoreMode::IfAbsent => true,
+            StoreMode::IfHashDiffers => existing.artifact.hash == artifact.hash,
+            StoreMode::IfMatch(version) => existing.version() != version,
+        }
+    }
+}
+
+/// Opaque token naming one stored revision of an entry
+///
+/// Every store gives the entry a new version, so a writer that read version
+/// `v` can replace the entry with `If-Match: "v"` and be refused if another
+/// writer stored in between. Versions are derived from `stored_at`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryVersion(DateTime<Utc>);
+
+impl EntryVersion {
+    /// When the revision was stored
+    pub fn stored_at(self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+impl std::fmt::Display for EntryVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:x}{:08x}",
+            self.0.timestamp(),
+            self.0.timestamp_subsec_nanos()
+        )
+    }
+}
+
+impl std::str::FromStr for EntryVersion {
+    type Err = AppError;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        let invalid = || AppError::bad_request(format!("invalid version: {}", token));
+        let token = token.trim().trim_matches('"');
+        if token.len() <= 8 || !token.is_ascii() {
+            return Err(invalid());
+        }
+        let (secs, nanos) = token.split_at(token.len() - 8);
+        let secs = i64::from_str_radix(secs, 16).map_err(|_| invalid())?;
+        let nanos = u32::from_str_radix(nanos, 16).map_err(|_| invalid())?;
+        DateTime::from_timestamp(secs, nanos)
+            .map(EntryVersion)
+            .ok_or_else(invalid)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum StoreStatus {
     Created,
     Updated,
+    /// Not cached because the store mode kept the existing entry
+    Unchanged,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -107,6 +600,9 @@ pub struct StoreResponse {
     pub key: String,
     pub status: StoreStatus,
     pub hash: String,
+    /// Version of the entry now at the key, for `If-Match`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<DateTime<Utc>>,
 }
@@ -115,10 +611,29 @@ pub struct StoreResponse {
 pub struct LookupResponse {
     pub key: String,
     pub artifact: ArtifactPayload,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_at: Option<DateTime<Utc>>,
+    /// Version of the entry, for `If-Match` on a later store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ttl_remaining_seconds: Option<u64>,
+    /// Time spent per phase, for `X-Scedge-Debug: timings`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<LookupTimings>,
+}
+
+/// Milliseconds a lookup spent in each phase
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LookupTimings {
+    /// Bulkhead admission, pipeline resolution and policy plugins
+    pub policy_ms: f64,
+    /// Local cache stage
+    pub backend_ms: f64,
+    /// Peer and upstream stages
+    pub upstream_ms: f64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -126,6 +641,221 @@ pub struct LookupQuery {
     pub key: String,
     #[serde(default)]
     pub tenant: Option<String>,
+    /// Maximum acceptable age in seconds; older entries are treated as misses
+    #[serde(default)]
+    pub max_age: Option<u64>,
+    #[serde(default)]
+    pub consistency: Consistency,
+    /// Respond with the bare answer in its `answer_content_type`
+    #[serde(default)]
+    pub raw: bool,
+    /// Tenant answer template to reshape the answer with
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+/// How current a lookup's view of the cache must be
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Consistency {
+    /// Node-local state may answer, such as a bloom filter miss
+    #[default]
+    Eventual,
+    /// Read the backend, observing every completed store
+    Strong,
+}
+
+/// Expiry details of a cached entry, read without the artifact body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryMetadata {
+    pub tenant: String,
+    pub stored_at: DateTime<Utc>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// TTL declared by the artifact when it was stored
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TtlQuery {
+    pub key: String,
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TtlResponse {
+    pub key: String,
+    pub tenant: String,
+    pub stored_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_remaining_seconds: Option<u64>,
+    pub age_seconds: u64,
+}
+
+/// Query parameters of `GET /diff`
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub key: String,
+    /// Hash the caller expects the edge to serve, e.g. the graph's current one
+    pub against_hash: String,
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+/// Cached artifact compared against an expected hash
+#[derive(Debug, Serialize)]
+pub struct DiffResponse {
+    pub key: String,
+    pub tenant: String,
+    pub hash: String,
+    pub against_hash: String,
+    /// Whether the cached hash differs from `against_hash`
+    pub differs: bool,
+    /// Whether the cached answer itself hashes to `against_hash`; omitted for
+    /// hashes without a known algorithm prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer_matches: Option<bool>,
+    pub stored_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    pub provenance: Vec<ProvenanceDiff>,
+}
+
+/// Provenance entry of a cached artifact compared against an expected hash
+#[derive(Debug, Serialize)]
+pub struct ProvenanceDiff {
+    #[serde(flatten)]
+    pub entry: ProvenanceInfo,
+    /// Whether the entry's hash differs from `against_hash`; omitted for
+    /// entries without a hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub differs: Option<bool>,
+}
+
+/// Batch lookup for a single tenant, authenticated once for the whole batch
+#[derive(Debug, Deserialize)]
+pub struct BatchLookupRequest {
+    pub tenant: String,
+    pub keys: Vec<String>,
+}
+
+/// Query parameters of `GET /lookup/by-hash`
+#[derive(Debug, Deserialize)]
+pub struct HashLookupQuery {
+    pub hash: String,
+    pub tenant: String,
+}
+
+/// Cached artifacts of one tenant sharing a content hash
+#[derive(Debug, Serialize)]
+pub struct HashLookupResponse {
+    pub tenant: String,
+    pub hash: String,
+    pub artifacts: Vec<LookupResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchLookupResponse {
+    pub tenant: String,
+    pub hits: Vec<LookupResponse>,
+    pub misses: Vec<String>,
+    /// Keys that could not be looked up
+    pub errors: Vec<BatchItemError>,
+}
+
+/// Store many artifacts, each handled like a `POST /store`
+///
+/// Items are kept as raw JSON so a malformed item fails on its own.
+#[derive(Debug, Deserialize)]
+pub struct StoreBatchRequest {
+    pub items: Vec<serde_json::Value>,
+}
+
+/// Stored item of a batch, by position in the request
+#[derive(Debug, Serialize)]
+pub struct BatchStoreResult {
+    pub index: usize,
+    #[serde(flatten)]
+    pub response: StoreResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StoreBatchResponse {
+    pub stored: Vec<BatchStoreResult>,
+    pub errors: Vec<BatchItemError>,
+}
+
+/// Failure of one item of a batch, so callers can retry only that item
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemError {
+    /// Position of the item in the request
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Error kind; policy denials carry the rule, e.g. `ttl`
+    pub code: String,
+    pub message: String,
+}
+
+impl BatchItemError {
+    pub fn new(index: usize, key: Option<String>, error: &AppError) -> Self {
+        Self {
+            index,
+            key,
+            code: error.code().to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Extend the expiry of keys given in full
+#[derive(Debug, Deserialize)]
+pub struct TouchRequest {
+    pub keys: Vec<String>,
+    pub ttl_seconds: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TouchResponse {
+    pub results: Vec<TouchResult>,
+}
+
+/// Extend the expiry of listed keys, or of every key under a prefix
+#[derive(Debug, Deserialize)]
+pub struct TouchBatchRequest {
+    pub tenant: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// Touch keys starting with `{tenant}:{prefix}` instead of listing them
+    #[serde(default)]
+    pub prefix: Option<String>,
+    pub ttl_seconds: u64,
+    /// Resume a partial prefix touch
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TouchResult {
+    pub key: String,
+    pub touched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TouchBatchResponse {
+    pub tenant: String,
+    pub results: Vec<TouchResult>,
+    pub partial: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,11 +866,210 @@ pub struct PurgeRequest {
     pub tenant: Option<String>,
     #[serde(default)]
     pub provenance_hash: Option<String>,
+    /// Purge every artifact of a family within the tenant
+    #[serde(default)]
+    pub family: Option<String>,
+}
+
+/// Hypothetical artifact policy checked by `POST /policy/evaluate`
+#[derive(Debug, Deserialize)]
+pub struct PolicyEvaluateRequest {
+    pub policy: PolicyContext,
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    /// Scopes the caller's JWT must grant, e.g. `cache:purge`
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// Result of one policy rule
+#[derive(Debug, Serialize)]
+pub struct RuleOutcome {
+    pub rule: &'static str,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PolicyEvaluateResponse {
+    pub tenant: String,
+    /// Whether every rule passed
+    pub allowed: bool,
+    pub rules: Vec<RuleOutcome>,
+}
+
+/// Answer to compute a canonical hash for
+#[derive(Debug, Deserialize)]
+pub struct HashRequest {
+    #[serde(alias = "content")]
+    pub answer: serde_json::Value,
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HashResponse {
+    pub algorithm: HashAlgorithm,
+    pub hash: String,
+}
+
+/// Presence check for many keys of one tenant
+#[derive(Debug, Deserialize)]
+pub struct ContainsRequest {
+    pub tenant: String,
+    pub keys: Vec<String>,
+    #[serde(default = "default_include_hashes")]
+    pub include_hashes: bool,
+}
+
+fn default_include_hashes() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyPresence {
+    pub present: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContainsResponse {
+    pub tenant: String,
+    pub results: BTreeMap<String, KeyPresence>,
+}
+
+/// Register a recurring purge rule for a tenant
+#[derive(Debug, Deserialize)]
+pub struct PurgeScheduleRequest {
+    pub tenant: String,
+    #[serde(flatten)]
+    pub schedule: PurgeSchedule,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurgeScheduleResponse {
+    pub tenant: String,
+    pub schedules: Vec<PurgeSchedule>,
+}
+
+/// Newly minted API key returned by `POST /tenant/keys/rotate`
+#[derive(Debug, Serialize)]
+pub struct ApiKeyRotationResponse {
+    pub tenant: String,
+    pub api_key: String,
+    /// When the previous key stops being accepted
+    pub previous_valid_until: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct PurgeResponse {
     pub purged: usize,
+    /// Always false; purges are no longer paged
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Up,
+    Down,
+    Disabled,
+}
+
+/// Health of a single subsystem reported by `/readyz`
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub status: ComponentStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessStatus {
+    Ready,
+    Degraded,
+    NotReady,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessResponse {
+    pub status: ReadinessStatus,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+/// Query parameters of `GET /admin/sync`
+#[derive(Debug, Deserialize)]
+pub struct SyncQuery {
+    /// Only entries written at or after this time; every entry when unset
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Audit record produced by a verified tenant data erasure
+#[derive(Debug, Clone, Serialize)]
+pub struct ErasureRecord {
+    pub tenant: String,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub purged: usize,
+    pub remaining: usize,
+    pub verified: bool,
+}
+
+/// Result of registering a tenant encryption key
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyRotationResponse {
+    pub tenant: String,
+    pub active_key_id: String,
+}
+
+/// Options of `POST /admin/tenants/{id}/dictionary`
+#[derive(Debug, Deserialize)]
+pub struct DictionaryTrainingRequest {
+    /// Maximum cached answers sampled
+    #[serde(default = "default_dictionary_samples")]
+    pub samples: usize,
+    /// Maximum dictionary size in bytes
+    #[serde(default = "default_dictionary_size")]
+    pub max_size: usize,
+}
+
+impl Default for DictionaryTrainingRequest {
+    fn default() -> Self {
+        Self {
+            samples: default_dictionary_samples(),
+            max_size: default_dictionary_size(),
+        }
+    }
+}
+
+fn default_dictionary_samples() -> usize {
+    1000
+}
+
+fn default_dictionary_size() -> usize {
+    112_640
+}
+
+/// Result of training a tenant compression dictionary
+#[derive(Debug, Clone, Serialize)]
+pub struct DictionaryTrainingResponse {
+    pub tenant: String,
+    pub version: u32,
+    pub size_bytes: usize,
+    pub samples: usize,
+}
+
+/// Body and response of `GET|PUT /admin/loglevel`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFilter {
+    /// `EnvFilter` directives, e.g. `info,scedge::cache=debug`
+    pub filter: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -162,4 +1091,28 @@ impl CachedArtifact {
             }
         })
     }
+
+    /// Whether the record was stored within the last `max_age_seconds`
+    pub fn is_within_age(&self, max_age_seconds: u64, now: DateTime<Utc>) -> bool {
+        (now - self.stored_at).num_seconds() <= max_age_seconds as i64
+    }
+
+    /// Version of this revision, for `If-Match`
+    pub fn version(&self) -> EntryVersion {
+        EntryVersion(self.stored_at)
+    }
+
+    /// Convert the cached record into the lookup response body
+    pub fn into_lookup_response(self, now: DateTime<Utc>) -> LookupResponse {
+        let ttl_remaining_seconds = self.ttl_remaining_seconds(now);
+        LookupResponse {
+            version: Some(self.version().to_string()),
+            key: self.key,
+            artifact: self.artifact,
+            stored_at: Some(self.stored_at),
+            expires_at: self.expires_at,
+            ttl_remaining_seconds,
+            timings: None,
+        }
+    }
 }
@@ -0,0 +1,67 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Outbound HTTP clients for upstream hydration and webhook delivery.
+//!
+//! Both clients use rustls so custom trust roots behave the same on every
+//! platform, independent of the system OpenSSL or certificate store. Edge
+//! sites that only reach the internet through a corporate proxy can route
+//! outbound traffic through it and trust the proxy's private CA; see
+//! [`OutboundConfig`].
+
+use std::path::Path;
+
+use anyhow::anyhow;
+use reqwest::{Certificate, ClientBuilder, NoProxy, Proxy};
+
+use crate::config::OutboundConfig;
+use crate::error::AppError;
+
+/// Start a rustls client builder with the configured proxy and extra roots
+pub fn client_builder(outbound: &OutboundConfig) -> Result<ClientBuilder, AppError> {
+    let mut builder = reqwest::Client::builder().use_rustls_tls();
+
+    if let Some(url) = &outbound.proxy_url {
+        let proxy = Proxy::all(url)
+            .map_err(|e| AppError::Internal(anyhow!("Invalid outbound proxy {}: {}", url, e)))?
+            .no_proxy(outbound.no_proxy.as_deref().and_then(NoProxy::from_string));
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(ca_path) = &outbound.ca_bundle {
+        for cert in load_ca_bundle(ca_path)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Read every certificate of a PEM trust bundle
+pub fn load_ca_bundle(path: &Path) -> Result<Vec<Certificate>, AppError> {
+    let bundle = std::fs::read_to_string(path).map_err(|e| {
+        AppError::Internal(anyhow!(
+            "Failed to read CA bundle {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    split_pem_bundle(&bundle)
+        .iter()
+        .map(|pem| {
+            Certificate::from_pem(pem.as_bytes())
+                .map_err(|e| AppError::Internal(anyhow!("Invalid CA certificate: {}", e)))
+        })
+        .collect()
+}
+
+/// Split a PEM bundle into individual certificates
+fn split_pem_bundle(bundle: &str) -> Vec<String> {
+    const END: &str = "-----END CERTIFICATE-----";
+    bundle
+        .split_inclusive(END)
+        .filter(|chunk| chunk.contains("-----BEGIN CERTIFICATE-----"))
+        .map(|chunk| chunk.trim().to_string())
+        .collect()
+}
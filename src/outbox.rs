@@ -0,0 +1,278 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Outbound artifact notifications.
+//!
+//! `POST /store?notify=true` enqueues an [`OutboxMessage`] in the cache
+//! backend right after the artifact is written. A single [`OutboxWorker`]
+//! drains the outbox, publishing the event to the NATS artifact subject and
+//! POSTing it to every webhook the tenant had configured at store time.
+//! Producers therefore integrate with the HTTP API only, and delivery never
+//! blocks the store request.
+//!
+//! Webhooks are POSTed through the [`outbound`](crate::outbound) client, so
+//! the configured proxy and CA bundle apply.
+//!
+//! Delivery is at least once. A claimed notification stays in the backend's
+//! in-flight list until every target accepted it, and a restarted worker
+//! returns whatever was left in flight to the queue. Targets that fail are
+//! retried with exponential backoff; a notification still failing after
+//! `SCEDGE_OUTBOX_MAX_ATTEMPTS` attempts, or one that cannot be parsed, is
+//! moved to the dead-letter list instead of blocking the queue.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::cache::Cache;
+use crate::config::{OutboundConfig, OutboxConfig};
+use crate::error::AppError;
+use crate::metrics::Metrics;
+use crate::outbound;
+
+/// How long the worker waits before polling an empty outbox again
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Timeout applied to each webhook delivery
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Artifact lifecycle events emitted to producers and subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ArtifactEvent {
+    /// An artifact was written to the cache
+    ArtifactStored {
+        tenant: String,
+        key: String,
+        hash: String,
+        stored_at: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// Notification persisted in the outbox until delivered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    pub event: ArtifactEvent,
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+}
+
+/// Enqueue a notification for the outbox worker
+pub async fn enqueue(cache: &Cache, message: &OutboxMessage) -> Result<(), AppError> {
+    let entry = serde_json::to_string(message).map_err(|e| {
+        AppError::Internal(anyhow::anyhow!("Failed to serialize notification: {}", e))
+    })?;
+    cache.outbox_push(entry).await
+}
+
+/// Background task delivering outbox notifications
+pub struct OutboxWorker {
+    cache: Cache,
+    event_bus: Option<async_nats::Client>,
+    subject: String,
+    http: reqwest::Client,
+    metrics: Metrics,
+    retry: OutboxConfig,
+}
+
+/// Where a notification is delivered
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    EventBus,
+    Webhook(String),
+}
+
+/// Claimed notification with targets left to deliver to
+struct Pending {
+    /// Entry as claimed, to acknowledge or dead-letter it
+    entry: String,
+    event: ArtifactEvent,
+    targets: Vec<Target>,
+    attempts: u32,
+}
+
+impl OutboxWorker {
+    pub fn new(
+        cache: Cache,
+        event_bus: Option<async_nats::Client>,
+        subject: String,
+        outbound: &OutboundConfig,
+        metrics: Metrics,
+        retry: &OutboxConfig,
+    ) -> Result<Self, AppError> {
+        let http = outbound::client_builder(outbound)?
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to build client: {}", e)))?;
+
+        Ok(Self {
+            cache,
+            event_bus,
+            subject,
+            http,
+            metrics,
+            retry: retry.clone(),
+        })
+    }
+
+    /// Spawn the delivery loop onto the runtime
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        match self.cache.outbox_recover().await {
+            Ok(0) => {}
+            Ok(recovered) => {
+                tracing::info!(recovered, "Requeued notifications left in flight")
+            }
+            Err(error) => tracing::warn!(%error, "Failed to requeue in-flight notifications"),
+        }
+
+        // Notifications waiting for a retry, by when it is due
+        let mut retries: BTreeMap<(Instant, u64), Pending> = BTreeMap::new();
+        let mut sequence = 0u64;
+
+        loop {
+            let next_due = retries.first_key_value().map(|(&(due, _), _)| due);
+            let pending = if next_due.is_some_and(|due| due <= Instant::now()) {
+                retries.pop_first().map(|(_, pending)| pending)
+            } else {
+                self.claim().await
+            };
+            let Some(pending) = pending else {
+                let wake = next_due.map_or(POLL_INTERVAL, |due| {
+                    due.saturating_duration_since(Instant::now())
+                        .min(POLL_INTERVAL)
+                });
+                tokio::time::sleep(wake).await;
+                continue;
+            };
+
+            if let Some((due, pending)) = self.attempt(pending).await {
+                sequence += 1;
+                retries.insert((due, sequence), pending);
+            }
+        }
+    }
+
+    /// Claim the next notification, dead-lettering malformed ones
+    async fn claim(&self) -> Option<Pending> {
+        let entry = match self.cache.outbox_claim().await {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return None,
+            Err(error) => {
+                tracing::warn!(%error, "Failed to read outbox");
+                return None;
+            }
+        };
+
+        match serde_json::from_str::<OutboxMessage>(&entry) {
+            Ok(message) => {
+                let targets = self
+                    .event_bus
+                    .iter()
+                    .map(|_| Target::EventBus)
+                    .chain(message.webhooks.into_iter().map(Target::Webhook))
+                    .collect();
+                Some(Pending {
+                    entry,
+                    event: message.event,
+                    targets,
+                    attempts: 0,
+                })
+            }
+            Err(error) => {
+                tracing::error!(%error, entry = %entry, "Dead-lettering malformed outbox entry");
+                self.dead_letter(&entry).await;
+                None
+            }
+        }
+    }
+
+    /// Deliver to the remaining targets, returning the notification and when
+    /// to retry it if any failed
+    async fn attempt(&self, mut pending: Pending) -> Option<(Instant, Pending)> {
+        let payload = match serde_json::to_vec(&pending.event) {
+            Ok(payload) => payload,
+            Err(error) => {
+                tracing::error!(%error, "Failed to serialize artifact event");
+                self.dead_letter(&pending.entry).await;
+                return None;
+            }
+        };
+
+        let mut failed = Vec::new();
+        for target in pending.targets {
+            if let Err(error) = self.deliver(&target, &payload).await {
+                tracing::warn!(%error, target = ?target, "Notification delivery failed");
+                failed.push(target);
+            }
+        }
+        pending.targets = failed;
+        pending.attempts += 1;
+
+        if pending.targets.is_empty() {
+            self.metrics.record_outbox_delivery("delivered");
+            if let Err(error) = self.cache.outbox_ack(&pending.entry).await {
+                tracing::warn!(%error, "Failed to acknowledge delivered notification");
+            }
+            return None;
+        }
+
+        if pending.attempts >= self.retry.max_attempts {
+            tracing::error!(
+                attempts = pending.attempts,
+                targets = ?pending.targets,
+                "Dead-lettering undeliverable notification"
+            );
+            self.dead_letter(&pending.entry).await;
+            return None;
+        }
+
+        self.metrics.record_outbox_delivery("retried");
+        Some((Instant::now() + self.backoff(pending.attempts), pending))
+    }
+
+    /// Delay before the retry following the `attempts`-th failure
+    fn backoff(&self, attempts: u32) -> Duration {
+        self.retry
+            .retry_base
+            .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+            .min(self.retry.retry_max)
+    }
+
+    async fn dead_letter(&self, entry: &str) {
+        self.metrics.record_outbox_delivery("dead_lettered");
+        if let Err(error) = self.cache.outbox_dead_letter(entry).await {
+            tracing::warn!(%error, "Failed to dead-letter notification");
+        }
+    }
+
+    async fn deliver(&self, target: &Target, payload: &[u8]) -> anyhow::Result<()> {
+        match target {
+            Target::EventBus => {
+                if let Some(client) = &self.event_bus {
+                    client
+                        .publish(self.subject.clone(), payload.to_vec().into())
+                        .await?;
+                }
+            }
+            Target::Webhook(webhook) => {
+                self.http
+                    .post(webhook)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(payload.to_vec())
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime overrides persisted in the cache backend.
+//!
+//! Operators mitigate incidents at runtime: a tenant's concurrency is capped,
+//! its writes are frozen, a feature is switched off, or a whole node goes
+//! read-only. These overrides are written to the backend's control namespace
+//! (`scedge:control:*` in Redis) so a restart does not silently revert them.
+//!
+//! Tenant overrides are applied on top of the tenants file whenever a tenant
+//! is installed, at startup and on `reload_config`. The node's read-only flag
+//! is restored at startup. API keys minted by a rotation are persisted the
+//! same way, together with the keys they retired, and so are purge schedules
+//! registered through the API.
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::Cache;
+use crate::error::AppError;
+use crate::policy::{PurgeSchedule, RetiredApiKey, TenantConfig};
+
+/// Tenant settings replaced at runtime; unset fields keep the configured value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TenantOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admission_control: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serve_stale_on_error: Option<bool>,
+}
+
+impl TenantOverrides {
+    /// Overlay the set fields onto `tenant`
+    pub fn apply(&self, tenant: &mut TenantConfig) {
+        if let Some(max_concurrency) = self.max_concurrency {
+            tenant.max_concurrency = Some(max_concurrency);
+        }
+        if let Some(read_only) = self.read_only {
+            tenant.read_only = read_only;
+        }
+        if let Some(admission_control) = self.admission_control {
+            tenant.admission_control = admission_control;
+        }
+        if let Some(serve_stale_on_error) = self.serve_stale_on_error {
+            tenant.serve_stale_on_error = serve_stale_on_error;
+        }
+    }
+}
+
+/// API keys of a tenant after one or more rotations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotatedApiKeys {
+    /// Key from the tenants file the rotations started from
+    pub configured: String,
+    pub api_key: String,
+    #[serde(default)]
+    pub retired: Vec<RetiredApiKey>,
+}
+
+impl RotatedApiKeys {
+    /// Replace `tenant`'s keys, unless the tenants file has changed its key
+    /// since the rotation; that key wins and the rotation is dropped
+    pub fn apply(&self, tenant: &mut TenantConfig) -> bool {
+        if tenant.api_key != self.configured {
+            return false;
+        }
+        tenant.api_key = self.api_key.clone();
+        tenant.retired_api_keys = self.retired.clone();
+        true
+    }
+}
+
+/// Node settings replaced at runtime
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeOverrides {
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+fn tenant_record(tenant_id: &str) -> String {
+    format!("overrides:tenant:{}", tenant_id)
+}
+
+fn api_keys_record(tenant_id: &str) -> String {
+    format!("api_keys:tenant:{}", tenant_id)
+}
+
+fn purge_schedules_record(tenant_id: &str) -> String {
+    format!("purge_schedules:tenant:{}", tenant_id)
+}
+
+fn node_record(node_id: &str) -> String {
+    format!("overrides:node:{}", node_id)
+}
+
+async fn load<T: serde::de::DeserializeOwned>(
+    cache: &Cache,
+    name: &str,
+) -> Result<Option<T>, AppError> {
+    match cache.control_get(name).await? {
+        Some(raw) => serde_json::from_str(&raw).map(Some).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Invalid control record {}: {}", name, e))
+        }),
+        None => Ok(None),
+    }
+}
+
+async fn save<T: Serialize>(cache: &Cache, name: &str, value: &T) -> Result<(), AppError> {
+    let raw = serde_json::to_string(value).map_err(|e| {
+        AppError::Internal(anyhow::anyhow!("Failed to encode control record: {}", e))
+    })?;
+    cache.control_set(name, &raw).await
+}
+
+/// Persisted overrides of a tenant
+pub async fn load_tenant(
+    cache: &Cache,
+    tenant_id: &str,
+) -> Result<Option<TenantOverrides>, AppError> {
+    load(cache, &tenant_record(tenant_id)).await
+}
+
+/// Persist a tenant's overrides, replacing the previous ones
+pub async fn save_tenant(
+    cache: &Cache,
+    tenant_id: &str,
+    overrides: &TenantOverrides,
+) -> Result<(), AppError> {
+    save(cache, &tenant_record(tenant_id), overrides).await
+}
+
+/// Forget a tenant's overrides
+pub async fn clear_tenant(cache: &Cache, tenant_id: &str) -> Result<(), AppError> {
+    cache.control_delete(&tenant_record(tenant_id)).await
+}
+
+/// Persisted API key rotations of a tenant
+pub async fn load_api_keys(
+    cache: &Cache,
+    tenant_id: &str,
+) -> Result<Option<RotatedApiKeys>, AppError> {
+    load(cache, &api_keys_record(tenant_id)).await
+}
+
+/// Persist a tenant's rotated API keys, replacing the previous ones
+pub async fn save_api_keys(
+    cache: &Cache,
+    tenant_id: &str,
+    keys: &RotatedApiKeys,
+) -> Result<(), AppError> {
+    save(cache, &api_keys_record(tenant_id), keys).await
+}
+
+/// Forget a tenant's rotated API keys
+pub async fn clear_api_keys(cache: &Cache, tenant_id: &str) -> Result<(), AppError> {
+    cache.control_delete(&api_keys_record(tenant_id)).await
+}
+
+/// Purge schedules registered for a tenant through the API
+pub async fn load_purge_schedules(
+    cache: &Cache,
+    tenant_id: &str,
+) -> Result<Option<Vec<PurgeSchedule>>, AppError> {
+    load(cache, &purge_schedules_record(tenant_id)).await
+}
+
+/// Persist a tenant's registered purge schedules, replacing the previous ones
+pub async fn save_purge_schedules(
+    cache: &Cache,
+    tenant_id: &str,
+    schedules: &[PurgeSchedule],
+) -> Result<(), AppError> {
+    save(cache, &purge_schedules_record(tenant_id), &schedules).await
+}
+
+/// Persisted overrides of a node
+pub async fn load_node(cache: &Cache, node_id: &str) -> Result<Option<NodeOverrides>, AppError> {
+    load(cache, &node_record(node_id)).await
+}
+
+/// Persist a node's overrides, replacing the previous ones
+pub async fn save_node(
+    cache: &Cache,
+    node_id: &str,
+    overrides: &NodeOverrides,
+) -> Result<(), AppError> {
+    save(cache, &node_record(node_id), overrides).await
+}
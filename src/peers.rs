@@ -0,0 +1,129 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sibling node lookups for Scedge Core.
+//!
+//! Multi-node PoPs that don't share a Redis can ask their siblings before
+//! paying for an upstream hydration. On a local miss, up to `fanout` randomly
+//! chosen peers are queried with hedged requests: the first starts right away
+//! and each further peer starts `hedge_delay` later, until one of them hits or
+//! the overall `timeout` elapses.
+//!
+//! Peer requests carry the [`PEER_HOP_HEADER`] header; nodes answering a peer
+//! request only consult their local cache, so lookups never bounce between
+//! siblings or multiply upstream traffic.
+
+use anyhow::anyhow;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use rand::seq::SliceRandom;
+use reqwest::{Client, StatusCode};
+
+use crate::config::PeerConfig;
+use crate::error::AppError;
+use crate::model::LookupResponse;
+
+/// Header marking a lookup issued by a sibling node
+pub const PEER_HOP_HEADER: &str = "x-scedge-peer-hop";
+
+/// HTTP client querying sibling Scedge nodes
+#[derive(Clone)]
+pub struct PeerClient {
+    config: PeerConfig,
+    client: Client,
+}
+
+impl PeerClient {
+    /// Construct a peer client using the provided configuration.
+    pub fn try_new(config: PeerConfig) -> Result<Self, AppError> {
+        let client = Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| AppError::Internal(anyhow!("Failed to build peer client: {}", e)))?;
+
+        Ok(Self { config, client })
+    }
+
+    /// Ask sibling nodes for an artifact, returning the first hit
+    ///
+    /// Peer errors and timeouts are logged and treated as misses.
+    pub async fn lookup(&self, key: &str, tenant: Option<&str>) -> Option<LookupResponse> {
+        let peers: Vec<&String> = self
+            .config
+            .peers
+            .choose_multiple(&mut rand::thread_rng(), self.config.fanout)
+            .collect();
+
+        let mut requests: FuturesUnordered<_> = peers
+            .into_iter()
+            .enumerate()
+            .map(|(attempt, peer)| async move {
+                tokio::time::sleep(self.config.hedge_delay * attempt as u32).await;
+                (peer, self.lookup_peer(peer, key, tenant).await)
+            })
+            .collect();
+
+        let first_hit = async {
+            while let Some((peer, result)) = requests.next().await {
+                match result {
+                    Ok(Some(record)) => {
+                        tracing::debug!(key, peer = %peer, "Peer lookup hit");
+                        return Some(record);
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        tracing::debug!(key, peer = %peer, error = %err, "Peer lookup failed")
+                    }
+                }
+            }
+            None
+        };
+
+        tokio::time::timeout(self.config.timeout, first_hit)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    async fn lookup_peer(
+        &self,
+        peer: &str,
+        key: &str,
+        tenant: Option<&str>,
+    ) -> Result<Option<LookupResponse>, AppError> {
+        let url = format!("{}/lookup", peer.trim_end_matches('/'));
+
+        let mut request = self
+            .client
+            .get(url)
+            .header(PEER_HOP_HEADER, "1")
+            .query(&[("key", key)]);
+        if let Some(tenant) = tenant {
+            request = request.query(&[("tenant", tenant)]);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("Peer request failed: {}", e)))?;
+
+        let status = response.status();
+
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !status.is_success() {
+            return Err(AppError::Internal(anyhow!(
+                "Peer returned unexpected status {}",
+                status
+            )));
+        }
+
+        let payload = response
+            .json::<LookupResponse>()
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("Failed to parse peer response: {}", e)))?;
+
+        Ok(Some(payload))
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! WASM policy plugins.
+//!
+//! Bespoke compliance logic can ship to edge nodes as a WebAssembly module
+//! instead of a Scedge rebuild. Operators configure one plugin for every
+//! tenant (`SCEDGE_POLICY_PLUGIN`), and each tenant may add its own
+//! (`policy_plugin` in the tenants file). Both must allow an operation.
+//!
+//! A plugin is a core WASM module without imports that exports:
+//!
+//! - `memory` - Its linear memory
+//! - `alloc(len: i32) -> i32` - Reserve `len` bytes for the request
+//! - `evaluate(ptr: i32, len: i32) -> i32` - Judge the request; `0` allows,
+//!   anything else denies
+//!
+//! The request is a UTF-8 JSON [`PluginRequest`]. Every call runs in a fresh
+//! instance with fuel and memory budgets, so plugins keep no state between
+//! requests and a runaway plugin fails closed instead of stalling the node
+//! or exhausting its memory.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use anyhow::anyhow;
+use serde::Serialize;
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::error::AppError;
+use crate::model::{ArtifactPayload, PolicyContext};
+use crate::policy::PolicyRule;
+
+/// Operation a plugin is consulted for
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginHook {
+    Store,
+    Lookup,
+}
+
+/// Input handed to a plugin's `evaluate`
+#[derive(Debug, Serialize)]
+pub struct PluginRequest<'a> {
+    pub hook: PluginHook,
+    pub key: &'a str,
+    pub policy: &'a PolicyContext,
+    pub tags: &'a [String],
+    pub metadata: Option<&'a serde_json::Value>,
+}
+
+#[derive(Clone)]
+struct Plugin {
+    name: String,
+    module: Module,
+}
+
+/// Compiled policy plugins for the operator and individual tenants
+#[derive(Clone)]
+pub struct PolicyPlugins {
+    engine: Engine,
+    fuel: u64,
+    max_memory_bytes: usize,
+    operator: Option<Plugin>,
+    tenants: Arc<RwLock<HashMap<String, Plugin>>>,
+}
+
+impl PolicyPlugins {
+    /// Create the plugin set; each call may spend at most `fuel` units and
+    /// grow its linear memory to at most `max_memory_bytes`
+    pub fn new(fuel: u64, max_memory_bytes: usize) -> Result<Self, AppError> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)
+            .map_err(|e| AppError::Internal(anyhow!("Failed to create WASM engine: {}", e)))?;
+
+        Ok(Self {
+            engine,
+            fuel,
+            max_memory_bytes,
+            operator: None,
+            tenants: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    /// Apply a plugin to every tenant
+    pub fn with_operator_plugin(mut self, path: &Path) -> Result<Self, AppError> {
+        self.operator = Some(self.compile(path)?);
+        Ok(self)
+    }
+
+    /// Install or replace a tenant's plugin
+    pub fn set_tenant_plugin(&self, tenant: &str, path: &Path) -> Result<(), AppError> {
+        let plugin = self.compile(path)?;
+        let mut tenants = self.tenants.write().unwrap_or_else(|e| e.into_inner());
+        tenants.insert(tenant.to_string(), plugin);
+        Ok(())
+    }
+
+    /// Whether any plugin checks the artifacts of `tenant`
+    pub fn applies_to(&self, tenant: &str) -> bool {
+        self.operator.is_some()
+            || self
+                .tenants
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains_key(tenant)
+    }
+
+    /// Run the operator and tenant plugins for an artifact
+    pub async fn check(
+        &self,
+        hook: PluginHook,
+        key: &str,
+        artifact: &ArtifactPayload,
+    ) -> Result<(), AppError> {
+        let tenant = &artifact.policy.tenant;
+        let tenant_plugin = {
+            let tenants = self.tenants.read().unwrap_or_else(|e| e.into_inner());
+            tenants.get(tenant).cloned()
+        };
+        let plugins: Vec<Plugin> = self.operator.iter().cloned().chain(tenant_plugin).collect();
+        if plugins.is_empty() {
+            return Ok(());
+        }
+
+        let request = serde_json::to_vec(&PluginRequest {
+            hook,
+            key,
+            policy: &artifact.policy,
+            tags: &artifact.tags,
+            metadata: artifact.metadata.as_ref(),
+        })
+        .map_err(|e| AppError::Internal(anyhow!("Failed to encode plugin request: {}", e)))?;
+
+        for plugin in plugins {
+            let engine = self.engine.clone();
+            let fuel = self.fuel;
+            let limits = StoreLimitsBuilder::new()
+                .memory_size(self.max_memory_bytes)
+                .instances(1)
+                .build();
+            let input = request.clone();
+            let name = plugin.name.clone();
+
+            let verdict = tokio::task::spawn_blocking(move || {
+                evaluate(&engine, &plugin.module, fuel, limits, &input)
+            })
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("Plugin task failed: {}", e)))?;
+
+            match verdict {
+                Ok(0) => {}
+                Ok(code) => {
+                    return Err(AppError::policy_denied(
+                        tenant,
+                        PolicyRule::Plugin,
+                        format!("Denied by policy plugin {} (code {})", name, code),
+                    ))
+                }
+                Err(err) => {
+                    tracing::warn!(plugin = %name, error = %err, "Policy plugin failed");
+                    return Err(AppError::policy_denied(
+                        tenant,
+                        PolicyRule::Plugin,
+                        format!("Policy plugin {} failed", name),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compile(&self, path: &Path) -> Result<Plugin, AppError> {
+        let module = Module::from_file(&self.engine, path).map_err(|e| {
+            AppError::Internal(anyhow!(
+                "Failed to load policy plugin {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        Ok(Plugin {
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            module,
+        })
+    }
+}
+
+/// Instantiate a plugin and call `evaluate` on the request
+///
+/// Growing memory past `limits` fails as `memory.grow` does, with `-1`;
+/// instantiating a module that starts out larger is an error.
+fn evaluate(
+    engine: &Engine,
+    module: &Module,
+    fuel: u64,
+    limits: StoreLimits,
+    input: &[u8],
+) -> wasmtime::Result<i32> {
+    let mut store = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(fuel)?;
+
+    let instance = Instance::new(&mut store, module, &[])?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| wasmtime::format_err!("plugin does not export memory"))?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+    let evaluate = instance.get_typed_func::<(i32, i32), i32>(&mut store, "evaluate")?;
+
+    let len = i32::try_from(input.len())?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, usize::try_from(ptr)?, input)?;
+
+    evaluate.call(&mut store, (ptr, len))
+}
@@ -6,13 +6,136 @@
 //! Provides JWT validation, API key authentication, and tenant-level policy enforcement
 //! including TTL limits, regional restrictions, and compliance requirements (PHI/PII).
 
+use chrono::{DateTime, Utc};
+use jsonwebtoken::errors::ErrorKind;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, OwnedSemaphorePermit, RwLock, Semaphore};
 
+use crate::admission::Admission;
+use crate::cache::{glob_match, Cache};
+use crate::crypto::{Keyring, TenantKey};
 use crate::error::AppError;
+use crate::hydration::{HydrationLimiter, HydrationPermit, HydrationPriority};
+use crate::keys::validate_tenant_id;
+use crate::metrics::Metrics;
+use crate::model::ArtifactPayload;
+use crate::overrides;
+use crate::plugins::PolicyPlugins;
+use crate::scheduler::parse_schedule;
+use crate::templates::AnswerTemplate;
+
+/// Capacity of the internal channel carrying policy events
+const POLICY_EVENT_CAPACITY: usize = 1024;
+
+/// Default wait for a bulkhead slot before a request is turned away
+const DEFAULT_BULKHEAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Policy rule responsible for a denial
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyRule {
+    ApiKey,
+    UnknownTenant,
+    Jwt,
+    JwtExpired,
+    Scope,
+    CrossTenant,
+    Ttl,
+    Region,
+    Compliance,
+    Plugin,
+    EntrySize,
+    ReadAcl,
+    Namespace,
+}
+
+impl PolicyRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyRule::ApiKey => "api_key",
+            PolicyRule::UnknownTenant => "unknown_tenant",
+            PolicyRule::Jwt => "jwt",
+            PolicyRule::JwtExpired => "jwt_expired",
+            PolicyRule::Scope => "scope",
+            PolicyRule::CrossTenant => "cross_tenant",
+            PolicyRule::Ttl => "ttl",
+            PolicyRule::Region => "region",
+            PolicyRule::Compliance => "compliance",
+            PolicyRule::Plugin => "plugin",
+            PolicyRule::EntrySize => "entry_size",
+            PolicyRule::ReadAcl => "read_acl",
+            PolicyRule::Namespace => "namespace",
+        }
+    }
+
+    /// Reason counted in `scedge_auth_failures_total` for credential denials
+    pub fn auth_failure(&self) -> Option<&'static str> {
+        match self {
+            PolicyRule::ApiKey => Some("bad_key"),
+            PolicyRule::UnknownTenant => Some("unknown_tenant"),
+            PolicyRule::Jwt => Some("invalid_jwt"),
+            PolicyRule::JwtExpired => Some("expired_jwt"),
+            PolicyRule::Scope => Some("missing_scope"),
+            _ => None,
+        }
+    }
+}
+
+/// Tenant limit a request had to wait behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleLimit {
+    /// `max_concurrency` bulkhead
+    Concurrency,
+    /// `max_hydrations` upstream slots
+    Hydration,
+}
+
+impl ThrottleLimit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThrottleLimit::Concurrency => "concurrency",
+            ThrottleLimit::Hydration => "hydration",
+        }
+    }
+}
+
+/// Denial details attached to error responses for the tracking middleware
+#[derive(Debug, Clone)]
+pub struct PolicyDenial {
+    pub tenant: String,
+    pub rule: PolicyRule,
+}
+
+/// Structured policy events published on the internal policy channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PolicyEvent {
+    /// A request was rejected by a tenant policy rule
+    PolicyDenied {
+        tenant: String,
+        rule: PolicyRule,
+        endpoint: String,
+        occurred_at: DateTime<Utc>,
+    },
+    /// A tenant replaced its API key through `POST /tenant/keys/rotate`
+    ApiKeyRotated {
+        tenant: String,
+        /// When the previous key stops being accepted
+        previous_valid_until: DateTime<Utc>,
+        occurred_at: DateTime<Utc>,
+    },
+    /// A request queued because its tenant was at one of its limits
+    TenantThrottled {
+        tenant: String,
+        limit: ThrottleLimit,
+        occurred_at: DateTime<Utc>,
+    },
+}
 
 /// JWT claims structure
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,6 +145,10 @@ pub struct Claims {
     pub iat: usize,  // Issued at
     #[serde(default)]
     pub scopes: Vec<String>, // Permissions/scopes
+    #[serde(default)]
+    pub user: Option<String>, // End user within the tenant
+    #[serde(default)]
+    pub namespace: Option<String>, // Cache namespace, e.g. `staging`
 }
 
 /// Tenant configuration
@@ -29,6 +156,9 @@ pub struct Claims {
 pub struct TenantConfig {
     pub tenant_id: String,
     pub api_key: String,
+    /// Previous API keys still accepted until their grace window ends
+    #[serde(default, skip_serializing)]
+    pub retired_api_keys: Vec<RetiredApiKey>,
     #[serde(default)]
     pub allowed_regions: Vec<String>,
     #[serde(default)]
@@ -37,6 +167,144 @@ pub struct TenantConfig {
     pub require_phi_compliance: bool,
     #[serde(default)]
     pub require_pii_compliance: bool,
+    #[serde(default)]
+    pub purge_schedules: Vec<PurgeSchedule>,
+    /// Maximum in-flight backend/upstream operations for this tenant
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Maximum concurrent upstream hydrations for this tenant
+    #[serde(default)]
+    pub max_hydrations: Option<usize>,
+    /// Key patterns whose hydrations wait ahead of cold misses
+    #[serde(default)]
+    pub pinned_keys: Vec<String>,
+    /// URLs receiving `ARTIFACT_STORED` notifications for `POST /store?notify=true`
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+    /// Origin fronted by the caching proxy at `/proxy/{tenant}/...`
+    #[serde(default)]
+    pub proxy_origin: Option<String>,
+    /// Encryption key versions for answers at rest; the last one is active
+    #[serde(default, skip_serializing)]
+    pub encryption_keys: Vec<TenantKey>,
+    /// Stages consulted by `GET /lookup`, in order; omitted stages are skipped
+    #[serde(default = "default_lookup_pipeline")]
+    pub lookup_pipeline: Vec<LookupStage>,
+    /// Upstream artifacts whose `metrics.score` is below this are served uncached
+    #[serde(default)]
+    pub min_cache_score: Option<f32>,
+    /// WASM policy plugin consulted on store and lookup, after the operator's
+    #[serde(default)]
+    pub policy_plugin: Option<PathBuf>,
+    /// Only cache keys that have been looked up repeatedly
+    #[serde(default)]
+    pub admission_control: bool,
+    /// Serve the last-known-good copy of a key when the backend read fails
+    #[serde(default)]
+    pub serve_stale_on_error: bool,
+    /// What lookups do with entries past their expiry the backend still holds
+    #[serde(default)]
+    pub expired_reads: ExpiredReads,
+    /// Reject the tenant's stores and touches; purges still apply
+    #[serde(default)]
+    pub read_only: bool,
+    /// Maximum `provenance` entries accepted per stored artifact
+    #[serde(default)]
+    pub max_provenance_entries: Option<usize>,
+    /// Maximum size of an artifact's serialized `metadata`, in bytes
+    #[serde(default)]
+    pub max_metadata_bytes: Option<usize>,
+    /// What happens to stores over the provenance or metadata limit
+    #[serde(default)]
+    pub oversize_policy: OversizePolicy,
+    /// Namespaces besides the default one the tenant's requests may select
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    /// Shapes `GET /lookup?template={name}` can render answers into
+    #[serde(default)]
+    pub answer_templates: HashMap<String, AnswerTemplate>,
+}
+
+/// Handling of lookups finding an entry past its `expires_at`
+///
+/// Such an entry is still held when Redis has not dropped it yet, e.g. it was
+/// stored without a native TTL or by a node whose clock lags this one's.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiredReads {
+    /// Delete the entry and continue as a miss
+    #[default]
+    Miss,
+    /// Serve the entry, flagged with `X-Cache: STALE`
+    ServeStale,
+    /// Hydrate the key from upstream and serve that, falling back to the
+    /// flagged entry when upstream fails or has no answer
+    Refresh,
+}
+
+/// Handling of stored artifacts over a tenant's entry size limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizePolicy {
+    /// Refuse the store with `400 Bad Request`
+    #[default]
+    Reject,
+    /// Keep the first provenance entries up to the limit and drop oversized metadata
+    Truncate,
+}
+
+impl TenantConfig {
+    /// Whether `api_key` is the current key or a retired one still in grace
+    pub fn accepts_api_key(&self, api_key: &str, now: DateTime<Utc>) -> bool {
+        self.api_key == api_key
+            || self
+                .retired_api_keys
+                .iter()
+                .any(|retired| retired.key == api_key && retired.valid_until > now)
+    }
+}
+
+/// API key replaced by a rotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetiredApiKey {
+    pub key: String,
+    pub valid_until: DateTime<Utc>,
+}
+
+/// Source consulted while resolving a lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LookupStage {
+    /// The local cache backend
+    #[serde(alias = "l2")]
+    Cache,
+    /// Sibling nodes listed in `SCEDGE_PEERS`
+    Peers,
+    /// The configured upstream graph
+    Upstream,
+}
+
+/// Cache first, then peers, then upstream
+pub fn default_lookup_pipeline() -> Vec<LookupStage> {
+    vec![
+        LookupStage::Cache,
+        LookupStage::Peers,
+        LookupStage::Upstream,
+    ]
+}
+
+/// Recurring invalidation rule executed by the purge scheduler
+///
+/// `cron` uses the six-field form with seconds (`sec min hour day month weekday`).
+/// Matching artifacts must start with `{tenant}:{prefix}` and, when `tag` is set,
+/// carry that tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeSchedule {
+    pub cron: String,
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
 }
 
 /// Policy enforcement engine
@@ -44,47 +312,276 @@ pub struct TenantConfig {
 pub struct PolicyEngine {
     tenants: Arc<RwLock<HashMap<String, TenantConfig>>>,
     jwt_secret: Option<String>,
+    events: broadcast::Sender<PolicyEvent>,
+    bulkheads: Arc<RwLock<HashMap<String, Arc<Semaphore>>>>,
+    hydrations: Arc<RwLock<HashMap<String, Arc<HydrationLimiter>>>>,
+    bulkhead_timeout: std::time::Duration,
+    admission: Option<Admission>,
 }
 
 impl PolicyEngine {
     pub fn new(jwt_secret: Option<String>) -> Self {
+        let (events, _) = broadcast::channel(POLICY_EVENT_CAPACITY);
         Self {
             tenants: Arc::new(RwLock::new(HashMap::new())),
             jwt_secret,
+            events,
+            bulkheads: Arc::new(RwLock::new(HashMap::new())),
+            hydrations: Arc::new(RwLock::new(HashMap::new())),
+            bulkhead_timeout: DEFAULT_BULKHEAD_TIMEOUT,
+            admission: None,
         }
     }
 
+    /// Turn requests away with `503` after waiting `timeout` for a bulkhead slot
+    pub fn with_bulkhead_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.bulkhead_timeout = timeout;
+        self
+    }
+
+    /// Keep the L1 admission filter in step with each tenant's
+    /// `admission_control`
+    pub fn with_admission(mut self, admission: Admission) -> Self {
+        self.admission = Some(admission);
+        self
+    }
+
+    /// Subscribe to policy events such as `POLICY_DENIED`
+    pub fn subscribe(&self) -> broadcast::Receiver<PolicyEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish a denial onto the internal policy channel
+    pub fn publish_denial(&self, denial: PolicyDenial, endpoint: &str) {
+        // Sending only fails when nobody is subscribed, which is fine
+        let _ = self.events.send(PolicyEvent::PolicyDenied {
+            tenant: denial.tenant,
+            rule: denial.rule,
+            endpoint: endpoint.to_string(),
+            occurred_at: Utc::now(),
+        });
+    }
+
+    fn publish_throttle(&self, tenant: &str, limit: ThrottleLimit) {
+        let _ = self.events.send(PolicyEvent::TenantThrottled {
+            tenant: tenant.to_string(),
+            limit,
+            occurred_at: Utc::now(),
+        });
+    }
+
     /// Load tenant configurations from a JSON file
     pub async fn load_tenants(&self, tenants: Vec<TenantConfig>) -> Result<(), AppError> {
-        let mut map = self.tenants.write().await;
         for tenant in tenants {
-            map.insert(tenant.tenant_id.clone(), tenant);
+            self.add_tenant(tenant).await;
         }
         Ok(())
     }
 
     /// Add a single tenant
     pub async fn add_tenant(&self, tenant: TenantConfig) {
+        {
+            let mut bulkheads = self.bulkheads.write().await;
+            match tenant.max_concurrency {
+                Some(limit) => {
+                    bulkheads.insert(tenant.tenant_id.clone(), Arc::new(Semaphore::new(limit)));
+                }
+                None => {
+                    bulkheads.remove(&tenant.tenant_id);
+                }
+            }
+        }
+
+        {
+            let mut hydrations = self.hydrations.write().await;
+            match tenant.max_hydrations {
+                Some(limit) => {
+                    hydrations.insert(
+                        tenant.tenant_id.clone(),
+                        Arc::new(HydrationLimiter::new(limit)),
+                    );
+                }
+                None => {
+                    hydrations.remove(&tenant.tenant_id);
+                }
+            }
+        }
+
+        if let Some(admission) = &self.admission {
+            admission.set_tenant(&tenant.tenant_id, tenant.admission_control);
+        }
+
         let mut map = self.tenants.write().await;
         map.insert(tenant.tenant_id.clone(), tenant);
     }
 
+    /// Wait for a slot in the tenant's bulkhead
+    ///
+    /// Hold the returned permit across backend and upstream calls so a burst
+    /// from one tenant queues behind its own `max_concurrency` limit instead of
+    /// starving other tenants. Tenants without a limit get `None`. Callers
+    /// must authorize the request first, so unauthenticated traffic cannot
+    /// occupy the queue. A request still waiting after the bulkhead timeout
+    /// fails with `503 Service Unavailable`.
+    pub async fn acquire_bulkhead(
+        &self,
+        tenant_id: &str,
+    ) -> Result<Option<OwnedSemaphorePermit>, AppError> {
+        let semaphore = match self.bulkheads.read().await.get(tenant_id) {
+            Some(semaphore) => semaphore.clone(),
+            None => return Ok(None),
+        };
+
+        if semaphore.available_permits() == 0 {
+            tracing::debug!(tenant_id, "Tenant bulkhead saturated, waiting for a slot");
+            self.publish_throttle(tenant_id, ThrottleLimit::Concurrency);
+        }
+
+        match tokio::time::timeout(self.bulkhead_timeout, semaphore.acquire_owned()).await {
+            Ok(permit) => permit
+                .map(Some)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Tenant bulkhead closed: {}", e))),
+            Err(_) => {
+                tracing::debug!(tenant_id, "Timed out waiting for a tenant bulkhead slot");
+                Err(AppError::unavailable("Tenant concurrency limit reached"))
+            }
+        }
+    }
+
+    /// Wait for one of the tenant's `max_hydrations` upstream slots
+    ///
+    /// Refresh-ahead of a hot key (`refresh`) and misses of keys matching the
+    /// tenant's `pinned_keys` are served before waiting cold misses. Tenants
+    /// without a limit get `None`.
+    pub async fn acquire_hydration(
+        &self,
+        tenant_id: &str,
+        key: &str,
+        refresh: bool,
+    ) -> Result<Option<HydrationPermit>, AppError> {
+        let limiter = match self.hydrations.read().await.get(tenant_id) {
+            Some(limiter) => limiter.clone(),
+            None => return Ok(None),
+        };
+
+        let pinned = refresh
+            || self
+                .tenants
+                .read()
+                .await
+                .get(tenant_id)
+                .is_some_and(|tenant| {
+                    tenant
+                        .pinned_keys
+                        .iter()
+                        .any(|pattern| glob_match(pattern, key))
+                });
+        let priority = if pinned {
+            HydrationPriority::Hot
+        } else {
+            HydrationPriority::Cold
+        };
+
+        if limiter.saturated() {
+            self.publish_throttle(tenant_id, ThrottleLimit::Hydration);
+        }
+        limiter.acquire(priority).await.map(Some)
+    }
+
     /// Validate API key for a tenant
     pub async fn validate_api_key(&self, tenant_id: &str, api_key: &str) -> Result<(), AppError> {
         let tenants = self.tenants.read().await;
 
         match tenants.get(tenant_id) {
             Some(config) => {
-                if config.api_key == api_key {
+                if config.accepts_api_key(api_key, Utc::now()) {
                     Ok(())
                 } else {
-                    Err(AppError::bad_request("Invalid API key"))
+                    Err(AppError::policy_denied(
+                        tenant_id,
+                        PolicyRule::ApiKey,
+                        "Invalid API key",
+                    ))
                 }
             }
-            None => Err(AppError::bad_request("Unknown tenant")),
+            None => Err(AppError::policy_denied(
+                tenant_id,
+                PolicyRule::UnknownTenant,
+                "Unknown tenant",
+            )),
         }
     }
 
+    /// Whether bearer JWTs can be validated
+    pub fn jwt_enabled(&self) -> bool {
+        self.jwt_secret.is_some()
+    }
+
+    /// Tenant owning an API key
+    pub async fn tenant_for_api_key(&self, api_key: &str) -> Option<String> {
+        let now = Utc::now();
+        let tenants = self.tenants.read().await;
+        tenants
+            .values()
+            .find(|config| config.accepts_api_key(api_key, now))
+            .map(|config| config.tenant_id.clone())
+    }
+
+    /// Replace a tenant's API key, keeping the old one valid for `grace`
+    ///
+    /// Returns the new key and the end of the old key's grace window, and
+    /// publishes an `API_KEY_ROTATED` event. The keys are persisted in the
+    /// control namespace before they take effect, so a restart or reload keeps
+    /// the rotation.
+    pub async fn rotate_api_key(
+        &self,
+        tenant_id: &str,
+        grace: std::time::Duration,
+        cache: &Cache,
+    ) -> Result<(String, DateTime<Utc>), AppError> {
+        let now = Utc::now();
+        let valid_until =
+            now + chrono::Duration::from_std(grace).unwrap_or(chrono::Duration::zero());
+        let new_key = generate_api_key(tenant_id);
+
+        {
+            // Held across the write so concurrent rotations cannot interleave
+            let mut tenants = self.tenants.write().await;
+            let config = tenants
+                .get_mut(tenant_id)
+                .ok_or_else(|| AppError::not_found("Unknown tenant"))?;
+
+            let mut retired = config.retired_api_keys.clone();
+            retired.retain(|retired| retired.valid_until > now);
+            retired.push(RetiredApiKey {
+                key: config.api_key.clone(),
+                valid_until,
+            });
+            let configured = overrides::load_api_keys(cache, tenant_id)
+                .await?
+                .map(|rotated| rotated.configured)
+                .unwrap_or_else(|| config.api_key.clone());
+            let rotated = overrides::RotatedApiKeys {
+                configured,
+                api_key: new_key.clone(),
+                retired,
+            };
+            overrides::save_api_keys(cache, tenant_id, &rotated).await?;
+
+            config.api_key = rotated.api_key;
+            config.retired_api_keys = rotated.retired;
+        }
+
+        // Sending only fails when nobody is subscribed, which is fine
+        let _ = self.events.send(PolicyEvent::ApiKeyRotated {
+            tenant: tenant_id.to_string(),
+            previous_valid_until: valid_until,
+            occurred_at: now,
+        });
+
+        Ok((new_key, valid_until))
+    }
+
     /// Validate JWT token
     pub fn validate_jwt(&self, token: &str) -> Result<Claims, AppError> {
         let secret = self
@@ -100,17 +597,218 @@ impl PolicyEngine {
             &DecodingKey::from_secret(secret.as_bytes()),
             &validation,
         )
-        .map_err(|e| AppError::bad_request(format!("Invalid JWT: {}", e)))?;
+        .map_err(|e| {
+            let rule = match e.kind() {
+                ErrorKind::ExpiredSignature => PolicyRule::JwtExpired,
+                _ => PolicyRule::Jwt,
+            };
+            AppError::policy_denied("", rule, format!("Invalid JWT: {}", e))
+        })?;
 
         Ok(token_data.claims)
     }
 
+    /// Validate a JWT and require it to grant `scope`
+    pub fn validate_jwt_scope(&self, token: &str, scope: &str) -> Result<Claims, AppError> {
+        let claims = self.validate_jwt(token)?;
+
+        if !claims.scopes.iter().any(|granted| granted == scope) {
+            return Err(AppError::policy_denied(
+                &claims.sub,
+                PolicyRule::Scope,
+                format!("JWT lacks required scope {}", scope),
+            ));
+        }
+
+        Ok(claims)
+    }
+
     /// Get tenant configuration
     pub async fn get_tenant(&self, tenant_id: &str) -> Option<TenantConfig> {
         let tenants = self.tenants.read().await;
         tenants.get(tenant_id).cloned()
     }
 
+    /// Lookup stages for a tenant; unknown tenants use the default order
+    pub async fn lookup_pipeline(&self, tenant_id: &str) -> Vec<LookupStage> {
+        let tenants = self.tenants.read().await;
+        tenants
+            .get(tenant_id)
+            .map(|tenant| tenant.lookup_pipeline.clone())
+            .unwrap_or_else(default_lookup_pipeline)
+    }
+
+    /// Whether an upstream artifact scores high enough to be cached for its tenant
+    ///
+    /// Artifacts without metrics and tenants without a threshold always qualify.
+    pub async fn meets_cache_score(&self, artifact: &ArtifactPayload) -> bool {
+        let tenants = self.tenants.read().await;
+        let threshold = tenants
+            .get(&artifact.policy.tenant)
+            .and_then(|tenant| tenant.min_cache_score);
+        match (threshold, artifact.score()) {
+            (Some(threshold), Some(score)) => score >= threshold,
+            _ => true,
+        }
+    }
+
+    /// IDs of every configured tenant, sorted
+    pub async fn tenant_ids(&self) -> Vec<String> {
+        let tenants = self.tenants.read().await;
+        let mut ids: Vec<String> = tenants.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Whether lookups fall back to stale in-process copies on backend errors
+    pub async fn serve_stale_on_error(&self, tenant_id: &str) -> bool {
+        let tenants = self.tenants.read().await;
+        tenants
+            .get(tenant_id)
+            .is_some_and(|tenant| tenant.serve_stale_on_error)
+    }
+
+    /// Handling of expired entries found by the tenant's lookups
+    pub async fn expired_reads(&self, tenant_id: &str) -> ExpiredReads {
+        let tenants = self.tenants.read().await;
+        tenants
+            .get(tenant_id)
+            .map(|tenant| tenant.expired_reads)
+            .unwrap_or_default()
+    }
+
+    /// Answer template `name` registered by the tenant
+    pub async fn answer_template(&self, tenant_id: &str, name: &str) -> Option<AnswerTemplate> {
+        let tenants = self.tenants.read().await;
+        tenants
+            .get(tenant_id)
+            .and_then(|tenant| tenant.answer_templates.get(name))
+            .cloned()
+    }
+
+    /// Whether the tenant may use the cache namespace `namespace`
+    pub async fn allows_namespace(&self, tenant_id: &str, namespace: &str) -> bool {
+        let tenants = self.tenants.read().await;
+        tenants
+            .get(tenant_id)
+            .is_some_and(|tenant| tenant.namespaces.iter().any(|n| n == namespace))
+    }
+
+    /// Whether the tenant's writes are frozen
+    pub async fn read_only(&self, tenant_id: &str) -> bool {
+        let tenants = self.tenants.read().await;
+        tenants
+            .get(tenant_id)
+            .is_some_and(|tenant| tenant.read_only)
+    }
+
+    /// Apply the tenant's provenance and metadata limits to an artifact being stored
+    ///
+    /// Depending on the tenant's `oversize_policy`, an artifact over a limit is
+    /// either refused or trimmed in place.
+    pub async fn enforce_entry_limits(
+        &self,
+        tenant_id: &str,
+        artifact: &mut ArtifactPayload,
+    ) -> Result<(), AppError> {
+        let Some(config) = self.get_tenant(tenant_id).await else {
+            return Ok(());
+        };
+
+        if let Some(max_entries) = config.max_provenance_entries {
+            let entries = artifact.provenance.len();
+            if entries > max_entries {
+                match config.oversize_policy {
+                    OversizePolicy::Reject => {
+                        return Err(AppError::policy_denied(
+                            tenant_id,
+                            PolicyRule::EntrySize,
+                            format!(
+                                "{} provenance entries exceed maximum allowed {} for tenant {}",
+                                entries, max_entries, tenant_id
+                            ),
+                        ));
+                    }
+                    OversizePolicy::Truncate => {
+                        artifact.provenance.truncate(max_entries);
+                        tracing::debug!(
+                            tenant_id,
+                            entries,
+                            max_entries,
+                            "Truncated provenance entries"
+                        );
+                    }
+                }
+            }
+        }
+
+        if let (Some(max_bytes), Some(metadata)) =
+            (config.max_metadata_bytes, artifact.metadata.as_ref())
+        {
+            let bytes = serde_json::to_vec(metadata).map(|v| v.len()).unwrap_or(0);
+            if bytes > max_bytes {
+                match config.oversize_policy {
+                    OversizePolicy::Reject => {
+                        return Err(AppError::policy_denied(
+                            tenant_id,
+                            PolicyRule::EntrySize,
+                            format!(
+                                "metadata of {} bytes exceeds maximum allowed {} for tenant {}",
+                                bytes, max_bytes, tenant_id
+                            ),
+                        ));
+                    }
+                    OversizePolicy::Truncate => {
+                        artifact.metadata = None;
+                        tracing::debug!(tenant_id, bytes, max_bytes, "Dropped oversized metadata");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Register an additional purge schedule for a tenant
+    ///
+    /// The schedule is persisted in the control namespace before it takes
+    /// effect, so a restart or reload keeps it.
+    pub async fn add_purge_schedule(
+        &self,
+        tenant_id: &str,
+        schedule: PurgeSchedule,
+        cache: &Cache,
+    ) -> Result<Vec<PurgeSchedule>, AppError> {
+        // Held across the write so concurrent registrations cannot interleave
+        let mut tenants = self.tenants.write().await;
+        let config = tenants
+            .get_mut(tenant_id)
+            .ok_or_else(|| AppError::bad_request("Unknown tenant"))?;
+
+        let mut registered = overrides::load_purge_schedules(cache, tenant_id)
+            .await?
+            .unwrap_or_default();
+        registered.push(schedule.clone());
+        overrides::save_purge_schedules(cache, tenant_id, &registered).await?;
+
+        config.purge_schedules.push(schedule);
+        Ok(config.purge_schedules.clone())
+    }
+
+    /// Snapshot of every tenant's purge schedules
+    pub async fn purge_schedules(&self) -> Vec<(String, PurgeSchedule)> {
+        let tenants = self.tenants.read().await;
+        tenants
+            .values()
+            .flat_map(|config| {
+                config
+                    .purge_schedules
+                    .iter()
+                    .map(|schedule| (config.tenant_id.clone(), schedule.clone()))
+            })
+            .collect()
+    }
+
     /// Validate that a TTL doesn't exceed tenant limits
     pub async fn validate_ttl(
         &self,
@@ -121,10 +819,14 @@ impl PolicyEngine {
             if let Some(config) = self.get_tenant(tenant_id).await {
                 if let Some(max_ttl) = config.max_ttl_seconds {
                     if ttl > max_ttl {
-                        return Err(AppError::bad_request(format!(
-                            "TTL {} exceeds maximum allowed {} for tenant {}",
-                            ttl, max_ttl, tenant_id
-                        )));
+                        return Err(AppError::policy_denied(
+                            tenant_id,
+                            PolicyRule::Ttl,
+                            format!(
+                                "TTL {} exceeds maximum allowed {} for tenant {}",
+                                ttl, max_ttl, tenant_id
+                            ),
+                        ));
                     }
                 }
             }
@@ -142,10 +844,11 @@ impl PolicyEngine {
             if !config.allowed_regions.is_empty() {
                 if let Some(r) = region {
                     if !config.allowed_regions.contains(&r.to_string()) {
-                        return Err(AppError::bad_request(format!(
-                            "Region {} not allowed for tenant {}",
-                            r, tenant_id
-                        )));
+                        return Err(AppError::policy_denied(
+                            tenant_id,
+                            PolicyRule::Region,
+                            format!("Region {} not allowed for tenant {}", r, tenant_id),
+                        ));
                     }
                 }
             }
@@ -175,12 +878,168 @@ impl PolicyEngine {
     }
 }
 
+/// `tenant` label of denials naming a tenant that is not configured
+const UNKNOWN_TENANT_LABEL: &str = "unknown";
+
+/// Record policy events in the audit log and denial counters
+pub fn spawn_policy_audit(
+    mut events: broadcast::Receiver<PolicyEvent>,
+    metrics: Metrics,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(PolicyEvent::PolicyDenied {
+                    tenant,
+                    rule,
+                    endpoint,
+                    occurred_at,
+                }) => {
+                    // Unknown tenants are caller-supplied; one label keeps them
+                    // from growing the series without bound
+                    let label = match rule {
+                        PolicyRule::UnknownTenant => UNKNOWN_TENANT_LABEL,
+                        _ => tenant.as_str(),
+                    };
+                    metrics.record_policy_denied(label, rule.as_str());
+                    if let Some(reason) = rule.auth_failure() {
+                        metrics.record_auth_failure(reason);
+                    }
+                    tracing::warn!(
+                        target: "scedge::audit",
+                        tenant = %tenant,
+                        rule = rule.as_str(),
+                        endpoint = %endpoint,
+                        occurred_at = %occurred_at,
+                        "POLICY_DENIED"
+                    );
+                }
+                Ok(PolicyEvent::ApiKeyRotated {
+                    tenant,
+                    previous_valid_until,
+                    occurred_at,
+                }) => {
+                    tracing::info!(
+                        target: "scedge::audit",
+                        tenant = %tenant,
+                        previous_valid_until = %previous_valid_until,
+                        occurred_at = %occurred_at,
+                        "API_KEY_ROTATED"
+                    );
+                }
+                Ok(PolicyEvent::TenantThrottled {
+                    tenant,
+                    limit,
+                    occurred_at,
+                }) => {
+                    metrics.record_throttled(&tenant, limit.as_str());
+                    tracing::debug!(
+                        target: "scedge::audit",
+                        tenant = %tenant,
+                        limit = limit.as_str(),
+                        occurred_at = %occurred_at,
+                        "TENANT_THROTTLED"
+                    );
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Policy audit lagged behind policy events");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
 impl Default for PolicyEngine {
     fn default() -> Self {
         Self::new(None)
     }
 }
 
+/// Apply a tenant from the tenants file to every component that needs it
+///
+/// Tenants with an invalid id are rejected.
+/// Runtime overrides and rotated API keys persisted for the tenant are applied
+/// on top of the file's settings, and purge schedules registered through the
+/// API are added to the file's. Invalid purge schedules are dropped with a warning. A missing compression
+/// dictionary is not an error; failing to load one is only logged.
+pub async fn install_tenant(
+    mut tenant: TenantConfig,
+    policy: &PolicyEngine,
+    keyring: &Keyring,
+    plugins: &PolicyPlugins,
+    cache: &Cache,
+) -> Result<(), AppError> {
+    validate_tenant_id(&tenant.tenant_id)?;
+    if let Some(overrides) = overrides::load_tenant(cache, &tenant.tenant_id).await? {
+        tracing::info!(tenant_id = %tenant.tenant_id, ?overrides, "Applying runtime overrides");
+        overrides.apply(&mut tenant);
+    }
+    if let Some(rotated) = overrides::load_api_keys(cache, &tenant.tenant_id).await? {
+        if rotated.apply(&mut tenant) {
+            tracing::info!(tenant_id = %tenant.tenant_id, "Applying rotated API key");
+        } else {
+            // The tenants file replaced the key the rotation started from
+            tracing::info!(tenant_id = %tenant.tenant_id, "Dropping API key rotation superseded by the tenants file");
+            overrides::clear_api_keys(cache, &tenant.tenant_id).await?;
+        }
+    }
+    if let Some(registered) = overrides::load_purge_schedules(cache, &tenant.tenant_id).await? {
+        tracing::info!(
+            tenant_id = %tenant.tenant_id,
+            count = registered.len(),
+            "Applying registered purge schedules"
+        );
+        tenant.purge_schedules.extend(registered);
+    }
+    tenant
+        .purge_schedules
+        .retain(|schedule| match parse_schedule(&schedule.cron) {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!(
+                    tenant_id = %tenant.tenant_id,
+                    cron = %schedule.cron,
+                    error = %e,
+                    "Ignoring invalid purge schedule"
+                );
+                false
+            }
+        });
+    for key in &tenant.encryption_keys {
+        keyring.add_key(&tenant.tenant_id, key)?;
+    }
+    // Keys registered at runtime are newer than the file's, so they stay active
+    let registered = cache.load_tenant_keys(&tenant.tenant_id).await?;
+    if registered > 0 {
+        tracing::info!(tenant_id = %tenant.tenant_id, registered, "Loaded registered encryption keys");
+    }
+    if let Some(path) = &tenant.policy_plugin {
+        plugins.set_tenant_plugin(&tenant.tenant_id, path)?;
+    }
+    match cache.load_dictionary(&tenant.tenant_id).await {
+        Ok(Some(version)) => tracing::debug!(
+            tenant_id = %tenant.tenant_id,
+            version,
+            "Loaded compression dictionary"
+        ),
+        Ok(None) => {}
+        Err(e) => tracing::warn!(
+            tenant_id = %tenant.tenant_id,
+            error = %e,
+            "Failed to load compression dictionary"
+        ),
+    }
+    tracing::debug!(tenant_id = %tenant.tenant_id, "Loaded tenant");
+    policy.add_tenant(tenant).await;
+    Ok(())
+}
+
+/// Mint a random API key prefixed with the tenant id
+fn generate_api_key(tenant_id: &str) -> String {
+    format!("{}_{}", tenant_id, hex::encode(rand::random::<[u8; 32]>()))
+}
+
 /// Extract bearer token from Authorization header
 pub fn extract_bearer_token(auth_header: Option<&str>) -> Option<String> {
     auth_header
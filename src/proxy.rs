@@ -0,0 +1,242 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generic HTTP caching proxy.
+//!
+//! `GET|POST /proxy/{tenant}/{path}` forwards the request to the tenant's
+//! configured `proxy_origin` and caches successful responses under
+//! `{tenant}:proxy:{digest}`, where the digest covers the method, path, query,
+//! and request body. Cached responses go through the same store as artifacts,
+//! so tenant policy, purges, and graph invalidation apply unchanged.
+//!
+//! Freshness follows the origin's `Cache-Control`, `Expires`, and `Age` as
+//! parsed by [`HttpFreshness`] (`no-store`, `no-cache`, and `private` bypass
+//! the cache), capped by the tenant's `max_ttl_seconds`. Without a lifetime
+//! the tenant maximum or the default TTL applies.
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, RawQuery, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::Response;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::AppState;
+use crate::error::AppError;
+use crate::model::{ArtifactPayload, PolicyContext, ProvenanceInfo};
+use crate::upstream::HttpFreshness;
+
+/// Response header reporting whether the proxy served from cache
+const CACHE_STATUS_HEADER: &str = "x-scedge-cache";
+
+/// Origin response as stored in the artifact `answer`
+#[derive(Debug, Serialize, Deserialize)]
+struct ProxiedResponse {
+    status: u16,
+    #[serde(default)]
+    content_type: Option<String>,
+    body: String,
+}
+
+/// Forward a request to the tenant origin, serving and filling the cache
+pub async fn handle_proxy(
+    State(state): State<AppState>,
+    Path((tenant_id, path)): Path<(String, String)>,
+    RawQuery(query): RawQuery,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, AppError> {
+    if let Some(api_key) = headers.get("x-api-key").and_then(|h| h.to_str().ok()) {
+        state.policy.validate_api_key(&tenant_id, api_key).await?;
+    }
+
+    let tenant = state
+        .policy
+        .get_tenant(&tenant_id)
+        .await
+        .ok_or_else(|| AppError::not_found("Unknown tenant"))?;
+    let origin = tenant
+        .proxy_origin
+        .as_deref()
+        .ok_or_else(|| AppError::not_found("No proxy origin configured for tenant"))?;
+
+    let key = proxy_cache_key(&tenant_id, &method, &path, query.as_deref(), &body);
+
+    let _permit = state.policy.acquire_bulkhead(&tenant_id).await?;
+
+    if let Some(cached) = state.cache.get(&key).await? {
+        if let Ok(response) = serde_json::from_value::<ProxiedResponse>(cached.artifact.answer) {
+            state.metrics.record_cache_hit();
+            state.metrics.record_tenant_lookup(&tenant_id, true);
+            return Ok(build_response(response, "HIT"));
+        }
+    }
+    state.metrics.record_cache_miss();
+    state.metrics.record_tenant_lookup(&tenant_id, false);
+
+    let mut url = format!("{}/{}", origin.trim_end_matches('/'), path);
+    if let Some(query) = &query {
+        url.push('?');
+        url.push_str(query);
+    }
+
+    // reqwest and axum depend on different `http` versions, so convert by value
+    let origin_method = reqwest::Method::from_bytes(method.as_str().as_bytes())
+        .map_err(|_| AppError::bad_request("Unsupported method"))?;
+    let mut request = state.http.request(origin_method, &url).body(body.to_vec());
+    for name in [header::CONTENT_TYPE, header::ACCEPT] {
+        if let Some(value) = headers.get(&name) {
+            request = request.header(name.as_str(), value.as_bytes());
+        }
+    }
+
+    let start = tokio::time::Instant::now();
+    state.metrics.record_upstream_request();
+    let origin_response = request.send().await.map_err(|e| {
+        state.metrics.record_upstream_failure();
+        AppError::Internal(anyhow::anyhow!("Proxy origin request failed: {}", e))
+    })?;
+
+    let status =
+        StatusCode::from_u16(origin_response.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let content_type = origin_response
+        .headers()
+        .get(header::CONTENT_TYPE.as_str())
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let freshness = HttpFreshness::from_headers(origin_response.headers());
+    let bytes = origin_response.bytes().await.map_err(|e| {
+        state.metrics.record_upstream_failure();
+        AppError::Internal(anyhow::anyhow!(
+            "Failed to read proxy origin response: {}",
+            e
+        ))
+    })?;
+    state
+        .metrics
+        .record_upstream_latency(start.elapsed().as_secs_f64());
+
+    let ttl = response_ttl(
+        &freshness,
+        tenant.max_ttl_seconds,
+        state.default_ttl_for(&tenant_id),
+    );
+
+    // Only successful, text-like responses are cached; everything else passes through
+    let body = match String::from_utf8(bytes.to_vec()) {
+        Ok(body) => body,
+        Err(_) => {
+            let mut response = Response::new(Body::from(bytes));
+            *response.status_mut() = status;
+            if let Some(value) = content_type.and_then(|v| HeaderValue::from_str(&v).ok()) {
+                response.headers_mut().insert(header::CONTENT_TYPE, value);
+            }
+            return Ok(response);
+        }
+    };
+
+    let proxied = ProxiedResponse {
+        status: status.as_u16(),
+        content_type,
+        body,
+    };
+
+    let ttl = match ttl {
+        Some(ttl) if status.is_success() => ttl,
+        _ => return Ok(build_response(proxied, "BYPASS")),
+    };
+
+    let artifact = ArtifactPayload {
+        answer: serde_json::to_value(&proxied)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to encode response: {}", e)))?,
+        answer_content_type: None,
+        policy: PolicyContext {
+            tenant: tenant_id.clone(),
+            phi: false,
+            pii: false,
+            region: None,
+            compliance_tags: Vec::new(),
+            allowed_subjects: Vec::new(),
+            allowed_scopes: Vec::new(),
+        },
+        provenance: vec![ProvenanceInfo {
+            source: url,
+            hash: None,
+            version: None,
+            generated_at: Some(Utc::now()),
+            capsule_id: None,
+        }],
+        metrics: None,
+        ttl_seconds: (ttl > 0).then_some(ttl),
+        hash: hex::encode(Sha256::digest(proxied.body.as_bytes())),
+        tags: vec!["proxy".to_string()],
+        depends_on: Vec::new(),
+        family: None,
+        metadata: None,
+    };
+
+    let expires_at = (ttl > 0).then(|| Utc::now() + Duration::seconds(ttl as i64));
+    state.cache.set(key, artifact, expires_at).await?;
+    state.metrics.record_cache_store();
+
+    Ok(build_response(proxied, "MISS"))
+}
+
+/// Cache key covering everything that distinguishes one proxied request from another
+fn proxy_cache_key(
+    tenant: &str,
+    method: &Method,
+    path: &str,
+    query: Option<&str>,
+    body: &[u8],
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update([0]);
+    hasher.update(path.as_bytes());
+    hasher.update([0]);
+    hasher.update(query.unwrap_or("").as_bytes());
+    hasher.update([0]);
+    hasher.update(body);
+    format!("{}:proxy:{}", tenant, hex::encode(hasher.finalize()))
+}
+
+/// TTL in seconds for an origin response, or `None` when it must not be cached
+fn response_ttl(
+    freshness: &HttpFreshness,
+    max_ttl_seconds: Option<u64>,
+    default_ttl_seconds: u64,
+) -> Option<u64> {
+    if freshness.no_store {
+        return None;
+    }
+
+    let ttl = match freshness.ttl_seconds {
+        Some(0) => return None,
+        Some(ttl) => ttl,
+        None => max_ttl_seconds.unwrap_or(default_ttl_seconds),
+    };
+
+    Some(match max_ttl_seconds {
+        Some(max_ttl) => ttl.min(max_ttl),
+        None => ttl,
+    })
+}
+
+fn build_response(proxied: ProxiedResponse, cache_status: &'static str) -> Response {
+    let mut response = Response::new(Body::from(proxied.body));
+    *response.status_mut() = StatusCode::from_u16(proxied.status).unwrap_or(StatusCode::OK);
+
+    let headers = response.headers_mut();
+    if let Some(value) = proxied
+        .content_type
+        .and_then(|v| HeaderValue::from_str(&v).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    headers.insert(CACHE_STATUS_HEADER, HeaderValue::from_static(cache_status));
+
+    response
+}
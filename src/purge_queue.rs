@@ -0,0 +1,129 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounded queue between graph event handling and key deletion.
+//!
+//! The invalidation engine resolves which keys an event affects and hands
+//! them to a [`PurgeQueue`] instead of deleting them inline. A small pool of
+//! workers drains the queue, coalescing pending batches into `UNLINK` calls of
+//! up to `batch_size` keys. A flood of invalidation events therefore waits on
+//! the queue, not on Redis, and NATS consumption and the HTTP path keep their
+//! share of the connection pool.
+//!
+//! When the queue is full, enqueueing waits for room, so backpressure reaches
+//! the invalidation engine rather than growing memory without bound. The
+//! number of queued keys is exported as `scedge_purge_queue_depth`.
+//!
+//! A batch the backend fails to delete is retried by its worker with
+//! exponential backoff until it succeeds, since the event that queued it has
+//! already been recorded as processed. While the backend is down the workers
+//! stall and the queue fills, which again pushes back on the engine.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, Mutex};
+
+use crate::cache::Cache;
+use crate::config::PurgeQueueConfig;
+use crate::error::AppError;
+use crate::metrics::Metrics;
+
+/// Delay before the first retry of a failed batch
+const RETRY_BASE: Duration = Duration::from_millis(100);
+
+/// Longest delay between retries of a failed batch
+const RETRY_MAX: Duration = Duration::from_secs(10);
+
+/// Handle for queueing keys to be purged by the worker pool
+#[derive(Clone)]
+pub struct PurgeQueue {
+    sender: mpsc::Sender<Vec<String>>,
+    metrics: Metrics,
+}
+
+impl PurgeQueue {
+    /// Start the worker pool and return a handle to its queue
+    pub fn spawn(cache: Cache, metrics: Metrics, config: &PurgeQueueConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.capacity);
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for worker in 0..config.workers {
+            let cache = cache.clone();
+            let metrics = metrics.clone();
+            let receiver = receiver.clone();
+            let batch_size = config.batch_size;
+            tokio::spawn(async move {
+                while let Some(keys) = next_batch(&receiver, batch_size).await {
+                    for chunk in keys.chunks(batch_size) {
+                        purge_batch(&cache, &metrics, worker, chunk).await;
+                        // Keys count as queued until they are deleted
+                        metrics.record_purge_dequeued(chunk.len());
+                    }
+                }
+            });
+        }
+
+        Self { sender, metrics }
+    }
+
+    /// Queue keys for deletion, waiting while the queue is full
+    ///
+    /// Returns the number of keys queued.
+    pub async fn enqueue(&self, keys: Vec<String>) -> Result<usize, AppError> {
+        let queued = keys.len();
+        if queued == 0 {
+            return Ok(0);
+        }
+
+        self.metrics.record_purge_enqueued(queued);
+        if self.sender.send(keys).await.is_err() {
+            self.metrics.record_purge_dequeued(queued);
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Purge queue workers stopped"
+            )));
+        }
+        Ok(queued)
+    }
+}
+
+/// Delete `keys`, retrying with backoff until the backend accepts the batch
+async fn purge_batch(cache: &Cache, metrics: &Metrics, worker: usize, keys: &[String]) {
+    let mut delay = RETRY_BASE;
+    loop {
+        match cache.delete_many(keys).await {
+            Ok(purged) => {
+                tracing::debug!(worker, purged, "Purge batch applied");
+                return;
+            }
+            Err(err) => {
+                tracing::error!(
+                    worker,
+                    keys = keys.len(),
+                    error = %err,
+                    retry_in_ms = delay.as_millis() as u64,
+                    "Purge batch failed"
+                );
+                metrics.record_purge_retry();
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(RETRY_MAX);
+            }
+        }
+    }
+}
+
+/// Wait for a batch, then coalesce whatever else is already queued
+async fn next_batch(
+    receiver: &Mutex<mpsc::Receiver<Vec<String>>>,
+    batch_size: usize,
+) -> Option<Vec<String>> {
+    let mut receiver = receiver.lock().await;
+    let mut keys = receiver.recv().await?;
+    while keys.len() < batch_size {
+        match receiver.try_recv() {
+            Ok(more) => keys.extend(more),
+            Err(_) => break,
+        }
+    }
+    Some(keys)
+}
@@ -0,0 +1,124 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pre-rendered lookup responses for the hottest keys.
+//!
+//! On an L1 hit, serializing the artifact to JSON is most of the work left.
+//! With `SCEDGE_L1_RENDERED_CAPACITY` set alongside the L1, a cache hit of a
+//! key looked up at least `SCEDGE_L1_RENDER_MIN_HITS` times keeps the
+//! serialized body and its headers here, and later lookups of the key are
+//! answered with the stored bytes.
+//!
+//! A body embeds `ttl_remaining_seconds`, so it is only reused while that
+//! value is unchanged, i.e. re-rendered at most once per second. Rendered
+//! forms are held at most the L1 max age; stores, touches, and purges through
+//! the [`Cache`](crate::cache::Cache) drop them right away.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+
+use crate::model::PolicyContext;
+
+/// Serialized cache hit of one key, with what is needed to re-authorize it
+pub struct RenderedLookup {
+    pub policy: PolicyContext,
+    pub stored_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub compute_cost: Option<f64>,
+    /// `ttl_remaining_seconds` embedded in the body and headers
+    pub ttl_remaining_seconds: Option<u64>,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+struct Entry {
+    lookup: Arc<RenderedLookup>,
+    rendered_at: Instant,
+}
+
+/// Bounded set of rendered lookups, keyed by cache key
+pub struct RenderedResponses {
+    capacity: usize,
+    max_age: Duration,
+    min_hits: u8,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl RenderedResponses {
+    pub fn new(capacity: usize, max_age: Duration, min_hits: u8) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            max_age,
+            min_hits,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Lookups of a key before its hits are rendered
+    pub fn min_hits(&self) -> u8 {
+        self.min_hits
+    }
+
+    /// Rendered lookup of `key` that is still exact at `now`
+    pub fn get(&self, key: &str, now: DateTime<Utc>) -> Option<Arc<RenderedLookup>> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = entries.get(key)?;
+        let ttl_remaining_seconds = entry
+            .lookup
+            .expires_at
+            .map(|deadline| (deadline - now).num_seconds().max(0) as u64);
+
+        if entry.rendered_at.elapsed() >= self.max_age
+            || entry.lookup.expires_at.is_some_and(|exp| exp <= now)
+        {
+            entries.remove(key);
+            return None;
+        }
+        if ttl_remaining_seconds != entry.lookup.ttl_remaining_seconds {
+            return None;
+        }
+        Some(entry.lookup.clone())
+    }
+
+    /// Keep a rendered lookup of `key`, replacing any previous one
+    ///
+    /// When full, entries past the max age are dropped first; if none are,
+    /// the new lookup is not kept.
+    pub fn insert(&self, key: String, lookup: RenderedLookup) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.rendered_at.elapsed() < self.max_age);
+            if entries.len() >= self.capacity {
+                return;
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                lookup: Arc::new(lookup),
+                rendered_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop every rendered lookup
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Drop the rendered lookups of stored, touched, or purged keys
+    pub fn forget(&self, keys: &[String]) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        for key in keys {
+            entries.remove(key);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTTP routes of the service.
+//!
+//! [`router`] wires every endpoint and middleware layer onto an [`AppState`].
+//! The `scedge` binary serves it; embedders and the `testing` harness build
+//! the same router around their own state.
+
+use axum::middleware;
+use axum::response::Html;
+use axum::routing::{delete, get, post};
+use axum::Router;
+use tower_http::trace::TraceLayer;
+
+use crate::admin::{
+    handle_delete_tenant_overrides, handle_get_log_level, handle_get_tenant_overrides,
+    handle_put_log_level, handle_put_tenant_overrides, handle_register_tenant_key, handle_sync,
+    handle_tenant_erasure, handle_tenant_export, handle_train_dictionary,
+};
+use crate::api::{
+    enforce_read_only, handle_batch_lookup, handle_contains, handle_diff, handle_hash,
+    handle_invalidate, handle_lookup, handle_lookup_by_hash, handle_policy_evaluate, handle_purge,
+    handle_register_purge_schedule, handle_rotate_api_key, handle_store, handle_store_batch,
+    handle_touch, handle_touch_batch, handle_ttl, health, mark_event_lag,
+    metrics as metrics_handler, readiness, record_actor, track_policy_denials, AppState,
+};
+use crate::console::{handle_console, handle_console_summary, handle_key_inspect};
+use crate::drain::drain_requests;
+use crate::mirror::mirror_lookups;
+use crate::proxy::handle_proxy;
+
+/// Every endpoint with its middleware, bound to `state`
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/healthz", get(health))
+        .route("/health", get(health))
+        .route("/readyz", get(readiness))
+        .route("/metrics", get(metrics_handler))
+        .route("/lookup", get(handle_lookup))
+        .route("/lookup/batch", post(handle_batch_lookup))
+        .route("/lookup/by-hash", get(handle_lookup_by_hash))
+        .route("/ttl", get(handle_ttl))
+        .route("/diff", get(handle_diff))
+        .route("/touch", post(handle_touch))
+        .route("/touch/batch", post(handle_touch_batch))
+        .route("/contains", post(handle_contains))
+        .route("/store", post(handle_store))
+        .route("/store/batch", post(handle_store_batch))
+        .route("/hash", post(handle_hash))
+        .route("/policy/evaluate", post(handle_policy_evaluate))
+        .route("/purge", post(handle_purge))
+        .route("/purge/schedules", post(handle_register_purge_schedule))
+        .route("/tenant/keys/rotate", post(handle_rotate_api_key))
+        .route("/invalidate", post(handle_invalidate))
+        .route("/proxy/:tenant/*path", get(handle_proxy).post(handle_proxy))
+        .route("/admin/tenants/:id/export", post(handle_tenant_export))
+        .route("/admin/tenants/:id/data", delete(handle_tenant_erasure))
+        .route("/admin/tenants/:id/keys", post(handle_register_tenant_key))
+        .route(
+            "/admin/tenants/:id/dictionary",
+            post(handle_train_dictionary),
+        )
+        .route(
+            "/admin/tenants/:id/overrides",
+            get(handle_get_tenant_overrides)
+                .put(handle_put_tenant_overrides)
+                .delete(handle_delete_tenant_overrides),
+        )
+        .route("/admin/sync", get(handle_sync))
+        .route(
+            "/admin/loglevel",
+            get(handle_get_log_level).put(handle_put_log_level),
+        )
+        .route("/admin/console/summary", get(handle_console_summary))
+        .route("/admin/keys", get(handle_key_inspect))
+        .route("/console", get(handle_console))
+        .route("/", get(index))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            track_policy_denials,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            mark_event_lag,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            enforce_read_only,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            mirror_lookups,
+        ))
+        .layer(middleware::from_fn_with_state(state.clone(), record_actor))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            drain_requests,
+        ))
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+async fn index() -> Html<&'static str> {
+    Html(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/static/index.html"
+    )))
+}
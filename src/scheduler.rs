@@ -0,0 +1,122 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scheduled purge rules.
+//!
+//! Tenants can register recurring invalidation schedules (cron expressions) for
+//! key prefixes or tags, either in the tenants file or through
+//! `POST /purge/schedules`. The scheduler task checks every rule once per second
+//! and purges matching artifacts whenever a rule fires.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use futures_util::StreamExt;
+use tokio::task::JoinHandle;
+
+use crate::cache::{escape_glob, Cache};
+use crate::error::AppError;
+use crate::metrics::Metrics;
+use crate::policy::{PolicyEngine, PurgeSchedule};
+
+/// Parse a six-field cron expression (`sec min hour day month weekday`)
+pub fn parse_schedule(expression: &str) -> Result<Schedule, AppError> {
+    Schedule::from_str(expression)
+        .map_err(|e| AppError::bad_request(format!("Invalid cron expression: {}", e)))
+}
+
+/// Background task executing tenant purge schedules
+pub struct PurgeScheduler {
+    cache: Cache,
+    policy: PolicyEngine,
+    metrics: Metrics,
+}
+
+impl PurgeScheduler {
+    pub fn new(cache: Cache, policy: PolicyEngine, metrics: Metrics) -> Self {
+        Self {
+            cache,
+            policy,
+            metrics,
+        }
+    }
+
+    /// Spawn the scheduler loop onto the runtime
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        let mut last_tick = Utc::now();
+
+        loop {
+            interval.tick().await;
+            let now = Utc::now();
+
+            for (tenant, schedule) in self.policy.purge_schedules().await {
+                if !is_due(&schedule, last_tick, now) {
+                    continue;
+                }
+
+                match self.execute(&tenant, &schedule).await {
+                    Ok(purged) => tracing::info!(
+                        tenant = %tenant,
+                        cron = %schedule.cron,
+                        purged,
+                        "Scheduled purge executed"
+                    ),
+                    Err(err) => tracing::error!(
+                        tenant = %tenant,
+                        cron = %schedule.cron,
+                        error = %err,
+                        "Scheduled purge failed"
+                    ),
+                }
+            }
+
+            last_tick = now;
+        }
+    }
+
+    /// Purge every artifact matched by a schedule
+    pub async fn execute(&self, tenant: &str, schedule: &PurgeSchedule) -> Result<usize, AppError> {
+        let pattern = format!(
+            "{}:{}*",
+            escape_glob(tenant),
+            escape_glob(schedule.prefix.as_deref().unwrap_or(""))
+        );
+
+        let keys = match &schedule.tag {
+            Some(tag) => {
+                let mut entries = self.cache.scan_entries(pattern);
+                let mut tagged = Vec::new();
+                while let Some(entry) = entries.next().await {
+                    let entry = entry?;
+                    if entry.artifact.tags.iter().any(|t| t == tag) {
+                        tagged.push(entry.key);
+                    }
+                }
+                tagged
+            }
+            None => self.cache.scan_by_pattern(&pattern).await?,
+        };
+
+        let purged = self.cache.delete_many(&keys).await?;
+        self.metrics.record_cache_purge(purged);
+
+        Ok(purged)
+    }
+}
+
+fn is_due(schedule: &PurgeSchedule, since: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    match parse_schedule(&schedule.cron) {
+        Ok(cron) => cron.after(&since).next().is_some_and(|next| next <= now),
+        Err(err) => {
+            tracing::debug!(cron = %schedule.cron, error = %err, "Skipping invalid schedule");
+            false
+        }
+    }
+}
@@ -0,0 +1,548 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic simulation of TTLs and invalidation, behind the `testing`
+//! feature.
+//!
+//! Expiry, `SUPERSEDED_BY` events, and purges interact in ways that are hard
+//! to cover with hand-written cases: an event delivered late, redelivered
+//! after its hash was stored again, or racing an entry that expires on its
+//! own. [`Simulation`] drives a [`Cache`] on the memory backend and an
+//! [`InvalidationEngine`] through a random but seeded sequence of stores,
+//! clock advances, event deliveries, purges, and sweeps, on a
+//! [`ManualClock`] so no step sleeps. After every lookup and every processed
+//! event, it checks what the cache serves against a reference model and
+//! these invariants:
+//!
+//! - an entry is never served at or after its expiry;
+//! - once a `SUPERSEDED_BY` event is processed, no entry stored before it
+//!   with that hash, or depending on it, is served again;
+//! - a purged key is not served until it is stored again;
+//! - a served entry is always the latest store of its key.
+//!
+//! The same seed replays the same run, so a failure is reproduced by its
+//! seed alone:
+//!
+//! ```ignore
+//! use scedge::simulation::{Simulation, SimulationConfig};
+//!
+//! for seed in 0..64 {
+//!     if let Err(violation) = Simulation::run(seed, SimulationConfig::default()).await {
+//!         panic!("{}", violation);
+//!     }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+
+use crate::cache::{Cache, MemoryCache};
+use crate::clock::ManualClock;
+use crate::events::{EventEnvelope, GraphEvent, InvalidationEngine};
+use crate::model::{ArtifactPayload, CachedArtifact};
+
+/// Tenants keys and events are drawn from
+const TENANTS: [&str; 2] = ["acme", "globex"];
+
+/// Shape of a simulated run
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Operations per run
+    pub steps: usize,
+    /// Keys per tenant
+    pub keys: usize,
+    /// Distinct artifact hashes, shared by both tenants
+    pub hashes: usize,
+    /// Longest TTL given to a store
+    pub max_ttl: StdDuration,
+    /// Retention of processed event ids in the ledger
+    pub ledger_retention: StdDuration,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            steps: 400,
+            keys: 6,
+            hashes: 4,
+            max_ttl: StdDuration::from_secs(120),
+            ledger_retention: StdDuration::from_secs(300),
+        }
+    }
+}
+
+/// One simulated operation
+#[derive(Debug, Clone)]
+pub enum Op {
+    /// Store an artifact, optionally declaring a dependency on a hash
+    Store {
+        key: String,
+        hash: String,
+        depends_on: Option<String>,
+        ttl_secs: u64,
+    },
+    /// Move the clock forward
+    Advance { secs: u64 },
+    /// Publish a `SUPERSEDED_BY` event, delivered by a later [`Op::Deliver`]
+    Supersede {
+        tenant: String,
+        old_hash: String,
+        event_id: String,
+    },
+    /// Process the oldest published event
+    Deliver,
+    /// Deliver an already processed event again
+    Redeliver { event_id: String },
+    /// Delete a key, as `POST /purge` does
+    Purge { key: String },
+    /// Drop expired entries and ledger ids
+    Sweep,
+    /// Read a key and check what is served
+    Lookup { key: String },
+}
+
+/// An invariant broken by a run, with what is needed to replay it
+#[derive(Debug)]
+pub struct Violation {
+    pub seed: u64,
+    /// Index of the failing operation in `trace`
+    pub step: usize,
+    pub message: String,
+    /// Every operation up to and including the failing one
+    pub trace: Vec<Op>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed {} step {}: {}", self.seed, self.step, self.message)?;
+        let from = self.trace.len().saturating_sub(20);
+        for (index, op) in self.trace.iter().enumerate().skip(from) {
+            writeln!(f, "  {:>4} {:?}", index, op)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Summary of a run that kept every invariant
+#[derive(Debug, Default, Clone)]
+pub struct SimulationReport {
+    pub stores: usize,
+    pub lookups: usize,
+    /// Lookups that found an entry
+    pub hits: usize,
+    /// Events processed, duplicates skipped by the ledger excluded
+    pub events_applied: usize,
+    pub purges: usize,
+}
+
+/// Entry as the reference model expects it
+#[derive(Debug, Clone)]
+struct ModelEntry {
+    seq: u64,
+    hash: String,
+    depends_on: Option<String>,
+    expires_at: DateTime<Utc>,
+}
+
+/// What the cache should hold after the operations applied so far
+#[derive(Debug, Default)]
+struct Model {
+    entries: BTreeMap<String, ModelEntry>,
+    /// Keys that declared a dependency on a hash, by `(tenant, hash)`; never
+    /// pruned on overwrite
+    dependents: BTreeMap<(String, String), BTreeSet<String>>,
+    /// Processed event ids until their retention ends
+    ledger: BTreeMap<String, DateTime<Utc>>,
+    /// Sequence number of the latest store of each key
+    latest: BTreeMap<String, u64>,
+    /// Sequence number at which each key was last purged
+    purged_at: BTreeMap<String, u64>,
+    /// Sequence number at which each `(tenant, hash)` was last superseded
+    superseded_at: BTreeMap<(String, String), u64>,
+}
+
+impl Model {
+    fn live(&self, key: &str, now: DateTime<Utc>) -> Option<&ModelEntry> {
+        self.entries.get(key).filter(|entry| entry.expires_at > now)
+    }
+
+    /// Apply a `SUPERSEDED_BY` event the way [`crate::invalidation::Invalidator::supersede`] does
+    fn supersede(&mut self, tenant: &str, old_hash: &str, now: DateTime<Utc>, seq: u64) {
+        let prefix = format!("{}:", tenant);
+        let mut purged: BTreeSet<String> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| {
+                key.starts_with(&prefix) && entry.expires_at > now && entry.hash == old_hash
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        // Only the tenant's own dependents cascade
+        purged.extend(
+            self.dependents
+                .remove(&(tenant.to_string(), old_hash.to_string()))
+                .unwrap_or_default(),
+        );
+        for key in &purged {
+            self.entries.remove(key);
+        }
+        self.superseded_at
+            .insert((tenant.to_string(), old_hash.to_string()), seq);
+    }
+}
+
+/// Seeded run of interleaved cache operations against a reference model
+pub struct Simulation {
+    seed: u64,
+    config: SimulationConfig,
+    rng: StdRng,
+    clock: ManualClock,
+    cache: Cache,
+    engine: InvalidationEngine,
+    model: Model,
+    /// Published events not delivered yet
+    pending: VecDeque<EventEnvelope>,
+    /// Events delivered at least once, for redelivery
+    delivered: Vec<EventEnvelope>,
+    trace: Vec<Op>,
+    report: SimulationReport,
+    next_event: u64,
+}
+
+impl Simulation {
+    pub fn new(seed: u64, config: SimulationConfig) -> Self {
+        let clock =
+            ManualClock::new(DateTime::from_timestamp(1_700_000_000, 0).unwrap_or_default());
+        let cache = Cache::new(MemoryCache::new().with_clock(clock.clone()));
+        let engine =
+            InvalidationEngine::new(cache.clone()).with_ledger_retention(config.ledger_retention);
+        Self {
+            seed,
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            clock,
+            cache,
+            engine,
+            model: Model::default(),
+            pending: VecDeque::new(),
+            delivered: Vec::new(),
+            trace: Vec::new(),
+            report: SimulationReport::default(),
+            next_event: 0,
+        }
+    }
+
+    /// Run `config.steps` operations from `seed`, stopping at the first violation
+    pub async fn run(seed: u64, config: SimulationConfig) -> Result<SimulationReport, Violation> {
+        let mut simulation = Self::new(seed, config);
+        for _ in 0..simulation.config.steps {
+            let op = simulation.next_op();
+            simulation.apply(op).await?;
+        }
+        // Whatever is still in flight is delivered before the final check
+        while !simulation.pending.is_empty() {
+            simulation.apply(Op::Deliver).await?;
+        }
+        Ok(simulation.report)
+    }
+
+    /// Draw the next operation
+    fn next_op(&mut self) -> Op {
+        let roll = self.rng.gen_range(0..100);
+        match roll {
+            0..=29 => Op::Store {
+                key: self.random_key(),
+                hash: self.random_hash(),
+                depends_on: self.rng.gen_bool(0.25).then(|| self.random_hash()),
+                ttl_secs: self.rng.gen_range(1..=self.config.max_ttl.as_secs().max(1)),
+            },
+            30..=44 => Op::Advance {
+                secs: self
+                    .rng
+                    .gen_range(1..=self.config.max_ttl.as_secs().max(1) / 2 + 1),
+            },
+            45..=54 => {
+                self.next_event += 1;
+                Op::Supersede {
+                    tenant: self.random_tenant().to_string(),
+                    old_hash: self.random_hash(),
+                    event_id: format!("evt-{}", self.next_event),
+                }
+            }
+            55..=64 => Op::Deliver,
+            65..=69 if !self.delivered.is_empty() => {
+                let index = self.rng.gen_range(0..self.delivered.len());
+                Op::Redeliver {
+                    event_id: self.delivered[index].event_id.clone().unwrap_or_default(),
+                }
+            }
+            70..=76 => Op::Purge {
+                key: self.random_key(),
+            },
+            77..=79 => Op::Sweep,
+            _ => Op::Lookup {
+                key: self.random_key(),
+            },
+        }
+    }
+
+    fn random_tenant(&mut self) -> &'static str {
+        TENANTS[self.rng.gen_range(0..TENANTS.len())]
+    }
+
+    fn random_key(&mut self) -> String {
+        let tenant = self.random_tenant();
+        format!(
+            "{}:k{}",
+            tenant,
+            self.rng.gen_range(0..self.config.keys.max(1))
+        )
+    }
+
+    fn random_hash(&mut self) -> String {
+        format!("h{}", self.rng.gen_range(0..self.config.hashes.max(1)))
+    }
+
+    /// Apply `op` to both the cache and the model, then check the invariants
+    async fn apply(&mut self, op: Op) -> Result<(), Violation> {
+        self.trace.push(op.clone());
+        let seq = self.trace.len() as u64;
+        let now = self.clock.now();
+
+        match op {
+            Op::Store {
+                key,
+                hash,
+                depends_on,
+                ttl_secs,
+            } => {
+                let expires_at = now + Duration::seconds(ttl_secs as i64);
+                let mut artifact = ArtifactPayload::builder()
+                    .answer(json!({ "seq": seq }))
+                    .tenant(crate::keys::key_tenant(&key))
+                    .hash(hash.clone());
+                if let Some(reference) = &depends_on {
+                    artifact = artifact.depends_on(reference.clone());
+                }
+                let artifact = artifact.build().map_err(|e| self.violation(e))?;
+                self.cache
+                    .set(key.clone(), artifact, Some(expires_at))
+                    .await
+                    .map_err(|e| self.violation(e))?;
+
+                if let Some(reference) = &depends_on {
+                    self.model
+                        .dependents
+                        .entry((crate::keys::key_tenant(&key).to_string(), reference.clone()))
+                        .or_default()
+                        .insert(key.clone());
+                }
+                self.model.latest.insert(key.clone(), seq);
+                self.model.entries.insert(
+                    key,
+                    ModelEntry {
+                        seq,
+                        hash,
+                        depends_on,
+                        expires_at,
+                    },
+                );
+                self.report.stores += 1;
+            }
+            Op::Advance { secs } => {
+                self.clock.advance(Duration::seconds(secs as i64));
+            }
+            Op::Supersede {
+                tenant,
+                old_hash,
+                event_id,
+            } => {
+                self.pending.push_back(EventEnvelope {
+                    event: GraphEvent::SupersededBy {
+                        old_hash: old_hash.clone(),
+                        new_hash: format!("{}-next", old_hash),
+                        tenant,
+                    },
+                    event_id: Some(event_id),
+                    traceparent: None,
+                });
+            }
+            Op::Deliver => {
+                if let Some(envelope) = self.pending.pop_front() {
+                    self.deliver(envelope.clone(), seq, now).await;
+                    self.delivered.push(envelope);
+                    self.check_all().await?;
+                }
+            }
+            Op::Redeliver { event_id } => {
+                let envelope = self
+                    .delivered
+                    .iter()
+                    .find(|envelope| envelope.event_id.as_deref() == Some(event_id.as_str()))
+                    .cloned();
+                if let Some(envelope) = envelope {
+                    self.deliver(envelope, seq, now).await;
+                    self.check_all().await?;
+                }
+            }
+            Op::Purge { key } => {
+                self.cache
+                    .delete_many(std::slice::from_ref(&key))
+                    .await
+                    .map_err(|e| self.violation(e))?;
+                self.model.entries.remove(&key);
+                self.model.purged_at.insert(key, seq);
+                self.report.purges += 1;
+            }
+            Op::Sweep => {
+                self.cache
+                    .sweep_expired()
+                    .await
+                    .map_err(|e| self.violation(e))?;
+                self.model.entries.retain(|_, entry| entry.expires_at > now);
+                self.model.ledger.retain(|_, expires_at| *expires_at > now);
+            }
+            Op::Lookup { key } => {
+                self.report.lookups += 1;
+                if self.check(&key).await? {
+                    self.report.hits += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand an event to the engine and mirror its ledger check in the model
+    async fn deliver(&mut self, envelope: EventEnvelope, seq: u64, now: DateTime<Utc>) {
+        let event_id = envelope.event_id.clone().unwrap_or_default();
+        self.model.ledger.retain(|_, expires_at| *expires_at > now);
+        let duplicate = self.model.ledger.contains_key(&event_id);
+
+        self.engine.handle_envelope(envelope.clone()).await;
+
+        if duplicate {
+            return;
+        }
+        let retention = Duration::from_std(self.config.ledger_retention).unwrap_or(Duration::MAX);
+        self.model.ledger.insert(event_id, now + retention);
+        if let GraphEvent::SupersededBy {
+            old_hash, tenant, ..
+        } = &envelope.event
+        {
+            self.model.supersede(tenant, old_hash, now, seq);
+        }
+        self.report.events_applied += 1;
+    }
+
+    /// Check every key the run can touch
+    async fn check_all(&self) -> Result<(), Violation> {
+        for tenant in TENANTS {
+            for index in 0..self.config.keys.max(1) {
+                self.check(&format!("{}:k{}", tenant, index)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read `key` and check it against the invariants and the model,
+    /// returning whether it was served
+    async fn check(&self, key: &str) -> Result<bool, Violation> {
+        let now = self.clock.now();
+        let served = self.cache.get(key).await.map_err(|e| self.violation(e))?;
+        let expected = self.model.live(key, now);
+
+        let Some(served) = served else {
+            return match expected {
+                Some(entry) => Err(self.violation(format!(
+                    "{} missing, expected the store at step {}",
+                    key,
+                    entry.seq - 1
+                ))),
+                None => Ok(false),
+            };
+        };
+
+        let seq = served_seq(&served);
+        let step = seq.saturating_sub(1);
+        if served
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= now)
+        {
+            return Err(self.violation(format!(
+                "{} served the store at step {} after it expired",
+                key, step
+            )));
+        }
+        let tenant = crate::keys::key_tenant(key).to_string();
+        let superseded = |hash: &str| {
+            self.model
+                .superseded_at
+                .get(&(tenant.clone(), hash.to_string()))
+                .is_some_and(|&at| at > seq)
+        };
+        if superseded(&served.artifact.hash) {
+            return Err(self.violation(format!(
+                "{} served hash {} after it was superseded",
+                key, served.artifact.hash
+            )));
+        }
+        if let Some(reference) = served
+            .artifact
+            .depends_on
+            .iter()
+            .find(|reference| superseded(reference))
+        {
+            return Err(self.violation(format!(
+                "{} served a dependent of {} after it was superseded",
+                key, reference
+            )));
+        }
+        if self.model.purged_at.get(key).is_some_and(|&at| at > seq) {
+            return Err(self.violation(format!(
+                "{} served the store at step {} after it was purged",
+                key, step
+            )));
+        }
+        if self.model.latest.get(key) != Some(&seq) {
+            return Err(self.violation(format!(
+                "{} served the store at step {} instead of the latest one",
+                key, step
+            )));
+        }
+        match expected {
+            Some(entry)
+                if entry.hash == served.artifact.hash
+                    && entry.depends_on.as_deref()
+                        == served.artifact.depends_on.first().map(String::as_str) =>
+            {
+                Ok(true)
+            }
+            _ => Err(self.violation(format!(
+                "{} served the store at step {}, which the model dropped",
+                key, step
+            ))),
+        }
+    }
+
+    fn violation(&self, message: impl fmt::Display) -> Violation {
+        Violation {
+            seed: self.seed,
+            step: self.trace.len().saturating_sub(1),
+            message: message.to_string(),
+            trace: self.trace.clone(),
+        }
+    }
+}
+
+/// Sequence number a store wrote into its answer
+fn served_seq(served: &CachedArtifact) -> u64 {
+    served.artifact.answer["seq"].as_u64().unwrap_or(0)
+}
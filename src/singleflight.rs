@@ -0,0 +1,102 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coalescing of concurrent upstream hydrations.
+//!
+//! When a popular key expires, every lookup that misses it at the same moment
+//! would hydrate it from the upstream graph. [`Singleflight`] lets the first
+//! caller for a `(tenant, key)` run the call while later callers wait for its
+//! result.
+//!
+//! A leader that is cancelled before finishing (e.g. its client hung up)
+//! leaves no result behind; one of the waiters then becomes the new leader.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+
+type CallKey = (String, String);
+
+/// In-flight calls by `(tenant, key)`, each shared with every concurrent caller
+pub struct Singleflight<T> {
+    calls: Arc<Mutex<HashMap<CallKey, watch::Receiver<Option<T>>>>>,
+}
+
+impl<T> Clone for Singleflight<T> {
+    fn clone(&self) -> Self {
+        Self {
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+impl<T> Default for Singleflight<T> {
+    fn default() -> Self {
+        Self {
+            calls: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+/// Removes a leader's entry once it finished or was cancelled
+struct Flight<T> {
+    calls: Arc<Mutex<HashMap<CallKey, watch::Receiver<Option<T>>>>>,
+    key: CallKey,
+}
+
+impl<T> Drop for Flight<T> {
+    fn drop(&mut self) {
+        let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        calls.remove(&self.key);
+    }
+}
+
+impl<T: Clone> Singleflight<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `call` unless one is already in flight for `(tenant, key)`
+    ///
+    /// Returns the result and whether it was shared from another caller's call.
+    pub async fn run<F, Fut>(&self, tenant: &str, key: &str, call: F) -> (T, bool)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let call_key = (tenant.to_string(), key.to_string());
+
+        let leader = loop {
+            let mut waiting = {
+                let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+                match calls.get(&call_key) {
+                    Some(receiver) => receiver.clone(),
+                    None => {
+                        let (sender, receiver) = watch::channel(None);
+                        calls.insert(call_key.clone(), receiver);
+                        break sender;
+                    }
+                }
+            };
+
+            let result = match waiting.wait_for(Option::is_some).await {
+                Ok(result) => result.clone(),
+                Err(_) => None,
+            };
+            if let Some(result) = result {
+                return (result, true);
+            }
+            // The leader was cancelled; take over its key
+        };
+
+        let _flight = Flight {
+            calls: self.calls.clone(),
+            key: call_key,
+        };
+        let result = call().await;
+        leader.send_replace(Some(result.clone()));
+        (result, false)
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-process copies of recently served artifacts for serve-stale-on-error.
+//!
+//! A brief Redis outage should not take down lookups for tenants that prefer
+//! an old answer over none. For tenants with `serve_stale_on_error`, every
+//! cache hit and store keeps a copy of the artifact here. When a later read
+//! of the key fails in the backend, the copy is served, however old, with
+//! `X-Cache: STALE-ERROR`.
+//!
+//! Copies are bounded by `SCEDGE_STALE_COPIES_CAPACITY`; the oldest inserted
+//! key is dropped first. Purges through the [`Cache`](crate::cache::Cache)
+//! drop copies too, so an invalidated artifact is never served from here.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use crate::model::CachedArtifact;
+
+#[derive(Default)]
+struct Copies {
+    entries: HashMap<String, CachedArtifact>,
+    /// Keys in insertion order
+    order: VecDeque<String>,
+}
+
+/// Bounded set of last-known-good artifacts, keyed by cache key
+pub struct StaleCopies {
+    capacity: usize,
+    copies: Mutex<Copies>,
+}
+
+impl StaleCopies {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            copies: Mutex::new(Copies::default()),
+        }
+    }
+
+    /// Keep `record` as the key's last-known-good copy
+    pub fn remember(&self, record: &CachedArtifact) {
+        let mut copies = self.copies.lock().unwrap_or_else(|e| e.into_inner());
+        if copies
+            .entries
+            .insert(record.key.clone(), record.clone())
+            .is_none()
+        {
+            copies.order.push_back(record.key.clone());
+        }
+
+        while copies.entries.len() > self.capacity {
+            let Some(oldest) = copies.order.pop_front() else {
+                break;
+            };
+            copies.entries.remove(&oldest);
+        }
+    }
+
+    /// Last-known-good copy of `key`
+    pub fn get(&self, key: &str) -> Option<CachedArtifact> {
+        let copies = self.copies.lock().unwrap_or_else(|e| e.into_inner());
+        copies.entries.get(key).cloned()
+    }
+
+    /// Drop every copy
+    pub fn clear(&self) {
+        *self.copies.lock().unwrap_or_else(|e| e.into_inner()) = Copies::default();
+    }
+
+    /// Drop the copies of purged keys
+    pub fn forget(&self, keys: &[String]) {
+        let mut copies = self.copies.lock().unwrap_or_else(|e| e.into_inner());
+        let removed: HashSet<&String> = keys
+            .iter()
+            .filter(|key| copies.entries.remove(*key).is_some())
+            .collect();
+        if !removed.is_empty() {
+            copies.order.retain(|key| !removed.contains(key));
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Periodic expiry sweeps.
+//!
+//! Redis expires entries on its own, but the memory, L1, and SQLite backends
+//! only hide expired entries from reads, so they would hold on to them until
+//! the key is read again. Every `SCEDGE_EXPIRY_SWEEP_SECS` the sweeper deletes
+//! expired entries, counts them in `scedge_artifacts_expired_total`, and
+//! refreshes the `scedge_cache_size` gauge.
+//!
+//! Index sets outlive the entries they list on every backend, so each sweep
+//! also prunes members whose entry is gone, including entries Redis expired.
+
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::cache::Cache;
+use crate::metrics::Metrics;
+
+/// Background task reaping expired entries
+pub struct ExpirySweeper {
+    cache: Cache,
+    metrics: Metrics,
+    interval: Duration,
+}
+
+impl ExpirySweeper {
+    pub fn new(cache: Cache, metrics: Metrics, interval: Duration) -> Self {
+        Self {
+            cache,
+            metrics,
+            interval,
+        }
+    }
+
+    /// Spawn the sweep loop onto the runtime
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.cache.sweep_expired().await {
+                Ok(0) => {}
+                Ok(swept) => {
+                    self.metrics.record_artifacts_expired(swept);
+                    tracing::debug!(swept, "Swept expired artifacts");
+                }
+                Err(err) => tracing::warn!(error = %err, "Expiry sweep failed"),
+            }
+
+            match self.cache.prune_indexes().await {
+                Ok(0) => {}
+                Ok(pruned) => tracing::debug!(pruned, "Pruned index members of expired entries"),
+                Err(err) => tracing::warn!(error = %err, "Index prune failed"),
+            }
+
+            match self.cache.key_count().await {
+                Ok(size) => self.metrics.update_cache_size(size as i64),
+                Err(err) => tracing::warn!(error = %err, "Failed to refresh cache size"),
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cold-start cache transfer from a sibling node.
+//!
+//! A freshly provisioned node starts empty and would otherwise pay an
+//! upstream hydration for every key of the working set. With
+//! `SCEDGE_SYNC_FROM` set, it streams `GET /admin/sync` from that sibling at
+//! startup and stores every live entry locally, keeping each entry's expiry.
+//! `SCEDGE_SYNC_WINDOW_SECS` limits the copy to entries written recently.
+//!
+//! The node serves requests while the copy runs; keys not copied yet are
+//! ordinary misses.
+
+use anyhow::anyhow;
+use chrono::Utc;
+use reqwest::Client;
+
+use crate::cache::Cache;
+use crate::config::SyncConfig;
+use crate::error::AppError;
+use crate::model::CachedArtifact;
+
+/// Copy the sibling's entries into `cache`, returning how many were stored
+pub async fn copy_from_sibling(config: &SyncConfig, cache: &Cache) -> Result<usize, AppError> {
+    let client = Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .build()
+        .map_err(|e| AppError::Internal(anyhow!("Failed to build sync client: {}", e)))?;
+
+    let mut request = client.get(format!("{}/admin/sync", config.source));
+    if let Some(window) = config.window {
+        let since = Utc::now() - chrono::Duration::from_std(window).unwrap_or_default();
+        request = request.query(&[("since", since.to_rfc3339())]);
+    }
+    if let Some(token) = &config.token {
+        request = request.bearer_auth(token);
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::Internal(anyhow!("Sync request failed: {}", e)))?;
+
+    let mut stored = 0;
+    let mut pending = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Internal(anyhow!("Sync stream failed: {}", e)))?
+    {
+        pending.extend_from_slice(&chunk);
+        while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            stored += store_line(cache, &line[..end]).await? as usize;
+        }
+    }
+    stored += store_line(cache, &pending).await? as usize;
+
+    Ok(stored)
+}
+
+/// Store one streamed entry unless it expired in transit
+async fn store_line(cache: &Cache, line: &[u8]) -> Result<bool, AppError> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(false);
+    }
+
+    let record: CachedArtifact = serde_json::from_slice(line)
+        .map_err(|e| AppError::Internal(anyhow!("Invalid sync entry: {}", e)))?;
+    if record
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Ok(false);
+    }
+
+    cache
+        .set(record.key, record.artifact, record.expires_at)
+        .await?;
+    Ok(true)
+}
@@ -0,0 +1,101 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! systemd socket activation and readiness notification.
+//!
+//! Under a `.socket` unit, systemd binds the listen address itself and passes
+//! the socket to the service as file descriptor 3 with `LISTEN_PID` and
+//! `LISTEN_FDS` set; [`listen_fds`] adopts it instead of binding the first
+//! configured listener, so connections made while the node starts wait
+//! in the kernel backlog instead of being refused.
+//!
+//! With `Type=notify`, [`notify`] tells systemd through `NOTIFY_SOCKET` that
+//! the node is serving (`READY=1`) or shutting down (`STOPPING=1`), so
+//! dependent units are ordered on actual readiness rather than on health-check
+//! timing. Outside systemd both are no-ops.
+
+use std::io;
+use std::net::TcpListener;
+
+/// First descriptor passed by socket activation (`SD_LISTEN_FDS_START`)
+#[cfg(unix)]
+const LISTEN_FDS_START: i32 = 3;
+
+/// Listener passed by systemd socket activation, if any
+///
+/// Only the first passed socket is used; extra ones are ignored with a
+/// warning. The activation variables are cleared so child processes do not
+/// adopt the socket a second time.
+#[cfg(unix)]
+pub fn listen_fds() -> io::Result<Option<TcpListener>> {
+    use std::os::unix::io::FromRawFd;
+
+    let for_us = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| pid == std::process::id());
+    let count = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|count| count.parse::<i32>().ok())
+        .unwrap_or(0);
+    std::env::remove_var("LISTEN_PID");
+    std::env::remove_var("LISTEN_FDS");
+    std::env::remove_var("LISTEN_FDNAMES");
+
+    if !for_us || count < 1 {
+        return Ok(None);
+    }
+    if count > 1 {
+        tracing::warn!(
+            count,
+            "systemd passed several sockets; only the first is served"
+        );
+    }
+
+    // SAFETY: systemd hands descriptors LISTEN_FDS_START.. to the process
+    // named in LISTEN_PID, which was checked above; nothing else owns it.
+    let listener = unsafe { TcpListener::from_raw_fd(LISTEN_FDS_START) };
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+#[cfg(not(unix))]
+pub fn listen_fds() -> io::Result<Option<TcpListener>> {
+    Ok(None)
+}
+
+/// Send a state string such as `READY=1` to the service manager
+///
+/// Does nothing when `NOTIFY_SOCKET` is unset. Failures are logged, since a
+/// lost notification must not take the node down.
+pub fn notify(state: &str) {
+    #[cfg(unix)]
+    if let Some(path) = std::env::var_os("NOTIFY_SOCKET") {
+        if let Err(error) = send_notification(&path, state) {
+            tracing::warn!(%error, state, "Failed to notify systemd");
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = state;
+}
+
+#[cfg(unix)]
+fn send_notification(path: &std::ffi::OsStr, state: &str) -> io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::UnixDatagram;
+
+    let socket = UnixDatagram::unbound()?;
+    match path.as_bytes() {
+        #[cfg(target_os = "linux")]
+        [b'@', name @ ..] => {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        _ => {
+            socket.send_to(state.as_bytes(), path)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tenant-registered answer templates.
+//!
+//! Consumers often need different shapes of the same answer. Instead of
+//! caching near-duplicate artifacts, a tenant registers named templates in
+//! its `answer_templates`, and `GET /lookup?template={name}` reshapes the
+//! cached answer on the way out.
+//!
+//! A template is a JSON value mirroring the output. Strings are JSON Pointers
+//! (RFC 6901) into the answer, `""` being the whole answer; pointers that
+//! resolve to nothing yield `null`. Objects and arrays are rendered member by
+//! member, other values are copied as is, and `{"$literal": value}` emits
+//! `value` without interpreting it:
+//!
+//! ```json
+//! { "title": "/document/title", "tags": ["/labels/0", "/labels/1"], "version": 2 }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Field marking a value to be emitted verbatim
+const LITERAL_FIELD: &str = "$literal";
+
+/// Validated template, rendered with [`AnswerTemplate::render`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Value", into = "Value")]
+pub struct AnswerTemplate(Value);
+
+impl TryFrom<Value> for AnswerTemplate {
+    type Error = String;
+
+    fn try_from(template: Value) -> Result<Self, Self::Error> {
+        validate(&template)?;
+        Ok(Self(template))
+    }
+}
+
+impl From<AnswerTemplate> for Value {
+    fn from(template: AnswerTemplate) -> Self {
+        template.0
+    }
+}
+
+impl AnswerTemplate {
+    /// Reshape `answer` into the template's output
+    pub fn render(&self, answer: &Value) -> Value {
+        render(&self.0, answer)
+    }
+}
+
+fn literal(fields: &Map<String, Value>) -> Option<&Value> {
+    match fields.len() {
+        1 => fields.get(LITERAL_FIELD),
+        _ => None,
+    }
+}
+
+fn validate(template: &Value) -> Result<(), String> {
+    match template {
+        Value::String(pointer) if !pointer.is_empty() && !pointer.starts_with('/') => Err(format!(
+            "template string `{}` is not a JSON Pointer; wrap literals in {{\"{}\": ...}}",
+            pointer, LITERAL_FIELD
+        )),
+        Value::Array(items) => items.iter().try_for_each(validate),
+        Value::Object(fields) if literal(fields).is_none() => {
+            fields.values().try_for_each(validate)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn render(template: &Value, answer: &Value) -> Value {
+    match template {
+        Value::String(pointer) => answer.pointer(pointer).cloned().unwrap_or(Value::Null),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|item| render(item, answer)).collect())
+        }
+        Value::Object(fields) => match literal(fields) {
+            Some(value) => value.clone(),
+            None => Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, field)| (name.clone(), render(field, answer)))
+                    .collect(),
+            ),
+        },
+        other => other.clone(),
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-request tenant resolution.
+//!
+//! [`TenantContext`] is an axum extractor that reads the caller's credentials
+//! once per request: a bearer JWT (when JWT validation is configured) or an
+//! `x-api-key`, plus an explicit `tenant` query parameter. Handlers then check
+//! the tenant an operation targets with [`TenantContext::authorize`] instead
+//! of parsing headers themselves.
+//!
+//! Credentials stay optional, as they always were for the data-plane
+//! endpoints: a request without one is unauthenticated and passes
+//! [`authorize`](TenantContext::authorize) for any tenant.
+//!
+//! A request may also select a cache namespace, e.g. `staging`, through the
+//! JWT `namespace` claim or the `x-scedge-namespace` header, so a tenant's
+//! staging traffic neither reads nor overwrites production entries. Keys of
+//! such requests are mapped with [`TenantContext::scope_key`], and the tenant
+//! must list the namespace in its `namespaces`.
+
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Query};
+use axum::http::header;
+use axum::http::request::Parts;
+use serde::Deserialize;
+
+use crate::api::AppState;
+use crate::error::AppError;
+use crate::keys::{
+    key_namespace, namespaced_key, strip_namespace, validate_namespace, validate_tenant_id,
+};
+use crate::model::PolicyContext;
+use crate::policy::{extract_bearer_token, Claims, PolicyEngine, PolicyRule};
+
+#[derive(Debug)]
+enum Credential {
+    Jwt(Claims),
+    ApiKey {
+        key: String,
+        /// Tenant the key belongs to, `None` for unknown keys
+        tenant: Option<String>,
+    },
+}
+
+/// Header selecting the cache namespace of a request
+pub const NAMESPACE_HEADER: &str = "x-scedge-namespace";
+
+#[derive(Debug, Default, Deserialize)]
+struct TenantParam {
+    tenant: Option<String>,
+}
+
+/// Caller identity resolved from a request's headers and query string
+#[derive(Debug)]
+pub struct TenantContext {
+    /// Tenant named by the `tenant` query parameter
+    pub requested: Option<String>,
+    credential: Option<Credential>,
+    /// Cache namespace, `None` for the default one
+    namespace: Option<String>,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for TenantContext {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, AppError> {
+        let requested = Query::<TenantParam>::try_from_uri(&parts.uri)
+            .map(|Query(param)| param.tenant)
+            .unwrap_or_default();
+
+        let bearer = extract_bearer_token(
+            parts
+                .headers
+                .get(header::AUTHORIZATION)
+                .and_then(|h| h.to_str().ok()),
+        );
+        let api_key = parts.headers.get("x-api-key").and_then(|h| h.to_str().ok());
+
+        let credential = match (bearer, api_key) {
+            (Some(token), _) if state.policy.jwt_enabled() => {
+                let claims = state.policy.validate_jwt(&token)?;
+                validate_tenant_id(&claims.sub)?;
+                Some(Credential::Jwt(claims))
+            }
+            (_, Some(key)) => Some(Credential::ApiKey {
+                tenant: state.policy.tenant_for_api_key(key).await,
+                key: key.to_string(),
+            }),
+            _ => None,
+        };
+
+        let header_namespace = parts
+            .headers
+            .get(NAMESPACE_HEADER)
+            .map(|h| {
+                h.to_str()
+                    .map(str::to_string)
+                    .map_err(|_| AppError::bad_request("invalid namespace header"))
+            })
+            .transpose()?;
+        let namespace = match (&credential, header_namespace) {
+            (Some(Credential::Jwt(claims)), header) if claims.namespace.is_some() => {
+                if header.is_some_and(|header| Some(&header) != claims.namespace.as_ref()) {
+                    return Err(AppError::policy_denied(
+                        &claims.sub,
+                        PolicyRule::Namespace,
+                        "Namespace header does not match the JWT namespace",
+                    ));
+                }
+                claims.namespace.clone()
+            }
+            (_, header) => header,
+        };
+        if let Some(namespace) = &namespace {
+            validate_namespace(namespace)?;
+        }
+
+        Ok(Self {
+            requested,
+            credential,
+            namespace,
+        })
+    }
+}
+
+impl TenantContext {
+    /// Tenant proven by the request's credential
+    pub fn caller(&self) -> Option<&str> {
+        match &self.credential {
+            Some(Credential::Jwt(claims)) => Some(&claims.sub),
+            Some(Credential::ApiKey { tenant, .. }) => tenant.as_deref(),
+            None => None,
+        }
+    }
+
+    /// Whether the request carried any credential
+    pub fn is_authenticated(&self) -> bool {
+        self.credential.is_some()
+    }
+
+    /// Cache namespace the request selected, `None` for the default one
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Key under which `key` is stored in the request's namespace
+    ///
+    /// Keys whose second segment starts with `@` are reserved for namespaces
+    /// and rejected, so no request reaches another namespace's entries.
+    pub fn scope_key(&self, key: &str) -> Result<String, AppError> {
+        if key_namespace(key).is_some() {
+            return Err(AppError::bad_request(format!(
+                "key `{}` starts a segment with `@`, which is reserved for namespaces",
+                key
+            )));
+        }
+        Ok(match &self.namespace {
+            Some(namespace) => namespaced_key(key, namespace),
+            None => key.to_string(),
+        })
+    }
+
+    /// [`scope_key`](Self::scope_key) of every key of a batch
+    pub fn scope_keys(&self, keys: &[String]) -> Result<Vec<String>, AppError> {
+        keys.iter().map(|key| self.scope_key(key)).collect()
+    }
+
+    /// Key as the caller addressed it, for responses
+    pub fn unscope_key(&self, key: &str) -> String {
+        match &self.namespace {
+            Some(_) => strip_namespace(key),
+            None => key.to_string(),
+        }
+    }
+
+    /// Check that the credential, if any, is valid for `tenant`, and that the
+    /// tenant may use the request's namespace
+    pub async fn authorize(&self, policy: &PolicyEngine, tenant: &str) -> Result<(), AppError> {
+        validate_tenant_id(tenant)?;
+        if let Some(namespace) = &self.namespace {
+            if !policy.allows_namespace(tenant, namespace).await {
+                return Err(AppError::policy_denied(
+                    tenant,
+                    PolicyRule::Namespace,
+                    format!("Tenant may not use namespace {}", namespace),
+                ));
+            }
+        }
+        match &self.credential {
+            None => Ok(()),
+            Some(Credential::ApiKey { key, .. }) => policy.validate_api_key(tenant, key).await,
+            Some(Credential::Jwt(claims)) if claims.sub == tenant => Ok(()),
+            Some(Credential::Jwt(_)) => Err(AppError::policy_denied(
+                tenant,
+                PolicyRule::CrossTenant,
+                "JWT is not valid for the requested tenant",
+            )),
+        }
+    }
+
+    /// Whether the credential may read an artifact with this policy
+    ///
+    /// Artifacts without `allowed_subjects` or `allowed_scopes` are readable
+    /// by anyone [`authorize`](Self::authorize)d for their tenant. Restricted
+    /// ones need a JWT whose `user` claim is an allowed subject or that grants
+    /// an allowed scope; API keys never read them.
+    pub fn can_read(&self, policy: &PolicyContext) -> bool {
+        if !policy.is_read_restricted() {
+            return true;
+        }
+        match &self.credential {
+            Some(Credential::Jwt(claims)) => {
+                claims
+                    .user
+                    .as_ref()
+                    .is_some_and(|user| policy.allowed_subjects.contains(user))
+                    || claims
+                        .scopes
+                        .iter()
+                        .any(|scope| policy.allowed_scopes.contains(scope))
+            }
+            _ => false,
+        }
+    }
+
+    /// Reject reads of an artifact restricted to other subjects
+    pub fn authorize_read(&self, policy: &PolicyContext) -> Result<(), AppError> {
+        if self.can_read(policy) {
+            return Ok(());
+        }
+        Err(AppError::policy_denied(
+            &policy.tenant,
+            PolicyRule::ReadAcl,
+            "Artifact is restricted to other subjects",
+        ))
+    }
+
+    /// Require a JWT credential to grant `scope`; API keys carry no scopes
+    pub fn require_scope(&self, scope: &str) -> Result<(), AppError> {
+        match &self.credential {
+            Some(Credential::Jwt(claims)) if !claims.scopes.iter().any(|s| s == scope) => {
+                Err(AppError::policy_denied(
+                    &claims.sub,
+                    PolicyRule::Scope,
+                    format!("JWT lacks required scope {}", scope),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
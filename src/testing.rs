@@ -0,0 +1,245 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-process harness for integration tests, behind the `testing` feature.
+//!
+//! Downstream services that call Scedge over HTTP should not need Redis or
+//! NATS in CI. [`TestApp`] assembles the full router around the memory
+//! backend and an in-process graph event channel feeding the invalidation
+//! engine, with the canned tenants [`ACME`] and [`GLOBEX`] installed.
+//!
+//! Requests go through the router without a socket:
+//!
+//! ```ignore
+//! use scedge::testing::{TestApp, ACME};
+//!
+//! let app = TestApp::new().await?;
+//! let key = ACME.key("answers:greeting");
+//! app.send(ACME.store(&key, serde_json::json!("hello"))).await;
+//! let response = app.send(ACME.lookup(&key)).await;
+//! assert_eq!(response.status(), axum::http::StatusCode::OK);
+//! ```
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use axum::response::Response;
+use axum::Router;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::admission::Admission;
+use crate::api::AppState;
+use crate::cache::{Cache, MemoryCache};
+use crate::console::RecentInvalidations;
+use crate::crypto::Keyring;
+use crate::drain::Drain;
+use crate::error::AppError;
+use crate::events::{graph_event_channel, EventEnvelope, EventResync, InvalidationEngine};
+use crate::keygen::ContentKeyGenerator;
+use crate::metrics::Metrics;
+use crate::model::{ArtifactPayload, StoreRequest};
+use crate::plugins::PolicyPlugins;
+use crate::policy::{install_tenant, PolicyEngine, TenantConfig};
+use crate::routes::router;
+use crate::singleflight::Singleflight;
+use crate::warmup::Warmup;
+
+/// Admin token accepted by the harness's admin endpoints
+pub const ADMIN_TOKEN: &str = "scedge-test-admin";
+
+/// Canned tenant with a fixed API key
+#[derive(Debug, Clone, Copy)]
+pub struct TestTenant {
+    pub id: &'static str,
+    pub api_key: &'static str,
+}
+
+/// First canned tenant
+pub const ACME: TestTenant = TestTenant {
+    id: "acme",
+    api_key: "acme-test-key",
+};
+
+/// Second canned tenant, for isolation checks against [`ACME`]
+pub const GLOBEX: TestTenant = TestTenant {
+    id: "globex",
+    api_key: "globex-test-key",
+};
+
+impl TestTenant {
+    /// Tenant configuration with every optional setting at its default
+    pub fn config(&self) -> TenantConfig {
+        serde_json::from_value(json!({
+            "tenant_id": self.id,
+            "api_key": self.api_key,
+        }))
+        .expect("canned tenant config is valid")
+    }
+
+    /// Cache key in this tenant's namespace
+    pub fn key(&self, suffix: &str) -> String {
+        format!("{}:{}", self.id, suffix)
+    }
+
+    /// Artifact of this tenant holding `answer`, hashed with SHA-256
+    pub fn artifact(&self, answer: Value) -> ArtifactPayload {
+        ArtifactPayload::builder()
+            .answer(answer)
+            .tenant(self.id)
+            .build()
+            .expect("canned artifact is valid")
+    }
+
+    /// `POST /store` of `answer` under `key`, hashed with SHA-256
+    pub fn store(&self, key: &str, answer: Value) -> Request<Body> {
+        let request = StoreRequest::builder()
+            .key(key)
+            .artifact(self.artifact(answer))
+            .build()
+            .expect("store key is valid");
+        self.post(
+            "/store",
+            serde_json::to_value(request).expect("store request serializes"),
+        )
+    }
+
+    /// `GET /lookup` of `key`
+    pub fn lookup(&self, key: &str) -> Request<Body> {
+        let uri = format!(
+            "/lookup?key={}&tenant={}",
+            encode_query(key),
+            encode_query(self.id)
+        );
+        self.request(Method::GET, &uri)
+            .body(Body::empty())
+            .expect("lookup request is valid")
+    }
+
+    /// `POST /purge` of `keys`
+    pub fn purge(&self, keys: &[&str]) -> Request<Body> {
+        self.post("/purge", json!({ "tenant": self.id, "keys": keys }))
+    }
+
+    fn post(&self, uri: &str, body: Value) -> Request<Body> {
+        self.request(Method::POST, uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("JSON request is valid")
+    }
+
+    fn request(&self, method: Method, uri: &str) -> axum::http::request::Builder {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("x-api-key", self.api_key)
+    }
+}
+
+/// Percent-encode a query parameter value
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Scedge service running in-process on the memory backend
+pub struct TestApp {
+    pub state: AppState,
+    pub router: Router,
+}
+
+impl TestApp {
+    /// Service with [`ACME`] and [`GLOBEX`] installed
+    pub async fn new() -> Result<Self, AppError> {
+        Self::with_tenants(vec![ACME.config(), GLOBEX.config()]).await
+    }
+
+    /// Service with the given tenants installed
+    pub async fn with_tenants(tenants: Vec<TenantConfig>) -> Result<Self, AppError> {
+        let keyring = Keyring::new();
+        let cache = Cache::new(MemoryCache::new()).with_keyring(keyring.clone());
+        let policy = PolicyEngine::new(None);
+        let plugins = PolicyPlugins::new(1_000_000, 16 << 20)?;
+        for tenant in tenants {
+            install_tenant(tenant, &policy, &keyring, &plugins, &cache).await?;
+        }
+
+        let graph_events = graph_event_channel();
+        let event_resync = EventResync::default();
+        InvalidationEngine::new(cache.clone())
+            .with_resync(event_resync.clone())
+            .spawn(graph_events.subscribe());
+        let recent_invalidations = RecentInvalidations::new();
+        recent_invalidations.spawn_recorder(graph_events.subscribe());
+
+        let metrics = Metrics::new()?;
+        let state = AppState {
+            cache,
+            metrics: metrics.clone(),
+            policy,
+            default_ttl_seconds: 3600,
+            upstream: None,
+            peers: None,
+            http: reqwest::Client::new(),
+            admin_token: Some(ADMIN_TOKEN.to_string()),
+            xfetch_beta: 0.0,
+            event_bus: None,
+            event_publisher: None,
+            graph_events,
+            event_lag: None,
+            event_resync,
+            recent_invalidations,
+            keyring,
+            ready_when_degraded: false,
+            verify_hashes: false,
+            debug_timings: false,
+            api_key_grace: std::time::Duration::from_secs(3600),
+            max_batch_lookup_keys: 1000,
+            plugins,
+            admission: Admission::new(1024, 2),
+            read_only: Arc::new(AtomicBool::new(false)),
+            log_level: None,
+            ttl_tuner: None,
+            mirror: None,
+            hydrations: Singleflight::new(),
+            drain: Drain::new(metrics),
+            key_generator: Arc::new(ContentKeyGenerator),
+            warmup: Warmup::default(),
+        };
+
+        Ok(Self {
+            router: router(state.clone()),
+            state,
+        })
+    }
+
+    /// Run `request` through the router
+    pub async fn send(&self, request: Request<Body>) -> Response {
+        match self.router.clone().oneshot(request).await {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        }
+    }
+
+    /// Deliver a graph event to the invalidation engine, as a transport would
+    pub fn publish(&self, event: impl Into<EventEnvelope>) {
+        let _ = self.state.graph_events.send(event.into());
+    }
+}
+
+/// Read a response body as JSON
+pub async fn json_body(response: Response) -> Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body is readable");
+    serde_json::from_slice(&bytes).unwrap_or(Value::Null)
+}
@@ -0,0 +1,201 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-tenant tuning of the default TTL from observed traffic.
+//!
+//! One `SCEDGE_DEFAULT_TTL` rarely fits dozens of tenants: some rewrite their
+//! knowledge hourly, others keep answers for weeks. With
+//! `SCEDGE_TTL_AUTOTUNE_ENABLED=true`, each node compares per tenant the
+//! lookups (from `scedge_tenant_lookups_total`), stores, and purged keys of
+//! the last interval and adjusts the TTL applied to that tenant's artifacts
+//! that carry no `ttl_seconds`:
+//!
+//! - more keys invalidated than `SCEDGE_TTL_AUTOTUNE_MAX_INVALIDATION_RATIO`
+//!   per store shortens the TTL, since the tenant's data is volatile
+//! - otherwise a hit ratio under `SCEDGE_TTL_AUTOTUNE_TARGET_HIT_RATIO`
+//!   lengthens it, since entries expire before they are reused
+//!
+//! Each step scales the TTL by [`STEP`] within the configured bounds.
+//! Intervals with fewer than [`MIN_LOOKUPS`] lookups keep the current TTL.
+//! Decisions are exported as `scedge_tenant_default_ttl_seconds` and
+//! `scedge_ttl_adjustments_total`. Tuned values are local to the node and
+//! start from the configured default on restart.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::config::TtlAutotuneConfig;
+use crate::keys::key_tenant;
+use crate::metrics::Metrics;
+
+/// Factor a TTL is multiplied or divided by per adjustment
+pub const STEP: f64 = 1.5;
+
+/// Lookups an interval needs before its hit ratio is trusted
+pub const MIN_LOOKUPS: u64 = 20;
+
+/// Outcome of one tuning interval for a tenant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtlDecision {
+    Shorten,
+    Lengthen,
+    Hold,
+}
+
+impl TtlDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TtlDecision::Shorten => "shorten",
+            TtlDecision::Lengthen => "lengthen",
+            TtlDecision::Hold => "hold",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    ttl_seconds: u64,
+    /// Cumulative hits and misses seen when the interval started
+    lookups_at_start: (u64, u64),
+    stores: u64,
+    invalidated: u64,
+}
+
+/// Effective default TTL per tenant, adjusted every interval
+#[derive(Clone)]
+pub struct TtlTuner {
+    config: TtlAutotuneConfig,
+    initial_ttl_seconds: u64,
+    windows: Arc<Mutex<HashMap<String, Window>>>,
+}
+
+impl TtlTuner {
+    /// Tuner starting every tenant at `default_ttl_seconds`
+    pub fn new(config: TtlAutotuneConfig, default_ttl_seconds: u64) -> Self {
+        let initial_ttl_seconds = default_ttl_seconds.clamp(
+            config.min_ttl.as_secs(),
+            config.max_ttl.as_secs().max(config.min_ttl.as_secs()),
+        );
+        Self {
+            config,
+            initial_ttl_seconds,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn window(&self) -> Window {
+        Window {
+            ttl_seconds: self.initial_ttl_seconds,
+            lookups_at_start: (0, 0),
+            stores: 0,
+            invalidated: 0,
+        }
+    }
+
+    fn update(&self, tenant: &str, apply: impl FnOnce(&mut Window)) {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        apply(
+            windows
+                .entry(tenant.to_string())
+                .or_insert_with(|| self.window()),
+        );
+    }
+
+    pub fn record_store(&self, tenant: &str) {
+        self.update(tenant, |window| window.stores += 1);
+    }
+
+    /// Count purged keys against their tenants
+    pub fn record_invalidated(&self, keys: &[String]) {
+        let mut per_tenant: HashMap<&str, u64> = HashMap::new();
+        for key in keys {
+            *per_tenant.entry(key_tenant(key)).or_default() += 1;
+        }
+        for (tenant, count) in per_tenant {
+            self.update(tenant, |window| window.invalidated += count);
+        }
+    }
+
+    /// TTL applied to the tenant's artifacts without `ttl_seconds`
+    pub fn default_ttl_seconds(&self, tenant: &str) -> u64 {
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows
+            .get(tenant)
+            .map_or(self.initial_ttl_seconds, |window| window.ttl_seconds)
+    }
+
+    /// Close the current interval, adjusting and reporting each tenant's TTL
+    pub fn adjust(&self, metrics: &Metrics) {
+        let min_ttl = self.config.min_ttl.as_secs();
+        let max_ttl = self.config.max_ttl.as_secs().max(min_ttl);
+
+        let lookup_counts = metrics.tenant_lookup_counts();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        for tenant in lookup_counts.keys() {
+            if !windows.contains_key(tenant) {
+                windows.insert(tenant.clone(), self.window());
+            }
+        }
+
+        for (tenant, window) in windows.iter_mut() {
+            let counts = lookup_counts.get(tenant).copied().unwrap_or_default();
+            let hits = counts.0.saturating_sub(window.lookups_at_start.0);
+            let misses = counts.1.saturating_sub(window.lookups_at_start.1);
+            let lookups = hits + misses;
+            let decision = if window.stores > 0
+                && window.invalidated as f64 / window.stores as f64
+                    > self.config.max_invalidation_ratio
+            {
+                TtlDecision::Shorten
+            } else if lookups >= MIN_LOOKUPS
+                && (hits as f64 / lookups as f64) < self.config.target_hit_ratio
+            {
+                TtlDecision::Lengthen
+            } else {
+                TtlDecision::Hold
+            };
+
+            let scaled = match decision {
+                TtlDecision::Shorten => window.ttl_seconds as f64 / STEP,
+                TtlDecision::Lengthen => window.ttl_seconds as f64 * STEP,
+                TtlDecision::Hold => window.ttl_seconds as f64,
+            };
+            let ttl_seconds = (scaled.round() as u64).clamp(min_ttl, max_ttl);
+            if ttl_seconds != window.ttl_seconds {
+                tracing::info!(
+                    tenant = %tenant,
+                    decision = decision.as_str(),
+                    from = window.ttl_seconds,
+                    to = ttl_seconds,
+                    hits,
+                    misses,
+                    stores = window.stores,
+                    invalidated = window.invalidated,
+                    "Adjusted tenant default TTL"
+                );
+            }
+
+            metrics.record_ttl_decision(tenant, decision.as_str(), ttl_seconds);
+            *window = Window {
+                ttl_seconds,
+                lookups_at_start: counts,
+                stores: 0,
+                invalidated: 0,
+            };
+        }
+    }
+
+    /// Adjust every `interval` in the background
+    pub fn spawn(&self, metrics: Metrics) -> tokio::task::JoinHandle<()> {
+        let tuner = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(tuner.config.interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                tuner.adjust(&metrics);
+            }
+        })
+    }
+}
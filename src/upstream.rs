@@ -5,44 +5,247 @@
 //!
 //! Handles cache miss hydration by calling a configured SynaGraph endpoint
 //! and translating the response into the local cache format.
+//!
+//! Upstream `Cache-Control`, `Expires`, and `Age` headers are parsed into
+//! [`HttpFreshness`] so SynaGraph can steer edge freshness per response; see
+//! [`TtlPrecedence`] for how they combine with the artifact's own TTL.
+//!
+//! The client is built through [`outbound`](crate::outbound), so the
+//! configured outbound proxy and CA bundle apply.
+//!
+//! When a client certificate is configured (mTLS / SPIFFE SVID), the
+//! certificate files are watched and the HTTP client is rebuilt whenever they
+//! are rotated.
+//!
+//! A `grpc://` or `grpcs://` upstream URL selects the
+//! [`GrpcHydrator`](crate::grpc::GrpcHydrator) instead of the HTTP API; both
+//! implement [`Hydrator`].
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
 
 use anyhow::anyhow;
-use reqwest::{Client, StatusCode};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, AGE, CACHE_CONTROL, DATE, EXPIRES};
+use reqwest::{Client, Identity, StatusCode};
 
-use crate::config::UpstreamConfig;
+use crate::config::{ClientTlsConfig, OutboundConfig, TtlPrecedence, UpstreamConfig};
 use crate::error::AppError;
+use crate::grpc::{self, GrpcHydrator};
 use crate::model::LookupResponse;
+use crate::outbound;
 
-/// HTTP client wrapper for talking to the upstream knowledge graph.
+/// Client for talking to the upstream knowledge graph.
 #[derive(Clone)]
 pub struct UpstreamClient {
+    ttl_precedence: TtlPrecedence,
+    client_tls: Option<ClientTlsConfig>,
+    hydrator: Arc<dyn Hydrator>,
+}
+
+/// A protocol for fetching cache misses from SynaGraph
+#[async_trait]
+pub trait Hydrator: Send + Sync {
+    /// Fetch an artifact; `Ok(None)` is an upstream miss
+    async fn lookup(
+        &self,
+        key: &str,
+        tenant: Option<&str>,
+    ) -> Result<Option<UpstreamRecord>, AppError>;
+
+    /// Check that the upstream graph is reachable
+    async fn health(&self) -> Result<(), AppError>;
+
+    /// Start using a rotated client certificate
+    fn reload_identity(&self, tls: &ClientTlsConfig) -> Result<(), AppError>;
+}
+
+/// Hydrator calling SynaGraph's HTTP lookup API
+pub struct HttpHydrator {
     base_url: String,
-    client: Client,
+    timeout: Duration,
+    outbound: OutboundConfig,
+    client: RwLock<Client>,
+}
+
+/// Upstream lookup result together with the response's HTTP freshness
+#[derive(Debug, Clone)]
+pub struct UpstreamRecord {
+    pub record: LookupResponse,
+    pub freshness: HttpFreshness,
+}
+
+/// Freshness directives of an HTTP response, as seen by a shared cache
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpFreshness {
+    /// `no-store`, `no-cache`, or `private`: the response must not be cached
+    pub no_store: bool,
+    /// Remaining lifetime in seconds, if the headers declare one
+    pub ttl_seconds: Option<u64>,
+}
+
+impl HttpFreshness {
+    /// Parse `Cache-Control`, `Expires`, `Date`, and `Age`
+    ///
+    /// `s-maxage` wins over `max-age`, and both win over `Expires`. `Age` is
+    /// subtracted from `max-age`; `Expires` is measured against `Date` when
+    /// present to avoid clock skew.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+
+        let mut freshness = Self::default();
+        let mut max_age = None;
+        let mut s_maxage = None;
+
+        for directive in header(CACHE_CONTROL).unwrap_or("").split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            match directive.split_once('=') {
+                Some(("max-age", value)) => max_age = value.trim_matches('"').parse::<u64>().ok(),
+                Some(("s-maxage", value)) => s_maxage = value.trim_matches('"').parse::<u64>().ok(),
+                None if matches!(directive.as_str(), "no-store" | "no-cache" | "private") => {
+                    freshness.no_store = true
+                }
+                _ => {}
+            }
+        }
+
+        let age = header(AGE)
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+
+        freshness.ttl_seconds = match s_maxage.or(max_age) {
+            Some(lifetime) => Some(lifetime.saturating_sub(age)),
+            None => header(EXPIRES).map(|expires| {
+                // An invalid Expires (e.g. "0") means already expired
+                let Some(expires) = parse_http_date(expires) else {
+                    return 0;
+                };
+                let now = header(DATE)
+                    .and_then(parse_http_date)
+                    .unwrap_or_else(Utc::now);
+                (expires - now).num_seconds().max(0) as u64
+            }),
+        };
+
+        freshness
+    }
+}
+
+/// Parse an IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`)
+fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
 }
 
 impl UpstreamClient {
     /// Construct a new upstream client using the provided configuration.
+    ///
+    /// The URL scheme picks the protocol: `grpc://` and `grpcs://` use the
+    /// gRPC Lookup service, anything else the HTTP API.
     pub fn try_new(config: UpstreamConfig) -> Result<Self, AppError> {
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .map_err(|e| AppError::Internal(anyhow!("Failed to build upstream client: {}", e)))?;
+        let hydrator: Arc<dyn Hydrator> = if grpc::is_grpc_url(&config.base_url) {
+            Arc::new(GrpcHydrator::try_new(&config)?)
+        } else {
+            Arc::new(HttpHydrator::try_new(&config)?)
+        };
 
         Ok(Self {
-            base_url: config.base_url,
-            client,
+            ttl_precedence: config.ttl_precedence,
+            client_tls: config.client_tls,
+            hydrator,
         })
     }
 
+    /// Watch the client certificate files and reload them on rotation
+    ///
+    /// Returns `None` when no client certificate is configured. If a rotated
+    /// certificate cannot be loaded, the previous one keeps serving.
+    pub fn spawn_identity_reload(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let tls = self.client_tls.clone()?;
+        let hydrator = self.hydrator.clone();
+
+        Some(tokio::spawn(async move {
+            let mut last_modified = identity_modified(&tls);
+            let mut interval = tokio::time::interval(tls.reload_interval);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let modified = identity_modified(&tls);
+                if modified == last_modified {
+                    continue;
+                }
+
+                match hydrator.reload_identity(&tls) {
+                    Ok(()) => {
+                        last_modified = modified;
+                        tracing::info!(
+                            cert = %tls.cert_path.display(),
+                            "Reloaded upstream client certificate"
+                        );
+                    }
+                    Err(err) => {
+                        tracing::warn!(error = %err, "Failed to reload upstream client certificate")
+                    }
+                }
+            }
+        }))
+    }
+
+    /// How header freshness combines with the artifact's own TTL
+    pub fn ttl_precedence(&self) -> TtlPrecedence {
+        self.ttl_precedence
+    }
+
     /// Fetch an artifact from the upstream graph.
     pub async fn lookup(
         &self,
         key: &str,
         tenant: Option<&str>,
-    ) -> Result<Option<LookupResponse>, AppError> {
+    ) -> Result<Option<UpstreamRecord>, AppError> {
+        self.hydrator.lookup(key, tenant).await
+    }
+
+    /// Check that the upstream graph is reachable
+    pub async fn health(&self) -> Result<(), AppError> {
+        self.hydrator.health().await
+    }
+}
+
+impl HttpHydrator {
+    /// Construct a hydrator for an `http://` or `https://` upstream URL
+    pub fn try_new(config: &UpstreamConfig) -> Result<Self, AppError> {
+        let client = build_client(config.timeout, &config.outbound, config.client_tls.as_ref())?;
+
+        Ok(Self {
+            base_url: config.base_url.clone(),
+            timeout: config.timeout,
+            outbound: config.outbound.clone(),
+            client: RwLock::new(client),
+        })
+    }
+
+    fn client(&self) -> Client {
+        self.client
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+#[async_trait]
+impl Hydrator for HttpHydrator {
+    async fn lookup(
+        &self,
+        key: &str,
+        tenant: Option<&str>,
+    ) -> Result<Option<UpstreamRecord>, AppError> {
         let url = format!("{}/lookup", self.base_url.trim_end_matches('/'));
 
-        let mut request = self.client.get(url).query(&[("key", key)]);
+        let mut request = self.client().get(url).query(&[("key", key)]);
         if let Some(tenant) = tenant {
             request = request.query(&[("tenant", tenant)]);
         }
@@ -66,11 +269,97 @@ impl UpstreamClient {
             )));
         }
 
-        let payload = response
+        let freshness = HttpFreshness::from_headers(response.headers());
+
+        let record = response
             .json::<LookupResponse>()
             .await
             .map_err(|e| AppError::Internal(anyhow!("Failed to parse upstream response: {}", e)))?;
 
-        Ok(Some(payload))
+        Ok(Some(UpstreamRecord { record, freshness }))
     }
+
+    /// Check that the upstream graph is reachable
+    ///
+    /// Any response below 500 counts as reachable, since not every deployment
+    /// exposes a dedicated health route.
+    async fn health(&self) -> Result<(), AppError> {
+        let url = format!("{}/healthz", self.base_url.trim_end_matches('/'));
+
+        let response = self
+            .client()
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("Upstream health check failed: {}", e)))?;
+
+        if response.status().is_server_error() {
+            return Err(AppError::Internal(anyhow!(
+                "Upstream health check returned {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn reload_identity(&self, tls: &ClientTlsConfig) -> Result<(), AppError> {
+        let client = build_client(self.timeout, &self.outbound, Some(tls))?;
+        *self.client.write().unwrap_or_else(|e| e.into_inner()) = client;
+        Ok(())
+    }
+}
+
+/// Build the HTTP client, loading the client identity and trust bundle if configured
+fn build_client(
+    timeout: Duration,
+    outbound: &OutboundConfig,
+    tls: Option<&ClientTlsConfig>,
+) -> Result<Client, AppError> {
+    let mut builder = outbound::client_builder(outbound)?.timeout(timeout);
+
+    if let Some(tls) = tls {
+        let mut pem = std::fs::read(&tls.cert_path).map_err(|e| {
+            AppError::Internal(anyhow!(
+                "Failed to read client certificate {}: {}",
+                tls.cert_path.display(),
+                e
+            ))
+        })?;
+        let key = std::fs::read(&tls.key_path).map_err(|e| {
+            AppError::Internal(anyhow!(
+                "Failed to read client key {}: {}",
+                tls.key_path.display(),
+                e
+            ))
+        })?;
+        pem.push(b'\n');
+        pem.extend_from_slice(&key);
+        let identity = Identity::from_pem(&pem)
+            .map_err(|e| AppError::Internal(anyhow!("Invalid client certificate: {}", e)))?;
+        builder = builder.identity(identity);
+
+        if let Some(ca_path) = &tls.ca_path {
+            for cert in outbound::load_ca_bundle(ca_path)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| AppError::Internal(anyhow!("Failed to build upstream client: {}", e)))
+}
+
+/// Latest modification time across the identity files
+fn identity_modified(tls: &ClientTlsConfig) -> Option<SystemTime> {
+    [
+        Some(&tls.cert_path),
+        Some(&tls.key_path),
+        tls.ca_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+    .max()
 }
@@ -0,0 +1,106 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Write-ahead log of cache mutations.
+//!
+//! When enabled, every store and purge going through the
+//! [`Cache`](crate::cache::Cache) is appended to a capped Redis Stream
+//! (`XADD ... MAXLEN ~`). External consumers can tail it with `XREAD` for
+//! auditing or replication. Because the stream lives in Redis, the feed
+//! survives node restarts.
+//!
+//! Each entry has the fields `op` (`store` or `purge`), `tenant`, `key`, and
+//! `actor`, plus `hash` for stores. The actor is the tenant that authenticated
+//! the HTTP request (`tenant:{id}`), `anonymous` for requests without
+//! credentials, or `system` for background work such as graph invalidation,
+//! scheduled purges, and early refreshes.
+//!
+//! Appends happen after the mutation. A failed append is logged rather than
+//! undoing the mutation.
+
+use std::future::Future;
+
+use crate::keys::key_tenant;
+use chrono::{DateTime, Utc};
+
+/// Actor recorded for mutations made outside an HTTP request
+pub const SYSTEM_ACTOR: &str = "system";
+
+tokio::task_local! {
+    static ACTOR: String;
+}
+
+/// Capped stream the log is written to
+#[derive(Debug, Clone)]
+pub struct WalConfig {
+    pub stream: String,
+    /// Approximate maximum number of entries kept
+    pub max_len: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum WalOp {
+    Store,
+    Purge,
+}
+
+impl WalOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WalOp::Store => "store",
+            WalOp::Purge => "purge",
+        }
+    }
+}
+
+/// One logged mutation
+#[derive(Debug, Clone)]
+pub struct WalEntry {
+    pub op: WalOp,
+    pub tenant: String,
+    pub key: String,
+    pub hash: Option<String>,
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+impl WalEntry {
+    /// Entry for `key`, attributed to the current actor
+    pub fn new(op: WalOp, key: &str, hash: Option<String>) -> Self {
+        Self {
+            op,
+            tenant: key_tenant(key).to_string(),
+            key: key.to_string(),
+            hash,
+            actor: current_actor(),
+            at: Utc::now(),
+        }
+    }
+
+    /// Stream fields as `(name, value)` pairs
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("op", self.op.as_str().to_string()),
+            ("tenant", self.tenant.clone()),
+            ("key", self.key.clone()),
+            ("actor", self.actor.clone()),
+            ("at", self.at.to_rfc3339()),
+        ];
+        if let Some(hash) = &self.hash {
+            fields.push(("hash", hash.clone()));
+        }
+        fields
+    }
+}
+
+/// Run `future` with mutations attributed to `actor`
+pub async fn with_actor<F: Future>(actor: String, future: F) -> F::Output {
+    ACTOR.scope(actor, future).await
+}
+
+/// Actor of the running task, [`SYSTEM_ACTOR`] outside a request
+pub fn current_actor() -> String {
+    ACTOR
+        .try_with(|actor| actor.clone())
+        .unwrap_or_else(|_| SYSTEM_ACTOR.to_string())
+}
@@ -0,0 +1,160 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cache warm-up from a manifest at startup.
+//!
+//! A freshly deployed edge node starts cold, and its first requests for the
+//! working set all pay an upstream hydration. With `SCEDGE_WARMUP_MANIFEST`
+//! set, the node hydrates the listed keys from upstream at boot and reports
+//! `not_ready` until they are cached, so load balancers only route to it once
+//! it is warm. The manifest lists full keys, per-tenant keys, or both:
+//!
+//! ```json
+//! {
+//!   "keys": ["acme:faq:pricing"],
+//!   "tenants": { "globex": ["greeting:en-US", "greeting:de-DE"] }
+//! }
+//! ```
+//!
+//! Per-tenant keys are resolved under `{tenant}:` like batch lookups. Keys
+//! already cached are skipped, and failures are logged without retrying;
+//! `SCEDGE_WARMUP_TIMEOUT_SECS` bounds how long readiness is held back.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anyhow::Context;
+use futures_util::{stream, StreamExt};
+use serde::Deserialize;
+
+use crate::api::{tenant_scoped_keys, warm_key, AppState};
+use crate::config::WarmupConfig;
+use crate::keys::{key_tenant, validate_key};
+use crate::systemd;
+
+/// Keys to hydrate at startup
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WarmupManifest {
+    /// Full keys, owned by the tenant of their first segment
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// Keys by tenant, resolved under `{tenant}:`
+    #[serde(default)]
+    pub tenants: BTreeMap<String, Vec<String>>,
+}
+
+impl WarmupManifest {
+    /// Read and validate a manifest
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read warm-up manifest {}", path.display()))?;
+        let manifest: Self = serde_json::from_str(&raw)
+            .with_context(|| format!("Invalid warm-up manifest {}", path.display()))?;
+        for (_, key) in manifest.targets() {
+            validate_key(&key)
+                .map_err(|e| anyhow::anyhow!("Invalid warm-up manifest key: {}", e))?;
+        }
+        Ok(manifest)
+    }
+
+    /// `(tenant, key)` of every listed key, without duplicates
+    pub fn targets(&self) -> Vec<(String, String)> {
+        let mut targets: Vec<(String, String)> = self
+            .keys
+            .iter()
+            .map(|key| (key_tenant(key).to_string(), key.clone()))
+            .chain(self.tenants.iter().flat_map(|(tenant, keys)| {
+                tenant_scoped_keys(tenant, keys)
+                    .into_iter()
+                    .map(|key| (tenant.clone(), key))
+            }))
+            .collect();
+        targets.sort();
+        targets.dedup();
+        targets
+    }
+}
+
+/// Warm-up progress, consulted by `/readyz`
+///
+/// The default value is a node without a manifest, which is never warming.
+#[derive(Clone, Default)]
+pub struct Warmup {
+    progress: Option<Arc<Progress>>,
+}
+
+struct Progress {
+    remaining: AtomicUsize,
+    finished: AtomicBool,
+}
+
+impl Warmup {
+    /// Whether the node is still warming up
+    pub fn is_warming(&self) -> bool {
+        self.progress
+            .as_ref()
+            .is_some_and(|progress| !progress.finished.load(Ordering::Acquire))
+    }
+
+    /// Whether a manifest was configured
+    pub fn is_enabled(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    /// Keys not hydrated yet
+    pub fn remaining(&self) -> usize {
+        self.progress
+            .as_ref()
+            .map_or(0, |progress| progress.remaining.load(Ordering::Acquire))
+    }
+
+    /// Start tracking a warm-up of `total` keys
+    pub fn start(total: usize) -> Self {
+        Self {
+            progress: Some(Arc::new(Progress {
+                remaining: AtomicUsize::new(total),
+                finished: AtomicBool::new(total == 0),
+            })),
+        }
+    }
+
+    /// Hydrate `targets` in the background, then report the node ready
+    pub fn spawn(&self, state: AppState, targets: Vec<(String, String)>, config: &WarmupConfig) {
+        let Some(progress) = self.progress.clone() else {
+            return;
+        };
+        let concurrency = config.concurrency;
+        let timeout = config.timeout;
+
+        tokio::spawn(async move {
+            let total = targets.len();
+            tracing::info!(keys = total, "Warming up cache from manifest");
+
+            let run = stream::iter(targets).for_each_concurrent(concurrency, |(tenant, key)| {
+                let state = state.clone();
+                let progress = progress.clone();
+                async move {
+                    if let Err(error) = warm_key(&state, &tenant, &key).await {
+                        tracing::warn!(%tenant, %key, %error, "Warm-up hydration failed");
+                    }
+                    progress.remaining.fetch_sub(1, Ordering::AcqRel);
+                }
+            });
+
+            if tokio::time::timeout(timeout, run).await.is_err() {
+                tracing::warn!(
+                    remaining = progress.remaining.load(Ordering::Acquire),
+                    timeout_secs = timeout.as_secs(),
+                    "Warm-up timed out, reporting ready"
+                );
+            } else {
+                tracing::info!(keys = total, "Cache warm-up finished");
+            }
+            progress.finished.store(true, Ordering::Release);
+            systemd::notify("READY=1");
+        });
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission control keeps single-use keys out of the L1 without dropping
+//! stores to the backend.
+
+use std::time::Duration;
+
+use scedge::admission::Admission;
+use scedge::cache::{Cache, CacheBackend, MemoryCache, TieredCache};
+use scedge::testing::{ACME, GLOBEX};
+use serde_json::json;
+
+fn tiered(admission: &Admission) -> (Cache, MemoryCache, MemoryCache) {
+    let l1 = MemoryCache::new().with_max_age(Duration::from_secs(3600));
+    let l2 = MemoryCache::new();
+    let cache =
+        Cache::new(TieredCache::with_l1(l2.clone(), l1.clone()).with_admission(admission.clone()));
+    (cache, l1, l2)
+}
+
+#[tokio::test]
+async fn unadmitted_stores_still_reach_the_backend() {
+    let admission = Admission::new(1024, 2);
+    admission.set_tenant("acme", true);
+    let (cache, l1, l2) = tiered(&admission);
+    let key = "acme:answers:greeting".to_string();
+
+    cache
+        .set(key.clone(), ACME.artifact(json!("hello")), None)
+        .await
+        .expect("store succeeds");
+    assert!(l2.get(&key).await.unwrap().is_some());
+    assert!(l1.get(&key).await.unwrap().is_none());
+
+    // Once the key has been looked up often enough, the L1 takes it
+    admission.record_access(&key);
+    admission.record_access(&key);
+    assert!(cache.get(&key).await.unwrap().is_some());
+    assert!(l1.get(&key).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn tenants_without_admission_control_fill_the_l1() {
+    let admission = Admission::new(1024, 2);
+    admission.set_tenant("acme", true);
+    let (cache, l1, l2) = tiered(&admission);
+    let key = "globex:answers:greeting".to_string();
+
+    cache
+        .set(key.clone(), GLOBEX.artifact(json!("hello")), None)
+        .await
+        .expect("store succeeds");
+    assert!(l2.get(&key).await.unwrap().is_some());
+    assert!(l1.get(&key).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn disabling_admission_control_admits_every_key() {
+    let admission = Admission::new(1024, 2);
+    admission.set_tenant("acme", true);
+    admission.set_tenant("acme", false);
+    let (cache, l1, _) = tiered(&admission);
+    let key = "acme:answers:greeting".to_string();
+
+    cache
+        .set(key.clone(), ACME.artifact(json!("hello")), None)
+        .await
+        .expect("store succeeds");
+    assert!(l1.get(&key).await.unwrap().is_some());
+}
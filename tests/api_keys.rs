@@ -0,0 +1,81 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! `POST /tenant/keys/rotate`: rotated keys survive a reinstall of the tenant,
+//! unless the tenants file has replaced the key since.
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use scedge::policy::{install_tenant, PolicyEngine};
+use scedge::testing::{json_body, TestApp, ACME};
+
+async fn rotate(app: &TestApp) -> String {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/tenant/keys/rotate")
+        .header("x-api-key", ACME.api_key)
+        .body(Body::empty())
+        .expect("rotation request is valid");
+    let response = app.send(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    json_body(response).await["api_key"]
+        .as_str()
+        .expect("rotation returns the new key")
+        .to_string()
+}
+
+/// Install `config` into a fresh policy engine over the app's backend, as a
+/// restarted node would
+async fn reinstall(app: &TestApp, config: scedge::policy::TenantConfig) -> PolicyEngine {
+    let policy = PolicyEngine::new(None);
+    install_tenant(
+        config,
+        &policy,
+        &app.state.keyring,
+        &app.state.plugins,
+        &app.state.cache,
+    )
+    .await
+    .expect("tenant installs");
+    policy
+}
+
+#[tokio::test]
+async fn rotated_keys_survive_a_reinstall() {
+    let app = TestApp::new().await.expect("test app starts");
+    let first = rotate(&app).await;
+    let second = rotate(&app).await;
+
+    let policy = reinstall(&app, ACME.config()).await;
+    for key in [second.as_str(), first.as_str(), ACME.api_key] {
+        assert_eq!(
+            policy.tenant_for_api_key(key).await.as_deref(),
+            Some(ACME.id),
+            "{} is accepted after the reinstall",
+            key
+        );
+    }
+    assert!(policy.validate_api_key(ACME.id, &second).await.is_ok());
+}
+
+#[tokio::test]
+async fn a_new_key_in_the_tenants_file_supersedes_the_rotation() {
+    let app = TestApp::new().await.expect("test app starts");
+    let rotated = rotate(&app).await;
+
+    let mut config = ACME.config();
+    config.api_key = "acme-replaced-key".to_string();
+    let policy = reinstall(&app, config).await;
+    assert_eq!(
+        policy
+            .tenant_for_api_key("acme-replaced-key")
+            .await
+            .as_deref(),
+        Some(ACME.id)
+    );
+    assert_eq!(policy.tenant_for_api_key(&rotated).await, None);
+
+    // The stale rotation is gone, so the original file key no longer revives it
+    let policy = reinstall(&app, ACME.config()).await;
+    assert_eq!(policy.tenant_for_api_key(&rotated).await, None);
+}
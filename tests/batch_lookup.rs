@@ -0,0 +1,39 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! `POST /lookup/batch` rejects batches over `SCEDGE_BATCH_LOOKUP_MAX_KEYS`.
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use scedge::testing::{TestApp, ACME};
+use serde_json::json;
+
+/// Default of `SCEDGE_BATCH_LOOKUP_MAX_KEYS`, which the test app uses
+const MAX_KEYS: usize = 1000;
+
+fn batch_lookup(count: usize) -> Request<Body> {
+    let keys: Vec<String> = (0..count).map(|i| ACME.key(&format!("k{}", i))).collect();
+    Request::builder()
+        .method(Method::POST)
+        .uri("/lookup/batch")
+        .header("x-api-key", ACME.api_key)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "tenant": ACME.id, "keys": keys }).to_string(),
+        ))
+        .expect("batch lookup request is valid")
+}
+
+#[tokio::test]
+async fn batches_up_to_the_limit_are_looked_up() {
+    let app = TestApp::new().await.expect("test app starts");
+    let response = app.send(batch_lookup(MAX_KEYS)).await;
+    assert!(response.status().is_success(), "{}", response.status());
+}
+
+#[tokio::test]
+async fn batches_over_the_limit_are_rejected() {
+    let app = TestApp::new().await.expect("test app starts");
+    let response = app.send(batch_lookup(MAX_KEYS + 1)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
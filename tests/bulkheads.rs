@@ -0,0 +1,59 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tenant bulkheads: requests are authenticated before they queue, and give up
+//! with `503` instead of waiting forever.
+
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use scedge::policy::PolicyEngine;
+use scedge::testing::{TestApp, TestTenant, ACME};
+use serde_json::json;
+
+fn limited(limit: usize) -> scedge::policy::TenantConfig {
+    let mut config = ACME.config();
+    config.max_concurrency = Some(limit);
+    config
+}
+
+#[tokio::test]
+async fn waiting_for_a_full_bulkhead_times_out() {
+    let policy = PolicyEngine::new(None).with_bulkhead_timeout(Duration::from_millis(20));
+    policy.add_tenant(limited(1)).await;
+
+    let held = policy.acquire_bulkhead(ACME.id).await.unwrap();
+    let err = policy.acquire_bulkhead(ACME.id).await.unwrap_err();
+    assert_eq!(err.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    drop(held);
+    assert!(policy.acquire_bulkhead(ACME.id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn bad_credentials_are_rejected_without_queueing() {
+    let app = TestApp::with_tenants(vec![limited(1)])
+        .await
+        .expect("test app starts");
+    let _held = app.state.policy.acquire_bulkhead(ACME.id).await.unwrap();
+
+    let impostor = TestTenant {
+        id: ACME.id,
+        api_key: "not-the-acme-key",
+    };
+    let lookup = tokio::time::timeout(
+        Duration::from_millis(200),
+        app.send(impostor.lookup(&ACME.key("report"))),
+    )
+    .await
+    .expect("lookup is answered without waiting for a slot");
+    assert_eq!(lookup.status(), StatusCode::BAD_REQUEST);
+
+    let store = tokio::time::timeout(
+        Duration::from_millis(200),
+        app.send(impostor.store(&ACME.key("report"), json!("hello"))),
+    )
+    .await
+    .expect("store is answered without waiting for a slot");
+    assert_eq!(store.status(), StatusCode::BAD_REQUEST);
+}
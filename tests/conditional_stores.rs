@@ -0,0 +1,90 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Concurrent conditional stores on the SQLite backend: exactly one writer
+//! wins each check-and-set.
+
+use std::sync::Arc;
+
+use scedge::cache::{CacheBackend, SetOutcome, SqliteCache};
+use scedge::model::StoreMode;
+use scedge::testing::ACME;
+use serde_json::json;
+
+const WRITERS: usize = 16;
+
+fn open(name: &str) -> (Arc<SqliteCache>, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!("scedge-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    (
+        Arc::new(SqliteCache::open(&path).expect("database opens")),
+        path,
+    )
+}
+
+async fn race(cache: &Arc<SqliteCache>, mode: StoreMode) -> Vec<Result<SetOutcome, String>> {
+    let writers: Vec<_> = (0..WRITERS)
+        .map(|writer| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                cache
+                    .set_conditional(
+                        "acme:answers:greeting".to_string(),
+                        ACME.artifact(json!(writer)),
+                        None,
+                        mode,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        })
+        .collect();
+    let mut outcomes = Vec::new();
+    for writer in writers {
+        outcomes.push(writer.await.expect("writer task completes"));
+    }
+    outcomes
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn only_one_if_absent_store_creates_the_entry() {
+    let (cache, path) = open("if-absent");
+
+    let outcomes = race(&cache, StoreMode::IfAbsent).await;
+    let created = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, Ok(SetOutcome::Created(_))))
+        .count();
+    let kept = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, Ok(SetOutcome::Kept(_))))
+        .count();
+    assert_eq!((created, kept), (1, WRITERS - 1));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn only_one_if_match_store_replaces_the_version() {
+    let (cache, path) = open("if-match");
+    let first = cache
+        .set(
+            "acme:answers:greeting".to_string(),
+            ACME.artifact(json!(0)),
+            None,
+        )
+        .await
+        .expect("store succeeds");
+
+    let outcomes = race(&cache, StoreMode::IfMatch(first.version())).await;
+    let updated = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, Ok(SetOutcome::Updated(_))))
+        .count();
+    assert_eq!(updated, 1);
+    assert!(outcomes
+        .iter()
+        .all(|outcome| matches!(outcome, Ok(SetOutcome::Updated(_) | SetOutcome::Kept(_)))));
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,61 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! `POST /contains` only reveals hashes of entries the caller may read.
+
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use scedge::model::{ArtifactPayload, StoreRequest};
+use scedge::testing::{json_body, TestApp, ACME};
+use serde_json::{json, Value};
+
+fn post(uri: &str, body: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("x-api-key", ACME.api_key)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("request is valid")
+}
+
+#[tokio::test]
+async fn restricted_entries_are_present_without_a_hash() {
+    let app = TestApp::new().await.expect("test app starts");
+
+    let open = app
+        .send(ACME.store(&ACME.key("open"), json!("hello")))
+        .await;
+    assert!(open.status().is_success(), "{}", open.status());
+
+    let restricted = StoreRequest::builder()
+        .key(ACME.key("restricted"))
+        .artifact(
+            ArtifactPayload::builder()
+                .answer(json!("secret"))
+                .tenant(ACME.id)
+                .allowed_subject("alice")
+                .build()
+                .expect("artifact is valid"),
+        )
+        .build()
+        .expect("store key is valid");
+    let response = app
+        .send(post("/store", serde_json::to_value(restricted).unwrap()))
+        .await;
+    assert!(response.status().is_success(), "{}", response.status());
+
+    let response = app
+        .send(post(
+            "/contains",
+            json!({ "tenant": ACME.id, "keys": ["open", "restricted"] }),
+        ))
+        .await;
+    let body = json_body(response).await;
+    let results = &body["results"];
+
+    assert_eq!(results[ACME.key("open")]["present"], json!(true));
+    assert!(results[ACME.key("open")]["hash"].is_string());
+    assert_eq!(results[ACME.key("restricted")]["present"], json!(true));
+    assert!(results[ACME.key("restricted")].get("hash").is_none());
+}
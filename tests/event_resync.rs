@@ -0,0 +1,51 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dropped graph events: the engine flushes what the node holds on top of the
+//! backend, since it cannot tell which entries the events would have purged.
+
+use std::time::Duration;
+
+use scedge::cache::{Cache, CacheBackend, MemoryCache, TieredCache};
+use scedge::events::{
+    graph_event_channel, EventEnvelope, EventResync, GraphEvent, InvalidationEngine,
+};
+use scedge::testing::ACME;
+use serde_json::json;
+
+#[tokio::test]
+async fn dropped_graph_events_flush_the_l1() {
+    let l2 = MemoryCache::new();
+    let cache = Cache::new(TieredCache::new(l2.clone(), 100, Duration::from_secs(3600)));
+    let key = "acme:answers:greeting".to_string();
+    cache
+        .set(key.clone(), ACME.artifact(json!("hello")), None)
+        .await
+        .expect("store succeeds");
+
+    // A purge this node never heard of leaves the L1 copy behind
+    l2.delete(&key).await.expect("delete succeeds");
+    assert!(cache.get(&key).await.expect("lookup succeeds").is_some());
+
+    // Overflow the channel before the engine reads from it
+    let events = graph_event_channel();
+    let receiver = events.subscribe();
+    for _ in 0..4096 {
+        let _ = events.send(EventEnvelope::from(GraphEvent::InvalidateTenant {
+            tenant: "nobody".to_string(),
+        }));
+    }
+    let resync = EventResync::default();
+    InvalidationEngine::new(cache.clone())
+        .with_resync(resync.clone())
+        .spawn(receiver);
+
+    let flushed = async {
+        while cache.get(&key).await.expect("lookup succeeds").is_some() || resync.is_resyncing() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), flushed)
+        .await
+        .expect("the L1 is flushed");
+}
@@ -0,0 +1,91 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dropping entries read past their expiry removes only the revision that was
+//! read, and is not a purge.
+
+use chrono::{Duration, TimeZone, Utc};
+use scedge::cache::{Cache, MemoryCache, StoredEntry};
+use scedge::clock::ManualClock;
+use scedge::model::{ArtifactPayload, CachedArtifact, Consistency};
+use scedge::testing::ACME;
+use serde_json::json;
+
+const KEY: &str = "acme:answers:greeting";
+
+fn cache() -> (Cache, ManualClock) {
+    let clock = ManualClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    (
+        Cache::new(MemoryCache::new().with_clock(clock.clone())),
+        clock,
+    )
+}
+
+async fn read_expired(cache: &Cache) -> CachedArtifact {
+    match cache.read(KEY, Consistency::Eventual).await.unwrap() {
+        Some(StoredEntry::Expired(record)) => record,
+        other => panic!("expected an expired entry, got {:?}", other.is_some()),
+    }
+}
+
+#[tokio::test]
+async fn a_replaced_entry_survives_discarding_the_expired_revision() {
+    let (cache, clock) = cache();
+    cache
+        .set(
+            KEY.to_string(),
+            ACME.artifact(json!("old")),
+            Some(clock.now() + Duration::seconds(10)),
+        )
+        .await
+        .unwrap();
+    clock.advance(Duration::seconds(20));
+    let expired = read_expired(&cache).await;
+
+    // Another writer stores between the read and the discard
+    cache
+        .set(
+            KEY.to_string(),
+            ACME.artifact(json!("new")),
+            Some(clock.now() + Duration::seconds(60)),
+        )
+        .await
+        .unwrap();
+
+    assert!(!cache.discard_expired(&expired).await.unwrap());
+    let live = cache.get(KEY).await.unwrap().expect("new entry is kept");
+    assert_eq!(live.artifact.answer, json!("new"));
+}
+
+#[tokio::test]
+async fn discarding_an_expired_entry_does_not_cascade() {
+    let (cache, clock) = cache();
+    cache
+        .set(
+            KEY.to_string(),
+            ACME.artifact(json!("old")),
+            Some(clock.now() + Duration::seconds(10)),
+        )
+        .await
+        .unwrap();
+    let dependent = ArtifactPayload::builder()
+        .answer(json!("summary"))
+        .tenant("acme")
+        .depends_on(KEY)
+        .build()
+        .unwrap();
+    cache
+        .set("acme:answers:summary".to_string(), dependent, None)
+        .await
+        .unwrap();
+    clock.advance(Duration::seconds(20));
+
+    let expired = read_expired(&cache).await;
+    assert!(cache.discard_expired(&expired).await.unwrap());
+    assert!(cache
+        .read(KEY, Consistency::Eventual)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(cache.get("acme:answers:summary").await.unwrap().is_some());
+}
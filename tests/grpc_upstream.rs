@@ -0,0 +1,180 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! The gRPC hydrator against an in-process SynaGraph Lookup service.
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use futures_util::stream;
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::{Bytes, Frame, Incoming};
+use hyper::header::HeaderMap;
+use hyper::server::conn::http2;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use scedge::config::{OutboundConfig, TtlPrecedence, UpstreamConfig};
+use scedge::error::AppError;
+use scedge::model::ArtifactPayload;
+use scedge::upstream::UpstreamClient;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+type Body = StreamBody<stream::Iter<std::vec::IntoIter<Result<Frame<Bytes>, Infallible>>>>;
+
+/// Reply with one message followed by `grpc-status` trailers
+fn reply(message: Option<Vec<u8>>, status: u32) -> Response<Body> {
+    let mut frames = Vec::new();
+    if let Some(message) = message {
+        let mut framed = vec![0];
+        framed.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        framed.extend_from_slice(&message);
+        frames.push(Ok(Frame::data(Bytes::from(framed))));
+    }
+    let mut trailers = HeaderMap::new();
+    trailers.insert("grpc-status", status.to_string().parse().unwrap());
+    frames.push(Ok(Frame::trailers(trailers)));
+
+    Response::builder()
+        .header("content-type", "application/grpc")
+        .body(StreamBody::new(stream::iter(frames)))
+        .unwrap()
+}
+
+/// `LookupReply` with the record and a `max_age_seconds`
+fn lookup_reply(key: &str, max_age_seconds: u8) -> Vec<u8> {
+    let artifact = ArtifactPayload::builder()
+        .answer(json!("hello"))
+        .tenant("acme")
+        .build()
+        .expect("artifact is valid");
+    let record = json!({ "key": key, "artifact": artifact }).to_string();
+
+    let mut message = vec![0x0a];
+    let mut len = record.len();
+    while len >= 0x80 {
+        message.push((len as u8) | 0x80);
+        len >>= 7;
+    }
+    message.push(len as u8);
+    message.extend_from_slice(record.as_bytes());
+    message.extend_from_slice(&[0x10, max_age_seconds]);
+    message
+}
+
+async fn serve(request: Request<Incoming>) -> Result<Response<Body>, Infallible> {
+    assert_eq!(request.headers()["content-type"], "application/grpc");
+    assert_eq!(request.headers()["grpc-timeout"], "500m");
+    let path = request.uri().path().to_string();
+    let body = request.into_body().collect().await.unwrap().to_bytes();
+
+    if path == "/grpc.health.v1.Health/Check" {
+        return Ok(reply(Some(vec![0x08, 1]), 0));
+    }
+    assert_eq!(path, "/synagraph.v1.SynaGraph/Lookup");
+
+    // Frame header, then field 1 (key) with a one-byte length
+    assert_eq!(body[5], 0x0a);
+    let key = String::from_utf8(body[7..7 + body[6] as usize].to_vec()).unwrap();
+
+    Ok(match key.as_str() {
+        "acme:answers:missing" => reply(None, 5),
+        "acme:answers:busy" => reply(None, 14),
+        "acme:answers:slow" => {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            reply(None, 0)
+        }
+        _ => reply(Some(lookup_reply(&key, 60)), 0),
+    })
+}
+
+async fn start_upstream() -> UpstreamClient {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(
+                http2::Builder::new(TokioExecutor::new())
+                    .serve_connection(TokioIo::new(stream), service_fn(serve)),
+            );
+        }
+    });
+
+    UpstreamClient::try_new(UpstreamConfig {
+        base_url: format!("grpc://{}", addr),
+        timeout: Duration::from_millis(500),
+        ttl_precedence: TtlPrecedence::Artifact,
+        client_tls: None,
+        outbound: OutboundConfig::default(),
+    })
+    .expect("gRPC upstream URL is accepted")
+}
+
+#[tokio::test]
+async fn hits_carry_the_record_and_its_freshness() {
+    let upstream = start_upstream().await;
+
+    let record = upstream
+        .lookup("acme:answers:greeting", Some("acme"))
+        .await
+        .expect("lookup succeeds")
+        .expect("upstream hit");
+    assert_eq!(record.record.key, "acme:answers:greeting");
+    assert_eq!(record.freshness.ttl_seconds, Some(60));
+    assert!(!record.freshness.no_store);
+
+    // The connection is reused for later calls
+    assert!(upstream
+        .lookup("acme:answers:other", None)
+        .await
+        .unwrap()
+        .is_some());
+    upstream.health().await.expect("upstream is serving");
+}
+
+#[tokio::test]
+async fn not_found_is_a_miss() {
+    let upstream = start_upstream().await;
+    let record = upstream
+        .lookup("acme:answers:missing", Some("acme"))
+        .await
+        .expect("a miss is not an error");
+    assert!(record.is_none());
+}
+
+#[tokio::test]
+async fn unavailable_statuses_map_to_unavailable() {
+    let upstream = start_upstream().await;
+    let err = upstream
+        .lookup("acme:answers:busy", Some("acme"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Unavailable(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn calls_give_up_at_the_deadline() {
+    let upstream = start_upstream().await;
+    let started = std::time::Instant::now();
+    let err = upstream
+        .lookup("acme:answers:slow", Some("acme"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::Unavailable(_)), "{:?}", err);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn grpc_urls_must_not_carry_a_path() {
+    let config = UpstreamConfig {
+        base_url: "grpc://synagraph:50051/v1".to_string(),
+        timeout: Duration::from_secs(1),
+        ttl_precedence: TtlPrecedence::Artifact,
+        client_tls: None,
+        outbound: OutboundConfig::default(),
+    };
+    assert!(UpstreamClient::try_new(config).is_err());
+}
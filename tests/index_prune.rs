@@ -0,0 +1,111 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Index sets are pruned of entries that expired, so they stay bounded by the
+//! live working set.
+
+use chrono::{Duration, Utc};
+use scedge::cache::{Cache, CacheBackend, MemoryCache, SqliteCache};
+use scedge::testing::ACME;
+use serde_json::json;
+
+async fn expired_members_are_pruned(backend: impl CacheBackend) {
+    let cache = Cache::new(backend);
+    let expired = "acme:answers:expired".to_string();
+    let live = "acme:answers:live".to_string();
+    cache
+        .set(
+            expired.clone(),
+            ACME.artifact(json!("hello")),
+            Some(Utc::now() + Duration::milliseconds(50)),
+        )
+        .await
+        .expect("store succeeds");
+    cache
+        .set(live.clone(), ACME.artifact(json!("hello")), None)
+        .await
+        .expect("store succeeds");
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    cache.sweep_expired().await.expect("sweep succeeds");
+    let mut listed = cache.tenant_keys("acme").await.expect("index is readable");
+    listed.sort();
+    assert_eq!(listed, vec![expired.clone(), live.clone()]);
+
+    assert!(cache.prune_indexes().await.expect("prune succeeds") >= 1);
+    assert_eq!(cache.tenant_keys("acme").await.unwrap(), vec![live]);
+}
+
+#[tokio::test]
+async fn memory_indexes_are_pruned() {
+    expired_members_are_pruned(MemoryCache::new()).await;
+}
+
+#[tokio::test]
+async fn sqlite_indexes_are_pruned() {
+    let path = std::env::temp_dir().join(format!("scedge-index-prune-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    expired_members_are_pruned(SqliteCache::open(&path).expect("database opens")).await;
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn entries_stored_before_the_indexes_are_backfilled() {
+    let backend = MemoryCache::new();
+    // Written straight to the backend, as by a release without the indexes
+    let legacy = backend
+        .set(
+            "acme:answers:legacy".to_string(),
+            ACME.artifact(json!("hello")),
+            None,
+        )
+        .await
+        .expect("store succeeds");
+    let cache = Cache::new(backend);
+
+    // Scanned for until the backfill has run
+    assert_eq!(
+        cache.tenant_keys("acme").await.unwrap(),
+        vec![legacy.key.clone()]
+    );
+    assert!(cache
+        .get_by_hash("acme", &legacy.artifact.hash)
+        .await
+        .unwrap()
+        .is_empty());
+
+    assert_eq!(cache.backfill_indexes().await.unwrap(), Some(1));
+    assert_eq!(cache.backfill_indexes().await.unwrap(), None);
+    assert_eq!(
+        cache
+            .get_by_hash("acme", &legacy.artifact.hash)
+            .await
+            .unwrap()[0]
+            .key,
+        legacy.key
+    );
+
+    assert_eq!(cache.purge_tenant("acme").await.unwrap(), 1);
+    assert!(cache.tenant_keys("acme").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn provenance_purges_find_entries_stored_before_the_indexes() {
+    let backend = MemoryCache::new();
+    let legacy = backend
+        .set(
+            "acme:answers:legacy".to_string(),
+            ACME.artifact(json!("hello")),
+            None,
+        )
+        .await
+        .expect("store succeeds");
+    let cache = Cache::new(backend);
+
+    let purged = cache
+        .purge_referencing_hash(Some("acme"), &legacy.artifact.hash)
+        .await
+        .expect("purge succeeds");
+    assert_eq!(purged, 1);
+    assert!(cache.get(&legacy.key).await.unwrap().is_none());
+}
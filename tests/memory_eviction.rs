@@ -0,0 +1,125 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! A full in-memory cache evicts in policy order, whether it is bounded by
+//! entries or by bytes, after dropping expired entries.
+
+use chrono::{Duration, TimeZone, Utc};
+use scedge::cache::{CacheBackend, EvictionPolicy, MemoryCache};
+use scedge::clock::ManualClock;
+use scedge::model::{ArtifactMetrics, ArtifactPayload};
+use serde_json::json;
+
+fn artifact(answer: &str, compute_cost: f64) -> ArtifactPayload {
+    ArtifactPayload::builder()
+        .answer(json!(answer))
+        .tenant("acme")
+        .metrics(ArtifactMetrics {
+            compute_cost: Some(compute_cost),
+            ..Default::default()
+        })
+        .build()
+        .expect("artifact is valid")
+}
+
+async fn store(cache: &MemoryCache, name: &str, compute_cost: f64) {
+    cache
+        .set(name.to_string(), artifact(name, compute_cost), None)
+        .await
+        .unwrap();
+}
+
+async fn held(cache: &MemoryCache, names: &[&str]) -> Vec<bool> {
+    let mut held = Vec::new();
+    for name in names {
+        held.push(cache.get(name).await.unwrap().is_some());
+    }
+    held
+}
+
+#[tokio::test]
+async fn lru_evicts_the_least_recently_read_entry() {
+    let cache = MemoryCache::new()
+        .with_max_entries(3)
+        .with_eviction(EvictionPolicy::Lru);
+    for name in ["a", "b", "c"] {
+        store(&cache, name, 0.0).await;
+    }
+    cache.get("a").await.unwrap();
+
+    store(&cache, "d", 0.0).await;
+    store(&cache, "e", 0.0).await;
+    assert_eq!(
+        held(&cache, &["a", "b", "c", "d", "e"]).await,
+        [true, false, false, true, true]
+    );
+}
+
+#[tokio::test]
+async fn cost_evicts_the_cheapest_entry() {
+    let cache = MemoryCache::new().with_max_entries(3);
+    store(&cache, "a", 5.0).await;
+    store(&cache, "b", 1.0).await;
+    store(&cache, "c", 3.0).await;
+
+    store(&cache, "d", 4.0).await;
+    assert_eq!(
+        held(&cache, &["a", "b", "c", "d"]).await,
+        [true, false, true, true]
+    );
+    store(&cache, "e", 6.0).await;
+    assert_eq!(
+        held(&cache, &["a", "c", "d", "e"]).await,
+        [true, false, true, true]
+    );
+}
+
+#[tokio::test]
+async fn byte_bounds_evict_in_policy_order() {
+    let answer = "x".repeat(1000);
+    let cache = MemoryCache::new()
+        .with_max_bytes(3000)
+        .with_eviction(EvictionPolicy::Lru);
+    for name in ["a", "b"] {
+        cache
+            .set(name.to_string(), artifact(&answer, 0.0), None)
+            .await
+            .unwrap();
+    }
+    cache.get("a").await.unwrap();
+
+    cache
+        .set("c".to_string(), artifact(&answer, 0.0), None)
+        .await
+        .unwrap();
+    assert_eq!(held(&cache, &["a", "b", "c"]).await, [true, false, true]);
+
+    // An entry over the whole budget is not kept, and evicts nothing
+    cache
+        .set("d".to_string(), artifact(&"x".repeat(4000), 0.0), None)
+        .await
+        .unwrap();
+    assert_eq!(held(&cache, &["a", "c", "d"]).await, [true, true, false]);
+}
+
+#[tokio::test]
+async fn expired_entries_go_before_evictions() {
+    let clock = ManualClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    let cache = MemoryCache::new()
+        .with_max_entries(2)
+        .with_clock(clock.clone());
+    cache
+        .set(
+            "a".to_string(),
+            artifact("a", 9.0),
+            Some(clock.now() + Duration::seconds(10)),
+        )
+        .await
+        .unwrap();
+    store(&cache, "b", 1.0).await;
+    clock.advance(Duration::seconds(20));
+
+    store(&cache, "c", 1.0).await;
+    assert_eq!(held(&cache, &["b", "c"]).await, [true, true]);
+    assert!(cache.get_or_expired("a").await.unwrap().is_none());
+}
@@ -0,0 +1,59 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Policy plugins run within a memory budget.
+
+use scedge::plugins::{PluginHook, PolicyPlugins};
+use scedge::testing::ACME;
+use serde_json::json;
+
+/// Plugin that grows its memory by 64 pages (4 MiB) and denies with code 1
+/// if `memory.grow` fails
+const GROWING_PLUGIN: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+    // Types: (i32) -> i32, (i32, i32) -> i32
+    0x01, 0x0c, 0x02, 0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f,
+    // Functions: alloc, evaluate
+    0x03, 0x03, 0x02, 0x00, 0x01, //
+    // Memory: one page, no maximum
+    0x05, 0x03, 0x01, 0x00, 0x01, //
+    // Exports: memory, alloc, evaluate
+    0x07, 0x1d, 0x03, 0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00, 0x05, b'a', b'l', b'l',
+    b'o', b'c', 0x00, 0x00, 0x08, b'e', b'v', b'a', b'l', b'u', b'a', b't', b'e', 0x00,
+    0x01, //
+    // alloc: 0; evaluate: memory.grow(64) == -1
+    0x0a, 0x11, 0x02, 0x04, 0x00, 0x41, 0x00, 0x0b, 0x0a, 0x00, 0x41, 0xc0, 0x00, 0x40, 0x00, 0x41,
+    0x7f, 0x46, 0x0b,
+];
+
+async fn allowed(max_memory_bytes: usize) -> bool {
+    let path = std::env::temp_dir().join(format!(
+        "scedge-plugin-{}-{}.wasm",
+        max_memory_bytes,
+        std::process::id()
+    ));
+    std::fs::write(&path, GROWING_PLUGIN).expect("plugin is written");
+    let plugins = PolicyPlugins::new(1_000_000, max_memory_bytes)
+        .and_then(|plugins| plugins.with_operator_plugin(&path))
+        .expect("plugin compiles");
+    let _ = std::fs::remove_file(&path);
+
+    plugins
+        .check(
+            PluginHook::Store,
+            &ACME.key("answers:greeting"),
+            &ACME.artifact(json!("hello")),
+        )
+        .await
+        .is_ok()
+}
+
+#[tokio::test]
+async fn memory_grows_within_the_budget() {
+    assert!(allowed(16 << 20).await);
+}
+
+#[tokio::test]
+async fn growing_past_the_budget_fails() {
+    assert!(!allowed(1 << 20).await);
+}
@@ -0,0 +1,47 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Policy denials are counted per tenant, with unknown tenants sharing one label.
+
+use std::time::Duration;
+
+use scedge::metrics::Metrics;
+use scedge::policy::{spawn_policy_audit, PolicyDenial, PolicyEngine, PolicyRule};
+
+fn deny(policy: &PolicyEngine, tenant: &str, rule: PolicyRule) {
+    policy.publish_denial(
+        PolicyDenial {
+            tenant: tenant.to_string(),
+            rule,
+        },
+        "/lookup",
+    );
+}
+
+#[tokio::test]
+async fn unknown_tenants_share_one_denial_label() {
+    let metrics = Metrics::new().expect("metrics register");
+    let policy = PolicyEngine::new(None);
+    spawn_policy_audit(policy.subscribe(), metrics.clone());
+
+    deny(&policy, "probe-1", PolicyRule::UnknownTenant);
+    deny(&policy, "probe-2", PolicyRule::UnknownTenant);
+    deny(&policy, "acme", PolicyRule::ApiKey);
+
+    let denials = |tenant: &str, rule: PolicyRule| {
+        metrics
+            .policy_denials
+            .with_label_values(&[tenant, rule.as_str()])
+            .get()
+    };
+    // The audit task handles events in order, so the last one arriving means all have
+    for _ in 0..100 {
+        if denials("acme", PolicyRule::ApiKey) == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(denials("unknown", PolicyRule::UnknownTenant), 2);
+    assert_eq!(denials("probe-1", PolicyRule::UnknownTenant), 0);
+    assert_eq!(denials("acme", PolicyRule::ApiKey), 1);
+}
@@ -0,0 +1,112 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! `POST /purge` authentication: every purge needs a credential, and a
+//! credential only purges its own tenant.
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use scedge::testing::{json_body, TestApp, TestTenant, ACME, GLOBEX};
+use serde_json::{json, Value};
+
+fn unauthenticated_purge(body: Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/purge")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("purge request is valid")
+}
+
+/// `POST /store` of an artifact of `tenant` depending on `reference`
+fn store_dependent(tenant: TestTenant, key: &str, reference: &str) -> Request<Body> {
+    let body = json!({
+        "key": key,
+        "artifact": {
+            "answer": "derived",
+            "policy": { "tenant": tenant.id },
+            "hash": "v1",
+            "depends_on": [reference],
+        },
+    });
+    Request::builder()
+        .method(Method::POST)
+        .uri("/store")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-api-key", tenant.api_key)
+        .body(Body::from(body.to_string()))
+        .expect("store request is valid")
+}
+
+async fn stored(app: &TestApp, key: &str) -> bool {
+    let tenant = if key.starts_with("acme:") {
+        ACME
+    } else {
+        GLOBEX
+    };
+    app.send(tenant.lookup(key)).await.status() == StatusCode::OK
+}
+
+#[tokio::test]
+async fn purges_without_credentials_are_rejected() {
+    let app = TestApp::new().await.expect("test app starts");
+    let key = ACME.key("answers:greeting");
+    app.send(ACME.store(&key, json!("hello"))).await;
+
+    for body in [
+        json!({ "tenant": ACME.id }),
+        json!({ "keys": [key] }),
+        json!({ "provenance_hash": "sha256:anything" }),
+        json!({ "tenant": ACME.id, "family": "answers" }),
+    ] {
+        let response = app.send(unauthenticated_purge(body.clone())).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{}", body);
+    }
+    assert!(stored(&app, &key).await);
+}
+
+#[tokio::test]
+async fn credentials_only_purge_their_own_tenant() {
+    let app = TestApp::new().await.expect("test app starts");
+    let acme_key = ACME.key("answers:greeting");
+    let globex_key = GLOBEX.key("answers:greeting");
+    app.send(ACME.store(&acme_key, json!("hello"))).await;
+    app.send(GLOBEX.store(&globex_key, json!("hallo"))).await;
+
+    let response = app.send(ACME.purge(&[globex_key.as_str()])).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(stored(&app, &globex_key).await);
+
+    let response = app.send(ACME.purge(&[acme_key.as_str()])).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json_body(response).await["purged"], 1);
+    assert!(!stored(&app, &acme_key).await);
+    assert!(stored(&app, &globex_key).await);
+}
+
+#[tokio::test]
+async fn provenance_purges_cascade_within_the_callers_tenant() {
+    let app = TestApp::new().await.expect("test app starts");
+    let reference = "sha256:shared-source";
+    let acme_key = ACME.key("derived:summary");
+    let globex_key = GLOBEX.key("derived:summary");
+    for (tenant, key) in [(ACME, &acme_key), (GLOBEX, &globex_key)] {
+        let response = app.send(store_dependent(tenant, key, reference)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let purge = Request::builder()
+        .method(Method::POST)
+        .uri("/purge")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-api-key", ACME.api_key)
+        .body(Body::from(
+            json!({ "provenance_hash": reference }).to_string(),
+        ))
+        .expect("purge request is valid");
+    let response = app.send(purge).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(!stored(&app, &acme_key).await);
+    assert!(stored(&app, &globex_key).await);
+}
@@ -0,0 +1,102 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! `POST /purge/schedules`: only the tenant's own credential registers
+//! schedules, and registered schedules survive a reinstall of the tenant.
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use scedge::policy::{install_tenant, PolicyEngine};
+use scedge::testing::{json_body, TestApp, ACME, GLOBEX};
+use serde_json::json;
+
+/// `POST /purge/schedules` of `schedule` for ACME, sent with `api_key`
+fn schedule_request(mut schedule: serde_json::Value, api_key: Option<&str>) -> Request<Body> {
+    schedule["tenant"] = json!(ACME.id);
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri("/purge/schedules")
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(api_key) = api_key {
+        request = request.header("x-api-key", api_key);
+    }
+    request
+        .body(Body::from(schedule.to_string()))
+        .expect("schedule request is valid")
+}
+
+/// Register `schedule` for ACME, returning how many schedules it now has
+async fn register(app: &TestApp, schedule: serde_json::Value) -> usize {
+    let response = app
+        .send(schedule_request(schedule, Some(ACME.api_key)))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    json_body(response).await["schedules"]
+        .as_array()
+        .map_or(0, Vec::len)
+}
+
+#[tokio::test]
+async fn registered_schedules_survive_a_reinstall() {
+    let app = TestApp::new().await.expect("test app starts");
+    let daily = json!({ "cron": "0 0 2 * * *", "prefix": "daily:" });
+    assert_eq!(register(&app, daily).await, 1);
+
+    let policy = PolicyEngine::new(None);
+    for config in [ACME.config(), GLOBEX.config()] {
+        install_tenant(
+            config,
+            &policy,
+            &app.state.keyring,
+            &app.state.plugins,
+            &app.state.cache,
+        )
+        .await
+        .expect("tenant installs");
+    }
+
+    let schedules = policy.purge_schedules().await;
+    assert_eq!(schedules.len(), 1);
+    let (tenant, schedule) = &schedules[0];
+    assert_eq!(tenant, ACME.id);
+    assert_eq!(schedule.prefix.as_deref(), Some("daily:"));
+}
+
+#[tokio::test]
+async fn registrations_accumulate() {
+    let app = TestApp::new().await.expect("test app starts");
+    let daily = json!({ "cron": "0 0 2 * * *", "prefix": "daily:" });
+    assert_eq!(register(&app, daily).await, 1);
+    let nightly = json!({ "cron": "0 0 3 * * *", "tag": "nightly" });
+    assert_eq!(register(&app, nightly).await, 2);
+
+    let policy = PolicyEngine::new(None);
+    install_tenant(
+        ACME.config(),
+        &policy,
+        &app.state.keyring,
+        &app.state.plugins,
+        &app.state.cache,
+    )
+    .await
+    .expect("tenant installs");
+    assert_eq!(policy.purge_schedules().await.len(), 2);
+}
+
+#[tokio::test]
+async fn registering_needs_the_tenants_own_credential() {
+    let app = TestApp::new().await.expect("test app starts");
+    let daily = json!({ "cron": "0 0 2 * * *", "prefix": "daily:" });
+
+    let anonymous = app.send(schedule_request(daily.clone(), None)).await;
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    let other_tenant = app
+        .send(schedule_request(daily, Some(GLOBEX.api_key)))
+        .await;
+    assert!(
+        other_tenant.status().is_client_error(),
+        "{}",
+        other_tenant.status()
+    );
+    assert!(app.state.policy.purge_schedules().await.is_empty());
+}
@@ -0,0 +1,52 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-encryption after a key rotation rewrites entries still holding the
+//! revision it read.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use scedge::cache::{Cache, CacheBackend, MemoryCache};
+use scedge::crypto::{Keyring, TenantKey};
+use scedge::model::ArtifactPayload;
+use serde_json::json;
+
+fn key(key_id: &str, byte: u8) -> TenantKey {
+    TenantKey {
+        key_id: key_id.to_string(),
+        key: BASE64.encode([byte; 32]),
+    }
+}
+
+#[tokio::test]
+async fn rotated_entries_are_resealed_under_the_active_key() {
+    let keyring = Keyring::new();
+    keyring.add_key("acme", &key("2025-01", 1)).unwrap();
+    let backend = MemoryCache::new();
+    let cache = Cache::new(backend.clone()).with_keyring(keyring.clone());
+
+    for name in ["greeting", "farewell"] {
+        let artifact = ArtifactPayload::builder()
+            .answer(json!(name))
+            .tenant("acme")
+            .build()
+            .expect("artifact is valid");
+        cache
+            .set(format!("acme:answers:{}", name), artifact, None)
+            .await
+            .unwrap();
+    }
+    let before = backend.get("acme:answers:greeting").await.unwrap().unwrap();
+
+    keyring.add_key("acme", &key("2025-02", 2)).unwrap();
+    assert_eq!(cache.reencrypt_tenant("acme").await.unwrap(), 2);
+
+    let after = backend.get("acme:answers:greeting").await.unwrap().unwrap();
+    assert!(!keyring.needs_reencryption("acme", &after.artifact.answer));
+    assert_ne!(after.version(), before.version());
+    let record = cache.get("acme:answers:greeting").await.unwrap().unwrap();
+    assert_eq!(record.artifact.answer, json!("greeting"));
+
+    // Nothing is left to rewrite
+    assert_eq!(cache.reencrypt_tenant("acme").await.unwrap(), 0);
+}
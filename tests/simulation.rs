@@ -0,0 +1,43 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Seeded simulations of TTLs, `SUPERSEDED_BY` events, and purges on the
+//! memory backend. `SCEDGE_SIM_SEED` replays a single seed.
+
+use scedge::simulation::{Simulation, SimulationConfig};
+
+/// Seeds run when `SCEDGE_SIM_SEED` is unset
+const SEEDS: u64 = 64;
+
+#[tokio::test]
+async fn ttl_and_invalidation_invariants_hold() {
+    let seeds = match std::env::var("SCEDGE_SIM_SEED") {
+        Ok(seed) => {
+            let seed = seed.parse().expect("SCEDGE_SIM_SEED is a number");
+            seed..seed + 1
+        }
+        Err(_) => 0..SEEDS,
+    };
+
+    for seed in seeds {
+        if let Err(violation) = Simulation::run(seed, SimulationConfig::default()).await {
+            panic!("{}", violation);
+        }
+    }
+}
+
+#[tokio::test]
+async fn runs_replay_from_their_seed() {
+    let config = SimulationConfig {
+        steps: 200,
+        ..SimulationConfig::default()
+    };
+    let run = |config| async move {
+        Simulation::run(7, config)
+            .await
+            .unwrap_or_else(|violation| panic!("{}", violation))
+    };
+    let first = run(config.clone()).await;
+    let second = run(config).await;
+    assert_eq!(format!("{:?}", first), format!("{:?}", second));
+}
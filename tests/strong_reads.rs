@@ -0,0 +1,66 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! `consistency=strong` reads skip the L1 and observe the backend's copy.
+
+use std::time::Duration;
+
+use scedge::cache::{Cache, CacheBackend, MemoryCache, StoredEntry, TieredCache};
+use scedge::model::Consistency;
+use scedge::testing::ACME;
+use serde_json::json;
+
+fn answer(entry: Option<StoredEntry>) -> Option<serde_json::Value> {
+    match entry {
+        Some(StoredEntry::Live(record)) => Some(record.artifact.answer),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn strong_reads_see_writes_made_behind_the_l1() {
+    let l2 = MemoryCache::new();
+    let cache = Cache::new(TieredCache::new(l2.clone(), 100, Duration::from_secs(3600)));
+    let key = "acme:answers:greeting".to_string();
+    cache
+        .set(key.clone(), ACME.artifact(json!("hello")), None)
+        .await
+        .expect("store succeeds");
+
+    // A sibling node overwrites the entry directly in the shared store
+    l2.set(key.clone(), ACME.artifact(json!("bonjour")), None)
+        .await
+        .expect("store succeeds");
+
+    let eventual = cache.read(&key, Consistency::Eventual).await.unwrap();
+    assert_eq!(answer(eventual), Some(json!("hello")));
+    let strong = cache.read(&key, Consistency::Strong).await.unwrap();
+    assert_eq!(answer(strong), Some(json!("bonjour")));
+
+    // The strong read refreshed the L1
+    let eventual = cache.read(&key, Consistency::Eventual).await.unwrap();
+    assert_eq!(answer(eventual), Some(json!("bonjour")));
+}
+
+#[tokio::test]
+async fn strong_reads_see_purges_made_behind_the_l1() {
+    let l2 = MemoryCache::new();
+    let cache = Cache::new(TieredCache::new(l2.clone(), 100, Duration::from_secs(3600)));
+    let key = "acme:answers:greeting".to_string();
+    cache
+        .set(key.clone(), ACME.artifact(json!("hello")), None)
+        .await
+        .expect("store succeeds");
+    l2.delete(&key).await.expect("delete succeeds");
+
+    assert!(cache
+        .read(&key, Consistency::Strong)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(cache
+        .read(&key, Consistency::Eventual)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,78 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tenant ids that could alias another tenant's keys or scan patterns are
+//! rejected, and tenant-wide purges stay within their tenant.
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use scedge::testing::{TestApp, TestTenant, ACME};
+use serde_json::json;
+
+/// Tenant whose id extends [`ACME`]'s
+const ACME_EU: TestTenant = TestTenant {
+    id: "acme-eu",
+    api_key: "acme-eu-test-key",
+};
+
+const ADVERSARIAL_IDS: &[&str] = &[
+    "acme:b", "acme*", "acme?", "acme[x]", "acme\\b", "", " acme",
+];
+
+fn purge_tenant(tenant: &TestTenant, target: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/purge")
+        .header("x-api-key", tenant.api_key)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "tenant": target }).to_string()))
+        .expect("purge request is valid")
+}
+
+#[tokio::test]
+async fn adversarial_tenant_ids_are_not_installed() {
+    for id in ADVERSARIAL_IDS {
+        let mut config = ACME.config();
+        config.tenant_id = id.to_string();
+        assert!(
+            TestApp::with_tenants(vec![config]).await.is_err(),
+            "tenant {:?} was installed",
+            id
+        );
+    }
+}
+
+#[tokio::test]
+async fn adversarial_tenant_ids_are_rejected_in_requests() {
+    let app = TestApp::new().await.expect("test app starts");
+    for id in ADVERSARIAL_IDS {
+        let response = app.send(purge_tenant(&ACME, id)).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::BAD_REQUEST,
+            "tenant {:?}",
+            id
+        );
+    }
+}
+
+#[tokio::test]
+async fn tenant_purges_do_not_reach_tenants_extending_the_id() {
+    let app = TestApp::with_tenants(vec![ACME.config(), ACME_EU.config()])
+        .await
+        .expect("test app starts");
+    for tenant in [ACME, ACME_EU] {
+        let response = app
+            .send(tenant.store(&tenant.key("report"), json!("hello")))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app.send(purge_tenant(&ACME, ACME.id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.send(ACME.lookup(&ACME.key("report"))).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app.send(ACME_EU.lookup(&ACME_EU.key("report"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,61 @@
+// Copyright 2025 Memophor Labs
+// SPDX-License-Identifier: Apache-2.0
+
+//! Touches move only the expiry: the entry keeps its version, and keys of
+//! other tenants or past their expiry are left alone.
+
+use chrono::{Duration, Utc};
+use scedge::cache::{CacheBackend, MemoryCache, SqliteCache};
+use scedge::testing::{ACME, GLOBEX};
+use serde_json::json;
+
+async fn touches_move_only_the_expiry(cache: impl CacheBackend) {
+    let stored = cache
+        .set(
+            "acme:answers:greeting".to_string(),
+            ACME.artifact(json!("hello")),
+            Some(Utc::now() + Duration::seconds(60)),
+        )
+        .await
+        .unwrap();
+    cache
+        .set(
+            "globex:answers:greeting".to_string(),
+            GLOBEX.artifact(json!("hello")),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let keys = [
+        "acme:answers:greeting".to_string(),
+        "globex:answers:greeting".to_string(),
+        "acme:answers:missing".to_string(),
+    ];
+    let touched = cache.touch_many("acme", &keys, 3600).await.unwrap();
+    let expires_at = touched[0].expect("own key is touched");
+    assert!(expires_at > Utc::now() + Duration::seconds(3000));
+    assert_eq!(touched[1], None);
+    assert_eq!(touched[2], None);
+
+    let record = cache.get("acme:answers:greeting").await.unwrap().unwrap();
+    assert_eq!(record.version(), stored.version());
+    assert_eq!(record.expires_at, Some(expires_at));
+    assert_eq!(record.artifact.answer, json!("hello"));
+
+    let other = cache.get("globex:answers:greeting").await.unwrap().unwrap();
+    assert_eq!(other.expires_at, None);
+}
+
+#[tokio::test]
+async fn memory_touches_move_only_the_expiry() {
+    touches_move_only_the_expiry(MemoryCache::new()).await;
+}
+
+#[tokio::test]
+async fn sqlite_touches_move_only_the_expiry() {
+    let path = std::env::temp_dir().join(format!("scedge-touch-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    touches_move_only_the_expiry(SqliteCache::open(&path).expect("database opens")).await;
+    let _ = std::fs::remove_file(&path);
+}